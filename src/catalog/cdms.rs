@@ -0,0 +1,96 @@
+use crate::constants::{SPEED_OF_LIGHT, WAVENUMBER_TO_KELVIN};
+use crate::lamda::ParseError;
+
+use super::{field, parse_field, CatalogLine, LineList};
+
+// Column ranges of the CDMS/JPL catalog format
+// (F13.4, F8.4, F8.4, I2, F10.4, I3, I7, I4, 6I2, 6I2).
+const FREQ: (usize, usize) = (0, 13);
+const LGINT: (usize, usize) = (21, 29);
+const ELO: (usize, usize) = (31, 41);
+const GUP: (usize, usize) = (41, 44);
+const QNFMT: (usize, usize) = (51, 55);
+const QN_UP: usize = 55;
+const QN_LOW: usize = 67;
+
+const REFERENCE_TEMPERATURE: f64 = 300.0;
+
+fn qnums(line: &str, start: usize, count: usize) -> String {
+    (0..count)
+        .map(|i| field(line, start + 2 * i, start + 2 * i + 2))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// CDMS lists intensities at 300 K instead of Einstein A coefficients, so the
+// partition function at that temperature is needed to convert them.
+pub fn parse(s: &str, species: &str, partition_function_300: f64) -> Result<LineList, ParseError> {
+    let lines = s
+        .lines()
+        .enumerate()
+        .filter(|el| !el.1.trim().is_empty())
+        .map(|(line_number, line)| {
+            let frequency_mhz: f64 = parse_field(line_number, line, FREQ, "frequency [MHz]", "floating point number")?;
+            let lgint: f64 = parse_field(line_number, line, LGINT, "log10 intensity", "floating point number")?;
+            let e_low: f64 = parse_field(line_number, line, ELO, "lower state energy [cm-1]", "floating point number")?;
+            let g_up: u32 = parse_field(line_number, line, GUP, "upper state degeneracy", "integer")?;
+            let qnfmt: u32 = parse_field(line_number, line, QNFMT, "quantum number format", "integer")?;
+
+            let count = match qnfmt % 10 {
+                0 => 6,
+                n => n.min(6) as usize,
+            };
+
+            let e_up = e_low + frequency_mhz * 1e6 / SPEED_OF_LIGHT;
+            let boltzmann = |e: f64| (-e * WAVENUMBER_TO_KELVIN / REFERENCE_TEMPERATURE).exp();
+            let aeinst = 2.7964e-16 * 10f64.powf(lgint) * frequency_mhz.powi(2) * partition_function_300
+                / g_up as f64
+                / (boltzmann(e_low) - boltzmann(e_up));
+
+            Ok(CatalogLine {
+                frequency: frequency_mhz * 1e-3,
+                aeinst,
+                e_low,
+                g_up: g_up as f64,
+                g_low: None,
+                qnums_up: qnums(line, QN_UP, count),
+                qnums_low: qnums(line, QN_LOW, count),
+            })
+        })
+        .collect::<Result<Vec<_>, ParseError>>()
+        .map_err(|e| e.locate(s))?;
+
+    Ok(LineList { species: String::from(species), lines })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::Span;
+
+    #[test]
+    fn parse_cdms_catalog() {
+        let s = "  115271.2018  0.0005 -5.0105 2    0.0000  3 -28503 101 1           0\n\
+                 \x20 230538.0000  0.0005 -4.1197 2    3.8450  5 -28503 101 2           1\n";
+
+        let list = parse(s, "CO", 108.8651).unwrap();
+
+        assert_eq!(list.lines.len(), 2);
+        assert_eq!(list.lines[0].qnums_up, "1");
+        assert_eq!(list.lines[0].qnums_low, "0");
+        assert!(
+            (list.lines[0].aeinst - 7.203e-08).abs() < 1e-10,
+            "Wrong Einstein A {} for CO 1-0",
+            list.lines[0].aeinst
+        );
+        assert_eq!(list.lines[1].frequency, 230.538);
+    }
+
+    #[test]
+    fn parse_cdms_catalog_with_wrong_field() {
+        let s = "  115271.2018  0.0005 -5.01x5 2    0.0000  3 -28503 101 1           0\n";
+
+        assert!(matches!(parse(s, "CO", 108.8651), Err(ParseError::UnknownItem { span: Span { column: 22, .. }, .. })));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::constants::SPEED_OF_LIGHT;
+use crate::lamda::ParseError;
+
+use super::{field, parse_field, CatalogLine, LineList};
+
+// Column ranges of the 160-character HITRAN2004+ `.par` format.
+const WAVENUMBER: (usize, usize) = (3, 15);
+const AEINST: (usize, usize) = (25, 35);
+const E_LOW: (usize, usize) = (45, 55);
+const GLOBAL_UP: (usize, usize) = (67, 82);
+const GLOBAL_LOW: (usize, usize) = (82, 97);
+const LOCAL_UP: (usize, usize) = (97, 112);
+const LOCAL_LOW: (usize, usize) = (112, 127);
+const G_UP: (usize, usize) = (146, 153);
+const G_LOW: (usize, usize) = (153, 160);
+
+fn qnums(line: &str, global: (usize, usize), local: (usize, usize)) -> String {
+    format!("{} {}", field(line, global.0, global.1), field(line, local.0, local.1))
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn parse(s: &str, species: &str) -> Result<LineList, ParseError> {
+    let lines = s
+        .lines()
+        .enumerate()
+        .filter(|el| !el.1.trim().is_empty())
+        .map(|(line_number, line)| {
+            let wavenumber: f64 = parse_field(line_number, line, WAVENUMBER, "wavenumber [cm-1]", "floating point number")?;
+            let aeinst: f64 = parse_field(line_number, line, AEINST, "Einstein A [s-1]", "floating point number")?;
+            let e_low: f64 = parse_field(line_number, line, E_LOW, "lower state energy [cm-1]", "floating point number")?;
+            let g_up: f64 = parse_field(line_number, line, G_UP, "upper state weight", "floating point number")?;
+            let g_low: f64 = parse_field(line_number, line, G_LOW, "lower state weight", "floating point number")?;
+
+            Ok(CatalogLine {
+                frequency: wavenumber * SPEED_OF_LIGHT * 1e-9,
+                aeinst,
+                e_low,
+                g_up,
+                g_low: Some(g_low),
+                qnums_up: qnums(line, GLOBAL_UP, LOCAL_UP),
+                qnums_low: qnums(line, GLOBAL_LOW, LOCAL_LOW),
+            })
+        })
+        .collect::<Result<Vec<_>, ParseError>>()
+        .map_err(|e| e.locate(s))?;
+
+    Ok(LineList { species: String::from(species), lines })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_hitran_par() {
+        let s = " 51    3.845033 6.141e-28 7.203e-08.07970.081    0.00000.73-.001460              0              0                    R  0      457665 5 8 2 1 1 7     3.0    1.0\n";
+
+        let list = parse(s, "CO").unwrap();
+        let line = &list.lines[0];
+
+        assert!((line.frequency - 115.2712).abs() < 1e-3, "Wrong frequency {}", line.frequency);
+        assert_eq!(line.aeinst, 7.203e-08);
+        assert_eq!(line.g_up, 3.0);
+        assert_eq!(line.g_low, Some(1.0));
+        assert_eq!(line.qnums_up, "0");
+        assert_eq!(line.qnums_low, "0 R 0");
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use crate::constants::SPEED_OF_LIGHT;
+use crate::lamda::{
+    CollisionPartnerId,
+    ElementData,
+    EnergyLevelRef,
+    FormatFeatures,
+    ParseError,
+    RadiativeTransition,
+    RadiativeTransitionRef,
+    Span,
+};
+use crate::molecular::{CollisionRate, MolecularData};
+
+pub mod cdms;
+pub mod hitran;
+#[cfg(feature = "vamdc")]
+pub mod vamdc;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CatalogLine {
+    pub frequency: f64,
+    pub aeinst: f64,
+    pub e_low: f64,
+    pub g_up: f64,
+    pub g_low: Option<f64>,
+    pub qnums_up: String,
+    pub qnums_low: String,
+}
+
+impl CatalogLine {
+    pub fn e_up(&self) -> f64 {
+        self.e_low + self.frequency * 1e9 / SPEED_OF_LIGHT
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineList {
+    pub species: String,
+    pub lines: Vec<CatalogLine>,
+}
+
+struct Level {
+    energy: f64,
+    stat_weight: Option<f64>,
+}
+
+// Levels that only ever appear as lower states of a CDMS line have no tabulated
+// weight; for those 2J+1 is assumed with J the first quantum number.
+fn fallback_stat_weight(qnums: &str) -> f64 {
+    qnums
+        .split_whitespace()
+        .next()
+        .and_then(|j| j.parse::<f64>().ok())
+        .map(|j| 2.0 * j + 1.0)
+        .unwrap_or(1.0)
+}
+
+impl LineList {
+    // Levels in order of increasing energy, keyed by their quantum numbers.
+    fn sorted_levels(&self) -> Vec<(&str, Level)> {
+        let mut levels: HashMap<&str, Level> = HashMap::new();
+
+        for line in self.lines.iter() {
+            let upper = levels.entry(&line.qnums_up).or_insert(Level { energy: line.e_up(), stat_weight: None });
+            upper.stat_weight = Some(line.g_up);
+
+            let lower = levels.entry(&line.qnums_low).or_insert(Level { energy: line.e_low, stat_weight: None });
+            if lower.stat_weight.is_none() {
+                lower.stat_weight = line.g_low;
+            }
+        }
+
+        let mut sorted = levels.into_iter().collect::<Vec<_>>();
+        sorted.sort_by(|a, b| a.1.energy.total_cmp(&b.1.energy).then(a.0.cmp(b.0)));
+
+        sorted
+    }
+
+    fn sorted_lines(&self) -> Vec<&CatalogLine> {
+        let mut lines = self.lines.iter().collect::<Vec<_>>();
+        lines.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+
+        lines
+    }
+
+    pub fn to_element_data(&self, weight: f64) -> ElementData {
+        let energy_levels = MolecularData::energy_levels(self).into_iter().map(EnergyLevelRef::into_owned).collect();
+
+        let radiative_transitions = MolecularData::radiative_transitions(self)
+            .into_iter()
+            .zip(self.sorted_lines())
+            .map(|(rt, line)| RadiativeTransition {
+                frequency: Some(line.frequency),
+                upper_energy: Some(line.e_up() * crate::constants::WAVENUMBER_TO_KELVIN),
+                ..rt.into_owned()
+            })
+            .collect();
+
+        ElementData {
+            name: self.species.clone(),
+            information: String::new(),
+            weight,
+            energy_levels,
+            radiative_transitions,
+            collision_partners: vec!(),
+            features: FormatFeatures { transition_frequencies: true, quantum_numbers: true, ..Default::default() },
+            ..Default::default()
+        }
+    }
+}
+
+// Line catalogs have no collisional data, so a `LineList` is only enough for
+// the solver together with rates from another source.
+impl MolecularData for LineList {
+    fn species(&self) -> &str {
+        &self.species
+    }
+
+    fn energy_levels(&self) -> Vec<EnergyLevelRef<'_>> {
+        self.sorted_levels()
+            .into_iter()
+            .zip(1..)
+            .map(|((qnums, level), n)| EnergyLevelRef {
+                level: n,
+                energy: level.energy,
+                stat_weight: level.stat_weight.unwrap_or_else(|| fallback_stat_weight(qnums)),
+                qnums,
+            })
+            .collect()
+    }
+
+    fn radiative_transitions(&self) -> Vec<RadiativeTransitionRef<'_>> {
+        let index = self
+            .sorted_levels()
+            .into_iter()
+            .zip(1..)
+            .map(|((qnums, _), n)| (qnums, n))
+            .collect::<HashMap<&str, u32>>();
+
+        self.sorted_lines()
+            .into_iter()
+            .zip(1..)
+            .map(|(line, n)| RadiativeTransitionRef {
+                transition: n,
+                up: index[line.qnums_up.as_str()],
+                low: index[line.qnums_low.as_str()],
+                aeinst: line.aeinst,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn collision_partners(&self) -> Vec<CollisionPartnerId> {
+        vec!()
+    }
+
+    fn collision_temperature_range(&self, _partner: CollisionPartnerId) -> Option<(f64, f64)> {
+        None
+    }
+
+    fn collision_rates(&self, _partner: CollisionPartnerId, _temperature: f64) -> Vec<CollisionRate> {
+        vec!()
+    }
+}
+
+pub(crate) fn field(line: &str, start: usize, end: usize) -> &str {
+    line.get(start..end.min(line.len())).unwrap_or("").trim()
+}
+
+pub(crate) fn parse_field<T: std::str::FromStr>(
+    line_number: usize,
+    line: &str,
+    (start, end): (usize, usize),
+    name: &str,
+    expected: &str,
+) -> Result<T, ParseError> {
+    let value = field(line, start, end);
+
+    if value.is_empty() {
+        return Err(ParseError::MissingField {
+            line_number,
+            span: Span::in_line(line.len(), 0),
+            line: String::from(line),
+            note: format!("Missing field `{}` with value of {} type", name, expected),
+            source: None,
+        });
+    }
+
+    value.parse::<T>().map_err(|_| ParseError::UnknownItem {
+        line_number,
+        span: Span::in_line(start, end.min(line.len()) - start),
+        line: String::from(line),
+        note: format!("Value `{}` from field `{}` has wrong type (should be {})", value, name, expected),
+        source: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn line_list_to_element_data() {
+        let list = LineList {
+            species: String::from("CO"),
+            lines: vec!(
+                CatalogLine {
+                    frequency: 230.538,
+                    aeinst: 6.91e-07,
+                    e_low: 3.8450,
+                    g_up: 5.0,
+                    g_low: None,
+                    qnums_up: String::from("2"),
+                    qnums_low: String::from("1"),
+                },
+                CatalogLine {
+                    frequency: 115.2712018,
+                    aeinst: 7.203e-08,
+                    e_low: 0.0,
+                    g_up: 3.0,
+                    g_low: None,
+                    qnums_up: String::from("1"),
+                    qnums_low: String::from("0"),
+                },
+            ),
+        };
+
+        let ed = list.to_element_data(28.0);
+
+        assert_eq!(ed.energy_levels.len(), 3);
+        assert_eq!(ed.energy_levels[0].qnums, "0");
+        assert_eq!(ed.energy_levels[0].stat_weight, 1.0);
+        assert_eq!(ed.energy_levels[2].stat_weight, 5.0);
+        assert_eq!((ed.radiative_transitions[0].up, ed.radiative_transitions[0].low), (2, 1));
+        assert_eq!((ed.radiative_transitions[1].up, ed.radiative_transitions[1].low), (3, 2));
+
+        assert_eq!(MolecularData::energy_levels(&list).len(), 3);
+        assert_eq!(
+            crate::radex::solve(&list, &Default::default()),
+            Err(crate::radex::SolverError::NoCollisionPartners),
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::constants::{SPEED_OF_LIGHT, WAVENUMBER_TO_KELVIN};
+
+use super::{CatalogLine, LineList};
+
+pub const CDMS_TAP_URL: &str = "https://cdms.astro.uni-koeln.de/cdms/tap/";
+
+#[derive(Debug)]
+pub enum VamdcError {
+    Http {
+        url: String,
+        note: String,
+    },
+    Xml {
+        position: u64,
+        note: String,
+    },
+    UnknownUnits {
+        element: String,
+        units: String,
+    },
+    UnknownState {
+        transition: String,
+        state: String,
+    },
+    MissingValue {
+        transition: String,
+        field: &'static str,
+    },
+}
+
+impl std::fmt::Display for VamdcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VamdcError::Http { url, note } => write!(f, "cannot query `{}`: {}", url, note),
+            VamdcError::Xml { position, note } => write!(f, "malformed XSAMS at byte {}: {}", position, note),
+            VamdcError::UnknownUnits { element, units } => write!(f, "unknown units `{}` of <{}>", units, element),
+            VamdcError::UnknownState { transition, state } => {
+                write!(f, "transition `{}` refers to unknown state `{}`", transition, state)
+            }
+            VamdcError::MissingValue { transition, field } => {
+                write!(f, "transition `{}` has no {}", transition, field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VamdcError {}
+
+#[derive(Default)]
+struct State {
+    energy: Option<f64>,
+    stat_weight: Option<f64>,
+    qnums: Vec<String>,
+}
+
+#[derive(Default)]
+struct Transition {
+    id: String,
+    frequency: Option<f64>,
+    aeinst: Option<f64>,
+    up: String,
+    low: String,
+}
+
+fn value(text: &str, position: u64) -> Result<f64, VamdcError> {
+    text.trim().parse::<f64>().map_err(|_| VamdcError::Xml { position, note: format!("`{}` is not a number", text) })
+}
+
+fn to_wavenumber(energy: f64, units: &str) -> Result<f64, VamdcError> {
+    match units {
+        "1/cm" => Ok(energy),
+        "K" => Ok(energy / WAVENUMBER_TO_KELVIN),
+        "MHz" => Ok(energy * 1e6 / SPEED_OF_LIGHT),
+        _ => Err(VamdcError::UnknownUnits { element: String::from("StateEnergy"), units: String::from(units) }),
+    }
+}
+
+fn to_ghz(frequency: f64, units: &str) -> Result<f64, VamdcError> {
+    match units {
+        "Hz" => Ok(frequency * 1e-9),
+        "MHz" => Ok(frequency * 1e-3),
+        "GHz" => Ok(frequency),
+        _ => Err(VamdcError::UnknownUnits { element: String::from("Frequency"), units: String::from(units) }),
+    }
+}
+
+fn attribute(e: &BytesStart<'_>, name: &str) -> Option<String> {
+    e.try_get_attribute(name).ok().flatten().and_then(|a| a.unescape_value().ok()).map(|v| v.into_owned())
+}
+
+// Reads the molecular states and radiative transitions of an XSAMS document,
+// the response format of VAMDC nodes. Quantum numbers of a state are written
+// as `name=value` pairs in document order.
+pub fn parse_xsams(s: &str, species: &str) -> Result<LineList, VamdcError> {
+    let mut reader = Reader::from_str(s);
+    reader.config_mut().trim_text(true);
+
+    let mut path: Vec<String> = vec!();
+    let mut units = String::new();
+    let mut states: HashMap<String, State> = HashMap::new();
+    let mut state_id = String::new();
+    let mut transitions = vec!();
+
+    loop {
+        let position = reader.buffer_position();
+        let xml_error = |e: quick_xml::Error| VamdcError::Xml { position, note: e.to_string() };
+
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                match name.as_str() {
+                    "MolecularState" => {
+                        state_id = attribute(&e, "stateID").unwrap_or_default();
+                        states.insert(state_id.clone(), State::default());
+                    }
+                    "RadiativeTransition" => transitions.push(Transition {
+                        id: attribute(&e, "id").unwrap_or_default(),
+                        ..Default::default()
+                    }),
+                    "Value" => units = attribute(&e, "units").unwrap_or_default(),
+                    _ => (),
+                }
+                path.push(name);
+            }
+            Event::End(_) => {
+                path.pop();
+            }
+            Event::Text(t) => {
+                let text = t.unescape().map_err(xml_error)?;
+                let within = |parent: &str| path.iter().any(|p| p == parent);
+                let parent = path.len().checked_sub(2).map_or("", |i| path[i].as_str());
+
+                match path.last().map(String::as_str) {
+                    Some("Value") if parent == "StateEnergy" && within("MolecularState") => {
+                        let energy = to_wavenumber(value(&text, position)?, &units)?;
+                        if let Some(state) = states.get_mut(&state_id) {
+                            state.energy.get_or_insert(energy);
+                        }
+                    }
+                    Some("TotalStatisticalWeight") if within("MolecularState") => {
+                        let stat_weight = value(&text, position)?;
+                        if let Some(state) = states.get_mut(&state_id) {
+                            state.stat_weight = Some(stat_weight);
+                        }
+                    }
+                    Some(qnum) if within("QNs") && within("MolecularState") => {
+                        if let Some(state) = states.get_mut(&state_id) {
+                            state.qnums.push(format!("{}={}", qnum, text.trim()));
+                        }
+                    }
+                    Some(field) if within("RadiativeTransition") => {
+                        let Some(transition) = transitions.last_mut() else { continue };
+                        match field {
+                            "Value" if parent == "Frequency" && transition.frequency.is_none() => {
+                                transition.frequency = Some(to_ghz(value(&text, position)?, &units)?);
+                            }
+                            "Value" if parent == "TransitionProbabilityA" => {
+                                transition.aeinst = Some(value(&text, position)?);
+                            }
+                            "UpperStateRef" => transition.up = String::from(text.trim()),
+                            "LowerStateRef" => transition.low = String::from(text.trim()),
+                            _ => (),
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    let lines = transitions
+        .into_iter()
+        .map(|t| {
+            let state = |id: &str| {
+                states.get(id).ok_or_else(|| VamdcError::UnknownState {
+                    transition: t.id.clone(),
+                    state: String::from(id),
+                })
+            };
+            let missing = |field| VamdcError::MissingValue { transition: t.id.clone(), field };
+            let (up, low) = (state(&t.up)?, state(&t.low)?);
+
+            Ok(CatalogLine {
+                frequency: t.frequency.ok_or_else(|| missing("frequency"))?,
+                aeinst: t.aeinst.ok_or_else(|| missing("Einstein A coefficient"))?,
+                e_low: low.energy.ok_or_else(|| missing("lower state energy"))?,
+                g_up: up.stat_weight.ok_or_else(|| missing("upper state weight"))?,
+                g_low: low.stat_weight,
+                qnums_up: up.qnums.join(" "),
+                qnums_low: low.qnums.join(" "),
+            })
+        })
+        .collect::<Result<Vec<_>, VamdcError>>()?;
+
+    Ok(LineList { species: String::from(species), lines })
+}
+
+// Client of a VAMDC TAP endpoint. Requests are made with `reqwest`, so the
+// futures have to be polled on a Tokio runtime.
+#[derive(Debug, Clone)]
+pub struct TapClient {
+    endpoint: String,
+    http: reqwest::Client,
+}
+
+impl TapClient {
+    pub fn new(endpoint: &str) -> Self {
+        Self { endpoint: String::from(endpoint), http: reqwest::Client::new() }
+    }
+
+    pub fn cdms() -> Self {
+        Self::new(CDMS_TAP_URL)
+    }
+
+    // VSS2 query for the lines of a molecule, given by its stoichiometric
+    // formula, between two frequencies in GHz.
+    pub fn query(formula: &str, min_frequency: f64, max_frequency: f64) -> String {
+        format!(
+            "SELECT ALL WHERE RadTransFrequency >= {} AND RadTransFrequency <= {} AND MoleculeStoichiometricFormula = '{}'",
+            min_frequency * 1e3,
+            max_frequency * 1e3,
+            formula.replace('\'', "''"),
+        )
+    }
+
+    pub async fn transitions(
+        &self,
+        formula: &str,
+        min_frequency: f64,
+        max_frequency: f64,
+    ) -> Result<LineList, VamdcError> {
+        let url = format!("{}/sync", self.endpoint.trim_end_matches('/'));
+        let http_error = |e: reqwest::Error| VamdcError::Http { url: url.clone(), note: e.to_string() };
+        let query = Self::query(formula, min_frequency, max_frequency);
+
+        let response = self
+            .http
+            .get(&url)
+            .query(&[("REQUEST", "doQuery"), ("LANG", "VSS2"), ("FORMAT", "XSAMS"), ("QUERY", query.as_str())])
+            .send()
+            .await
+            .map_err(http_error)?;
+
+        // Nodes answer 204 when nothing matches the query
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok(LineList { species: String::from(formula), lines: vec!() });
+        }
+
+        let xsams = response.error_for_status().map_err(http_error)?.text().await.map_err(http_error)?;
+        parse_xsams(&xsams, formula)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn xsams_line_list() {
+        let xsams = r#"<?xml version="1.0" encoding="UTF-8"?>
+<XSAMSData xmlns="http://vamdc.org/xml/xsams/1.0" xmlns:dcs="http://vamdc.org/xml/xsams/1.0/cases/dcs">
+  <Species><Molecules><Molecule speciesID="XCDMS-149">
+    <MolecularState stateID="SCDMS-1">
+      <MolecularStateCharacterisation>
+        <StateEnergy energyOrigin="Zero"><Value units="1/cm">0.0</Value></StateEnergy>
+        <TotalStatisticalWeight>1</TotalStatisticalWeight>
+      </MolecularStateCharacterisation>
+      <Case caseID="dcs"><dcs:QNs><dcs:v>0</dcs:v><dcs:J>0</dcs:J></dcs:QNs></Case>
+    </MolecularState>
+    <MolecularState stateID="SCDMS-2">
+      <MolecularStateCharacterisation>
+        <StateEnergy energyOrigin="Zero"><Value units="1/cm">3.845</Value></StateEnergy>
+        <TotalStatisticalWeight>3</TotalStatisticalWeight>
+      </MolecularStateCharacterisation>
+      <Case caseID="dcs"><dcs:QNs><dcs:v>0</dcs:v><dcs:J>1</dcs:J></dcs:QNs></Case>
+    </MolecularState>
+  </Molecule></Molecules></Species>
+  <Processes><Radiative>
+    <RadiativeTransition id="PCDMS-1" process="excitation">
+      <EnergyWavelength>
+        <Frequency><Value units="MHz">115271.2018</Value><Accuracy>0.0005</Accuracy></Frequency>
+      </EnergyWavelength>
+      <UpperStateRef>SCDMS-2</UpperStateRef>
+      <LowerStateRef>SCDMS-1</LowerStateRef>
+      <Probability><TransitionProbabilityA><Value units="1/s">7.203e-08</Value></TransitionProbabilityA></Probability>
+    </RadiativeTransition>
+  </Radiative></Processes>
+</XSAMSData>"#;
+
+        let list = parse_xsams(xsams, "CO").unwrap();
+
+        assert_eq!(list.lines.len(), 1);
+        assert!((list.lines[0].frequency - 115.2712018).abs() < 1e-9, "Wrong result for frequency");
+        assert_eq!(list.lines[0].aeinst, 7.203e-08);
+        assert_eq!((list.lines[0].g_up, list.lines[0].g_low), (3.0, Some(1.0)));
+        assert_eq!(list.lines[0].qnums_up, "v=0 J=1");
+        assert_eq!(list.to_element_data(28.0).energy_levels.len(), 2);
+
+        match parse_xsams(&xsams.replace("<UpperStateRef>SCDMS-2", "<UpperStateRef>SCDMS-3"), "CO") {
+            Err(VamdcError::UnknownState { state, .. }) => assert_eq!(state, "SCDMS-3"),
+            other => panic!("Wrong result for unknown state: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, ValueEnum};
+
+use ism::catalog::{cdms, hitran};
+use ism::interop::{csv, ecsv, schema, spectralradex, table, votable};
+use ism::lamda::ElementData;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum InputFormat {
+    Lamda,
+    Json,
+    Cdms,
+    Hitran,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Lamda,
+    Json,
+    Toml,
+    Yaml,
+    Csv,
+    Ecsv,
+    Votable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum TableKind {
+    Lines,
+    Levels,
+    Rates,
+}
+
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    input: PathBuf,
+    /// Input format, guessed from the file extension when omitted
+    #[arg(long, value_enum)]
+    from: Option<InputFormat>,
+    /// Output format
+    #[arg(long, value_enum)]
+    to: OutputFormat,
+    /// Table written by the tabular output formats
+    #[arg(long, value_enum, default_value = "lines")]
+    table: TableKind,
+    /// Species name for catalog inputs (defaults to the file name)
+    #[arg(long)]
+    species: Option<String>,
+    /// Molecular weight for catalog inputs
+    #[arg(long)]
+    weight: Option<f64>,
+    /// Partition function at 300 K, needed to derive Einstein A from CDMS intensities
+    #[arg(long)]
+    partition_function: Option<f64>,
+    /// Output file (standard output when omitted)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+fn guess_format(path: &Path) -> Result<InputFormat, String> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("dat") | Some("txt") => Ok(InputFormat::Lamda),
+        Some("json") => Ok(InputFormat::Json),
+        Some("cat") => Ok(InputFormat::Cdms),
+        Some("par") => Ok(InputFormat::Hitran),
+        _ => Err(format!("cannot guess the format of `{}`, use --from", path.display())),
+    }
+}
+
+fn read_input(args: &ConvertArgs) -> Result<ElementData, String> {
+    let format = match args.from {
+        Some(format) => format,
+        None => guess_format(&args.input)?,
+    };
+
+    let species = || args.species.clone().unwrap_or_else(|| {
+        args.input.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()
+    });
+    let weight = || args.weight.ok_or_else(|| String::from("--weight is required for catalog inputs"));
+
+    match format {
+        InputFormat::Lamda => super::read_element_data(&args.input),
+        InputFormat::Json => spectralradex::element_data_from_json(&super::read_file(&args.input)?)
+            .map_err(|e| format!("{} is not a valid molecular data JSON file: {}", args.input.display(), e)),
+        InputFormat::Cdms => {
+            let q = args.partition_function
+                .ok_or_else(|| String::from("--partition-function is required for CDMS inputs"))?;
+            let list = cdms::parse(&super::read_file(&args.input)?, &species(), q)
+                .map_err(|e| format!("{} is not a valid CDMS catalog\n{}", args.input.display(), e))?;
+            Ok(list.to_element_data(weight()?))
+        },
+        InputFormat::Hitran => {
+            let list = hitran::parse(&super::read_file(&args.input)?, &species())
+                .map_err(|e| format!("{} is not a valid HITRAN file\n{}", args.input.display(), e))?;
+            Ok(list.to_element_data(weight()?))
+        },
+    }
+}
+
+pub fn run(args: &ConvertArgs) -> Result<ExitCode, String> {
+    let data = read_input(args)?;
+
+    let tabular = || match args.table {
+        TableKind::Lines => table::line_list(&data),
+        TableKind::Levels => table::levels(&data),
+        TableKind::Rates => table::collision_rates(&data),
+    };
+
+    let output = match args.to {
+        OutputFormat::Lamda => data.to_lamda_string(),
+        OutputFormat::Json => spectralradex::element_data_to_json(&data).map_err(|e| e.to_string())? + "\n",
+        OutputFormat::Toml => schema::to_toml(&data).map_err(|e| e.to_string())?,
+        OutputFormat::Yaml => schema::to_yaml(&data).map_err(|e| e.to_string())?,
+        OutputFormat::Csv => csv::to_string(&tabular()),
+        OutputFormat::Ecsv => ecsv::to_string(&tabular()),
+        OutputFormat::Votable => votable::to_string(&tabular()),
+    };
+
+    match &args.output {
+        Some(path) => std::fs::write(path, output)
+            .map_err(|e| format!("cannot write `{}`: {}", path.display(), e))?,
+        None => std::io::stdout()
+            .write_all(output.as_bytes())
+            .map_err(|e| e.to_string())?,
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn json_to_lamda_round_trip() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let dir = std::env::temp_dir();
+        let input = dir.join("ism_convert_round_trip.json");
+        let output = dir.join("ism_convert_round_trip.dat");
+        std::fs::write(&input, spectralradex::element_data_to_json(&data).unwrap()).unwrap();
+
+        let args = ConvertArgs {
+            input: input.clone(),
+            from: None,
+            to: OutputFormat::Lamda,
+            table: TableKind::Lines,
+            species: None,
+            weight: None,
+            partition_function: None,
+            output: Some(output.clone()),
+        };
+        run(&args).unwrap();
+        let converted = std::fs::read_to_string(&output).unwrap().parse::<ElementData>().unwrap();
+
+        assert_eq!(converted, data, "Wrong result for `oatom.dat` converted to JSON and back to LAMDA");
+        std::fs::remove_file(&input).ok();
+        std::fs::remove_file(&output).ok();
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, ValueEnum};
+
+use ism::interop::fits;
+use ism::lamda::{CollisionPartnerId, Diagnostics};
+use ism::radex::{self, Conditions};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum GeometryArg {
+    Sphere,
+    Lvg,
+    Slab,
+}
+
+impl From<GeometryArg> for radex::Geometry {
+    fn from(item: GeometryArg) -> Self {
+        match item {
+            GeometryArg::Sphere => radex::Geometry::Sphere,
+            GeometryArg::Lvg => radex::Geometry::LargeVelocityGradient,
+            GeometryArg::Slab => radex::Geometry::Slab,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ExciteArgs {
+    /// LAMDA datafile of the species
+    #[arg(long)]
+    mol: PathBuf,
+    /// Kinetic temperature [K]
+    #[arg(long)]
+    tkin: f64,
+    /// Background radiation temperature [K]
+    #[arg(long, default_value_t = 2.73)]
+    tbg: f64,
+    /// Total H2 density [cm-3]
+    #[arg(long)]
+    nh2: Option<f64>,
+    /// Para-H2 density [cm-3]
+    #[arg(long)]
+    nph2: Option<f64>,
+    /// Ortho-H2 density [cm-3]
+    #[arg(long)]
+    noh2: Option<f64>,
+    /// Electron density [cm-3]
+    #[arg(long)]
+    ne: Option<f64>,
+    /// Atomic hydrogen density [cm-3]
+    #[arg(long)]
+    nh: Option<f64>,
+    /// Helium density [cm-3]
+    #[arg(long)]
+    nhe: Option<f64>,
+    /// Proton density [cm-3]
+    #[arg(long)]
+    nhp: Option<f64>,
+    /// Column density of the species [cm-2]
+    #[arg(long)]
+    cdmol: f64,
+    /// Line width (FWHM) [km s-1]
+    #[arg(long, default_value_t = 1.0)]
+    dv: f64,
+    /// Escape probability geometry
+    #[arg(long, value_enum, default_value = "sphere")]
+    geometry: GeometryArg,
+    /// Lowest frequency to report [GHz]
+    #[arg(long, default_value_t = 0.0)]
+    fmin: f64,
+    /// Highest frequency to report [GHz]
+    #[arg(long, default_value_t = f64::INFINITY)]
+    fmax: f64,
+    /// Also write the levels, transitions, populations and results to a FITS file
+    #[arg(long)]
+    fits: Option<PathBuf>,
+}
+
+pub fn run(args: &ExciteArgs) -> Result<ExitCode, String> {
+    let data = super::read_element_data(&args.mol)?;
+
+    let densities = [
+        (CollisionPartnerId::H2, args.nh2),
+        (CollisionPartnerId::pH2, args.nph2),
+        (CollisionPartnerId::oH2, args.noh2),
+        (CollisionPartnerId::electrons, args.ne),
+        (CollisionPartnerId::HI, args.nh),
+        (CollisionPartnerId::He, args.nhe),
+        (CollisionPartnerId::HII, args.nhp),
+    ]
+    .into_iter()
+    .filter_map(|(partner, density)| density.map(|n| (partner, n)))
+    .collect::<Vec<_>>();
+    if densities.is_empty() {
+        return Err(String::from("at least one collision partner density is required, e.g. --nh2"));
+    }
+
+    let conditions = Conditions {
+        tkin: args.tkin,
+        tbg: args.tbg,
+        densities,
+        cdmol: args.cdmol,
+        linewidth: args.dv,
+        geometry: args.geometry.into(),
+    };
+    let mut diagnostics = Diagnostics::new();
+    let solution = radex::solve_with_diagnostics(&data, &conditions, &mut diagnostics).map_err(|e| e.to_string())?;
+    for warning in diagnostics.warnings() {
+        eprintln!("warning: {}", warning.message);
+    }
+    if let Some(path) = &args.fits {
+        let file = std::fs::File::create(path).map_err(|e| format!("cannot write `{}`: {}", path.display(), e))?;
+        fits::write_model_run(&data, &conditions, &solution, &mut std::io::BufWriter::new(file))
+            .map_err(|e| format!("cannot write `{}`: {}", path.display(), e))?;
+    }
+
+    println!("* Molecule:        {}", data.summary().species);
+    println!("* T(kin) [K]:      {}", conditions.tkin);
+    for (partner, density) in conditions.densities.iter() {
+        println!("* n({:<4}) [cm-3]:  {:e}", partner.to_string(), density);
+    }
+    println!("* T(bg) [K]:       {}", conditions.tbg);
+    println!("* N(mol) [cm-2]:   {:e}", conditions.cdmol);
+    println!("* dV [km/s]:       {}", conditions.linewidth);
+    println!("* Geometry:        {}", conditions.geometry);
+    println!("* Iterations:      {}", solution.iterations);
+    println!(
+        "{:>15} {:>15} {:>9} {:>13} {:>9} {:>11} {:>11} {:>11} {:>11} {:>13} {:>13}",
+        "qn up", "qn low", "E_up [K]", "freq [GHz]", "T_ex [K]", "tau", "T_R [K]", "pop up", "pop low",
+        "K km/s", "erg/cm2/s"
+    );
+    for line in solution.lines.iter().filter(|l| l.frequency >= args.fmin && l.frequency <= args.fmax) {
+        println!(
+            "{:>15} {:>15} {:>9.1} {:>13.4} {:>9.3} {:>11.3e} {:>11.3e} {:>11.3e} {:>11.3e} {:>13.3e} {:>13.3e}",
+            line.qnums_up, line.qnums_low, line.e_up, line.frequency, line.tex, line.tau, line.t_r,
+            line.pop_up, line.pop_low, line.flux_kkms, line.flux_cgs
+        );
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,80 @@
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use ism::config::ModelConfig;
+use ism::interop::{csv, table};
+use ism::progress::Progress;
+use ism::radex::{self, Conditions};
+
+pub fn run(path: &PathBuf) -> Result<ExitCode, String> {
+    let config = ModelConfig::from_path(path)
+        .map_err(|e| format!("{} is not a valid model configuration\n{}", path.display(), e))?;
+
+    let data = super::read_element_data(&config.species.molfile)?;
+    let grid = config.grid().map_err(|e| e.to_string())?;
+    let fmin = config.output.fmin.unwrap_or(0.0);
+    let fmax = config.output.fmax.unwrap_or(f64::INFINITY);
+
+    let mut points = vec!();
+    let mut failed = 0;
+    let interactive = std::io::stderr().is_terminal();
+    let progress = |p: Progress<'_, Conditions>| if interactive {
+        eprint!("\rsolving grid point {} of {}", p.completed, p.total);
+    };
+    let solutions = radex::solve_grid_with_progress(&data, &grid, &progress);
+    if interactive {
+        eprintln!();
+    }
+
+    for (conditions, solution) in grid.iter().zip(solutions) {
+        match solution {
+            Ok(mut solution) => {
+                solution.lines.retain(|l| l.frequency >= fmin && l.frequency <= fmax);
+                points.push((conditions.clone(), solution));
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("error: tkin = {}, cdmol = {:e}, densities = {:?}: {}",
+                    conditions.tkin, conditions.cdmol, conditions.densities, e);
+            }
+        }
+    }
+
+    let name = match &config.species.name {
+        Some(name) => name.clone(),
+        None => config.species.molfile.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(),
+    };
+    let results = table::grid_results(&name, &points);
+
+    if let Some(output) = &config.output.csv {
+        std::fs::write(output, csv::to_string(&results))
+            .map_err(|e| format!("cannot write `{}`: {}", output.display(), e))?;
+    }
+    if let Some(output) = &config.output.parquet {
+        write_parquet(&results, output)?;
+    }
+    if config.output.csv.is_none() && config.output.parquet.is_none() {
+        print!("{}", csv::to_string(&results));
+    }
+
+    eprintln!("{} of {} grid points solved", points.len(), grid.len());
+    match failed {
+        0 => Ok(ExitCode::SUCCESS),
+        _ => Ok(ExitCode::FAILURE),
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(results: &table::Table, path: &Path) -> Result<(), String> {
+    use ism::interop::arrow;
+
+    let batch = arrow::table_record_batch(results).map_err(|e| e.to_string())?;
+    let file = std::fs::File::create(path).map_err(|e| format!("cannot write `{}`: {}", path.display(), e))?;
+    arrow::write_parquet(&batch, file).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet(_results: &table::Table, _path: &Path) -> Result<(), String> {
+    Err(String::from("Parquet output needs ism built with the `parquet` feature"))
+}
@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+pub fn run(file: &PathBuf, json: bool) -> Result<ExitCode, String> {
+    let summary = super::read_element_data(file)?.summary();
+
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&summary).map_err(|e| e.to_string())?),
+        false => print!("{}", summary),
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+mod convert;
+mod excite;
+mod grid;
+mod info;
+#[cfg(feature = "fetch")]
+mod sync;
+mod validate;
+
+#[derive(Debug, Parser)]
+#[command(name = "ism", version, about = "Tools for interstellar medium molecular data")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check LAMDA datafiles and report problems
+    Validate {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Print the findings as JSON diagnostics
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a summary of a LAMDA datafile
+    Info {
+        file: PathBuf,
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Convert molecular data between formats
+    Convert(convert::ConvertArgs),
+    /// Solve the level populations of a single zone and print line intensities
+    Excite(excite::ExciteArgs),
+    /// Run the solver over a parameter grid described by a TOML or YAML model configuration
+    Grid {
+        job: PathBuf,
+    },
+    /// Mirror the LAMDA archive into a directory, downloading only new and changed files
+    #[cfg(feature = "fetch")]
+    Sync {
+        dir: PathBuf,
+        /// Archive directory to mirror instead of the LAMDA datafiles
+        #[arg(long)]
+        url: Option<String>,
+    },
+}
+
+pub fn run(cli: Cli) -> Result<ExitCode, String> {
+    match cli.command {
+        Command::Validate { files, json } => validate::run(&files, json),
+        Command::Info { file, json } => info::run(&file, json),
+        Command::Convert(args) => convert::run(&args),
+        Command::Excite(args) => excite::run(&args),
+        Command::Grid { job } => grid::run(&job),
+        #[cfg(feature = "fetch")]
+        Command::Sync { dir, url } => sync::run(&dir, url.as_deref()),
+    }
+}
+
+pub(crate) fn read_file(path: &PathBuf) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("cannot read `{}`: {}", path.display(), e))?;
+    Ok(ism::lamda::decode(&bytes, ism::lamda::Decoding::Latin1Fallback)
+        .map_err(|e| e.to_string())?
+        .into_owned())
+}
+
+pub(crate) fn read_element_data(path: &PathBuf) -> Result<ism::lamda::ElementData, String> {
+    ism::lamda::ElementData::from_path(path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,42 @@
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use ism::fetch::Fetcher;
+use ism::lamda::LamdaDatabase;
+use ism::progress::Progress;
+
+pub fn run(dir: &PathBuf, url: Option<&str>) -> Result<ExitCode, String> {
+    let mut fetcher = Fetcher::open_mirror(dir).map_err(|e| e.to_string())?;
+    if let Some(url) = url {
+        fetcher = fetcher.with_base_url(url);
+    }
+
+    let interactive = std::io::stderr().is_terminal();
+    let progress = |p: Progress<'_, str>| if interactive {
+        eprint!("\r\x1b[Kchecking {} of {}: {}", p.completed, p.total, p.current);
+    };
+    let report = fetcher.sync(&progress);
+    if interactive {
+        eprintln!();
+    }
+    let report = report.map_err(|e| e.to_string())?;
+
+    for file in report.added.iter() {
+        println!("added    {}", file);
+    }
+    for file in report.updated.iter() {
+        println!("updated  {}", file);
+    }
+    for file in report.removed.iter() {
+        println!("removed  {} (kept locally)", file);
+    }
+
+    let database = LamdaDatabase::open(dir).map_err(|e| e.to_string())?;
+    println!(
+        "{} added, {} updated, {} unchanged; {} species indexed in {}",
+        report.added.len(), report.updated.len(), report.unchanged, database.species().count(), dir.display()
+    );
+
+    Ok(ExitCode::SUCCESS)
+}
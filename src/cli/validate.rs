@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use ism::lamda::{Diagnostic, Diagnostics, ElementDataRef, Severity};
+
+#[derive(serde::Serialize)]
+struct FileReport<'a> {
+    file: &'a PathBuf,
+    diagnostics: Vec<Diagnostic>,
+}
+
+pub fn run(files: &[PathBuf], json: bool) -> Result<ExitCode, String> {
+    let mut failed = 0;
+    let mut reports = vec!();
+
+    for path in files.iter() {
+        // An unreadable file fails, the others are still checked
+        let contents = match super::read_file(path) {
+            Ok(contents) => contents,
+            Err(message) => {
+                failed += 1;
+                if !json {
+                    println!("error: {}", message);
+                }
+                reports.push(FileReport { file: path, diagnostics: vec!(Diagnostic::error("io", message)) });
+                continue;
+            }
+        };
+        let mut diagnostics = Diagnostics::new();
+        // Consistency across sections, for data that parsed
+        let (findings, error) = match ElementDataRef::parse_with_diagnostics(&contents, &mut diagnostics) {
+            Ok(data) => (data.into_owned().validate(), None),
+            Err(e) => (vec!(), Some(e)),
+        };
+        let inconsistent = findings.iter().any(|d| d.severity == Severity::Error);
+
+        if let Some(e) = &error {
+            failed += 1;
+            if !json {
+                println!("error: {} is not a valid LAMDA datafile", path.display());
+                print!("{}", e.render(&contents));
+            }
+        } else if inconsistent {
+            failed += 1;
+            if !json {
+                println!("error: {} has inconsistent data", path.display());
+            }
+        } else if !json {
+            println!("{}: ok", path.display());
+        }
+        if !json {
+            for warning in diagnostics.warnings() {
+                println!("warning: {}:{}: {}", path.display(), warning.line, warning.message);
+            }
+            for finding in findings.iter() {
+                let severity = match finding.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                println!("{}: {}: {} [{}]", severity, path.display(), finding.message, finding.code);
+            }
+        }
+
+        let mut diagnostics = diagnostics.into_vec();
+        diagnostics.extend(error.map(|e| e.diagnostic()));
+        diagnostics.extend(findings);
+        reports.push(FileReport { file: path, diagnostics });
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports).map_err(|e| e.to_string())?);
+    }
+
+    match failed {
+        0 => Ok(ExitCode::SUCCESS),
+        n => {
+            if !json {
+                println!("{} of {} files failed validation", n, files.len());
+            }
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::iau::unit_string::{value_in, UnitError};
+use crate::lamda::CollisionPartnerId;
+use crate::radex::{Conditions, Geometry};
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Toml(toml::de::Error),
+    Yaml(serde_yaml::Error),
+    UnknownFormat {
+        path: PathBuf,
+    },
+    UnknownCollisionPartner {
+        name: String,
+    },
+    EmptyRange {
+        parameter: String,
+    },
+    Unit {
+        parameter: String,
+        error: UnitError,
+    },
+    UnsupportedOutput {
+        output: String,
+        feature: String,
+    },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, error } => write!(f, "cannot read `{}`: {}", path.display(), error),
+            ConfigError::Toml(e) => write!(f, "{}", e),
+            ConfigError::Yaml(e) => write!(f, "{}", e),
+            ConfigError::UnknownFormat { path } => {
+                write!(f, "cannot guess the format of `{}`, expected .toml, .yaml or .yml", path.display())
+            }
+            ConfigError::UnknownCollisionPartner { name } => write!(f, "unknown collision partner `{}`", name),
+            ConfigError::EmptyRange { parameter } => write!(f, "parameter `{}` has no values", parameter),
+            ConfigError::Unit { parameter, error } => write!(f, "parameter `{}`: {}", parameter, error),
+            ConfigError::UnsupportedOutput { output, feature } => {
+                write!(f, "`output.{}` needs ism built with the `{}` feature", output, feature)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { error, .. } => Some(error),
+            ConfigError::Toml(e) => Some(e),
+            ConfigError::Yaml(e) => Some(e),
+            ConfigError::Unit { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// A parameter is a single value, an explicit list or an evenly spaced range.
+// Plain numbers are in the unit of the parameter; a single value may also be
+// given with its unit, e.g. "1.3e4 cm-3" or "0.8 km/s".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParameterValues {
+    Single(f64),
+    Quantity(String),
+    List(Vec<f64>),
+    Range {
+        start: f64,
+        stop: f64,
+        num: usize,
+        #[serde(default)]
+        log: bool,
+    },
+}
+
+impl ParameterValues {
+    // Values in `unit`, the unit of the parameter
+    pub fn values_in(&self, unit: &str) -> Result<Vec<f64>, UnitError> {
+        Ok(match self {
+            ParameterValues::Single(x) => vec!(*x),
+            ParameterValues::Quantity(s) => vec!(value_in(s, unit)?),
+            ParameterValues::List(v) => v.clone(),
+            ParameterValues::Range { start, num: 1, .. } => vec!(*start),
+            ParameterValues::Range { start, stop, num, log } => {
+                let step = |i: usize| i as f64 / (*num as f64 - 1.0);
+                (0..*num)
+                    .map(|i| match log {
+                        true => 10f64.powf(start.log10() + step(i) * (stop.log10() - start.log10())),
+                        false => start + step(i) * (stop - start),
+                    })
+                    .collect()
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpeciesConfig {
+    // LAMDA datafile, relative to the configuration file
+    pub molfile: PathBuf,
+    pub name: Option<String>,
+}
+
+fn default_dv() -> ParameterValues {
+    ParameterValues::Single(1.0)
+}
+
+fn default_geometry() -> Geometry {
+    Geometry::Sphere
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PhysicalConditionsConfig {
+    // Kinetic temperature, K
+    pub tkin: ParameterValues,
+    // Column density, cm-2
+    pub cdmol: ParameterValues,
+    // Line width (FWHM), km s-1
+    #[serde(default = "default_dv")]
+    pub dv: ParameterValues,
+    // Collision partner densities, cm-3, keyed by partner name (`h2`, `p-h2`, `e`, ...)
+    pub densities: BTreeMap<String, ParameterValues>,
+    #[serde(default = "default_geometry")]
+    pub geometry: Geometry,
+}
+
+fn default_tbg() -> ParameterValues {
+    ParameterValues::Single(2.73)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackgroundConfig {
+    // Background radiation temperature, K
+    #[serde(default = "default_tbg")]
+    pub tbg: ParameterValues,
+}
+
+impl Default for BackgroundConfig {
+    fn default() -> Self {
+        Self { tbg: default_tbg() }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputConfig {
+    pub csv: Option<PathBuf>,
+    pub parquet: Option<PathBuf>,
+    // Frequency window of the reported lines, GHz
+    pub fmin: Option<f64>,
+    pub fmax: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelConfig {
+    pub species: SpeciesConfig,
+    pub conditions: PhysicalConditionsConfig,
+    #[serde(default)]
+    pub background: BackgroundConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+}
+
+impl ModelConfig {
+    pub fn from_toml_str(s: &str) -> Result<Self, ConfigError> {
+        toml::from_str::<Self>(s).map_err(ConfigError::Toml)?.checked()
+    }
+
+    pub fn from_yaml_str(s: &str) -> Result<Self, ConfigError> {
+        serde_yaml::from_str::<Self>(s).map_err(ConfigError::Yaml)?.checked()
+    }
+
+    // Reads a TOML or YAML configuration, chosen by the file extension. Relative
+    // paths inside the file are resolved against the directory of the file.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)
+            .map_err(|error| ConfigError::Io { path: path.to_path_buf(), error })?;
+
+        let mut config = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str(&s)?,
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&s)?,
+            _ => return Err(ConfigError::UnknownFormat { path: path.to_path_buf() }),
+        };
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        config.species.molfile = base.join(&config.species.molfile);
+        config.output.csv = config.output.csv.map(|p| base.join(p));
+        config.output.parquet = config.output.parquet.map(|p| base.join(p));
+
+        Ok(config)
+    }
+
+    // Also rejects outputs this build cannot write, before any model is solved
+    fn checked(self) -> Result<Self, ConfigError> {
+        if cfg!(not(feature = "parquet")) && self.output.parquet.is_some() {
+            return Err(ConfigError::UnsupportedOutput { output: String::from("parquet"), feature: String::from("parquet") });
+        }
+        self.grid().map(|_| self)
+    }
+
+    // Physical conditions of every model, the cartesian product of all parameters.
+    pub fn grid(&self) -> Result<Vec<Conditions>, ConfigError> {
+        let c = &self.conditions;
+        let mut grid = vec!(Conditions { geometry: c.geometry, densities: vec!(), ..Default::default() });
+
+        let mut expand = |parameter: &str, values: &ParameterValues, unit: &str, set: &dyn Fn(&mut Conditions, f64)| {
+            let values = values
+                .values_in(unit)
+                .map_err(|error| ConfigError::Unit { parameter: String::from(parameter), error })?;
+            if values.is_empty() {
+                return Err(ConfigError::EmptyRange { parameter: String::from(parameter) });
+            }
+            grid = grid
+                .iter()
+                .flat_map(|point| values.iter().map(move |&x| {
+                    let mut point = point.clone();
+                    set(&mut point, x);
+                    point
+                }))
+                .collect();
+            Ok(())
+        };
+
+        expand("tkin", &c.tkin, "K", &|p, x| p.tkin = x)?;
+        expand("tbg", &self.background.tbg, "K", &|p, x| p.tbg = x)?;
+        expand("cdmol", &c.cdmol, "cm-2", &|p, x| p.cdmol = x)?;
+        expand("dv", &c.dv, "km s-1", &|p, x| p.linewidth = x)?;
+        for (name, values) in c.densities.iter() {
+            let partner = CollisionPartnerId::from_name(name)
+                .ok_or_else(|| ConfigError::UnknownCollisionPartner { name: name.clone() })?;
+            expand(name, values, "cm-3", &move |p, x| p.densities.push((partner.clone(), x)))?;
+        }
+
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn toml_and_yaml_agree() {
+        let toml = r#"
+[species]
+molfile = "co.dat"
+
+[conditions]
+tkin = [10.0, 20.0]
+cdmol = { start = 1e13, stop = 1e15, num = 3, log = true }
+geometry = "lvg"
+
+[conditions.densities]
+h2 = 1e4
+
+[output]
+csv = "grid.csv"
+"#;
+        let yaml = r#"
+species:
+  molfile: co.dat
+conditions:
+  tkin: [10.0, 20.0]
+  cdmol: { start: 1.0e13, stop: 1.0e15, num: 3, log: true }
+  geometry: lvg
+  densities:
+    h2: 1.0e4
+output:
+  csv: grid.csv
+"#;
+
+        let config = ModelConfig::from_toml_str(toml).unwrap();
+        let grid = config.grid().unwrap();
+
+        assert_eq!(config, ModelConfig::from_yaml_str(yaml).unwrap());
+        assert_eq!(grid.len(), 6);
+        assert_eq!(grid[0].geometry, Geometry::LargeVelocityGradient);
+        assert_eq!(grid[0].densities, vec!((CollisionPartnerId::H2, 1e4)));
+        assert!((grid[1].cdmol - 1e14).abs() < 1e2, "Wrong log range value {}", grid[1].cdmol);
+    }
+
+    #[test]
+    fn unknown_partner() {
+        let s = "[species]\nmolfile = \"co.dat\"\n[conditions]\ntkin = 10\ncdmol = 1e13\n[conditions.densities]\nxx = 1\n";
+
+        match ModelConfig::from_toml_str(s) {
+            Err(ConfigError::UnknownCollisionPartner { name }) => assert_eq!(name, "xx"),
+            other => panic!("Wrong result for unknown partner: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn registered_partner() {
+        let s = "[species]\nmolfile = \"co.dat\"\n[conditions]\ntkin = 10\ncdmol = 1e13\n[conditions.densities]\nD2 = 1e3\n";
+        assert!(CollisionPartnerId::register(43, "D2"));
+        let grid = ModelConfig::from_toml_str(s).unwrap().grid().unwrap();
+
+        assert_eq!(grid[0].densities, vec!((CollisionPartnerId::Other(43, String::from("D2")), 1e3)));
+    }
+
+    #[test]
+    fn parquet_output() {
+        let s = "[species]\nmolfile = \"co.dat\"\n[conditions]\ntkin = 10\ncdmol = 1e13\n[conditions.densities]\nh2 = 1e4\n[output]\nparquet = \"grid.parquet\"\n";
+
+        let result = ModelConfig::from_toml_str(s);
+
+        #[cfg(feature = "parquet")]
+        assert!(result.is_ok(), "Wrong result for parquet output: {:?}", result);
+        #[cfg(not(feature = "parquet"))]
+        match result {
+            Err(ConfigError::UnsupportedOutput { output, .. }) => assert_eq!(output, "parquet"),
+            other => panic!("Wrong result for parquet output without the feature: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn values_with_units() {
+        let s = "[species]\nmolfile = \"co.dat\"\n[conditions]\ntkin = \"20 K\"\ncdmol = 1e13\ndv = \"800 m/s\"\n\
+                 [conditions.densities]\nh2 = \"1e10 m-3\"\n";
+        let grid = ModelConfig::from_toml_str(s).unwrap().grid().unwrap();
+
+        assert!((grid[0].linewidth - 0.8).abs() < 1e-12, "Wrong result for line width {}", grid[0].linewidth);
+        assert!((grid[0].densities[0].1 - 1e4).abs() < 1e-6, "Wrong result for density");
+
+        match ModelConfig::from_toml_str(&s.replace("\"20 K\"", "\"20 km/s\"")) {
+            Err(ConfigError::Unit { parameter, error: UnitError::WrongDimension { .. } }) => assert_eq!(parameter, "tkin"),
+            other => panic!("Wrong result for temperature in km/s: {:?}", other),
+        }
+    }
+}
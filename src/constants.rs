@@ -0,0 +1,7 @@
+// Physical constants in CGS units (CODATA 2018).
+pub const SPEED_OF_LIGHT: f64 = 2.997_924_58e10;
+pub const PLANCK: f64 = 6.626_070_15e-27;
+pub const BOLTZMANN: f64 = 1.380_649e-16;
+
+// Conversion of a level energy in cm-1 to kelvins, h c / k.
+pub const WAVENUMBER_TO_KELVIN: f64 = PLANCK * SPEED_OF_LIGHT / BOLTZMANN;
@@ -0,0 +1,166 @@
+use std::error::Error;
+
+#[cfg(feature = "vamdc")]
+use crate::catalog::vamdc::VamdcError;
+#[cfg(feature = "config")]
+use crate::config::ConfigError;
+#[cfg(all(feature = "fetch", not(target_arch = "wasm32")))]
+use crate::fetch::FetchError;
+use crate::iau::unit_string::UnitError;
+use crate::lamda::{DatabaseError, MergeError, ParseError, ReadError};
+use crate::model::ModelError;
+use crate::radex::SolverError;
+use crate::spectrum::SpectrumError;
+
+// Error of any fallible call of the crate, for applications that hold a
+// single error type. Every variant displays as the error it wraps and
+// forwards its `source()`, so the chain is the same as with the wrapped error.
+#[derive(Debug)]
+pub enum IsmError {
+    Parse(ParseError),
+    Merge(MergeError),
+    Io(std::io::Error),
+    Database(DatabaseError),
+    Solver(SolverError),
+    Model(ModelError),
+    Unit(UnitError),
+    Spectrum(SpectrumError),
+    #[cfg(feature = "config")]
+    Config(ConfigError),
+    #[cfg(all(feature = "fetch", not(target_arch = "wasm32")))]
+    Fetch(FetchError),
+    #[cfg(feature = "vamdc")]
+    Vamdc(VamdcError),
+}
+
+impl IsmError {
+    // Stable identifier of the kind of error, e.g. for exit codes or logs
+    pub fn code(&self) -> &'static str {
+        match self {
+            IsmError::Parse(_) => "parse",
+            IsmError::Merge(_) => "merge",
+            IsmError::Io(_) => "io",
+            IsmError::Database(DatabaseError::UnknownSpecies { .. }) => "unknown-species",
+            IsmError::Database(DatabaseError::AmbiguousSpecies { .. }) => "ambiguous-species",
+            IsmError::Database(DatabaseError::Cancelled) => "cancelled",
+            IsmError::Database(DatabaseError::Parse { .. }) => "parse",
+            IsmError::Database(DatabaseError::Io { .. }) => "io",
+            IsmError::Solver(SolverError::NotConverged { .. }) => "not-converged",
+            IsmError::Solver(SolverError::Cancelled) => "cancelled",
+            IsmError::Solver(_) => "solver",
+            IsmError::Model(_) => "model",
+            IsmError::Unit(_) => "unit",
+            IsmError::Spectrum(_) => "spectrum",
+            #[cfg(feature = "config")]
+            IsmError::Config(_) => "config",
+            #[cfg(all(feature = "fetch", not(target_arch = "wasm32")))]
+            IsmError::Fetch(FetchError::ChecksumMismatch { .. }) => "checksum-mismatch",
+            #[cfg(all(feature = "fetch", not(target_arch = "wasm32")))]
+            IsmError::Fetch(_) => "fetch",
+            #[cfg(feature = "vamdc")]
+            IsmError::Vamdc(_) => "vamdc",
+        }
+    }
+
+    fn inner(&self) -> &(dyn Error + 'static) {
+        match self {
+            IsmError::Parse(e) => e,
+            IsmError::Merge(e) => e,
+            IsmError::Io(e) => e,
+            IsmError::Database(e) => e,
+            IsmError::Solver(e) => e,
+            IsmError::Model(e) => e,
+            IsmError::Unit(e) => e,
+            IsmError::Spectrum(e) => e,
+            #[cfg(feature = "config")]
+            IsmError::Config(e) => e,
+            #[cfg(all(feature = "fetch", not(target_arch = "wasm32")))]
+            IsmError::Fetch(e) => e,
+            #[cfg(feature = "vamdc")]
+            IsmError::Vamdc(e) => e,
+        }
+    }
+}
+
+impl std::fmt::Display for IsmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.inner())
+    }
+}
+
+impl Error for IsmError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.inner().source()
+    }
+}
+
+macro_rules! from_error {
+    ($($(#[$cfg:meta])* $error:ty => $variant:ident,)*) => {
+        $(
+            $(#[$cfg])*
+            impl From<$error> for IsmError {
+                fn from(e: $error) -> Self {
+                    IsmError::$variant(e)
+                }
+            }
+        )*
+    };
+}
+
+from_error! {
+    ParseError => Parse,
+    MergeError => Merge,
+    std::io::Error => Io,
+    DatabaseError => Database,
+    SolverError => Solver,
+    ModelError => Model,
+    UnitError => Unit,
+    SpectrumError => Spectrum,
+    #[cfg(feature = "config")]
+    ConfigError => Config,
+    #[cfg(all(feature = "fetch", not(target_arch = "wasm32")))]
+    FetchError => Fetch,
+    #[cfg(feature = "vamdc")]
+    VamdcError => Vamdc,
+}
+
+// A datafile read from a stream fails like one read into memory first
+impl From<ReadError> for IsmError {
+    fn from(e: ReadError) -> Self {
+        match e {
+            ReadError::Io(e) => IsmError::Io(e),
+            ReadError::Parse(e) => IsmError::Parse(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::ElementData;
+
+    fn read(path: &std::path::Path) -> Result<ElementData, IsmError> {
+        Ok(std::fs::read_to_string(path)?.parse::<ElementData>()?)
+    }
+
+    #[test]
+    fn wrap_errors() {
+        let missing = read(&std::env::temp_dir().join("ism-no-such-datafile.dat")).unwrap_err();
+        assert_eq!(missing.code(), "io");
+
+        let database = IsmError::from(DatabaseError::Io {
+            path: "co.dat".into(),
+            error: std::io::Error::from(std::io::ErrorKind::NotFound),
+        });
+        assert_eq!(database.code(), "io");
+        assert!(database.to_string().starts_with("cannot read `co.dat`"), "Wrong result for display");
+        assert!(
+            database.source().is_some_and(|e| e.is::<std::io::Error>()),
+            "Wrong result for source of a database error"
+        );
+
+        assert_eq!(IsmError::from(SolverError::NotConverged { iterations: 10 }).code(), "not-converged");
+        assert!(IsmError::from(SolverError::SingularMatrix).source().is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use super::{datafile_name, io_error, sha256_hex, FetchError, Fetcher};
+use crate::lamda::{Decoding, ElementData};
+
+// Datafile of the cache directory. The download time is the modification time
+// of the cached copy, which `store` replaces on every download.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub species: String,
+    pub file: String,
+    pub downloaded: Option<SystemTime>,
+    pub checksum: String,
+}
+
+impl Fetcher {
+    // Datafiles of the manifest, with the time their cached copy was
+    // downloaded; `None` for files that are not cached.
+    pub fn entries(&self) -> Vec<CacheEntry> {
+        self.manifest
+            .entries
+            .iter()
+            .map(|(file, checksum)| CacheEntry {
+                species: String::from(file.trim_end_matches(".dat")),
+                file: file.clone(),
+                downloaded: std::fs::metadata(self.cache_dir.join(file)).and_then(|m| m.modified()).ok(),
+                checksum: checksum.clone(),
+            })
+            .collect()
+    }
+
+    // Path of the datafile of `species`, e.g. `co` or `hco+@xpol`. A species
+    // that is not in the manifest yet is downloaded, recorded and the manifest
+    // saved, so later runs read the cached copy.
+    pub fn path(&mut self, species: &str) -> Result<PathBuf, FetchError> {
+        let file = datafile_name(species)?;
+        if self.manifest.checksum(&file).is_some() {
+            return self.fetch(&file);
+        }
+
+        let path = self.track(&file)?;
+        self.save_manifest()?;
+
+        Ok(path)
+    }
+
+    pub fn get(&mut self, species: &str) -> Result<ElementData, FetchError> {
+        let path = self.path(species)?;
+        let bytes = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+        let file = datafile_name(species)?;
+
+        ElementData::from_bytes(&bytes, Decoding::default()).map_err(|error| FetchError::Parse { file, error })
+    }
+
+    // Downloads again the datafiles cached longer than `max_age` ago, or not
+    // cached at all, records their checksums and saves the manifest. Returns
+    // the files whose data changed upstream.
+    pub fn refresh(&mut self, max_age: Duration) -> Result<Vec<String>, FetchError> {
+        let now = SystemTime::now();
+        let stale = self
+            .entries()
+            .into_iter()
+            .filter(|e| e.downloaded.is_none_or(|t| now.duration_since(t).unwrap_or_default() > max_age))
+            .collect::<Vec<_>>();
+
+        let mut changed = vec!();
+        for entry in stale {
+            let bytes = self.download(&entry.file)?;
+            self.store(&entry.file, &bytes)?;
+            let checksum = sha256_hex(&bytes);
+            if checksum != entry.checksum {
+                self.manifest.insert(&entry.file, checksum);
+                changed.push(entry.file);
+            }
+        }
+        self.save_manifest()?;
+
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::fetch::Manifest;
+
+    #[test]
+    fn cached_datafiles() {
+        let dir = std::env::temp_dir().join(format!("ism-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let data = include_str!("../../data/oatom.dat");
+        std::fs::write(dir.join("oatom.dat"), data).unwrap();
+
+        // Nothing listens there, so any download attempt fails
+        let mut fetcher = Fetcher::new(&dir, Manifest::from_dir(&dir).unwrap()).with_base_url("http://127.0.0.1:9");
+        let entries = fetcher.entries();
+        assert_eq!((entries[0].species.as_str(), entries[0].checksum.clone()), ("oatom", sha256_hex(data.as_bytes())));
+        assert!(entries[0].downloaded.is_some(), "Missing download time of a cached file");
+
+        assert_eq!(fetcher.get("OATOM.dat").unwrap().name(), "O");
+        assert_eq!(fetcher.refresh(Duration::MAX).unwrap(), Vec::<String>::new());
+        assert!(matches!(fetcher.refresh(Duration::ZERO), Err(FetchError::Http { .. })));
+        assert!(matches!(fetcher.path("co"), Err(FetchError::Http { .. })));
+        assert!(matches!(fetcher.path("../oatom"), Err(FetchError::InvalidName { .. })));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
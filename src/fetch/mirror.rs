@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+
+use super::{agent, io_error, sha256_hex, FetchError, Fetcher, Manifest, DEFAULT_TIMEOUT};
+use crate::progress::{Progress, ProgressCallback};
+
+// Files kept next to the datafiles of a mirror: the checksums of every file,
+// and the HTTP validators (ETag or Last-Modified) used to skip unchanged files.
+pub const MANIFEST_FILE: &str = "SHA256SUMS";
+const VALIDATORS_FILE: &str = ".validators";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: usize,
+    // Files of the mirror that are no longer in the archive; they are kept
+    pub removed: Vec<String>,
+}
+
+fn percent_decode(s: &str) -> String {
+    let mut bytes = vec!();
+    let mut rest = s.as_bytes();
+
+    while let Some((&b, tail)) = rest.split_first() {
+        let hex = tail.get(..2).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (b, hex) {
+            (b'%', Some(decoded)) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// Datafile names linked from an HTML directory listing.
+pub(crate) fn parse_index(html: &str) -> Vec<String> {
+    let mut files = html
+        .split("href=\"")
+        .skip(1)
+        .filter_map(|s| s.split('"').next())
+        .filter(|href| href.ends_with(".dat") && !href.contains(['/', '?']))
+        .map(percent_decode)
+        .collect::<Vec<_>>();
+    files.sort();
+    files.dedup();
+
+    files
+}
+
+struct Download {
+    bytes: Vec<u8>,
+    validator: Option<String>,
+}
+
+fn read_validators(dir: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(dir.join(VALIDATORS_FILE))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.split_once('\t'))
+        .map(|(file, validator)| (String::from(file), String::from(validator)))
+        .collect()
+}
+
+fn write_validators(dir: &Path, validators: &BTreeMap<String, String>) -> Result<(), FetchError> {
+    let s = validators.iter().map(|(file, validator)| format!("{}\t{}\n", file, validator)).collect::<String>();
+    let path = dir.join(VALIDATORS_FILE);
+
+    std::fs::write(&path, s).map_err(|e| io_error(&path, e))
+}
+
+impl Fetcher {
+    // Fetcher over a local mirror, with the manifest the mirror was last synced with.
+    pub fn open_mirror<P: AsRef<Path>>(dir: P) -> Result<Self, FetchError> {
+        let dir = dir.as_ref();
+        let manifest = match std::fs::read_to_string(dir.join(MANIFEST_FILE)) {
+            Ok(s) => Manifest::parse(&s)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Manifest::default(),
+            Err(e) => return Err(io_error(&dir.join(MANIFEST_FILE), e)),
+        };
+
+        Ok(Self::new(dir, manifest))
+    }
+
+    pub fn save_manifest(&self) -> Result<(), FetchError> {
+        let path = self.cache_dir.join(MANIFEST_FILE);
+        std::fs::write(&path, self.manifest.to_string()).map_err(|e| io_error(&path, e))
+    }
+
+    // Datafiles listed in the archive directory.
+    pub fn list_remote(&self) -> Result<Vec<String>, FetchError> {
+        let url = format!("{}/", self.base_url.trim_end_matches('/'));
+        let http_error = |note: String| FetchError::Http { url: url.clone(), note };
+        let html = agent(DEFAULT_TIMEOUT)
+            .get(&url)
+            .call()
+            .map_err(|e| http_error(e.to_string()))?
+            .into_string()
+            .map_err(|e| http_error(e.to_string()))?;
+
+        Ok(parse_index(&html))
+    }
+
+    // `None` when `file` has not changed since `validator` was received.
+    fn download_if_changed(&self, file: &str, validator: Option<&str>) -> Result<Option<Download>, FetchError> {
+        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), file);
+        let http_error = |note: String| FetchError::Http { url: url.clone(), note };
+
+        let request = agent(DEFAULT_TIMEOUT).get(&url);
+        let request = match validator {
+            Some(etag) if etag.starts_with('"') || etag.starts_with("W/") => request.set("If-None-Match", etag),
+            Some(date) => request.set("If-Modified-Since", date),
+            None => request,
+        };
+        let response = request.call().map_err(|e| http_error(e.to_string()))?;
+        if response.status() == 304 {
+            return Ok(None);
+        }
+
+        let validator = response.header("ETag").or(response.header("Last-Modified")).map(String::from);
+        let mut bytes = vec!();
+        response.into_reader().read_to_end(&mut bytes).map_err(|e| http_error(e.to_string()))?;
+
+        Ok(Some(Download { bytes, validator }))
+    }
+
+    // Mirrors every datafile of the archive into the cache directory. Files are
+    // requested conditionally, so only new and changed files are downloaded.
+    // The manifest is saved afterwards; open the mirror with
+    // `LamdaDatabase::open` to index it.
+    pub fn sync(&mut self, progress: ProgressCallback<'_, str>) -> Result<SyncReport, FetchError> {
+        let remote = self.list_remote()?;
+        let mut validators = read_validators(&self.cache_dir);
+        let mut report = SyncReport::default();
+
+        for (i, file) in remote.iter().enumerate() {
+            let cached = self.cache_dir.join(file).exists();
+            let validator = validators.get(file).filter(|_| cached).map(String::as_str);
+
+            match self.download_if_changed(file, validator)? {
+                None => report.unchanged += 1,
+                Some(Download { bytes, validator }) => {
+                    let checksum = sha256_hex(&bytes);
+                    match self.manifest.checksum(file) {
+                        Some(old) if old == checksum && cached => report.unchanged += 1,
+                        Some(_) => report.updated.push(file.clone()),
+                        None => report.added.push(file.clone()),
+                    }
+                    self.store(file, &bytes)?;
+                    self.manifest.insert(file, checksum);
+                    match validator {
+                        Some(validator) => validators.insert(file.clone(), validator),
+                        None => validators.remove(file),
+                    };
+                }
+            }
+            progress(Progress { completed: i + 1, total: remote.len(), current: file.as_str() });
+        }
+
+        report.removed = self
+            .manifest
+            .files()
+            .filter(|f| remote.binary_search_by(|r| r.as_str().cmp(f)).is_err())
+            .map(String::from)
+            .collect();
+
+        write_validators(&self.cache_dir, &validators)?;
+        self.save_manifest()?;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn archive_index() {
+        let html = r#"<tr><td><a href="?C=N;O=D">Name</a></td></tr>
+<tr><td><a href="co.dat">co.dat</a></td><td>2020-06-12 10:03</td></tr>
+<tr><td><a href="hco%2B@xpol.dat">hco+@xpol.dat</a></td></tr>
+<tr><td><a href="README">README</a></td></tr>
+<tr><td><a href="/~moldata/">Parent Directory</a></td></tr>
+<tr><td><a href="co.dat">co.dat</a></td></tr>"#;
+
+        assert_eq!(parse_index(html), vec!("co.dat", "hco+@xpol.dat"));
+    }
+
+    #[test]
+    fn open_empty_mirror() {
+        let dir = std::env::temp_dir().join(format!("ism-mirror-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut fetcher = Fetcher::open_mirror(&dir).unwrap();
+        fetcher.manifest.insert("co.dat", sha256_hex(b"co"));
+        fetcher.save_manifest().unwrap();
+
+        assert_eq!(Fetcher::open_mirror(&dir).unwrap().manifest(), fetcher.manifest());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use crate::lamda::{parse_overview, Decoding, ElementData, MoleculeEntry, ParseError};
+
+mod cache;
+mod mirror;
+
+pub use cache::CacheEntry;
+pub use mirror::{SyncReport, MANIFEST_FILE};
+
+pub const LAMDA_DATAFILES_URL: &str = "https://home.strw.leidenuniv.nl/~moldata/datafiles/";
+// Page listing the molecules of the database with their datafiles
+pub const LAMDA_MOLECULES_URL: &str = "https://home.strw.leidenuniv.nl/~moldata/";
+
+// Time allowed for a whole download, and for connecting within it
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub enum FetchError {
+    Http {
+        url: String,
+        note: String,
+    },
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    UnknownFile {
+        file: String,
+    },
+    InvalidName {
+        name: String,
+    },
+    ChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+    Manifest {
+        line_number: usize,
+        line: String,
+    },
+    Parse {
+        file: String,
+        error: ParseError,
+    },
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::Http { url, note } => write!(f, "cannot download `{}`: {}", url, note),
+            FetchError::Io { path, error } => write!(f, "cannot access `{}`: {}", path.display(), error),
+            FetchError::UnknownFile { file } => write!(f, "`{}` is not in the manifest", file),
+            FetchError::InvalidName { name } => write!(f, "`{}` is not a datafile name", name),
+            FetchError::ChecksumMismatch { file, expected, actual } => {
+                write!(f, "checksum of `{}` is {}, but the manifest expects {}", file, actual, expected)
+            }
+            FetchError::Manifest { line_number, line } => {
+                write!(f, "line {} of the manifest is not `<sha256>  <file>`: {}", line_number + 1, line)
+            }
+            FetchError::Parse { file, error } => write!(f, "cannot parse `{}`: {}", file, error),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FetchError::Io { error, .. } => Some(error),
+            FetchError::Parse { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> FetchError {
+    FetchError::Io { path: path.to_path_buf(), error }
+}
+
+fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout_connect(CONNECT_TIMEOUT.min(timeout)).timeout(timeout).build()
+}
+
+// Body of `url`, with the status of a failed request in the error
+fn get(url: &str, timeout: Duration) -> Result<Vec<u8>, FetchError> {
+    let http_error = |note: String| FetchError::Http { url: String::from(url), note };
+
+    let response = agent(timeout).get(url).call().map_err(|e| match e {
+        ureq::Error::Status(404, _) => http_error(String::from("no such file on the server (HTTP 404)")),
+        ureq::Error::Status(code, response) => http_error(format!("HTTP {} {}", code, response.status_text())),
+        ureq::Error::Transport(t) => http_error(t.to_string()),
+    })?;
+
+    let mut bytes = vec!();
+    response.into_reader().read_to_end(&mut bytes).map_err(|e| http_error(e.to_string()))?;
+
+    Ok(bytes)
+}
+
+// File names are joined to the cache directory, so they may not leave it
+fn check_file_name(file: &str) -> Result<(), FetchError> {
+    match file.is_empty() || file.contains(['/', '\\']) || file.contains("..") {
+        true => Err(FetchError::InvalidName { name: String::from(file) }),
+        false => Ok(()),
+    }
+}
+
+// Writes `bytes` to `dir/file` through a temporary file, so an interrupted
+// write never leaves a truncated datafile behind
+fn store(dir: &Path, file: &str, bytes: &[u8]) -> Result<PathBuf, FetchError> {
+    check_file_name(file)?;
+    let path = dir.join(file);
+    let partial = dir.join(format!(".{}.part", file));
+
+    std::fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+    std::fs::write(&partial, bytes).map_err(|e| io_error(&partial, e))?;
+    std::fs::rename(&partial, &path).map_err(|e| io_error(&path, e))?;
+
+    Ok(path)
+}
+
+// LAMDA file name of a molecule, e.g. `co.dat` for `CO`
+fn datafile_name(molecule: &str) -> Result<String, FetchError> {
+    let name = molecule.trim().to_lowercase();
+    check_file_name(&name)?;
+
+    match name.ends_with(".dat") {
+        true => Ok(name),
+        false => Ok(format!("{}.dat", name)),
+    }
+}
+
+// Datafile of `molecule` downloaded from the LAMDA website and parsed, e.g.
+// `fetch("co")` or `fetch("hco+@xpol")`. Nothing is cached; see `Fetcher`
+// for verified local copies.
+pub fn fetch(molecule: &str) -> Result<ElementData, FetchError> {
+    fetch_with_timeout(molecule, DEFAULT_TIMEOUT)
+}
+
+pub fn fetch_with_timeout(molecule: &str, timeout: Duration) -> Result<ElementData, FetchError> {
+    let file = datafile_name(molecule)?;
+    let bytes = get(&format!("{}{}", LAMDA_DATAFILES_URL, file), timeout)?;
+
+    ElementData::from_bytes(&bytes, Decoding::default()).map_err(|error| FetchError::Parse { file, error })
+}
+
+// Datafiles the LAMDA website lists, to see what is available before
+// downloading any of them
+pub fn fetch_overview() -> Result<Vec<MoleculeEntry>, FetchError> {
+    let bytes = get(LAMDA_MOLECULES_URL, DEFAULT_TIMEOUT)?;
+
+    Ok(parse_overview(&String::from_utf8_lossy(&bytes)))
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Known datafiles with their SHA-256 checksums. The text form has one
+// `<checksum>  <file>` line per file, as written and checked by `sha256sum`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    entries: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub fn parse(s: &str) -> Result<Self, FetchError> {
+        let mut entries = BTreeMap::new();
+
+        for (line_number, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let (checksum, file) = line
+                .split_once(char::is_whitespace)
+                .map(|(c, f)| (c, f.trim_start().trim_start_matches('*')))
+                .filter(|(c, f)| c.len() == 64 && c.chars().all(|c| c.is_ascii_hexdigit()) && !f.is_empty())
+                .ok_or_else(|| FetchError::Manifest { line_number, line: String::from(line) })?;
+            entries.insert(String::from(file), checksum.to_ascii_lowercase());
+        }
+
+        Ok(Self { entries })
+    }
+
+    // Checksums of the `.dat` files of `dir`.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, FetchError> {
+        let dir = dir.as_ref();
+
+        let mut manifest = Self::default();
+        for entry in std::fs::read_dir(dir).map_err(|e| io_error(dir, e))? {
+            let path = entry.map_err(|e| io_error(dir, e))?.path();
+            if let (Some("dat"), Some(file)) = (path.extension().and_then(|e| e.to_str()), path.file_name()) {
+                let bytes = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+                manifest.insert(&file.to_string_lossy(), sha256_hex(&bytes));
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn insert(&mut self, file: &str, checksum: String) {
+        self.entries.insert(String::from(file), checksum);
+    }
+
+    pub fn checksum(&self, file: &str) -> Option<&str> {
+        self.entries.get(file).map(String::as_str)
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn verify(&self, file: &str, bytes: &[u8]) -> Result<(), FetchError> {
+        let expected = self.checksum(file).ok_or_else(|| FetchError::UnknownFile { file: String::from(file) })?;
+        let actual = sha256_hex(bytes);
+
+        match actual == expected {
+            true => Ok(()),
+            false => Err(FetchError::ChecksumMismatch { file: String::from(file), expected: String::from(expected), actual }),
+        }
+    }
+}
+
+impl std::fmt::Display for Manifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (file, checksum) in self.entries.iter() {
+            writeln!(f, "{}  {}", checksum, file)?;
+        }
+        Ok(())
+    }
+}
+
+// Downloads datafiles into a cache directory and checks every file against
+// the manifest, so cached data is known to be what was recorded.
+#[derive(Debug, Clone)]
+pub struct Fetcher {
+    base_url: String,
+    cache_dir: PathBuf,
+    manifest: Manifest,
+}
+
+impl Fetcher {
+    pub fn new<P: Into<PathBuf>>(cache_dir: P, manifest: Manifest) -> Self {
+        Self { base_url: String::from(LAMDA_DATAFILES_URL), cache_dir: cache_dir.into(), manifest }
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = String::from(base_url);
+        self
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    fn download(&self, file: &str) -> Result<Vec<u8>, FetchError> {
+        get(&format!("{}/{}", self.base_url.trim_end_matches('/'), file), DEFAULT_TIMEOUT)
+    }
+
+    fn store(&self, file: &str, bytes: &[u8]) -> Result<PathBuf, FetchError> {
+        store(&self.cache_dir, file, bytes)
+    }
+
+    // Path of the cached `file`. The file is downloaded when it is missing from
+    // the cache or does not match the manifest, and rejected when the
+    // download does not match either.
+    pub fn fetch(&self, file: &str) -> Result<PathBuf, FetchError> {
+        check_file_name(file)?;
+        if self.manifest.checksum(file).is_none() {
+            return Err(FetchError::UnknownFile { file: String::from(file) });
+        }
+
+        let path = self.cache_dir.join(file);
+        if let Ok(bytes) = std::fs::read(&path) {
+            if self.manifest.verify(file, &bytes).is_ok() {
+                return Ok(path);
+            }
+        }
+
+        let bytes = self.download(file)?;
+        self.manifest.verify(file, &bytes)?;
+        self.store(file, &bytes)
+    }
+
+    // Downloads a file that is not in the manifest yet and records its checksum.
+    pub fn track(&mut self, file: &str) -> Result<PathBuf, FetchError> {
+        let bytes = self.download(file)?;
+        self.manifest.insert(file, sha256_hex(&bytes));
+        self.store(file, &bytes)
+    }
+
+    // Manifest files whose upstream contents no longer match their checksum.
+    pub fn changed(&self) -> Result<Vec<String>, FetchError> {
+        let mut changed = vec!();
+        for file in self.manifest.files() {
+            if self.manifest.verify(file, &self.download(file)?).is_err() {
+                changed.push(String::from(file));
+            }
+        }
+
+        Ok(changed)
+    }
+
+    // Downloads every manifest file again, replaces the cached copies of those
+    // that changed upstream and records their new checksums.
+    pub fn refresh_changed(&mut self) -> Result<Vec<String>, FetchError> {
+        let mut changed = vec!();
+        for file in self.manifest.files().map(String::from).collect::<Vec<_>>() {
+            let bytes = self.download(&file)?;
+            if self.manifest.verify(&file, &bytes).is_err() {
+                self.store(&file, &bytes)?;
+                self.manifest.insert(&file, sha256_hex(&bytes));
+                changed.push(file);
+            }
+        }
+
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn manifest_round_trip() {
+        let s = format!("{}  co.dat\n{} *hco+@xpol.dat\n", sha256_hex(b"co"), sha256_hex(b"hco+"));
+        let manifest = Manifest::parse(&s).unwrap();
+
+        assert_eq!(manifest.files().collect::<Vec<_>>(), vec!("co.dat", "hco+@xpol.dat"));
+        assert_eq!(Manifest::parse(&manifest.to_string()).unwrap(), manifest);
+        assert!(manifest.verify("co.dat", b"co").is_ok());
+        assert!(matches!(manifest.verify("co.dat", b"c0"), Err(FetchError::ChecksumMismatch { .. })));
+        assert!(matches!(Manifest::parse("abc co.dat"), Err(FetchError::Manifest { line_number: 0, .. })));
+    }
+
+    #[test]
+    fn molecule_file_names() {
+        assert_eq!(datafile_name("CO").unwrap(), "co.dat");
+        assert_eq!(datafile_name(" hco+@xpol.dat").unwrap(), "hco+@xpol.dat");
+        for name in ["../co", "data/co", "..\\co", ""] {
+            assert!(matches!(datafile_name(name), Err(FetchError::InvalidName { .. })), "Wrong result for `{}`", name);
+        }
+
+        let error = FetchError::Parse { file: String::from("co.dat"), error: ParseError::end_of_input(3) };
+        assert!(std::error::Error::source(&error).is_some(), "Missing source for `{}`", error);
+    }
+
+    #[test]
+    fn fetch_verified_cache() {
+        let dir = std::env::temp_dir().join(format!("ism-fetch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("co.dat"), "co").unwrap();
+
+        let manifest = Manifest::from_dir(&dir).unwrap();
+        // Nothing listens there, so any download attempt fails
+        let fetcher = Fetcher::new(&dir, manifest).with_base_url("http://127.0.0.1:9");
+
+        assert_eq!(fetcher.fetch("co.dat").unwrap(), dir.join("co.dat"));
+        assert!(matches!(fetcher.fetch("cs.dat"), Err(FetchError::UnknownFile { .. })));
+
+        std::fs::write(dir.join("co.dat"), "tampered").unwrap();
+        assert!(matches!(fetcher.fetch("co.dat"), Err(FetchError::Http { .. })));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
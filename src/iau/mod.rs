@@ -12,12 +12,16 @@ uom::system! {
     }
 }
 
+#[cfg(feature = "std")]
+pub mod unit_string;
+
 pub mod quantities {
     IAUQ!(crate::iau);
 }
 
+// Only the storage types enabled by the `f32` and `f64` features are generated.
 uom::storage_types! {
-    pub types: All;
+    pub types: f32, f64;
 
     IAUQ!(crate::iau, V);
 }
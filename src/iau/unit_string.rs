@@ -0,0 +1,489 @@
+use core::marker::PhantomData;
+
+// Unit strings as written by astropy and the FITS standard, e.g. "km s-1",
+// "km/s" or "erg s-1 cm-2 Hz-1 sr-1". Units are reduced to a scale in CGS
+// units and kelvin, so any two strings of the same dimension convert into
+// each other.
+
+const AU: f64 = 1.495_978_707e13;
+const PARSEC: f64 = 3.085_677_581_491_367e18;
+const SOLAR_MASS: f64 = 1.988_5e33;
+const DAY: f64 = 86_400.0;
+const JULIAN_YEAR: f64 = 365.25 * DAY;
+const DEGREE: f64 = core::f64::consts::PI / 180.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    UnknownUnit {
+        unit: String,
+        symbol: String,
+    },
+    Syntax {
+        unit: String,
+        note: String,
+    },
+    Incompatible {
+        from: String,
+        to: String,
+    },
+    MissingNumber {
+        input: String,
+    },
+    WrongDimension {
+        input: String,
+        found: String,
+        expected: String,
+    },
+}
+
+impl std::fmt::Display for UnitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnitError::UnknownUnit { unit, symbol } => write!(f, "unknown unit `{}` in `{}`", symbol, unit),
+            UnitError::Syntax { unit, note } => write!(f, "cannot read unit `{}`: {}", unit, note),
+            UnitError::Incompatible { from, to } => write!(f, "`{}` cannot be converted to `{}`", from, to),
+            UnitError::MissingNumber { input } => write!(f, "`{}` does not start with a number", input),
+            UnitError::WrongDimension { input, found, expected } => {
+                write!(f, "`{}` is {}, but {} is expected", input, found, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+// Exponents of the base dimensions. Angles are dimensionless, as in FITS.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Dimension {
+    pub length: i32,
+    pub mass: i32,
+    pub time: i32,
+    pub temperature: i32,
+}
+
+impl Dimension {
+    const fn new(length: i32, mass: i32, time: i32, temperature: i32) -> Self {
+        Self { length, mass, time, temperature }
+    }
+
+    // Name of the dimension with its article, e.g. "a velocity"
+    fn describe(&self) -> String {
+        let name = match (self.length, self.mass, self.time, self.temperature) {
+            (0, 0, 0, 0) => "dimensionless",
+            (1, 0, 0, 0) => "a length",
+            (0, 1, 0, 0) => "a mass",
+            (0, 0, 1, 0) => "a time",
+            (0, 0, 0, 1) => "a temperature",
+            (1, 0, -1, 0) => "a velocity",
+            (0, 0, -1, 0) => "a frequency",
+            (-2, 0, 0, 0) => "a column density",
+            (-3, 0, 0, 0) => "a number density",
+            (-3, 1, 0, 0) => "a mass density",
+            (2, 1, -2, 0) => "an energy",
+            (2, 1, -3, 0) => "a power",
+            _ => return format!("a quantity in {}", UnitExpression { scale: 1.0, dimension: *self }),
+        };
+        String::from(name)
+    }
+
+    fn scaled(self, n: i32) -> Self {
+        Self::new(self.length * n, self.mass * n, self.time * n, self.temperature * n)
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.length + other.length,
+            self.mass + other.mass,
+            self.time + other.time,
+            self.temperature + other.temperature,
+        )
+    }
+}
+
+const NONE: Dimension = Dimension::new(0, 0, 0, 0);
+const LENGTH: Dimension = Dimension::new(1, 0, 0, 0);
+const MASS: Dimension = Dimension::new(0, 1, 0, 0);
+const TIME: Dimension = Dimension::new(0, 0, 1, 0);
+const ENERGY: Dimension = Dimension::new(2, 1, -2, 0);
+const POWER: Dimension = Dimension::new(2, 1, -3, 0);
+
+// (symbol, size in CGS, dimension, takes SI prefixes)
+const UNITS: [(&str, f64, Dimension, bool); 36] = [
+    ("m", 1e2, LENGTH, true),
+    ("g", 1.0, MASS, true),
+    ("s", 1.0, TIME, true),
+    ("K", 1.0, Dimension::new(0, 0, 0, 1), true),
+    ("Hz", 1.0, Dimension::new(0, 0, -1, 0), true),
+    ("N", 1e5, Dimension::new(1, 1, -2, 0), true),
+    ("Pa", 1e1, Dimension::new(-1, 1, -2, 0), true),
+    ("J", 1e7, ENERGY, true),
+    ("W", 1e7, POWER, true),
+    ("eV", 1.602_176_634e-12, ENERGY, true),
+    ("Jy", 1e-23, Dimension::new(0, 1, -2, 0), true),
+    ("erg", 1.0, ENERGY, false),
+    ("dyn", 1.0, Dimension::new(1, 1, -2, 0), false),
+    ("Angstrom", 1e-8, LENGTH, false),
+    ("AU", AU, LENGTH, false),
+    ("au", AU, LENGTH, false),
+    ("pc", PARSEC, LENGTH, true),
+    ("lyr", 9.460_730_472_580_8e17, LENGTH, false),
+    ("solRad", 6.957e10, LENGTH, false),
+    ("solMass", SOLAR_MASS, MASS, false),
+    ("Msun", SOLAR_MASS, MASS, false),
+    ("solLum", 3.828e33, POWER, false),
+    ("min", 60.0, TIME, false),
+    ("h", 3_600.0, TIME, false),
+    ("d", DAY, TIME, false),
+    ("yr", JULIAN_YEAR, TIME, true),
+    ("a", JULIAN_YEAR, TIME, true),
+    ("rad", 1.0, NONE, true),
+    ("sr", 1.0, NONE, true),
+    ("deg", DEGREE, NONE, false),
+    ("arcmin", DEGREE / 60.0, NONE, false),
+    ("arcsec", DEGREE / 3_600.0, NONE, false),
+    ("mas", DEGREE / 3.6e6, NONE, false),
+    ("ct", 1.0, NONE, false),
+    ("ph", 1.0, NONE, false),
+    ("beam", 1.0, NONE, false),
+];
+
+const PREFIXES: [(&str, f64); 20] = [
+    ("da", 1e1),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("z", 1e-21),
+    ("y", 1e-24),
+];
+
+fn lookup(symbol: &str) -> Option<(f64, Dimension)> {
+    let unit = |s: &str| UNITS.iter().find(|u| u.0 == s);
+
+    if let Some(&(_, scale, dimension, _)) = unit(symbol) {
+        return Some((scale, dimension));
+    }
+    PREFIXES.iter().find_map(|&(prefix, factor)| {
+        let base = unit(symbol.strip_prefix(prefix)?).filter(|u| u.3)?;
+        Some((factor * base.1, base.2))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitExpression {
+    // Size of the unit in CGS units and kelvin
+    pub scale: f64,
+    pub dimension: Dimension,
+}
+
+impl UnitExpression {
+    pub fn is_compatible(&self, other: &Self) -> bool {
+        self.dimension == other.dimension
+    }
+
+    // Factor that turns a value in `self` into a value in `other`
+    pub fn factor_to(&self, other: &Self) -> Option<f64> {
+        self.is_compatible(other).then(|| self.scale / other.scale)
+    }
+}
+
+// A scale such as `1e-20` or `10^-20`; `**` is read as `^` beforehand.
+fn parse_number(term: &str) -> Option<f64> {
+    match term.split_once('^') {
+        Some((base, exponent)) => Some(base.parse::<f64>().ok()?.powi(exponent.trim_matches(['(', ')']).parse().ok()?)),
+        None => term.parse::<f64>().ok(),
+    }
+}
+
+fn parse_term(unit: &str, term: &str) -> Result<(f64, Dimension), UnitError> {
+    if let Some(scale) = parse_number(term) {
+        return Ok((scale, NONE));
+    }
+
+    let syntax = |note: &str| UnitError::Syntax { unit: String::from(unit), note: String::from(note) };
+    let (symbol, exponent) = match term.split_once('^') {
+        Some((symbol, exponent)) => (symbol, exponent.trim_matches(['(', ')'])),
+        None => {
+            let split = term.find(|c: char| c.is_ascii_digit() || c == '-' || c == '+').unwrap_or(term.len());
+            term.split_at(split)
+        }
+    };
+    let exponent = match exponent {
+        "" => 1,
+        e => e.parse::<i32>().map_err(|_| syntax(&format!("exponent of `{}` is not an integer", symbol)))?,
+    };
+    let (scale, dimension) = lookup(symbol)
+        .ok_or_else(|| UnitError::UnknownUnit { unit: String::from(unit), symbol: String::from(symbol) })?;
+
+    Ok((scale.powi(exponent), dimension.scaled(exponent)))
+}
+
+impl core::str::FromStr for UnitExpression {
+    type Err = UnitError;
+
+    // Terms are separated by spaces, `.` or `*`; a `/` divides by the term
+    // or parenthesised group that follows it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax = |note: &str| UnitError::Syntax { unit: String::from(s), note: String::from(note) };
+        let mut expression = Self { scale: 1.0, dimension: NONE };
+        let mut divide = false;
+        let mut group: Option<i32> = None;
+
+        let normalized = s.replace("**", "^");
+        let tokens = normalized
+            .split_inclusive(['/', '(', ')', '*', ' ', '\t'])
+            .flat_map(|t| {
+                let (word, separator) = t.split_at(t.len() - t.ends_with(['/', '(', ')', '*', ' ', '\t']) as usize);
+                [word, separator]
+            })
+            .map(str::trim)
+            .filter(|t| !t.is_empty() && *t != "*");
+
+        for token in tokens {
+            match token {
+                "/" if divide => return Err(syntax("two `/` in a row")),
+                "/" => divide = true,
+                "(" if group.is_some() => return Err(syntax("nested parentheses")),
+                "(" => (group, divide) = (Some(if divide { -1 } else { 1 }), false),
+                ")" if group.is_none() => return Err(syntax("unbalanced parentheses")),
+                ")" => group = None,
+                word => {
+                    let sign = group.unwrap_or(1) * if divide { -1 } else { 1 };
+                    divide = false;
+
+                    // `.` separates terms, but also belongs to numbers such as `1.5e-3`
+                    let terms = match parse_number(word) {
+                        Some(_) => vec!(word),
+                        None => word.split('.').collect(),
+                    };
+                    for term in terms {
+                        let (scale, dimension) = parse_term(s, term)?;
+                        expression.scale *= scale.powi(sign);
+                        expression.dimension = expression.dimension.add(dimension.scaled(sign));
+                    }
+                }
+            }
+        }
+        if divide || group.is_some() {
+            return Err(syntax("expression ends before its last term"));
+        }
+
+        Ok(expression)
+    }
+}
+
+// Canonical form in CGS units, e.g. "1e-23 g s-2" for Jy, which reads back
+// into the same expression.
+impl std::fmt::Display for UnitExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let d = self.dimension;
+        let terms = [("cm", d.length), ("g", d.mass), ("s", d.time), ("K", d.temperature)]
+            .into_iter()
+            .filter(|&(_, n)| n != 0)
+            .map(|(symbol, n)| match n {
+                1 => String::from(symbol),
+                n => format!("{}{}", symbol, n),
+            })
+            .collect::<Vec<_>>();
+
+        match (self.scale, terms.is_empty()) {
+            (scale, true) => write!(f, "{}", scale),
+            (1.0, false) => write!(f, "{}", terms.join(" ")),
+            (scale, false) => write!(f, "{} {}", scale, terms.join(" ")),
+        }
+    }
+}
+
+pub fn convert(value: f64, from: &str, to: &str) -> Result<f64, UnitError> {
+    let (source, target) = (from.parse::<UnitExpression>()?, to.parse::<UnitExpression>()?);
+
+    source
+        .factor_to(&target)
+        .map(|factor| value * factor)
+        .ok_or_else(|| UnitError::Incompatible { from: String::from(from), to: String::from(to) })
+}
+
+// Number and unit of strings such as "1.3e4 cm-3", "0.8 km/s" or "2.5Msun";
+// a bare number is dimensionless.
+pub fn parse_value(s: &str) -> Result<(f64, UnitExpression), UnitError> {
+    let s = s.trim();
+    let run = s.find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))).unwrap_or(s.len());
+    let (value, rest) = (1..=run)
+        .rev()
+        .find_map(|end| s[..end].parse::<f64>().ok().map(|value| (value, &s[end..])))
+        .ok_or_else(|| UnitError::MissingNumber { input: String::from(s) })?;
+
+    Ok((value, rest.trim().parse()?))
+}
+
+fn expect_dimension(s: &str, found: &UnitExpression, expected: Dimension, unit: &str) -> Result<(), UnitError> {
+    match found.dimension == expected {
+        true => Ok(()),
+        false => Err(UnitError::WrongDimension {
+            input: String::from(s.trim()),
+            found: found.dimension.describe(),
+            expected: format!("{} ({})", expected.describe(), unit),
+        }),
+    }
+}
+
+// Value of a string such as "0.8 km/s" in `unit`, which has to be of the
+// same dimension.
+pub fn value_in(s: &str, unit: &str) -> Result<f64, UnitError> {
+    let (value, found) = parse_value(s)?;
+    let target = unit.parse::<UnitExpression>()?;
+    expect_dimension(s, &found, target.dimension, unit)?;
+
+    Ok(value * found.scale / target.scale)
+}
+
+// IAU quantities with the astropy string and CGS size of their base unit.
+pub trait UnitQuantity: Sized {
+    const BASE_UNIT: &'static str;
+    const BASE_SCALE: f64;
+    const DIMENSION: Dimension;
+
+    fn from_base(value: f64) -> Self;
+
+    fn to_base(&self) -> f64;
+}
+
+macro_rules! unit_quantity {
+    ($quantity:ident, $unit:literal, $scale:expr, $dimension:expr) => {
+        impl UnitQuantity for crate::iau::f64::$quantity {
+            const BASE_UNIT: &'static str = $unit;
+            const BASE_SCALE: f64 = $scale;
+            const DIMENSION: Dimension = $dimension;
+
+            fn from_base(value: f64) -> Self {
+                Self { dimension: PhantomData, units: PhantomData, value }
+            }
+
+            fn to_base(&self) -> f64 {
+                self.value
+            }
+        }
+    };
+}
+
+#[cfg(feature = "f64")]
+unit_quantity!(Length, "AU", AU, LENGTH);
+#[cfg(feature = "f64")]
+unit_quantity!(Mass, "solMass", SOLAR_MASS, MASS);
+#[cfg(feature = "f64")]
+unit_quantity!(Time, "d", DAY, TIME);
+
+fn quantity_unit<Q: UnitQuantity>(unit: &str) -> Result<UnitExpression, UnitError> {
+    let expression = unit.parse::<UnitExpression>()?;
+    match expression.dimension == Q::DIMENSION {
+        true => Ok(expression),
+        false => Err(UnitError::Incompatible { from: String::from(unit), to: String::from(Q::BASE_UNIT) }),
+    }
+}
+
+// Quantity of `value` in `unit`, e.g. `parse_quantity::<Length>(1.0, "pc")`.
+pub fn parse_quantity<Q: UnitQuantity>(value: f64, unit: &str) -> Result<Q, UnitError> {
+    Ok(Q::from_base(value * quantity_unit::<Q>(unit)?.scale / Q::BASE_SCALE))
+}
+
+// IAU quantity of a string such as "2.5 Msun" or "1 pc".
+pub fn quantity_from_str<Q: UnitQuantity>(s: &str) -> Result<Q, UnitError> {
+    let (value, found) = parse_value(s)?;
+    expect_dimension(s, &found, Q::DIMENSION, Q::BASE_UNIT)?;
+
+    Ok(Q::from_base(value * found.scale / Q::BASE_SCALE))
+}
+
+// Value of `quantity` in `unit`.
+pub fn quantity_value<Q: UnitQuantity>(quantity: &Q, unit: &str) -> Result<f64, UnitError> {
+    Ok(quantity.to_base() * Q::BASE_SCALE / quantity_unit::<Q>(unit)?.scale)
+}
+
+// Value and unit string for writing `quantity`, in its IAU base unit.
+pub fn write_quantity<Q: UnitQuantity>(quantity: &Q) -> (f64, &'static str) {
+    (quantity.to_base(), Q::BASE_UNIT)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    #[cfg(feature = "f64")]
+    use crate::iau::f64::{Length, Mass};
+
+    fn close(a: f64, b: f64) -> bool {
+        ((a - b) / b).abs() < 1e-12
+    }
+
+    #[test]
+    fn convert_unit_strings() {
+        assert!(close(convert(1.0, "km s-1", "cm/s").unwrap(), 1e5), "Wrong result for km s-1");
+        assert!(close(convert(1.0, "Jy", "erg s-1 cm-2 Hz-1").unwrap(), 1e-23), "Wrong result for Jy");
+        assert!(close(convert(2.0, "erg / (s cm2 Hz sr)", "erg.s**-1.cm**-2.Hz**-1.sr**-1").unwrap(), 2.0));
+        assert!(close(convert(1.0, "10**-3 W m-2", "erg s-1 cm-2").unwrap(), 1.0), "Wrong result for scaled unit");
+        assert!(close(convert(1.0, "Gyr", "Myr").unwrap(), 1e3), "Wrong result for prefixed years");
+
+        assert_eq!(
+            convert(1.0, "km", "s"),
+            Err(UnitError::Incompatible { from: String::from("km"), to: String::from("s") }),
+        );
+        assert!(matches!("erg furlong-1".parse::<UnitExpression>(), Err(UnitError::UnknownUnit { .. })));
+    }
+
+    #[test]
+    fn values_with_units() {
+        assert!(close(value_in("1.3e4 cm-3", "m-3").unwrap(), 1.3e10), "Wrong result for density");
+        assert!(close(value_in("0.8km/s", "km s-1").unwrap(), 0.8), "Wrong result for velocity");
+        assert!(close(value_in("3eV", "erg").unwrap(), 4.806_529_902e-12), "Wrong result for energy");
+        assert!(close(value_in(" 20 ", "").unwrap(), 20.0), "Wrong result for bare number");
+
+        assert_eq!(value_in("cm-3", "cm-3"), Err(UnitError::MissingNumber { input: String::from("cm-3") }));
+        assert_eq!(
+            value_in("1e4 K", "cm-3").unwrap_err().to_string(),
+            "`1e4 K` is a temperature, but a number density (cm-3) is expected"
+        );
+    }
+
+    #[test]
+    fn canonical_form_round_trip() {
+        for unit in ["Jy", "km s-1", "erg s-1 cm-2 Hz-1 sr-1", "K km s-1", "solMass pc-3", "deg2"] {
+            let expression = unit.parse::<UnitExpression>().unwrap();
+            let again = expression.to_string().parse::<UnitExpression>().unwrap();
+
+            assert_eq!(again.dimension, expression.dimension, "Wrong dimension for {}", unit);
+            assert!(close(again.scale, expression.scale), "Wrong scale for {}: {}", unit, expression);
+        }
+    }
+
+    #[cfg(feature = "f64")]
+    #[test]
+    fn iau_quantities() {
+        let distance: Length = parse_quantity(1.0, "pc").unwrap();
+        assert!(close(write_quantity(&distance).0, 206_264.806_247), "Wrong result for pc in AU");
+        assert!(close(quantity_value(&distance, "km").unwrap(), 3.085_677_581_491_367e13));
+
+        let mass: Mass = quantity_from_str("2.5 Msun").unwrap();
+        assert!(close(mass.value, 2.5), "Wrong result for solar masses");
+        assert_eq!(
+            quantity_from_str::<Length>("0.8 km/s").unwrap_err().to_string(),
+            "`0.8 km/s` is a velocity, but a length (AU) is expected"
+        );
+
+        let mass: Mass = parse_quantity(2e33, "g").unwrap();
+        assert!(close(quantity_value(&mass, "kg").unwrap(), 2e30));
+        assert!(parse_quantity::<Mass>(1.0, "km").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ::arrow::array::{ArrayRef, Float64Array, StringArray, UInt32Array};
+use ::arrow::datatypes::{DataType, Field, Schema};
+use ::arrow::error::ArrowError;
+use ::arrow::record_batch::RecordBatch;
+
+use super::table::{ColumnData, Table};
+use crate::lamda::ElementData;
+
+fn field(name: &str, data_type: DataType, units: Option<&str>) -> Field {
+    let field = Field::new(name, data_type, false);
+    match units {
+        Some(units) => field.with_metadata(HashMap::from([(String::from("units"), String::from(units))])),
+        None => field,
+    }
+}
+
+fn schema(data: &ElementData, fields: Vec<Field>) -> Arc<Schema> {
+    let metadata = HashMap::from([
+        (String::from("molecule"), data.name.clone()),
+        (String::from("weight"), data.weight.to_string()),
+    ]);
+
+    Arc::new(Schema::new_with_metadata(fields, metadata))
+}
+
+pub fn levels_record_batch(data: &ElementData) -> Result<RecordBatch, ArrowError> {
+    let levels = &data.energy_levels;
+    let schema = schema(data, vec!(
+        field("level", DataType::UInt32, None),
+        field("energy", DataType::Float64, Some("cm-1")),
+        field("weight", DataType::Float64, None),
+        field("qnums", DataType::Utf8, None),
+    ));
+    let columns: Vec<ArrayRef> = vec!(
+        Arc::new(UInt32Array::from_iter_values(levels.iter().map(|el| el.level))),
+        Arc::new(Float64Array::from_iter_values(levels.iter().map(|el| el.energy))),
+        Arc::new(Float64Array::from_iter_values(levels.iter().map(|el| el.stat_weight))),
+        Arc::new(StringArray::from_iter_values(levels.iter().map(|el| el.qnums.as_str()))),
+    );
+
+    RecordBatch::try_new(schema, columns)
+}
+
+pub fn radiative_transitions_record_batch(data: &ElementData) -> Result<RecordBatch, ArrowError> {
+    let transitions = &data.radiative_transitions;
+    let schema = schema(data, vec!(
+        field("transition", DataType::UInt32, None),
+        field("up", DataType::UInt32, None),
+        field("low", DataType::UInt32, None),
+        field("aeinst", DataType::Float64, Some("s-1")),
+        field("frequency", DataType::Float64, Some("GHz")).with_nullable(true),
+        field("upper_energy", DataType::Float64, Some("K")).with_nullable(true),
+        field("extra", DataType::Utf8, None),
+    ));
+    let columns: Vec<ArrayRef> = vec!(
+        Arc::new(UInt32Array::from_iter_values(transitions.iter().map(|rt| rt.transition))),
+        Arc::new(UInt32Array::from_iter_values(transitions.iter().map(|rt| rt.up))),
+        Arc::new(UInt32Array::from_iter_values(transitions.iter().map(|rt| rt.low))),
+        Arc::new(Float64Array::from_iter_values(transitions.iter().map(|rt| rt.aeinst))),
+        Arc::new(Float64Array::from_iter(transitions.iter().map(|rt| rt.frequency))),
+        Arc::new(Float64Array::from_iter(transitions.iter().map(|rt| rt.upper_energy))),
+        Arc::new(StringArray::from_iter_values(transitions.iter().map(|rt| rt.extra.as_str()))),
+    );
+
+    RecordBatch::try_new(schema, columns)
+}
+
+// Rates are stored in long format, one row per (partner, transition, temperature),
+// which is what query engines handle best.
+pub fn collision_rates_record_batch(data: &ElementData) -> Result<RecordBatch, ArrowError> {
+    let rows = data.collision_partners
+        .iter()
+        .flat_map(|cp| cp.rates.iter().flat_map(move |cr| {
+            cp.temperatures
+                .iter()
+                .zip(cr.rates.iter())
+                .map(move |(temperature, rate)| (cp, cr, *temperature, *rate))
+        }))
+        .collect::<Vec<_>>();
+
+    let partners = rows.iter().map(|row| row.0.name.to_string()).collect::<Vec<_>>();
+    let schema = schema(data, vec!(
+        field("partner", DataType::Utf8, None),
+        field("transition", DataType::UInt32, None),
+        field("up", DataType::UInt32, None),
+        field("low", DataType::UInt32, None),
+        field("temperature", DataType::Float64, Some("K")),
+        field("rate", DataType::Float64, Some("cm3 s-1")),
+    ));
+    let columns: Vec<ArrayRef> = vec!(
+        Arc::new(StringArray::from_iter_values(partners.iter())),
+        Arc::new(UInt32Array::from_iter_values(rows.iter().map(|row| row.1.transition))),
+        Arc::new(UInt32Array::from_iter_values(rows.iter().map(|row| row.1.up))),
+        Arc::new(UInt32Array::from_iter_values(rows.iter().map(|row| row.1.low))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|row| row.2))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|row| row.3))),
+    );
+
+    RecordBatch::try_new(schema, columns)
+}
+
+pub fn table_record_batch(table: &Table) -> Result<RecordBatch, ArrowError> {
+    let fields = table.columns
+        .iter()
+        .map(|column| {
+            let data_type = match column.data {
+                ColumnData::UInt(_) => DataType::UInt32,
+                ColumnData::Float(_) => DataType::Float64,
+                ColumnData::Text(_) => DataType::Utf8,
+            };
+            field(&column.name, data_type, column.unit.as_deref())
+        })
+        .collect::<Vec<_>>();
+    let columns = table.columns
+        .iter()
+        .map(|column| -> ArrayRef {
+            match &column.data {
+                ColumnData::UInt(v) => Arc::new(UInt32Array::from(v.clone())),
+                ColumnData::Float(v) => Arc::new(Float64Array::from(v.clone())),
+                ColumnData::Text(v) => Arc::new(StringArray::from_iter_values(v.iter())),
+            }
+        })
+        .collect::<Vec<_>>();
+    let metadata = table.meta.iter().cloned().collect::<HashMap<_, _>>();
+
+    RecordBatch::try_new(Arc::new(Schema::new_with_metadata(fields, metadata)), columns)
+}
+
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: std::io::Write + Send>(
+    batch: &RecordBatch,
+    writer: W
+) -> Result<(), ::parquet::errors::ParquetError> {
+    let mut writer = ::parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+// Writes `levels.parquet`, `radiative_transitions.parquet` and
+// `collision_rates.parquet` into `dir`.
+#[cfg(all(feature = "parquet", not(target_arch = "wasm32")))]
+pub fn write_parquet_tables<P: AsRef<std::path::Path>>(
+    data: &ElementData,
+    dir: P
+) -> Result<(), ::parquet::errors::ParquetError> {
+    let tables = [
+        ("levels.parquet", levels_record_batch(data)?),
+        ("radiative_transitions.parquet", radiative_transitions_record_batch(data)?),
+        ("collision_rates.parquet", collision_rates_record_batch(data)?),
+    ];
+
+    for (name, batch) in tables.iter() {
+        let file = std::fs::File::create(dir.as_ref().join(name))?;
+        write_parquet(batch, file)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{CollisionPartnerData, CollisionPartnerId, CollisionalRates, EnergyLevel, RadiativeTransition};
+
+    fn element_data() -> ElementData {
+        ElementData {
+            name: String::from("CO"),
+            weight: 28.0,
+            energy_levels: vec!(
+                EnergyLevel { level: 1, energy: 0.0, stat_weight: 1.0, qnums: String::from("0") },
+                EnergyLevel { level: 2, energy: 3.845, stat_weight: 3.0, qnums: String::from("1") },
+            ),
+            radiative_transitions: vec!(
+                RadiativeTransition { transition: 1, up: 2, low: 1, aeinst: 7.203e-08, ..Default::default() },
+            ),
+            collision_partners: vec!(CollisionPartnerData {
+                name: CollisionPartnerId::pH2,
+                information: String::new(),
+                temperatures: vec!(10.0, 20.0),
+                rates: vec!(CollisionalRates { transition: 1, up: 2, low: 1, rates: vec!(3.3e-11, 3.4e-11) }),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn element_data_record_batches() -> Result<(), ArrowError> {
+        let ed = element_data();
+
+        let levels = levels_record_batch(&ed)?;
+        let transitions = radiative_transitions_record_batch(&ed)?;
+        let rates = collision_rates_record_batch(&ed)?;
+
+        assert_eq!(levels.num_rows(), 2);
+        assert_eq!(transitions.num_rows(), 1);
+        assert_eq!(rates.num_rows(), 2);
+        assert_eq!(
+            levels.schema().field_with_name("energy")?.metadata().get("units"),
+            Some(&String::from("cm-1"))
+        );
+        assert_eq!(levels.schema().metadata().get("molecule"), Some(&String::from("CO")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn generic_table_record_batch() -> Result<(), ArrowError> {
+        let batch = table_record_batch(&crate::interop::table::line_list(&element_data()))?;
+
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.schema().field_with_name("qn_up")?.data_type(), &DataType::Utf8);
+        assert_eq!(
+            batch.schema().field_with_name("freq")?.metadata().get("units"),
+            Some(&String::from("GHz"))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn write_levels_parquet() {
+        let batch = levels_record_batch(&element_data()).unwrap();
+        let mut buffer: Vec<u8> = vec!();
+
+        write_parquet(&batch, &mut buffer).unwrap();
+
+        assert!(buffer.starts_with(b"PAR1"));
+    }
+}
@@ -0,0 +1,88 @@
+use std::io::{Error, ErrorKind, Write};
+
+use crate::spectrum::{Axis, Spectrum};
+
+use super::fits;
+
+fn invalid(spectrum: &Spectrum, note: &str) -> Error {
+    Error::new(ErrorKind::InvalidInput, format!("cannot write spectrum `{}` for CLASS: {}", spectrum.name, note))
+}
+
+// Writes a spectrum as the one-dimensional FITS image that GILDAS/CLASS reads
+// with `FITS READ`. The spectral axis is the frequency offset from RESTFREQ,
+// which falls on channel CRPIX1; the source velocity is zero.
+pub fn write<W: Write>(spectrum: &Spectrum, writer: &mut W) -> std::io::Result<()> {
+    let frequencies = spectrum.frequencies().map_err(|e| invalid(spectrum, &e.to_string()))?;
+    let velocities = spectrum.velocities().map_err(|e| invalid(spectrum, &e.to_string()))?;
+    if frequencies.len() < 2 {
+        return Err(invalid(spectrum, "at least two channels are needed"));
+    }
+
+    let rest = spectrum.rest_frequency * 1e9;
+    let step = Axis::new("Hz", frequencies.iter().map(|f| f * 1e9).collect())
+        .step()
+        .ok_or_else(|| invalid(spectrum, "channels are not evenly spaced"))?;
+    let reference = 1.0 + (rest - frequencies[0] * 1e9) / step;
+
+    let observation = &spectrum.observation;
+    let pairs: [(&str, String); 20] = [
+        ("CTYPE1", String::from("FREQ")),
+        ("CRVAL1", String::from("0")),
+        ("CDELT1", step.to_string()),
+        ("CRPIX1", reference.to_string()),
+        ("CTYPE2", String::from("RA---GLS")),
+        ("CRVAL2", observation.ra.to_string()),
+        ("CTYPE3", String::from("DEC--GLS")),
+        ("CRVAL3", observation.dec.to_string()),
+        ("EQUINOX", String::from("2000")),
+        ("BUNIT", spectrum.intensity.unit.clone()),
+        ("OBJECT", observation.object.clone()),
+        ("LINE", spectrum.name.clone()),
+        ("TELESCOP", observation.telescope.clone()),
+        ("DATE-OBS", observation.date.clone()),
+        ("RESTFREQ", rest.to_string()),
+        ("VELO-LSR", String::from("0")),
+        ("DELTAV", ((velocities[1] - velocities[0]) * 1e3).to_string()),
+        ("TSYS", observation.tsys.to_string()),
+        ("OBSTIME", observation.exposure.to_string()),
+        ("ORIGIN", String::from("ism")),
+    ];
+    let meta = pairs.into_iter().map(|(key, value)| (String::from(key), value)).collect::<Vec<_>>();
+
+    fits::write_image(&[spectrum.len(), 1, 1], &meta, &spectrum.intensity.values, writer)
+}
+
+pub fn to_bytes(spectrum: &Spectrum) -> std::io::Result<Vec<u8>> {
+    let mut buffer: Vec<u8> = vec!();
+    write(spectrum, &mut buffer)?;
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::spectrum::Observation;
+
+    #[test]
+    fn write_class_spectrum() {
+        let spectrum = Spectrum {
+            name: String::from("CO(1-0)"),
+            rest_frequency: 115.271_202,
+            spectral: Axis::new("km s-1", vec!(-1.0, 0.0, 1.0)),
+            intensity: Axis::new("K", vec!(0.5, 2.0, 0.5)),
+            observation: Observation { object: String::from("TMC-1"), ..Default::default() },
+        };
+
+        let bytes = to_bytes(&spectrum).unwrap();
+        let cards = bytes[..2880].chunks(80).map(|c| String::from_utf8_lossy(c).trim_end().to_string()).collect::<Vec<_>>();
+
+        assert_eq!(bytes.len(), 2 * 2880);
+        assert_eq!(cards[1], "BITPIX  =                  -32 / single precision floats");
+        assert!(cards.contains(&String::from("CRPIX1  =                    2")), "Wrong reference channel in {:?}", cards);
+        assert!(cards.contains(&String::from("DELTAV  =                 1000")), "Wrong channel width in {:?}", cards);
+        assert!(cards.contains(&String::from("LINE    = 'CO(1-0) '")));
+        assert_eq!(f32::from_be_bytes(bytes[2884..2888].try_into().unwrap()), 2.0);
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::Write;
+
+use super::table::Table;
+
+fn quote(s: &str) -> String {
+    match s.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", s.replace('"', "\"\"")),
+        false => String::from(s),
+    }
+}
+
+pub fn write<W: Write>(table: &Table, writer: &mut W) -> std::io::Result<()> {
+    let header = table.columns
+        .iter()
+        .map(|c| match &c.unit {
+            Some(unit) => quote(&format!("{} [{}]", c.name, unit)),
+            None => quote(&c.name),
+        })
+        .collect::<Vec<_>>();
+    writeln!(writer, "{}", header.join(","))?;
+
+    for row in 0..table.num_rows() {
+        let cells = table.columns
+            .iter()
+            .map(|c| quote(&c.data.cell(row)))
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", cells.join(","))?;
+    }
+
+    Ok(())
+}
+
+pub fn to_string(table: &Table) -> String {
+    let mut buffer: Vec<u8> = vec!();
+    write(table, &mut buffer).expect("Writing to memory should not fail");
+
+    String::from_utf8(buffer).expect("CSV output should be valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::interop::table::{Column, ColumnData};
+
+    #[test]
+    fn write_csv_table() {
+        let table = Table {
+            name: String::from("CO"),
+            meta: vec!(),
+            columns: vec!(
+                Column::new("qnums", None, "", ColumnData::Text(vec!(String::from("J=1, v=0")))),
+                Column::new("energy", Some(String::from("cm-1")), "", ColumnData::Float(vec!(3.845))),
+            ),
+        };
+
+        assert_eq!(to_string(&table), "qnums,energy [cm-1]\n\"J=1, v=0\",3.845\n");
+    }
+}
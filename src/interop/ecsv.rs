@@ -0,0 +1,103 @@
+use std::io::Write;
+
+use super::table::{ColumnData, Table};
+
+fn datatype(data: &ColumnData) -> &'static str {
+    match data {
+        ColumnData::UInt(_) => "uint32",
+        ColumnData::Float(_) => "float64",
+        ColumnData::Text(_) => "string",
+    }
+}
+
+fn quote(s: &str) -> String {
+    match s.is_empty() || s.contains(|c: char| c.is_whitespace() || c == '"' || c == '#') {
+        true => format!("\"{}\"", s.replace('"', "\"\"")),
+        false => String::from(s),
+    }
+}
+
+fn yaml_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+pub fn write<W: Write>(table: &Table, writer: &mut W) -> std::io::Result<()> {
+    writeln!(writer, "# %ECSV 1.0")?;
+    writeln!(writer, "# ---")?;
+    writeln!(writer, "# datatype:")?;
+    for column in table.columns.iter() {
+        write!(writer, "# - {{name: {}", column.name)?;
+        if let Some(unit) = &column.unit {
+            write!(writer, ", unit: {}", yaml_string(unit))?;
+        }
+        write!(writer, ", datatype: {}", datatype(&column.data))?;
+        if !column.description.is_empty() {
+            write!(writer, ", description: {}", yaml_string(&column.description))?;
+        }
+        writeln!(writer, "}}")?;
+    }
+    if !table.meta.is_empty() {
+        writeln!(writer, "# meta: !!omap")?;
+        for (key, value) in table.meta.iter() {
+            writeln!(writer, "# - {{{}: {}}}", key, yaml_string(value))?;
+        }
+    }
+    writeln!(writer, "# schema: astropy-2.0")?;
+
+    let names = table.columns
+        .iter()
+        .map(|c| quote(&c.name))
+        .collect::<Vec<_>>();
+    writeln!(writer, "{}", names.join(" "))?;
+
+    for row in 0..table.num_rows() {
+        let cells = table.columns
+            .iter()
+            .map(|c| quote(&c.data.cell(row)))
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", cells.join(" "))?;
+    }
+
+    Ok(())
+}
+
+pub fn to_string(table: &Table) -> String {
+    let mut buffer: Vec<u8> = vec!();
+    write(table, &mut buffer).expect("Writing to memory should not fail");
+
+    String::from_utf8(buffer).expect("ECSV output should be valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::interop::table::Column;
+
+    #[test]
+    fn write_ecsv_table() {
+        let table = Table {
+            name: String::from("CO"),
+            meta: vec!((String::from("molecule"), String::from("CO"))),
+            columns: vec!(
+                Column::new("transition", None, "", ColumnData::UInt(vec!(1))),
+                Column::new("qn_up", None, "", ColumnData::Text(vec!(String::from("J 1")))),
+                Column::new("aeinst", Some(String::from("s-1")), "Einstein A", ColumnData::Float(vec!(7.203e-08))),
+            ),
+        };
+
+        let expected = "# %ECSV 1.0\n\
+            # ---\n\
+            # datatype:\n\
+            # - {name: transition, datatype: uint32}\n\
+            # - {name: qn_up, datatype: string}\n\
+            # - {name: aeinst, unit: 's-1', datatype: float64, description: 'Einstein A'}\n\
+            # meta: !!omap\n\
+            # - {molecule: 'CO'}\n\
+            # schema: astropy-2.0\n\
+            transition qn_up aeinst\n\
+            1 \"J 1\" 7.203e-8\n";
+
+        assert_eq!(to_string(&table), expected);
+    }
+}
@@ -0,0 +1,594 @@
+use std::io::Write;
+
+use crate::constants::SPEED_OF_LIGHT;
+use crate::iau::unit_string;
+use crate::lamda::ElementData;
+use crate::radex::{Conditions, Solution};
+use crate::spectrum::{Axis, Cube, Observation, Spectrum, SpectrumError};
+
+use super::table::{self, Column, ColumnData, Table};
+
+const BLOCK: usize = 2880;
+const CARD: usize = 80;
+// Offset of unsigned 32-bit integers stored as signed ones (TZERO)
+const UNSIGNED_ZERO: i64 = 1 << 31;
+
+fn card(key: &str, value: &str, comment: &str) -> String {
+    // Strings start right after the indicator, other values end in column 30
+    let mut card = match key.len() <= 8 {
+        true if value.starts_with('\'') => format!("{:<8}= {:<20}", key, value),
+        true => format!("{:<8}= {:>20}", key, value),
+        false => format!("HIERARCH {} = {}", key, value),
+    };
+    if !comment.is_empty() {
+        card.push_str(" / ");
+        card.push_str(comment);
+    }
+
+    // Header cards hold printable ASCII only
+    let mut card = card.chars().map(|c| if c.is_ascii() && !c.is_ascii_control() { c } else { '?' }).collect::<String>();
+    card.truncate(CARD);
+    format!("{:<80}", card)
+}
+
+fn string(s: &str) -> String {
+    let mut quoted = s.replace('\'', "''");
+    quoted.truncate(66);
+    format!("'{:<8}'", quoted)
+}
+
+fn logical(b: bool) -> String {
+    String::from(if b { "T" } else { "F" })
+}
+
+fn keyword(key: &str) -> String {
+    key.to_uppercase().replace(|c: char| !(c.is_ascii_alphanumeric() || c == '-'), "_")
+}
+
+// Numbers are written as numbers, anything else as a string
+fn value(s: &str) -> String {
+    match s.parse::<f64>() {
+        Ok(_) if s.parse::<i64>().is_ok() => s.to_string(),
+        Ok(x) if x.is_finite() => match format!("{:E}", x) {
+            f if f.contains('.') => f,
+            f => f.replace('E', ".0E"),
+        },
+        _ => string(s),
+    }
+}
+
+fn write_header<W: Write>(cards: &[String], writer: &mut W) -> std::io::Result<()> {
+    for card in cards.iter() {
+        writer.write_all(card.as_bytes())?;
+    }
+    writer.write_all(format!("{:<80}", "END").as_bytes())?;
+
+    let size = (cards.len() + 1) * CARD;
+    writer.write_all(&vec!(b' '; (BLOCK - size % BLOCK) % BLOCK))
+}
+
+fn text_width(data: &[String]) -> usize {
+    data.iter().map(|s| s.len()).max().unwrap_or(0).max(1)
+}
+
+// Column of a binary table extension: a column of a `Table`, or a fixed-size
+// array of floats in every row, such as the DATA column of SDFITS.
+pub(crate) enum Field<'a> {
+    Scalar(&'a Column),
+    Array {
+        name: &'a str,
+        unit: Option<&'a str>,
+        rows: Vec<&'a [f64]>,
+    },
+}
+
+impl Field<'_> {
+    fn rows(&self) -> usize {
+        match self {
+            Field::Scalar(column) => column.data.len(),
+            Field::Array { rows, .. } => rows.len(),
+        }
+    }
+
+    fn format(&self) -> (String, usize) {
+        match self {
+            Field::Scalar(column) => match &column.data {
+                ColumnData::UInt(_) => (String::from("1J"), 4),
+                ColumnData::Float(_) => (String::from("1D"), 8),
+                ColumnData::Text(v) => (format!("{}A", text_width(v)), text_width(v)),
+            },
+            Field::Array { rows, .. } => {
+                let n = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+                (format!("{}D", n), 8 * n)
+            }
+        }
+    }
+}
+
+pub(crate) fn write_extension<W: Write>(
+    name: &str,
+    meta: &[(String, String)],
+    fields: &[Field<'_>],
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let formats = fields.iter().map(|f| f.format()).collect::<Vec<_>>();
+    let row_width = formats.iter().map(|f| f.1).sum::<usize>();
+    let rows = fields.first().map(|f| f.rows()).unwrap_or(0);
+
+    let mut cards = vec!(
+        card("XTENSION", &string("BINTABLE"), "binary table extension"),
+        card("BITPIX", "8", "array data type"),
+        card("NAXIS", "2", "number of array dimensions"),
+        card("NAXIS1", &row_width.to_string(), "length of a row in bytes"),
+        card("NAXIS2", &rows.to_string(), "number of rows"),
+        card("PCOUNT", "0", "size of the heap"),
+        card("GCOUNT", "1", "one data group"),
+        card("TFIELDS", &fields.len().to_string(), "number of columns"),
+        card("EXTNAME", &string(name), ""),
+    );
+    for (i, (field, (tform, _))) in fields.iter().zip(formats.iter()).enumerate() {
+        let n = i + 1;
+        let (name, description, unit) = match field {
+            Field::Scalar(column) => (column.name.as_str(), column.description.as_str(), column.unit.as_deref()),
+            Field::Array { name, unit, .. } => (*name, "", *unit),
+        };
+        cards.push(card(&format!("TTYPE{}", n), &string(name), description));
+        cards.push(card(&format!("TFORM{}", n), &string(tform), ""));
+        if let Field::Scalar(Column { data: ColumnData::UInt(_), .. }) = field {
+            cards.push(card(&format!("TZERO{}", n), &UNSIGNED_ZERO.to_string(), "unsigned integers"));
+        }
+        if let Some(unit) = unit {
+            cards.push(card(&format!("TUNIT{}", n), &string(unit), ""));
+        }
+    }
+    for (key, v) in meta.iter() {
+        cards.push(card(&keyword(key), &value(v), ""));
+    }
+    write_header(&cards, writer)?;
+
+    let mut data = Vec::with_capacity(row_width * rows);
+    for row in 0..rows {
+        for (field, (_, width)) in fields.iter().zip(formats.iter()) {
+            match field {
+                Field::Scalar(column) => match &column.data {
+                    ColumnData::UInt(v) => data.extend_from_slice(&((v[row] as i64 - UNSIGNED_ZERO) as i32).to_be_bytes()),
+                    ColumnData::Float(v) => data.extend_from_slice(&v[row].to_be_bytes()),
+                    ColumnData::Text(v) => data.extend(v[row].bytes().chain(std::iter::repeat(b' ')).take(*width)),
+                },
+                // Shorter arrays are padded with NaN, the FITS blank of floats
+                Field::Array { rows: values, .. } => {
+                    for x in values[row].iter().copied().chain(std::iter::repeat(f64::NAN)).take(width / 8) {
+                        data.extend_from_slice(&x.to_be_bytes());
+                    }
+                }
+            }
+        }
+    }
+    data.resize(data.len() + (BLOCK - data.len() % BLOCK) % BLOCK, 0);
+
+    writer.write_all(&data)
+}
+
+fn write_table<W: Write>(table: &Table, writer: &mut W) -> std::io::Result<()> {
+    let fields = table.columns.iter().map(Field::Scalar).collect::<Vec<_>>();
+    write_extension(&table.name, &table.meta, &fields, writer)
+}
+
+pub(crate) fn write_primary<W: Write>(meta: &[(String, String)], writer: &mut W) -> std::io::Result<()> {
+    let mut cards = vec!(
+        card("SIMPLE", &logical(true), "conforms to FITS standard"),
+        card("BITPIX", "8", "array data type"),
+        card("NAXIS", "0", "no primary data"),
+        card("EXTEND", &logical(true), "extensions follow"),
+    );
+    for (key, v) in meta.iter() {
+        cards.push(card(&keyword(key), &value(v), ""));
+    }
+
+    write_header(&cards, writer)
+}
+
+// Primary HDU with an image of single precision floats, the axis lengths
+// given by `axes`
+pub(crate) fn write_image<W: Write>(
+    axes: &[usize],
+    meta: &[(String, String)],
+    data: &[f64],
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let mut cards = vec!(
+        card("SIMPLE", &logical(true), "conforms to FITS standard"),
+        card("BITPIX", "-32", "single precision floats"),
+        card("NAXIS", &axes.len().to_string(), "number of array dimensions"),
+    );
+    for (i, n) in axes.iter().enumerate() {
+        cards.push(card(&format!("NAXIS{}", i + 1), &n.to_string(), ""));
+    }
+    for (key, v) in meta.iter() {
+        cards.push(card(&keyword(key), &value(v), ""));
+    }
+    write_header(&cards, writer)?;
+
+    let mut bytes = data.iter().flat_map(|&x| (x as f32).to_be_bytes()).collect::<Vec<_>>();
+    bytes.resize(bytes.len() + (BLOCK - bytes.len() % BLOCK) % BLOCK, 0);
+
+    writer.write_all(&bytes)
+}
+
+// Writes an empty primary HDU with the `meta` keywords and one binary table
+// extension per table, named by the table.
+pub fn write<W: Write>(meta: &[(String, String)], tables: &[Table], writer: &mut W) -> std::io::Result<()> {
+    write_primary(meta, writer)?;
+
+    for table in tables.iter() {
+        write_table(table, writer)?;
+    }
+
+    Ok(())
+}
+
+pub fn to_bytes(meta: &[(String, String)], tables: &[Table]) -> Vec<u8> {
+    let mut buffer: Vec<u8> = vec!();
+    write(meta, tables, &mut buffer).expect("Writing to memory should not fail");
+
+    buffer
+}
+
+// Primary keywords with the physical conditions of a run
+pub fn conditions_meta(data: &ElementData, conditions: &Conditions, solution: &Solution) -> Vec<(String, String)> {
+    let mut meta = vec!(
+        (String::from("molecule"), data.name.clone()),
+        (String::from("tkin"), conditions.tkin.to_string()),
+        (String::from("tbg"), conditions.tbg.to_string()),
+        (String::from("cdmol"), conditions.cdmol.to_string()),
+        (String::from("dv"), conditions.linewidth.to_string()),
+        (String::from("geometry"), conditions.geometry.to_string()),
+        (String::from("niter"), solution.iterations.to_string()),
+    );
+    for (partner, density) in conditions.densities.iter() {
+        meta.push((format!("n_{}", partner), density.to_string()));
+    }
+
+    meta
+}
+
+// A complete excitation run: the conditions in the primary header, then the
+// levels, the radiative transitions, the level populations and the results
+// per transition.
+pub fn write_model_run<W: Write>(
+    data: &ElementData,
+    conditions: &Conditions,
+    solution: &Solution,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let named = |name: &str, table: Table| Table { name: String::from(name), ..table };
+    let tables = vec!(
+        named("LEVELS", table::levels(data)),
+        named("TRANSITIONS", table::line_list(data)),
+        named("POPULATIONS", table::populations(data, solution)),
+        named("RESULTS", table::grid_results(&data.name, &[(conditions.clone(), solution.clone())])),
+    );
+
+    write(&conditions_meta(data, conditions, solution), &tables, writer)
+}
+
+// Keywords of the header at the start of `bytes`, string values without
+// their quotes, and the length of the header in bytes.
+fn read_header(bytes: &[u8]) -> Result<(Vec<(String, String)>, usize), SpectrumError> {
+    let mut cards = vec!();
+
+    for (i, card) in bytes.chunks_exact(CARD).enumerate() {
+        // Cards are split as bytes; they hold printable ASCII only
+        if !card.is_ascii() {
+            return Err(fits_error(&format!("header card {} is not ASCII", i + 1)));
+        }
+        let (key, indicator, field) = (String::from_utf8_lossy(&card[..8]), &card[8..10], String::from_utf8_lossy(&card[10..]));
+        let key = key.trim_end();
+        if key == "END" {
+            let size = (i + 1) * CARD;
+            return Ok((cards, size + (BLOCK - size % BLOCK) % BLOCK));
+        }
+        if indicator != b"= " {
+            continue;
+        }
+
+        let field = field.trim_start();
+        let value = match field.strip_prefix('\'') {
+            Some(quoted) => {
+                let mut value = String::new();
+                let mut chars = quoted.chars().peekable();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\'' if chars.peek() == Some(&'\'') => {
+                            value.push('\'');
+                            chars.next();
+                        }
+                        '\'' => break,
+                        c => value.push(c),
+                    }
+                }
+                value.trim_end().to_string()
+            }
+            None => field.split('/').next().unwrap_or_default().trim().to_string(),
+        };
+        cards.push((key.to_string(), value));
+    }
+
+    Err(SpectrumError::Fits { note: String::from("header has no END card") })
+}
+
+// Primary image of a FITS file, scaled by BSCALE and BZERO
+struct Image {
+    header: Vec<(String, String)>,
+    axes: Vec<usize>,
+    data: Vec<f64>,
+}
+
+impl Image {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.header.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    fn number(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(|v| v.replace('D', "E").parse::<f64>().ok())
+    }
+
+    // Spectral axis `n` from its CTYPE: frequencies (FREQ, in CLASS files an
+    // offset from RESTFREQ that is read as LSR velocity) or velocities (VRAD,
+    // VELO, VLSR, FELO)
+    fn spectral_axis(&self, n: usize) -> Result<Axis, SpectrumError> {
+        let key = |name: &str| format!("{}{}", name, n);
+        let crval = self.number(&key("CRVAL")).unwrap_or(0.0);
+        let cdelt = self.number(&key("CDELT")).ok_or_else(|| fits_error(&format!("{} is missing", key("CDELT"))))?;
+        let crpix = self.number(&key("CRPIX")).unwrap_or(1.0);
+        let axis = (0..self.axes[n - 1]).map(|i| crval + (i as f64 + 1.0 - crpix) * cdelt).collect::<Vec<_>>();
+        let rest = self.rest_frequency();
+
+        let ctype = self.get(&key("CTYPE")).unwrap_or_default();
+        match ctype.get(..4).unwrap_or(ctype) {
+            "FREQ" => {
+                let unit = self.get(&key("CUNIT")).unwrap_or("Hz");
+                let hz = unit_string::convert(1.0, unit, "Hz")?;
+                match rest > 0.0 && (crval * hz).abs() < rest / 2.0 {
+                    true => {
+                        let vlsr = self.number("VELO-LSR").or_else(|| self.number("VLSR")).unwrap_or(0.0) * 1e-3;
+                        let c = SPEED_OF_LIGHT * 1e-5;
+                        Ok(Axis::new("km s-1", axis.iter().map(|f| vlsr - c * f * hz / rest).collect()))
+                    }
+                    false => Ok(Axis::new(unit, axis)),
+                }
+            }
+            "VRAD" | "VELO" | "VLSR" | "FELO" => Ok(Axis::new(self.get(&key("CUNIT")).unwrap_or("m s-1"), axis)),
+            _ => Err(fits_error(&format!("spectral axis `{}` is not supported", ctype))),
+        }
+    }
+
+    // Rest frequency, Hz
+    fn rest_frequency(&self) -> f64 {
+        self.number("RESTFREQ").or_else(|| self.number("RESTFRQ")).unwrap_or(0.0)
+    }
+
+    fn observation(&self) -> Observation {
+        let text = |key: &str| String::from(self.get(key).unwrap_or_default());
+        let sky = |axis: &str, prefix: &str| {
+            let ctype = self.get(&format!("CTYPE{}", axis)).unwrap_or_default();
+            self.number(&format!("CRVAL{}", axis)).filter(|_| ctype.starts_with(prefix)).unwrap_or(0.0)
+        };
+
+        Observation {
+            object: text("OBJECT"),
+            telescope: text("TELESCOP"),
+            date: text("DATE-OBS"),
+            ra: sky("2", "RA"),
+            dec: sky("3", "DEC"),
+            exposure: self.number("OBSTIME").or_else(|| self.number("EXPOSURE")).unwrap_or(0.0),
+            tsys: self.number("TSYS").unwrap_or(0.0),
+        }
+    }
+}
+
+fn fits_error(note: &str) -> SpectrumError {
+    SpectrumError::Fits { note: String::from(note) }
+}
+
+fn read_image(bytes: &[u8]) -> Result<Image, SpectrumError> {
+    let (header, start) = read_header(bytes)?;
+    let mut image = Image { header, axes: vec!(), data: vec!() };
+
+    // The standard allows up to 999 axes
+    let naxis = image.number("NAXIS").unwrap_or(0.0) as usize;
+    if naxis > 999 {
+        return Err(fits_error("NAXIS is larger than 999"));
+    }
+    image.axes = (1..=naxis).map(|i| image.number(&format!("NAXIS{}", i)).unwrap_or(0.0) as usize).collect();
+    if naxis == 0 || image.axes.contains(&0) {
+        return Err(fits_error("primary HDU has no image"));
+    }
+
+    let bitpix = image.number("BITPIX").map(|b| b as i32);
+    let size: usize = match bitpix {
+        Some(8) => 1,
+        Some(16) => 2,
+        Some(32) | Some(-32) => 4,
+        Some(64) | Some(-64) => 8,
+        _ => return Err(fits_error("BITPIX is missing or not a valid data type")),
+    };
+    let length = image.axes
+        .iter()
+        .try_fold(size, |length, &n| length.checked_mul(n))
+        .ok_or_else(|| fits_error("image is larger than the address space"))?;
+    let data = start
+        .checked_add(length)
+        .and_then(|end| bytes.get(start..end))
+        .ok_or_else(|| fits_error("data is shorter than the image"))?;
+    let (bscale, bzero) = (image.number("BSCALE").unwrap_or(1.0), image.number("BZERO").unwrap_or(0.0));
+    image.data = data
+        .chunks_exact(size)
+        .map(|b| match bitpix {
+            Some(8) => b[0] as f64,
+            Some(16) => i16::from_be_bytes([b[0], b[1]]) as f64,
+            Some(32) => i32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Some(64) => i64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f64,
+            Some(-32) => f32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            _ => f64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]),
+        })
+        .map(|x| bzero + bscale * x)
+        .collect();
+
+    Ok(image)
+}
+
+// Reads the spectrum in the primary image of a FITS file, the layout written
+// by CLASS and most single-dish pipelines, with the spectral axis first.
+// Further axes have to be of length one.
+pub fn read_spectrum(bytes: &[u8]) -> Result<Spectrum, SpectrumError> {
+    let image = read_image(bytes)?;
+    if image.axes[1..].iter().any(|&n| n != 1) {
+        return Err(fits_error("image has more than one spectrum"));
+    }
+
+    Ok(Spectrum {
+        name: String::from(image.get("LINE").or_else(|| image.get("OBJECT")).unwrap_or_default()),
+        rest_frequency: image.rest_frequency() * 1e-9,
+        spectral: image.spectral_axis(1)?,
+        intensity: Axis::new(image.get("BUNIT").unwrap_or_default(), image.data.clone()),
+        observation: image.observation(),
+    })
+}
+
+// Reads a spectral cube from the primary image of a FITS file, two spatial
+// axes followed by the spectral axis; a fourth (Stokes) axis has to be of
+// length one.
+pub fn read_cube(bytes: &[u8]) -> Result<Cube, SpectrumError> {
+    let image = read_image(bytes)?;
+    if image.axes.len() < 3 || image.axes[3..].iter().any(|&n| n != 1) {
+        return Err(fits_error("image is not a spectral cube"));
+    }
+
+    Cube::new(
+        image.get("LINE").or_else(|| image.get("OBJECT")).unwrap_or_default(),
+        image.rest_frequency() * 1e-9,
+        (image.axes[0], image.axes[1]),
+        image.spectral_axis(3)?,
+        image.get("BUNIT").unwrap_or_default(),
+        image.data.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn header(bytes: &[u8]) -> Vec<String> {
+        bytes.chunks(CARD).map(|c| String::from_utf8_lossy(c).trim_end().to_string()).collect()
+    }
+
+    #[test]
+    fn write_fits_tables() {
+        let table = Table {
+            name: String::from("LINES"),
+            meta: vec!((String::from("molecule"), String::from("HCO+"))),
+            columns: vec!(
+                Column::new("transition", None, "Transition number", ColumnData::UInt(vec!(1, 2))),
+                Column::new("qn_up", None, "", ColumnData::Text(vec!(String::from("1"), String::from("10")))),
+                Column::new("aeinst", Some(String::from("s-1")), "", ColumnData::Float(vec!(4.25e-05, 4.07e-04))),
+            ),
+        };
+
+        let bytes = to_bytes(&[(String::from("tkin"), String::from("20"))], &[table]);
+        assert_eq!(bytes.len(), 3 * BLOCK, "Wrong size of two headers and one data block");
+
+        let primary = header(&bytes[..BLOCK]);
+        assert_eq!(primary[0], "SIMPLE  =                    T / conforms to FITS standard");
+        assert_eq!(primary[4], "TKIN    =                   20");
+        assert_eq!(value("4.25e-5"), "4.25E-5");
+        assert_eq!(value("1e20"), "1.0E20");
+        assert_eq!(primary[5], "END");
+
+        let extension = header(&bytes[BLOCK..2 * BLOCK]);
+        assert!(extension.contains(&String::from("NAXIS1  =                   14 / length of a row in bytes")));
+        assert!(extension.contains(&String::from("TFORM2  = '2A      '")), "Wrong text column in {:?}", extension);
+        assert!(extension.contains(&String::from("TUNIT3  = 's-1     '")), "Missing unit in {:?}", extension);
+        assert!(extension.contains(&String::from("MOLECULE= 'HCO+    '")));
+
+        let data = &bytes[2 * BLOCK..];
+        assert_eq!(i32::from_be_bytes(data[0..4].try_into().unwrap()) as i64 + UNSIGNED_ZERO, 1);
+        assert_eq!(&data[4..6], b"1 ");
+        assert_eq!(f64::from_be_bytes(data[6..14].try_into().unwrap()), 4.25e-05);
+    }
+
+    #[test]
+    fn read_fits_spectrum() {
+        let spectrum = Spectrum {
+            name: String::from("CO(1-0)"),
+            rest_frequency: 115.271_202,
+            spectral: Axis::new("km s-1", vec!(-1.0, 0.0, 1.0)),
+            intensity: Axis::new("K", vec!(0.5, 2.0, 0.5)),
+            observation: Observation { object: String::from("TMC-1"), tsys: 150.0, ..Default::default() },
+        };
+
+        let read = read_spectrum(&crate::interop::class::to_bytes(&spectrum).unwrap()).unwrap();
+        let velocities = read.velocities().unwrap();
+
+        assert_eq!(read.name, "CO(1-0)");
+        assert_eq!(read.intensity, spectrum.intensity);
+        assert_eq!(read.observation, spectrum.observation);
+        assert!(velocities.iter().zip([-1.0, 0.0, 1.0]).all(|(v, w)| (v - w).abs() < 1e-9), "Wrong velocities {:?}", velocities);
+
+        assert!(matches!(read_spectrum(&to_bytes(&[], &[])), Err(SpectrumError::Fits { .. })));
+    }
+
+    #[test]
+    fn read_fits_cube() {
+        let meta = [("CTYPE3", "VRAD"), ("CRVAL3", "0"), ("CDELT3", "1000"), ("CRPIX3", "2"), ("CUNIT3", "m/s"), ("BUNIT", "K")]
+            .map(|(k, v)| (String::from(k), String::from(v)));
+        let data = (0..6).map(|i| i as f64).collect::<Vec<_>>();
+        let mut bytes = vec!();
+        write_image(&[2, 1, 3], &meta, &data, &mut bytes).unwrap();
+
+        let cube = read_cube(&bytes).unwrap();
+
+        assert_eq!((cube.width, cube.height, cube.channels()), (2, 1, 3));
+        assert_eq!(cube.spectral.to_unit("km s-1").unwrap(), vec!(-1.0, 0.0, 1.0));
+        assert_eq!(cube.spectrum(1, 0).intensity.values, vec!(1.0, 3.0, 5.0));
+        assert!(matches!(read_spectrum(&bytes), Err(SpectrumError::Fits { .. })));
+    }
+
+    #[test]
+    fn reject_malformed_images() {
+        let image = |cards: &[String]| {
+            let mut bytes = vec!();
+            write_header(cards, &mut bytes).unwrap();
+            bytes.resize(bytes.len() + BLOCK, 0);
+            read_spectrum(&bytes)
+        };
+        let fits_note = |result: Result<Spectrum, SpectrumError>| match result {
+            Err(SpectrumError::Fits { note }) => note,
+            other => panic!("Wrong result for malformed image: {:?}", other),
+        };
+        let simple = card("SIMPLE", &logical(true), "");
+
+        // A two byte character across the end of the keyword
+        let mut non_ascii = String::from("OBJECTX\u{e9}= 'Orion'").into_bytes();
+        non_ascii.resize(CARD, b' ');
+        let mut bytes = simple.clone().into_bytes();
+        bytes.extend(non_ascii);
+        bytes.extend(format!("{:<80}", "END").into_bytes());
+        bytes.resize(BLOCK, b' ');
+        assert!(matches!(read_spectrum(&bytes), Err(SpectrumError::Fits { note }) if note.contains("not ASCII")));
+
+        // Without a data type nothing limits the size of the axis
+        let no_bitpix = [simple.clone(), card("NAXIS", "1", ""), card("NAXIS1", "100000000000", "")];
+        assert!(fits_note(image(&no_bitpix)).contains("BITPIX"));
+
+        let huge = [
+            simple,
+            card("BITPIX", "-64", ""),
+            card("NAXIS", "2", ""),
+            card("NAXIS1", "4611686018427387904", ""),
+            card("NAXIS2", "4", ""),
+        ];
+        assert_eq!(fits_note(image(&huge)), "image is larger than the address space");
+    }
+}
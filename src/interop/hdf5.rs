@@ -0,0 +1,341 @@
+use std::path::Path;
+
+use ::hdf5::types::VarLenUnicode;
+use ::hdf5::{Group, Location};
+
+use crate::lamda::{CollisionPartnerData, CollisionPartnerId, ElementData};
+use crate::radex::{Conditions, LineResult, Solution};
+use crate::spectrum::Cube;
+
+// Layout of a written file:
+//   /                        attrs: molecule, information, weight [amu]
+//   /levels                  level, energy [cm-1], weight, qnums
+//   /radiative_transitions   transition, up, low, aeinst [s-1], frequency [GHz], upper_energy [K], extra,
+//                            einstein_a [s-1] (nlev x nlev)
+//   /collisions/<partner>    temperatures [K], transition, up, low, rates [cm3 s-1] (ntrans x ntemp),
+//                            rate_matrix [cm3 s-1] (ntemp x nlev x nlev)
+// The matrices are indexed as in DESPOTIC: by the position of the levels in
+// /levels, upper level first, e.g. rate_matrix[t, u, l] is the downward rate
+// from u to l at the t-th temperature. Level pairs without data are zero.
+pub fn write_element_data<P: AsRef<Path>>(data: &ElementData, path: P) -> ::hdf5::Result<()> {
+    let file = ::hdf5::File::create(path)?;
+
+    write_element_data_to_group(data, &file)
+}
+
+pub fn write_element_data_to_group(data: &ElementData, group: &Group) -> ::hdf5::Result<()> {
+    write_string_attr(group, "molecule", &data.name)?;
+    write_string_attr(group, "information", &data.information)?;
+    write_scalar_attr(group, "weight", data.weight, "amu")?;
+
+    let levels = group.create_group("levels")?;
+    write_dataset(&levels, "level", &data.energy_levels.iter().map(|el| el.level).collect::<Vec<_>>(), None)?;
+    write_dataset(&levels, "energy", &data.energy_levels.iter().map(|el| el.energy).collect::<Vec<_>>(), Some("cm-1"))?;
+    write_dataset(&levels, "weight", &data.energy_levels.iter().map(|el| el.stat_weight).collect::<Vec<_>>(), None)?;
+    write_strings(&levels, "qnums", data.energy_levels.iter().map(|el| el.qnums.as_str()))?;
+
+    let transitions = group.create_group("radiative_transitions")?;
+    let radiative = &data.radiative_transitions;
+    write_dataset(&transitions, "transition", &radiative.iter().map(|rt| rt.transition).collect::<Vec<_>>(), None)?;
+    write_dataset(&transitions, "up", &radiative.iter().map(|rt| rt.up).collect::<Vec<_>>(), None)?;
+    write_dataset(&transitions, "low", &radiative.iter().map(|rt| rt.low).collect::<Vec<_>>(), None)?;
+    write_dataset(&transitions, "aeinst", &radiative.iter().map(|rt| rt.aeinst).collect::<Vec<_>>(), Some("s-1"))?;
+    // NaN for transitions without the column
+    let frequency = radiative.iter().map(|rt| rt.frequency.unwrap_or(f64::NAN)).collect::<Vec<_>>();
+    let upper_energy = radiative.iter().map(|rt| rt.upper_energy.unwrap_or(f64::NAN)).collect::<Vec<_>>();
+    write_dataset(&transitions, "frequency", &frequency, Some("GHz"))?;
+    write_dataset(&transitions, "upper_energy", &upper_energy, Some("K"))?;
+    write_strings(&transitions, "extra", radiative.iter().map(|rt| rt.extra.as_str()))?;
+
+    let dataset = transitions.new_dataset_builder().with_data(&data.einstein_a_matrix()).create("einstein_a")?;
+    write_string_attr(&dataset, "units", "s-1")?;
+
+    let collisions = group.create_group("collisions")?;
+    for partner in data.collision_partners.iter() {
+        write_collision_partner(partner, data, &collisions)?;
+    }
+
+    Ok(())
+}
+
+fn write_collision_partner(partner: &CollisionPartnerData, data: &ElementData, collisions: &Group) -> ::hdf5::Result<()> {
+    let group = collisions.create_group(&partner.name.to_string())?;
+    write_scalar_attr(&group, "id", partner.name.code(), "")?;
+    write_string_attr(&group, "information", &partner.information)?;
+
+    let rates = &partner.rates;
+    write_dataset(&group, "temperatures", &partner.temperatures, Some("K"))?;
+    write_dataset(&group, "transition", &rates.iter().map(|cr| cr.transition).collect::<Vec<_>>(), None)?;
+    write_dataset(&group, "up", &rates.iter().map(|cr| cr.up).collect::<Vec<_>>(), None)?;
+    write_dataset(&group, "low", &rates.iter().map(|cr| cr.low).collect::<Vec<_>>(), None)?;
+
+    let ntemp = partner.temperatures.len();
+    let matrix = ndarray::Array2::from_shape_fn((rates.len(), ntemp), |(i, j)| {
+        rates[i].rates.get(j).copied().unwrap_or(f64::NAN)
+    });
+    let dataset = group.new_dataset_builder().with_data(&matrix).create("rates")?;
+    write_string_attr(&dataset, "units", "cm3 s-1")?;
+
+    // Groups are named after the partner id, so the id finds this partner
+    if let Some(rate_matrix) = data.rate_matrices(partner.name.clone()) {
+        let dataset = group.new_dataset_builder().with_data(&rate_matrix).create("rate_matrix")?;
+        write_string_attr(&dataset, "units", "cm3 s-1")?;
+    }
+
+    Ok(())
+}
+
+// Layout of a written grid of models, one row per grid point:
+//   /                        attrs: molecule
+//   /conditions              tkin [K], tbg [K], cdmol [cm-2], linewidth [km s-1], geometry,
+//                            densities/<partner> [cm-3]
+//   /lines                   transition, up, low, qnums_up, qnums_low, e_up [K], frequency [GHz],
+//                            wavelength [um]
+//   /results                 tex [K], tau, t_r [K], pop_up, pop_low, flux_kkms [K km s-1],
+//                            flux_cgs [erg cm-2 s-1] (npoint x nline),
+//                            populations (npoint x nlev), iterations
+// All points need the lines of the first one.
+pub fn write_grid_results<P: AsRef<Path>>(name: &str, points: &[(Conditions, Solution)], path: P) -> ::hdf5::Result<()> {
+    let file = ::hdf5::File::create(path)?;
+
+    write_grid_results_to_group(name, points, &file)
+}
+
+pub fn write_grid_results_to_group(name: &str, points: &[(Conditions, Solution)], group: &Group) -> ::hdf5::Result<()> {
+    let lines = points.first().map(|(_, solution)| solution.lines.as_slice()).unwrap_or(&[]);
+    let nlev = points.first().map(|(_, solution)| solution.populations.len()).unwrap_or(0);
+    let same_lines = |solution: &Solution| {
+        solution.populations.len() == nlev
+            && solution.lines.len() == lines.len()
+            && solution.lines.iter().zip(lines.iter()).all(|(a, b)| a.transition == b.transition)
+    };
+    if let Some(i) = points.iter().position(|(_, solution)| !same_lines(solution)) {
+        return Err(::hdf5::Error::from(format!("grid point {} does not have the lines of the first point", i)));
+    }
+
+    write_string_attr(group, "molecule", name)?;
+
+    let conditions = group.create_group("conditions")?;
+    let condition = |f: fn(&Conditions) -> f64| points.iter().map(|(c, _)| f(c)).collect::<Vec<_>>();
+    write_dataset(&conditions, "tkin", &condition(|c| c.tkin), Some("K"))?;
+    write_dataset(&conditions, "tbg", &condition(|c| c.tbg), Some("K"))?;
+    write_dataset(&conditions, "cdmol", &condition(|c| c.cdmol), Some("cm-2"))?;
+    write_dataset(&conditions, "linewidth", &condition(|c| c.linewidth), Some("km s-1"))?;
+    write_dataset(&conditions, "geometry", &points.iter().map(|(c, _)| u32::from(c.geometry)).collect::<Vec<_>>(), None)?;
+
+    let mut partners: Vec<CollisionPartnerId> = vec!();
+    for (conditions, _) in points.iter() {
+        for (partner, _) in conditions.densities.iter() {
+            if !partners.contains(partner) {
+                partners.push(partner.clone());
+            }
+        }
+    }
+    let densities = conditions.create_group("densities")?;
+    for partner in partners {
+        let density = points
+            .iter()
+            .map(|(c, _)| c.densities.iter().find(|(p, _)| *p == partner).map(|(_, n)| *n).unwrap_or(0.0))
+            .collect::<Vec<_>>();
+        write_dataset(&densities, &partner.to_string(), &density, Some("cm-3"))?;
+    }
+
+    let group_lines = group.create_group("lines")?;
+    let line = |f: fn(&LineResult) -> f64| lines.iter().map(f).collect::<Vec<_>>();
+    write_dataset(&group_lines, "transition", &lines.iter().map(|l| l.transition).collect::<Vec<_>>(), None)?;
+    write_dataset(&group_lines, "up", &lines.iter().map(|l| l.up).collect::<Vec<_>>(), None)?;
+    write_dataset(&group_lines, "low", &lines.iter().map(|l| l.low).collect::<Vec<_>>(), None)?;
+    write_strings(&group_lines, "qnums_up", lines.iter().map(|l| l.qnums_up.as_str()))?;
+    write_strings(&group_lines, "qnums_low", lines.iter().map(|l| l.qnums_low.as_str()))?;
+    write_dataset(&group_lines, "e_up", &line(|l| l.e_up), Some("K"))?;
+    write_dataset(&group_lines, "frequency", &line(|l| l.frequency), Some("GHz"))?;
+    write_dataset(&group_lines, "wavelength", &line(|l| l.wavelength), Some("um"))?;
+
+    let results = group.create_group("results")?;
+    let result = |name: &str, f: fn(&LineResult) -> f64, units: &str| -> ::hdf5::Result<()> {
+        let matrix = ndarray::Array2::from_shape_fn((points.len(), lines.len()), |(i, j)| f(&points[i].1.lines[j]));
+        let dataset = results.new_dataset_builder().with_data(&matrix).create(name)?;
+        if !units.is_empty() {
+            write_string_attr(&dataset, "units", units)?;
+        }
+        Ok(())
+    };
+    result("tex", |l| l.tex, "K")?;
+    result("tau", |l| l.tau, "")?;
+    result("t_r", |l| l.t_r, "K")?;
+    result("pop_up", |l| l.pop_up, "")?;
+    result("pop_low", |l| l.pop_low, "")?;
+    result("flux_kkms", |l| l.flux_kkms, "K km s-1")?;
+    result("flux_cgs", |l| l.flux_cgs, "erg cm-2 s-1")?;
+
+    let populations = ndarray::Array2::from_shape_fn((points.len(), nlev), |(i, j)| points[i].1.populations[j]);
+    results.new_dataset_builder().with_data(&populations).create("populations")?;
+    let iterations = points.iter().map(|(_, solution)| solution.iterations as u64).collect::<Vec<_>>();
+    write_dataset(&results, "iterations", &iterations, None)?;
+
+    Ok(())
+}
+
+// Layout of a written cube:
+//   /                        attrs: name, rest_frequency [GHz]
+//   /spectral                axis values, attr units
+//   /data                    intensity (nchan x height x width), attr units
+// The data is stored as in FITS and `Cube::data`, x varying fastest.
+pub fn write_cube<P: AsRef<Path>>(cube: &Cube, path: P) -> ::hdf5::Result<()> {
+    let file = ::hdf5::File::create(path)?;
+
+    write_cube_to_group(cube, &file)
+}
+
+pub fn write_cube_to_group(cube: &Cube, group: &Group) -> ::hdf5::Result<()> {
+    write_string_attr(group, "name", &cube.name)?;
+    write_scalar_attr(group, "rest_frequency", cube.rest_frequency, "GHz")?;
+
+    write_dataset(group, "spectral", &cube.spectral.values, Some(&cube.spectral.unit))?;
+
+    let data = ndarray::ArrayView3::from_shape((cube.channels(), cube.height, cube.width), &cube.data)
+        .map_err(|e| ::hdf5::Error::from(e.to_string()))?;
+    let dataset = group.new_dataset_builder().with_data(data).create("data")?;
+    write_string_attr(&dataset, "units", &cube.intensity_unit)?;
+
+    Ok(())
+}
+
+fn write_dataset<T: ::hdf5::H5Type>(
+    group: &Group,
+    name: &str,
+    values: &[T],
+    units: Option<&str>
+) -> ::hdf5::Result<()> {
+    let dataset = group.new_dataset_builder().with_data(values).create(name)?;
+    if let Some(units) = units {
+        write_string_attr(&dataset, "units", units)?;
+    }
+
+    Ok(())
+}
+
+fn write_strings<'a>(group: &Group, name: &str, values: impl Iterator<Item = &'a str>) -> ::hdf5::Result<()> {
+    let values = values
+        .map(to_varlen)
+        .collect::<::hdf5::Result<Vec<_>>>()?;
+    group.new_dataset_builder().with_data(&values).create(name)?;
+
+    Ok(())
+}
+
+fn write_string_attr(location: &Location, name: &str, value: &str) -> ::hdf5::Result<()> {
+    location
+        .new_attr::<VarLenUnicode>()
+        .create(name)?
+        .write_scalar(&to_varlen(value)?)
+}
+
+fn write_scalar_attr<T: ::hdf5::H5Type>(location: &Location, name: &str, value: T, units: &str) -> ::hdf5::Result<()> {
+    location.new_attr::<T>().create(name)?.write_scalar(&value)?;
+    if !units.is_empty() {
+        write_string_attr(location, &format!("{}_units", name), units)?;
+    }
+
+    Ok(())
+}
+
+fn to_varlen(s: &str) -> ::hdf5::Result<VarLenUnicode> {
+    s.parse::<VarLenUnicode>().map_err(|e| ::hdf5::Error::from(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const CO: &str = r#"!MOLECULE
+        CO
+        !MOLECULAR WEIGHT
+        28.0
+        !NUMBER OF ENERGY LEVELS
+        2
+        !LEVEL + ENERGIES(cm^-1) + WEIGHT + J
+            1     0.000000000  1.0     0
+            2     3.845033413  3.0     1
+        !NUMBER OF RADIATIVE TRANSITIONS
+        1
+        !TRANS + UP + LOW + EINSTEINA(s^-1) + FREQ(GHz) + E_u(K)
+            1     2     1  7.203e-08          115.2712018     5.53
+        !NUMBER OF COLL PARTNERS
+        1
+        !COLLISIONS BETWEEN
+        2 CO-pH2 from Yang et al. (2010)
+        !NUMBER OF COLL TRANS
+        1
+        !NUMBER OF COLL TEMPS
+        2
+        !COLL TEMPS
+            10.0  20.0
+        !TRANS + UP + LOW + COLLRATES(cm^3 s^-1)
+            1     2     1  3.3e-11  3.4e-11
+        "#;
+
+    #[test]
+    fn write_element_data_layout() -> ::hdf5::Result<()> {
+        let ed = CO.parse::<ElementData>().unwrap();
+
+        let path = std::env::temp_dir().join("ism_write_element_data_layout.h5");
+        write_element_data(&ed, &path)?;
+
+        let file = ::hdf5::File::open(&path)?;
+        let energy: Vec<f64> = file.dataset("levels/energy")?.read_raw()?;
+        let rates = file.dataset("collisions/p-H2/rates")?;
+        let rate_matrix: ndarray::Array3<f64> = file.dataset("collisions/p-H2/rate_matrix")?.read()?;
+        let einstein_a: ndarray::Array2<f64> = file.dataset("radiative_transitions/einstein_a")?.read()?;
+
+        assert_eq!(energy, vec!(0.0, 3.845033413));
+        assert_eq!(rates.shape(), vec!(1, 2));
+        assert_eq!(rate_matrix.shape(), &[2, 2, 2]);
+        assert_eq!((rate_matrix[[0, 1, 0]], rate_matrix[[1, 1, 0]], rate_matrix[[1, 0, 1]]), (3.3e-11, 3.4e-11, 0.0));
+        assert_eq!(einstein_a[[1, 0]], 7.203e-08, "Wrong result for the Einstein A matrix");
+
+        std::fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn write_grid_and_cube_layout() -> ::hdf5::Result<()> {
+        let ed = CO.parse::<ElementData>().unwrap();
+        let points = [10.0, 20.0, 40.0]
+            .iter()
+            .map(|&tkin| {
+                let conditions = Conditions { tkin, densities: vec!((CollisionPartnerId::pH2, 1.0e4)), ..Default::default() };
+                let solution = crate::radex::solve(&ed, &conditions).unwrap();
+                (conditions, solution)
+            })
+            .collect::<Vec<_>>();
+
+        let path = std::env::temp_dir().join("ism_write_grid_layout.h5");
+        write_grid_results(&ed.name, &points, &path)?;
+
+        let file = ::hdf5::File::open(&path)?;
+        let tkin: Vec<f64> = file.dataset("conditions/tkin")?.read_raw()?;
+        let density: Vec<f64> = file.dataset("conditions/densities/p-H2")?.read_raw()?;
+        let tex: ndarray::Array2<f64> = file.dataset("results/tex")?.read()?;
+
+        assert_eq!(tkin, vec!(10.0, 20.0, 40.0));
+        assert_eq!(density, vec!(1.0e4; 3));
+        assert_eq!(tex.shape(), &[3, ed.radiative_transitions.len()]);
+        assert_eq!(tex[[2, 0]], points[2].1.lines[0].tex, "Wrong result for the excitation temperatures");
+        std::fs::remove_file(&path).ok();
+
+        let axis = crate::spectrum::Axis::new("km s-1", vec!(-1.0, 0.0, 1.0));
+        let cube = Cube::new("CO", 115.2712018, (2, 1), axis, "K", vec!(0.0, 1.0, 2.0, 3.0, 4.0, 5.0)).unwrap();
+        let path = std::env::temp_dir().join("ism_write_cube_layout.h5");
+        write_cube(&cube, &path)?;
+
+        let file = ::hdf5::File::open(&path)?;
+        let data: ndarray::Array3<f64> = file.dataset("data")?.read()?;
+        assert_eq!(data.shape(), &[3, 1, 2]);
+        assert_eq!(data[[1, 0, 1]], 3.0, "Wrong result for the cube data");
+
+        std::fs::remove_file(&path).ok();
+        Ok(())
+    }
+}
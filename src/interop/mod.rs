@@ -0,0 +1,15 @@
+pub mod table;
+pub mod csv;
+pub mod ecsv;
+pub mod votable;
+pub mod fits;
+pub mod sdfits;
+pub mod class;
+#[cfg(feature = "json")]
+pub mod spectralradex;
+#[cfg(feature = "json")]
+pub mod schema;
+#[cfg(feature = "hdf5")]
+pub mod hdf5;
+#[cfg(feature = "arrow")]
+pub mod arrow;
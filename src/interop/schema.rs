@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::lamda::{CollisionPartnerData, ElementData};
+
+// Version of the layout below, raised on any change that breaks readers
+pub const SCHEMA_VERSION: u32 = 1;
+
+// Molecular data as exported to JSON, TOML and YAML, for tools that do not
+// read LAMDA files. Field names are fixed; units are those of the datafiles:
+//   schema         SCHEMA_VERSION
+//   molecule       name, information, weight [amu]
+//   levels         [{ level, energy [cm-1], weight, qnums }]
+//   transitions    [{ transition, up, low, einstein_a [s-1], frequency [GHz]?, upper_energy [K]? }]
+//   partners       [{ partner, id, information, temperatures [K],
+//                     rates: [{ transition, up, low, rates [cm3 s-1] }] }]
+// `rates` of a transition has one value per temperature. Optional fields are
+// left out when the datafile has no value for them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MolecularData {
+    pub schema: u32,
+    pub molecule: Molecule,
+    pub levels: Vec<Level>,
+    pub transitions: Vec<Transition>,
+    pub partners: Vec<Partner>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Molecule {
+    pub name: String,
+    pub information: String,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Level {
+    pub level: u32,
+    pub energy: f64,
+    pub weight: f64,
+    pub qnums: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub einstein_a: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upper_energy: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Partner {
+    pub partner: String,
+    pub id: u32,
+    pub information: String,
+    pub temperatures: Vec<f64>,
+    pub rates: Vec<Rates>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rates {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub rates: Vec<f64>,
+}
+
+impl From<&CollisionPartnerData> for Partner {
+    fn from(partner: &CollisionPartnerData) -> Self {
+        Self {
+            partner: partner.name.to_string(),
+            id: partner.name.code(),
+            information: partner.information.clone(),
+            temperatures: partner.temperatures.clone(),
+            rates: partner.rates
+                .iter()
+                .map(|cr| Rates { transition: cr.transition, up: cr.up, low: cr.low, rates: cr.rates.clone() })
+                .collect(),
+        }
+    }
+}
+
+impl From<&ElementData> for MolecularData {
+    fn from(data: &ElementData) -> Self {
+        Self {
+            schema: SCHEMA_VERSION,
+            molecule: Molecule { name: data.name.clone(), information: data.information.clone(), weight: data.weight },
+            levels: data.energy_levels
+                .iter()
+                .map(|el| Level { level: el.level, energy: el.energy, weight: el.stat_weight, qnums: el.qnums.clone() })
+                .collect(),
+            transitions: data.radiative_transitions
+                .iter()
+                .map(|rt| Transition {
+                    transition: rt.transition,
+                    up: rt.up,
+                    low: rt.low,
+                    einstein_a: rt.aeinst,
+                    frequency: rt.frequency,
+                    upper_energy: rt.upper_energy,
+                })
+                .collect(),
+            partners: data.collision_partners.iter().map(Partner::from).collect(),
+        }
+    }
+}
+
+pub fn to_json(data: &ElementData) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&MolecularData::from(data))
+}
+
+#[cfg(feature = "config")]
+pub fn to_toml(data: &ElementData) -> Result<String, toml::ser::Error> {
+    toml::to_string(&MolecularData::from(data))
+}
+
+#[cfg(feature = "config")]
+pub fn to_yaml(data: &ElementData) -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_string(&MolecularData::from(data))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn export_schema() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let expected = MolecularData::from(&data);
+        assert_eq!(expected.partners[0].rates[0].rates.len(), expected.partners[0].temperatures.len());
+
+        let json = to_json(&data).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema"], SCHEMA_VERSION);
+        assert_eq!(value["molecule"]["name"], "O");
+        assert_eq!(value["levels"][1]["energy"], data.energy_levels()[1].energy);
+        assert_eq!(value["partners"][0]["id"], data.collision_partners()[0].name.code());
+        assert_eq!(serde_json::from_str::<MolecularData>(&json).unwrap(), expected, "Wrong result for JSON");
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn export_toml_and_yaml() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let expected = MolecularData::from(&data);
+
+        let toml = to_toml(&data).unwrap();
+        assert_eq!(toml::from_str::<MolecularData>(&toml).unwrap(), expected, "Wrong result for TOML");
+        let yaml = to_yaml(&data).unwrap();
+        assert_eq!(serde_yaml::from_str::<MolecularData>(&yaml).unwrap(), expected, "Wrong result for YAML");
+    }
+}
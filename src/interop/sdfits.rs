@@ -0,0 +1,122 @@
+use std::io::{Error, ErrorKind, Write};
+
+use crate::iau::unit_string::UnitError;
+use crate::spectrum::{Axis, Spectrum};
+
+use super::fits::{self, Field};
+use super::table::{Column, ColumnData};
+
+fn invalid(spectrum: &Spectrum, note: &str) -> Error {
+    Error::new(ErrorKind::InvalidInput, format!("cannot write spectrum `{}` to SDFITS: {}", spectrum.name, note))
+}
+
+// Reference value, increment and width in Hz of the first channel of a
+// spectrum; velocities are written in m s-1 with CTYPE1 = 'VRAD', frequencies
+// in Hz with CTYPE1 = 'FREQ'.
+fn spectral_axis(spectrum: &Spectrum) -> std::io::Result<(&'static str, f64, f64, f64)> {
+    let unit_error = |e: UnitError| invalid(spectrum, &e.to_string());
+    let frequencies = spectrum.frequencies().map_err(unit_error)?;
+    let (ctype, axis) = match spectrum.has_velocity_axis() {
+        true => ("VRAD", spectrum.spectral.to_unit("m s-1").map_err(unit_error)?),
+        false => ("FREQ", frequencies.iter().map(|f| f * 1e9).collect()),
+    };
+    let step = match axis.len() {
+        1 => 0.0,
+        _ => Axis::new("", axis.clone())
+            .step()
+            .ok_or_else(|| invalid(spectrum, "channels are not evenly spaced"))?,
+    };
+    let width = match frequencies.len() {
+        1 => 0.0,
+        n => (frequencies[n - 1] - frequencies[0]).abs() * 1e9 * n as f64 / (n - 1) as f64,
+    };
+
+    Ok((ctype, axis[0], step, width))
+}
+
+// Writes the spectra as the rows of a single SINGLE DISH binary table, the
+// layout read by GBTIDL and CLASS. All spectra need the same number of
+// evenly spaced channels.
+pub fn write<W: Write>(spectra: &[Spectrum], writer: &mut W) -> std::io::Result<()> {
+    let channels = spectra.first().map(|s| s.len()).unwrap_or(0);
+    let mut axes = vec!();
+    for spectrum in spectra.iter() {
+        if spectrum.is_empty() || spectrum.len() != channels {
+            return Err(invalid(spectrum, "all spectra need the same, non-zero number of channels"));
+        }
+        axes.push(spectral_axis(spectrum)?);
+    }
+
+    let text = |f: fn(&Spectrum) -> String| ColumnData::Text(spectra.iter().map(f).collect());
+    let float = |f: fn(&Spectrum) -> f64| ColumnData::Float(spectra.iter().map(f).collect());
+    let hz = || Some(String::from("Hz"));
+    let columns = vec!(
+        Column::new("OBJECT", None, "Name of the source", text(|s| s.observation.object.clone())),
+        Column::new("TELESCOP", None, "Telescope", text(|s| s.observation.telescope.clone())),
+        Column::new("DATE-OBS", None, "Date of the observation", text(|s| s.observation.date.clone())),
+        Column::new("EXPOSURE", Some(String::from("s")), "Integration time", float(|s| s.observation.exposure)),
+        Column::new("TSYS", Some(String::from("K")), "System temperature", float(|s| s.observation.tsys)),
+        Column::new("BANDWID", hz(), "Bandwidth", ColumnData::Float(axes.iter().map(|a| a.3).collect())),
+        Column::new("CTYPE1", None, "Spectral axis", ColumnData::Text(axes.iter().map(|a| String::from(a.0)).collect())),
+        Column::new("CRVAL1", None, "Spectral value of the reference channel", ColumnData::Float(axes.iter().map(|a| a.1).collect())),
+        Column::new("CRPIX1", None, "Reference channel", ColumnData::Float(vec!(1.0; spectra.len()))),
+        Column::new("CDELT1", None, "Channel spacing", ColumnData::Float(axes.iter().map(|a| a.2).collect())),
+        Column::new("CTYPE2", None, "", ColumnData::Text(vec!(String::from("RA"); spectra.len()))),
+        Column::new("CRVAL2", Some(String::from("deg")), "Right ascension (J2000)", float(|s| s.observation.ra)),
+        Column::new("CTYPE3", None, "", ColumnData::Text(vec!(String::from("DEC"); spectra.len()))),
+        Column::new("CRVAL3", Some(String::from("deg")), "Declination (J2000)", float(|s| s.observation.dec)),
+        Column::new("RESTFREQ", hz(), "Rest frequency", float(|s| s.rest_frequency * 1e9)),
+        Column::new("VELDEF", None, "Velocity definition", ColumnData::Text(vec!(String::from("RADI-LSR"); spectra.len()))),
+    );
+
+    let mut fields = columns.iter().map(Field::Scalar).collect::<Vec<_>>();
+    fields.push(Field::Array {
+        name: "DATA",
+        unit: spectra.first().map(|s| s.intensity.unit.as_str()),
+        rows: spectra.iter().map(|s| s.intensity.values.as_slice()).collect(),
+    });
+
+    let meta = vec!(
+        (String::from("NMATRIX"), String::from("1")),
+        (String::from("MAXIS"), String::from("1")),
+        (String::from("MAXIS1"), channels.to_string()),
+    );
+    fits::write_primary(&[], writer)?;
+    fits::write_extension("SINGLE DISH", &meta, &fields, writer)
+}
+
+pub fn to_bytes(spectra: &[Spectrum]) -> std::io::Result<Vec<u8>> {
+    let mut buffer: Vec<u8> = vec!();
+    write(spectra, &mut buffer)?;
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::spectrum::Observation;
+
+    #[test]
+    fn write_sdfits_spectrum() {
+        let spectrum = Spectrum {
+            name: String::from("CO"),
+            rest_frequency: 115.271_202,
+            spectral: Axis::new("km s-1", vec!(-1.0, 0.0, 1.0)),
+            intensity: Axis::new("K", vec!(0.5, 2.0, 0.5)),
+            observation: Observation { object: String::from("TMC-1"), telescope: String::from("IRAM30M"), ..Default::default() },
+        };
+
+        let bytes = to_bytes(std::slice::from_ref(&spectrum)).unwrap();
+        let header = String::from_utf8_lossy(&bytes[2880..]).into_owned();
+
+        assert_eq!(bytes.len(), 4 * 2880, "Wrong size of a two block extension header and one data block");
+        assert!(header.contains("EXTNAME = 'SINGLE DISH'"), "Missing extension name in `{}`", header);
+        assert!(header.contains("TFORM17 = '3D      '"), "Wrong DATA column in `{}`", header);
+        assert!(header.contains("MAXIS1  =                    3"));
+
+        let uneven = Spectrum { spectral: Axis::new("km s-1", vec!(-1.0, 0.0, 3.0)), ..spectrum };
+        assert_eq!(to_bytes(&[uneven]).unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,524 @@
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::table::{Column, ColumnData, Table};
+use crate::lamda::{
+    CollisionPartnerData,
+    CollisionPartnerId,
+    CollisionalRates,
+    ElementData,
+    EnergyLevel,
+    FormatFeatures,
+    transition_columns,
+    RadiativeTransition,
+};
+use crate::radex::LineResult;
+
+pub use crate::radex::Geometry;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum GeometryRepr {
+    Code(u32),
+    Name(String),
+}
+
+impl TryFrom<GeometryRepr> for Geometry {
+    type Error = String;
+
+    fn try_from(item: GeometryRepr) -> Result<Self, Self::Error> {
+        match item {
+            GeometryRepr::Code(1) => Ok(Geometry::Sphere),
+            GeometryRepr::Code(2) => Ok(Geometry::LargeVelocityGradient),
+            GeometryRepr::Code(3) => Ok(Geometry::Slab),
+            GeometryRepr::Name(name) => match name.to_ascii_lowercase().as_str() {
+                "uni" | "sphere" => Ok(Geometry::Sphere),
+                "lvg" => Ok(Geometry::LargeVelocityGradient),
+                "slab" => Ok(Geometry::Slab),
+                _ => Err(format!("unknown geometry `{}`", name)),
+            },
+            GeometryRepr::Code(n) => Err(format!("unknown geometry code {}", n)),
+        }
+    }
+}
+
+// Keys follow the SpectralRadex parameter dictionary; the aliases accept the
+// keyword names of `ndradex.run`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameters {
+    #[serde(alias = "query")]
+    pub molfile: String,
+    #[serde(alias = "T_kin")]
+    pub tkin: f64,
+    #[serde(alias = "T_bg")]
+    pub tbg: f64,
+    #[serde(alias = "N_mol")]
+    pub cdmol: f64,
+    #[serde(alias = "n_H2", default)]
+    pub h2: f64,
+    #[serde(alias = "n_H", default)]
+    pub h: f64,
+    #[serde(rename = "e-", alias = "n_e", default)]
+    pub e: f64,
+    #[serde(rename = "p-h2", alias = "n_pH2", default)]
+    pub ph2: f64,
+    #[serde(rename = "o-h2", alias = "n_oH2", default)]
+    pub oh2: f64,
+    #[serde(rename = "h+", alias = "n_Hp", default)]
+    pub hp: f64,
+    #[serde(alias = "n_He", default)]
+    pub he: f64,
+    #[serde(alias = "dv")]
+    pub linewidth: f64,
+    #[serde(default)]
+    pub fmin: f64,
+    #[serde(default = "default_fmax")]
+    pub fmax: f64,
+    #[serde(alias = "geom")]
+    pub geometry: Geometry,
+}
+
+fn default_fmax() -> f64 {
+    1000.0
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            molfile: String::from("co.dat"),
+            tkin: 30.0,
+            tbg: 2.73,
+            cdmol: 1.0e13,
+            h2: 1.0e5,
+            h: 0.0,
+            e: 0.0,
+            ph2: 0.0,
+            oh2: 0.0,
+            hp: 0.0,
+            he: 0.0,
+            linewidth: 1.0,
+            fmin: 0.0,
+            fmax: default_fmax(),
+            geometry: Geometry::Sphere,
+        }
+    }
+}
+
+impl Parameters {
+    pub fn from_json(s: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    // SpectralRadex has densities of the LAMDA partners only
+    pub fn density(&self, partner: CollisionPartnerId) -> Result<f64, String> {
+        match partner {
+            CollisionPartnerId::H2 => Ok(self.h2),
+            CollisionPartnerId::pH2 => Ok(self.ph2),
+            CollisionPartnerId::oH2 => Ok(self.oh2),
+            CollisionPartnerId::electrons => Ok(self.e),
+            CollisionPartnerId::HI => Ok(self.h),
+            CollisionPartnerId::He => Ok(self.he),
+            CollisionPartnerId::HII => Ok(self.hp),
+            CollisionPartnerId::Other(..) => Err(format!("no density parameter for collision partner `{}`", partner)),
+        }
+    }
+}
+
+// One row of the SpectralRadex output frame (`DataFrame.to_json(orient="records")`);
+// the aliases accept the variable names of the ndradex dataset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultRow {
+    #[serde(rename = "QN Upper")]
+    pub qn_upper: String,
+    #[serde(rename = "QN Lower")]
+    pub qn_lower: String,
+    #[serde(rename = "E_UP (K)", alias = "E_up")]
+    pub e_up: f64,
+    #[serde(rename = "freq")]
+    pub frequency: f64,
+    #[serde(rename = "WAVEL (um)", alias = "wavel")]
+    pub wavelength: f64,
+    #[serde(rename = "T_ex")]
+    pub tex: f64,
+    #[serde(rename = "tau")]
+    pub tau: f64,
+    #[serde(rename = "T_R (K)", alias = "T_r")]
+    pub t_r: f64,
+    #[serde(rename = "POP UP", alias = "pop_u")]
+    pub pop_up: f64,
+    #[serde(rename = "POP LOW", alias = "pop_l")]
+    pub pop_low: f64,
+    #[serde(rename = "FLUX (K*km/s)", alias = "I")]
+    pub flux_kkms: f64,
+    #[serde(rename = "FLUX (Erg/cm2/s)", alias = "F")]
+    pub flux_cgs: f64,
+}
+
+impl From<&LineResult> for ResultRow {
+    fn from(item: &LineResult) -> Self {
+        Self {
+            qn_upper: item.qnums_up.clone(),
+            qn_lower: item.qnums_low.clone(),
+            e_up: item.e_up,
+            frequency: item.frequency,
+            wavelength: item.wavelength,
+            tex: item.tex,
+            tau: item.tau,
+            t_r: item.t_r,
+            pop_up: item.pop_up,
+            pop_low: item.pop_low,
+            flux_kkms: item.flux_kkms,
+            flux_cgs: item.flux_cgs,
+        }
+    }
+}
+
+pub fn results_from_json(s: &str) -> Result<Vec<ResultRow>, serde_json::Error> {
+    serde_json::from_str(s)
+}
+
+pub fn results_to_json(results: &[ResultRow]) -> Result<String, serde_json::Error> {
+    serde_json::to_string(results)
+}
+
+pub fn results_table(name: &str, results: &[ResultRow]) -> Table {
+    let float = |f: fn(&ResultRow) -> f64| ColumnData::Float(results.iter().map(f).collect());
+    let text = |f: fn(&ResultRow) -> &String| ColumnData::Text(results.iter().map(|r| f(r).clone()).collect());
+
+    Table {
+        name: String::from(name),
+        meta: vec!(),
+        columns: vec!(
+            Column::new("qn_up", None, "Upper level quantum numbers", text(|r| &r.qn_upper)),
+            Column::new("qn_low", None, "Lower level quantum numbers", text(|r| &r.qn_lower)),
+            Column::new("e_up", Some(String::from("K")), "Upper level energy", float(|r| r.e_up)),
+            Column::new("freq", Some(String::from("GHz")), "Rest frequency", float(|r| r.frequency)),
+            Column::new("wavel", Some(String::from("um")), "Rest wavelength", float(|r| r.wavelength)),
+            Column::new("t_ex", Some(String::from("K")), "Excitation temperature", float(|r| r.tex)),
+            Column::new("tau", None, "Optical depth", float(|r| r.tau)),
+            Column::new("t_r", Some(String::from("K")), "Radiation temperature", float(|r| r.t_r)),
+            Column::new("pop_up", None, "Upper level population", float(|r| r.pop_up)),
+            Column::new("pop_low", None, "Lower level population", float(|r| r.pop_low)),
+            Column::new("flux_kkms", Some(String::from("K km s-1")), "Integrated intensity", float(|r| r.flux_kkms)),
+            Column::new("flux", Some(String::from("erg cm-2 s-1")), "Line flux", float(|r| r.flux_cgs)),
+        ),
+    }
+}
+
+// Molecular data dictionary in the table layout of `astroquery.lamda`, which
+// both Python packages use to read LAMDA files.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct MoleculeDict {
+    molecule: String,
+    #[serde(default)]
+    information: String,
+    weight: f64,
+    enlevels: Vec<EnergyLevelRow>,
+    radtransitions: Vec<RadiativeTransitionRow>,
+    #[serde(serialize_with = "serialize_in_order", deserialize_with = "deserialize_in_order")]
+    collrates: Vec<(String, CollisionPartnerDict)>,
+}
+
+// `collrates` is a JSON object keyed by partner, kept in the order of the
+// partners in the datafile
+fn serialize_in_order<S: Serializer>(partners: &[(String, CollisionPartnerDict)], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(partners.len()))?;
+    for (key, partner) in partners.iter() {
+        map.serialize_entry(key, partner)?;
+    }
+    map.end()
+}
+
+fn deserialize_in_order<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<(String, CollisionPartnerDict)>, D::Error> {
+    struct InOrder;
+
+    impl<'de> Visitor<'de> for InOrder {
+        type Value = Vec<(String, CollisionPartnerDict)>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a map of collision partners")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut partners = vec!();
+            while let Some(entry) = map.next_entry()? {
+                partners.push(entry);
+            }
+            Ok(partners)
+        }
+    }
+
+    deserializer.deserialize_map(InOrder)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct EnergyLevelRow {
+    #[serde(rename = "Level")]
+    level: u32,
+    #[serde(rename = "Energy")]
+    energy: f64,
+    #[serde(rename = "Weight")]
+    weight: f64,
+    #[serde(rename = "J", default)]
+    qnums: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct RadiativeTransitionRow {
+    #[serde(rename = "Transition")]
+    transition: u32,
+    #[serde(rename = "Upper")]
+    upper: u32,
+    #[serde(rename = "Lower")]
+    lower: u32,
+    #[serde(rename = "EinsteinA")]
+    aeinst: f64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    extra: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CollisionPartnerDict {
+    #[serde(default)]
+    information: String,
+    temperatures: Vec<f64>,
+    rates: Vec<CollisionalRatesRow>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CollisionalRatesRow {
+    #[serde(rename = "Transition")]
+    transition: u32,
+    #[serde(rename = "Upper")]
+    upper: u32,
+    #[serde(rename = "Lower")]
+    lower: u32,
+    #[serde(rename = "C_ij")]
+    rates: Vec<f64>,
+}
+
+fn collision_partner_key(id: &CollisionPartnerId) -> String {
+    let key = match id {
+        CollisionPartnerId::H2 => "H2",
+        CollisionPartnerId::pH2 => "PH2",
+        CollisionPartnerId::oH2 => "OH2",
+        CollisionPartnerId::electrons => "E",
+        CollisionPartnerId::HI => "H",
+        CollisionPartnerId::He => "HE",
+        CollisionPartnerId::HII => "H+",
+        // Other partners are kept under their name, or their code without one
+        CollisionPartnerId::Other(code, name) if name.is_empty() => return code.to_string(),
+        CollisionPartnerId::Other(_, name) => name.as_str(),
+    };
+
+    String::from(key)
+}
+
+fn collision_partner_from_key(key: &str) -> Option<CollisionPartnerId> {
+    match key.to_ascii_uppercase().as_str() {
+        "H2" => Some(CollisionPartnerId::H2),
+        "PH2" => Some(CollisionPartnerId::pH2),
+        "OH2" => Some(CollisionPartnerId::oH2),
+        "E" => Some(CollisionPartnerId::electrons),
+        "H" => Some(CollisionPartnerId::HI),
+        "HE" => Some(CollisionPartnerId::He),
+        "H+" => Some(CollisionPartnerId::HII),
+        _ => match key.parse::<u32>() {
+            Ok(code) => Some(CollisionPartnerId::from_code(code)),
+            Err(_) => CollisionPartnerId::from_name(key),
+        },
+    }
+}
+
+pub fn element_data_to_json(data: &ElementData) -> Result<String, serde_json::Error> {
+    let dict = MoleculeDict {
+        molecule: data.name.clone(),
+        information: data.information.clone(),
+        weight: data.weight,
+        enlevels: data.energy_levels
+            .iter()
+            .map(|el| EnergyLevelRow {
+                level: el.level,
+                energy: el.energy,
+                weight: el.stat_weight,
+                qnums: el.qnums.clone(),
+            })
+            .collect(),
+        radtransitions: data.radiative_transitions
+            .iter()
+            .map(|rt| RadiativeTransitionRow {
+                transition: rt.transition,
+                upper: rt.up,
+                lower: rt.low,
+                aeinst: rt.aeinst,
+                extra: rt.columns(),
+            })
+            .collect(),
+        collrates: data.collision_partners
+            .iter()
+            .map(|cp| (
+                collision_partner_key(&cp.name),
+                CollisionPartnerDict {
+                    information: cp.information.clone(),
+                    temperatures: cp.temperatures.clone(),
+                    rates: cp.rates
+                        .iter()
+                        .map(|cr| CollisionalRatesRow {
+                            transition: cr.transition,
+                            upper: cr.up,
+                            lower: cr.low,
+                            rates: cr.rates.clone(),
+                        })
+                        .collect(),
+                }
+            ))
+            .collect(),
+    };
+
+    serde_json::to_string(&dict)
+}
+
+pub fn element_data_from_json(s: &str) -> Result<ElementData, serde_json::Error> {
+    let dict: MoleculeDict = serde_json::from_str(s)?;
+
+    let collision_partners = dict.collrates
+        .into_iter()
+        .map(|(key, cp)| Ok(CollisionPartnerData {
+            name: collision_partner_from_key(&key).ok_or_else(|| {
+                <serde_json::Error as serde::de::Error>::custom(
+                    format!("unknown collision partner `{}`", key)
+                )
+            })?,
+            information: cp.information,
+            temperatures: cp.temperatures,
+            rates: cp.rates
+                .into_iter()
+                .map(|cr| CollisionalRates {
+                    transition: cr.transition,
+                    up: cr.upper,
+                    low: cr.lower,
+                    rates: cr.rates,
+                })
+                .collect(),
+        }))
+        .collect::<Result<Vec<_>, serde_json::Error>>()?;
+
+    Ok(ElementData {
+        name: dict.molecule,
+        information: dict.information,
+        weight: dict.weight,
+        energy_levels: dict.enlevels
+            .into_iter()
+            .map(|el| EnergyLevel {
+                level: el.level,
+                energy: el.energy,
+                stat_weight: el.weight,
+                qnums: el.qnums,
+            })
+            .collect(),
+        radiative_transitions: dict.radtransitions
+            .into_iter()
+            .map(|rt| {
+                let (frequency, upper_energy, extra) = transition_columns(&rt.extra);
+                RadiativeTransition {
+                    transition: rt.transition,
+                    up: rt.upper,
+                    low: rt.lower,
+                    aeinst: rt.aeinst,
+                    frequency,
+                    upper_energy,
+                    extra: String::from(extra),
+                }
+            })
+            .collect(),
+        collision_partners,
+        features: FormatFeatures::default(),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_spectralradex_parameters() {
+        let s = r#"{"molfile": "hco+.dat", "tkin": 20.0, "tbg": 2.73, "cdmol": 1e14,
+            "h2": 1e4, "h": 0.0, "e-": 0.0, "p-h2": 0.0, "o-h2": 0.0, "h+": 0.0,
+            "linewidth": 2.0, "fmin": 0.0, "fmax": 500.0, "geometry": 2}"#;
+
+        let expected = Parameters {
+            molfile: String::from("hco+.dat"),
+            tkin: 20.0,
+            cdmol: 1e14,
+            h2: 1e4,
+            linewidth: 2.0,
+            fmax: 500.0,
+            geometry: Geometry::LargeVelocityGradient,
+            ..Default::default()
+        };
+
+        assert_eq!(Parameters::from_json(s).unwrap(), expected, "Wrong result for parameters `{}`", s);
+    }
+
+    #[test]
+    fn parse_ndradex_parameters() {
+        let s = r#"{"query": "co", "T_kin": 100.0, "N_mol": 1e15, "n_H2": 1e3,
+            "T_bg": 2.73, "dv": 1.0, "geom": "slab"}"#;
+
+        let parameters = Parameters::from_json(s).unwrap();
+
+        assert_eq!(parameters.tkin, 100.0);
+        assert_eq!(parameters.density(CollisionPartnerId::H2), Ok(1e3));
+        assert!(parameters.density(CollisionPartnerId::Other(41, String::new())).is_err());
+        assert_eq!(parameters.geometry, Geometry::Slab);
+        assert!(parameters.to_json().unwrap().contains(r#""geometry":3"#));
+    }
+
+    #[test]
+    fn element_data_json_round_trip() {
+        let ed = ElementData {
+            name: String::from("CO"),
+            weight: 28.0,
+            energy_levels: vec!(
+                EnergyLevel { level: 1, energy: 0.0, stat_weight: 1.0, qnums: String::from("0") },
+                EnergyLevel { level: 2, energy: 3.845, stat_weight: 3.0, qnums: String::from("1") },
+            ),
+            radiative_transitions: vec!(
+                RadiativeTransition { transition: 1, up: 2, low: 1, aeinst: 7.203e-08, ..Default::default() },
+            ),
+            collision_partners: vec!(CollisionPartnerData {
+                name: CollisionPartnerId::pH2,
+                information: String::new(),
+                temperatures: vec!(10.0, 20.0),
+                rates: vec!(CollisionalRates { transition: 1, up: 2, low: 1, rates: vec!(3.3e-11, 3.4e-11) }),
+            }),
+            ..Default::default()
+        };
+
+        let json = element_data_to_json(&ed).unwrap();
+
+        assert!(json.contains(r#""PH2""#), "Collision partner key missing in `{}`", json);
+        assert_eq!(element_data_from_json(&json).unwrap(), ed);
+    }
+
+    #[test]
+    fn collision_partner_order_round_trip() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let names = data.collision_partners().iter().map(|cp| cp.name()).collect::<Vec<_>>();
+        assert!(names.windows(2).any(|w| collision_partner_key(&w[0]) > collision_partner_key(&w[1])));
+
+        let parsed = element_data_from_json(&element_data_to_json(&data).unwrap()).unwrap();
+        assert_eq!(parsed.collision_partners().iter().map(|cp| cp.name()).collect::<Vec<_>>(), names);
+        assert_eq!(parsed.collision_partners(), data.collision_partners(), "Wrong result for partners of `oatom.dat`");
+
+        let unknown = r#"{"molecule": "X", "weight": 1.0, "enlevels": [], "radtransitions": [], "collrates": {"XY": {"temperatures": [], "rates": []}}}"#;
+        assert!(element_data_from_json(unknown).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use crate::iau::Unit;
+use crate::iau::{length, time};
+use crate::lamda::{CollisionPartnerId, ElementData};
+use crate::radex::{Conditions, LineResult, Solution};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnData {
+    UInt(Vec<u32>),
+    Float(Vec<f64>),
+    Text(Vec<String>),
+}
+
+impl ColumnData {
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnData::UInt(v) => v.len(),
+            ColumnData::Float(v) => v.len(),
+            ColumnData::Text(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn cell(&self, row: usize) -> String {
+        match self {
+            ColumnData::UInt(v) => v[row].to_string(),
+            ColumnData::Float(v) => match v[row].abs() {
+                x if x == 0.0 || (1e-3..1e6).contains(&x) => v[row].to_string(),
+                _ => format!("{:e}", v[row]),
+            },
+            ColumnData::Text(v) => v[row].clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub unit: Option<String>,
+    pub description: String,
+    pub data: ColumnData,
+}
+
+impl Column {
+    pub fn new(name: &str, unit: Option<String>, description: &str, data: ColumnData) -> Self {
+        Self {
+            name: String::from(name),
+            unit,
+            description: String::from(description),
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub name: String,
+    pub meta: Vec<(String, String)>,
+    pub columns: Vec<Column>,
+}
+
+impl Table {
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map(|c| c.data.len()).unwrap_or(0)
+    }
+}
+
+fn inverse(unit: &str) -> String {
+    format!("{}-1", unit)
+}
+
+// One row per radiative transition. The frequency and E_up are the ones of
+// the file columns when present and follow from the levels otherwise.
+pub fn line_list(data: &ElementData) -> Table {
+    let transitions = &data.radiative_transitions;
+    let level = |n: u32| data.energy_level(n);
+    let qnums = |n: u32| level(n).map(|el| el.qnums.clone()).unwrap_or_default();
+    let g_up = |n: u32| level(n).map(|el| el.stat_weight).unwrap_or(f64::NAN);
+
+    Table {
+        name: data.name.clone(),
+        meta: vec!(
+            (String::from("molecule"), data.name.clone()),
+            (String::from("weight"), data.weight.to_string()),
+        ),
+        columns: vec!(
+            Column::new("transition", None, "Transition number",
+                ColumnData::UInt(transitions.iter().map(|rt| rt.transition).collect())),
+            Column::new("up", None, "Upper level number",
+                ColumnData::UInt(transitions.iter().map(|rt| rt.up).collect())),
+            Column::new("low", None, "Lower level number",
+                ColumnData::UInt(transitions.iter().map(|rt| rt.low).collect())),
+            Column::new("qn_up", None, "Upper level quantum numbers",
+                ColumnData::Text(transitions.iter().map(|rt| qnums(rt.up)).collect())),
+            Column::new("qn_low", None, "Lower level quantum numbers",
+                ColumnData::Text(transitions.iter().map(|rt| qnums(rt.low)).collect())),
+            Column::new("aeinst", Some(inverse(time::second::abbreviation())), "Einstein A coefficient",
+                ColumnData::Float(transitions.iter().map(|rt| rt.aeinst).collect())),
+            Column::new("freq", Some(String::from("GHz")), "Rest frequency",
+                ColumnData::Float(transitions.iter().map(|rt| data.frequency(rt).unwrap_or(f64::NAN)).collect())),
+            Column::new("e_up", Some(String::from("K")), "Upper level energy",
+                ColumnData::Float(transitions.iter().map(|rt| data.upper_energy(rt).unwrap_or(f64::NAN)).collect())),
+            Column::new("g_up", None, "Upper level statistical weight",
+                ColumnData::Float(transitions.iter().map(|rt| g_up(rt.up)).collect())),
+            Column::new("label", None, "Transition label",
+                ColumnData::Text(transitions.iter().map(|rt| rt.label(data)).collect())),
+            Column::new("species", None, "Species",
+                ColumnData::Text(transitions.iter().map(|_| data.name.clone()).collect())),
+        ),
+    }
+}
+
+pub fn levels(data: &ElementData) -> Table {
+    let levels = &data.energy_levels;
+
+    Table {
+        name: data.name.clone(),
+        meta: vec!((String::from("molecule"), data.name.clone())),
+        columns: vec!(
+            Column::new("level", None, "Level number",
+                ColumnData::UInt(levels.iter().map(|el| el.level).collect())),
+            Column::new("energy", Some(inverse(length::centimeter::abbreviation())), "Level energy",
+                ColumnData::Float(levels.iter().map(|el| el.energy).collect())),
+            Column::new("weight", None, "Statistical weight",
+                ColumnData::Float(levels.iter().map(|el| el.stat_weight).collect())),
+            Column::new("qnums", None, "Quantum numbers",
+                ColumnData::Text(levels.iter().map(|el| el.qnums.clone()).collect())),
+        ),
+    }
+}
+
+pub fn collision_rates(data: &ElementData) -> Table {
+    let rows = data.collision_partners
+        .iter()
+        .flat_map(|cp| cp.rates.iter().flat_map(move |cr| {
+            cp.temperatures
+                .iter()
+                .zip(cr.rates.iter())
+                .map(move |(temperature, rate)| (cp, cr, *temperature, *rate))
+        }))
+        .collect::<Vec<_>>();
+
+    Table {
+        name: data.name.clone(),
+        meta: vec!((String::from("molecule"), data.name.clone())),
+        columns: vec!(
+            Column::new("partner", None, "Collision partner",
+                ColumnData::Text(rows.iter().map(|row| row.0.name.to_string()).collect())),
+            Column::new("transition", None, "Transition number",
+                ColumnData::UInt(rows.iter().map(|row| row.1.transition).collect())),
+            Column::new("up", None, "Upper level number",
+                ColumnData::UInt(rows.iter().map(|row| row.1.up).collect())),
+            Column::new("low", None, "Lower level number",
+                ColumnData::UInt(rows.iter().map(|row| row.1.low).collect())),
+            Column::new("temperature", Some(String::from("K")), "Kinetic temperature",
+                ColumnData::Float(rows.iter().map(|row| row.2).collect())),
+            Column::new("rate", Some(format!("{}3 {}", length::centimeter::abbreviation(), inverse(time::second::abbreviation()))),
+                "Downward rate coefficient",
+                ColumnData::Float(rows.iter().map(|row| row.3).collect())),
+        ),
+    }
+}
+
+// Level populations of a solution, in the order of the levels in `data`.
+pub fn populations(data: &ElementData, solution: &Solution) -> Table {
+    let levels = &data.energy_levels;
+
+    Table {
+        name: data.name.clone(),
+        meta: vec!((String::from("molecule"), data.name.clone())),
+        columns: vec!(
+            Column::new("level", None, "Level number",
+                ColumnData::UInt(levels.iter().map(|el| el.level).collect())),
+            Column::new("qnums", None, "Quantum numbers",
+                ColumnData::Text(levels.iter().map(|el| el.qnums.clone()).collect())),
+            Column::new("energy", Some(inverse(length::centimeter::abbreviation())), "Level energy",
+                ColumnData::Float(levels.iter().map(|el| el.energy).collect())),
+            Column::new("population", None, "Fractional population",
+                ColumnData::Float(solution.populations.clone())),
+        ),
+    }
+}
+
+// One row per (grid point, transition), the physical conditions repeated on
+// every row so that the table can be filtered without joins.
+pub fn grid_results(name: &str, points: &[(Conditions, Solution)]) -> Table {
+    let rows = points
+        .iter()
+        .flat_map(|(conditions, solution)| solution.lines.iter().map(move |line| (conditions, line)))
+        .collect::<Vec<_>>();
+    let condition = |f: fn(&Conditions) -> f64| ColumnData::Float(rows.iter().map(|row| f(row.0)).collect());
+    let line = |f: fn(&LineResult) -> f64| ColumnData::Float(rows.iter().map(|row| f(row.1)).collect());
+    let kelvin = || Some(String::from("K"));
+
+    let mut partners: Vec<CollisionPartnerId> = vec!();
+    for (conditions, _) in points.iter() {
+        for (partner, _) in conditions.densities.iter() {
+            if !partners.contains(partner) {
+                partners.push(partner.clone());
+            }
+        }
+    }
+
+    let mut columns = vec!(
+        Column::new("tkin", kelvin(), "Kinetic temperature", condition(|c| c.tkin)),
+        Column::new("tbg", kelvin(), "Background temperature", condition(|c| c.tbg)),
+        Column::new("cdmol", Some(String::from("cm-2")), "Column density", condition(|c| c.cdmol)),
+        Column::new("dv", Some(String::from("km s-1")), "Line width (FWHM)", condition(|c| c.linewidth)),
+    );
+    for partner in partners {
+        let density = rows
+            .iter()
+            .map(|row| row.0.densities.iter().find(|(p, _)| *p == partner).map(|(_, n)| *n).unwrap_or(0.0))
+            .collect();
+        columns.push(Column::new(&format!("n_{}", partner), Some(String::from("cm-3")),
+            &format!("{} density", partner), ColumnData::Float(density)));
+    }
+    columns.extend(vec!(
+        Column::new("transition", None, "Transition number",
+            ColumnData::UInt(rows.iter().map(|row| row.1.transition).collect())),
+        Column::new("qn_up", None, "Upper level quantum numbers",
+            ColumnData::Text(rows.iter().map(|row| row.1.qnums_up.clone()).collect())),
+        Column::new("qn_low", None, "Lower level quantum numbers",
+            ColumnData::Text(rows.iter().map(|row| row.1.qnums_low.clone()).collect())),
+        Column::new("e_up", kelvin(), "Upper level energy", line(|l| l.e_up)),
+        Column::new("freq", Some(String::from("GHz")), "Rest frequency", line(|l| l.frequency)),
+        Column::new("t_ex", kelvin(), "Excitation temperature", line(|l| l.tex)),
+        Column::new("tau", None, "Optical depth", line(|l| l.tau)),
+        Column::new("t_r", kelvin(), "Radiation temperature", line(|l| l.t_r)),
+        Column::new("pop_up", None, "Upper level population", line(|l| l.pop_up)),
+        Column::new("pop_low", None, "Lower level population", line(|l| l.pop_low)),
+        Column::new("flux_kkms", Some(String::from("K km s-1")), "Integrated intensity", line(|l| l.flux_kkms)),
+        Column::new("flux", Some(String::from("erg cm-2 s-1")), "Line flux", line(|l| l.flux_cgs)),
+    ));
+
+    Table {
+        name: String::from(name),
+        meta: vec!(),
+        columns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{EnergyLevel, RadiativeTransition};
+
+    #[test]
+    fn line_list_columns() {
+        let ed = ElementData {
+            name: String::from("CO"),
+            energy_levels: vec!(
+                EnergyLevel { level: 1, energy: 0.0, stat_weight: 1.0, qnums: String::from("0") },
+                EnergyLevel { level: 2, energy: 3.845033413, stat_weight: 3.0, qnums: String::from("1") },
+            ),
+            radiative_transitions: vec!(
+                RadiativeTransition { transition: 1, up: 2, low: 1, aeinst: 7.203e-08, ..Default::default() },
+            ),
+            ..Default::default()
+        };
+
+        let table = line_list(&ed);
+        let freq = table.columns.iter().find(|c| c.name == "freq").unwrap();
+
+        assert_eq!(table.num_rows(), 1);
+        assert_eq!(table.columns[5].unit, Some(String::from("s-1")));
+        match &freq.data {
+            ColumnData::Float(v) => assert!((v[0] - 115.271).abs() < 1e-3, "Wrong frequency {}", v[0]),
+            _ => panic!("Frequency column should hold floating point numbers"),
+        }
+    }
+
+    #[test]
+    fn line_list_csv() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let csv = crate::interop::csv::to_string(&line_list(&data));
+        let mut lines = csv.lines();
+
+        assert_eq!(lines.next(), Some("transition,up,low,qn_up,qn_low,aeinst [s-1],freq [GHz],e_up [K],g_up,label,species"));
+        assert_eq!(lines.next(), Some("1,2,1,3_P_1 ! 2S+1 L J = 3 P 1,3_P_2 ! 2S+1 L J = 3 P 2,8.91e-5,4744.77749,227.712,3,O 3_P_1-3_P_2 4744.777 GHz,O"),
+            "Wrong result for first line");
+        assert_eq!(lines.count(), data.radiative_transitions().len() - 1);
+    }
+}
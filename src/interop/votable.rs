@@ -0,0 +1,98 @@
+use std::io::Write;
+
+use super::table::{ColumnData, Table};
+
+fn datatype(data: &ColumnData) -> &'static str {
+    match data {
+        ColumnData::UInt(_) => "long",
+        ColumnData::Float(_) => "double",
+        ColumnData::Text(_) => "char",
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn write<W: Write>(table: &Table, writer: &mut W) -> std::io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<VOTABLE version="1.4" xmlns="http://www.ivoa.net/xml/VOTable/v1.3">"#)?;
+    writeln!(writer, "  <RESOURCE>")?;
+    writeln!(writer, r#"    <TABLE name="{}">"#, escape(&table.name))?;
+    for (key, value) in table.meta.iter() {
+        writeln!(
+            writer,
+            r#"      <PARAM name="{}" datatype="char" arraysize="*" value="{}"/>"#,
+            escape(key),
+            escape(value)
+        )?;
+    }
+    for column in table.columns.iter() {
+        write!(writer, r#"      <FIELD name="{}" datatype="{}""#, escape(&column.name), datatype(&column.data))?;
+        if let ColumnData::Text(_) = column.data {
+            write!(writer, r#" arraysize="*""#)?;
+        }
+        if let Some(unit) = &column.unit {
+            write!(writer, r#" unit="{}""#, escape(unit))?;
+        }
+        match column.description.is_empty() {
+            true => writeln!(writer, "/>")?,
+            false => {
+                writeln!(writer, ">")?;
+                writeln!(writer, "        <DESCRIPTION>{}</DESCRIPTION>", escape(&column.description))?;
+                writeln!(writer, "      </FIELD>")?;
+            }
+        }
+    }
+    writeln!(writer, "      <DATA>")?;
+    writeln!(writer, "        <TABLEDATA>")?;
+    for row in 0..table.num_rows() {
+        write!(writer, "          <TR>")?;
+        for column in table.columns.iter() {
+            write!(writer, "<TD>{}</TD>", escape(&column.data.cell(row)))?;
+        }
+        writeln!(writer, "</TR>")?;
+    }
+    writeln!(writer, "        </TABLEDATA>")?;
+    writeln!(writer, "      </DATA>")?;
+    writeln!(writer, "    </TABLE>")?;
+    writeln!(writer, "  </RESOURCE>")?;
+    writeln!(writer, "</VOTABLE>")?;
+
+    Ok(())
+}
+
+pub fn to_string(table: &Table) -> String {
+    let mut buffer: Vec<u8> = vec!();
+    write(table, &mut buffer).expect("Writing to memory should not fail");
+
+    String::from_utf8(buffer).expect("VOTable output should be valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::interop::table::Column;
+
+    #[test]
+    fn write_votable_table() {
+        let table = Table {
+            name: String::from("HCO+"),
+            meta: vec!(),
+            columns: vec!(
+                Column::new("qn_up", None, "", ColumnData::Text(vec!(String::from("J<1>")))),
+                Column::new("aeinst", Some(String::from("s-1")), "", ColumnData::Float(vec!(4.25e-05))),
+            ),
+        };
+
+        let s = to_string(&table);
+
+        assert!(s.contains(r#"<TABLE name="HCO+">"#), "Missing table element in `{}`", s);
+        assert!(s.contains(r#"<FIELD name="aeinst" datatype="double" unit="s-1"/>"#), "Missing field in `{}`", s);
+        assert!(s.contains("<TR><TD>J&lt;1&gt;</TD><TD>4.25e-5</TD></TR>"), "Missing row in `{}`", s);
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use crate::molecular::MolecularData;
+
+use super::{CollisionPartnerId, OrthoParaRatio};
+
+// Ortho-to-para ratio of H2 in thermal equilibrium at `temperature` K,
+// 9 exp(-170.6 K / T) up to its high temperature limit of 3
+pub fn thermal_ortho_para_ratio(temperature: f64) -> f64 {
+    (9.0 * (-170.6 / temperature).exp()).min(3.0)
+}
+
+// Densities of the collision partners relative to that of H2. The defaults
+// are for dense molecular gas: He/H = 0.1, an ionization fraction of 1e-8,
+// little atomic hydrogen and H2 in thermal ortho-to-para equilibrium.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartnerAbundances {
+    pub h2: f64,
+    pub ortho_para: OrthoParaRatio,
+    pub electrons: f64,
+    pub h: f64,
+    pub he: f64,
+    pub h_plus: f64,
+}
+
+impl Default for PartnerAbundances {
+    fn default() -> Self {
+        Self {
+            h2: 1.0,
+            ortho_para: OrthoParaRatio::Thermal,
+            electrons: 1e-8,
+            h: 1e-4,
+            he: 0.2,
+            h_plus: 0.0,
+        }
+    }
+}
+
+impl PartnerAbundances {
+    // Densities in cm-3 of the partners `data` has rates for, at H2 density
+    // `n_h2` and kinetic temperature `temperature` K. H2 is split into its
+    // ortho and para forms when the data has no rates for H2 as a whole.
+    pub fn densities<D: MolecularData + ?Sized>(
+        &self,
+        data: &D,
+        n_h2: f64,
+        temperature: f64,
+    ) -> Vec<(CollisionPartnerId, f64)> {
+        let partners = data.collision_partners();
+        let opr = self.ortho_para.at(temperature);
+        let has_h2 = partners.contains(&CollisionPartnerId::H2);
+
+        partners
+            .into_iter()
+            .map(|partner| {
+                let abundance = match partner {
+                    CollisionPartnerId::H2 => self.h2,
+                    CollisionPartnerId::pH2 if !has_h2 => self.h2 / (1.0 + opr),
+                    CollisionPartnerId::oH2 if !has_h2 => self.h2 * opr / (1.0 + opr),
+                    CollisionPartnerId::electrons => self.electrons,
+                    CollisionPartnerId::HI => self.h,
+                    CollisionPartnerId::He => self.he,
+                    CollisionPartnerId::HII => self.h_plus,
+                    _ => 0.0,
+                };
+                (partner, abundance * n_h2)
+            })
+            .filter(|(_, density)| *density > 0.0)
+            .collect()
+    }
+
+    // Total downward collision rates sum n_i k_i, s-1, of every transition
+    // keyed by its upper and lower level
+    pub fn total_collision_rates<D: MolecularData + ?Sized>(
+        &self,
+        data: &D,
+        n_h2: f64,
+        temperature: f64,
+    ) -> BTreeMap<(u32, u32), f64> {
+        let mut total = BTreeMap::new();
+        for (partner, density) in self.densities(data, n_h2, temperature) {
+            for rate in data.collision_rates(partner, temperature) {
+                *total.entry((rate.up, rate.low)).or_insert(0.0) += density * rate.rate;
+            }
+        }
+
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::ElementData;
+
+    #[test]
+    fn thermal_ratio() {
+        assert!(thermal_ortho_para_ratio(10.0) < 1e-6);
+        assert!((thermal_ortho_para_ratio(85.3) - 9.0 * (-2.0f64).exp()).abs() < 1e-12);
+        assert_eq!(thermal_ortho_para_ratio(1000.0), 3.0, "Wrong result for the high temperature limit");
+    }
+
+    #[test]
+    fn partner_densities() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let abundances = PartnerAbundances::default();
+
+        let densities = abundances.densities(&data, 1e4, 100.0);
+        let opr = thermal_ortho_para_ratio(100.0);
+        for (partner, density) in densities.iter() {
+            let expected = match partner {
+                CollisionPartnerId::pH2 => 1e4 / (1.0 + opr),
+                CollisionPartnerId::oH2 => 1e4 * opr / (1.0 + opr),
+                CollisionPartnerId::electrons => 1e-4,
+                CollisionPartnerId::HI => 1.0,
+                CollisionPartnerId::He => 2e3,
+                _ => 1e4,
+            };
+            assert!((density / expected - 1.0).abs() < 1e-12, "Wrong result for {} density {}", partner, density);
+        }
+
+        let total = abundances.total_collision_rates(&data, 1e4, 100.0);
+        let (up, low) = (2, 1);
+        let expected = densities
+            .iter()
+            .flat_map(|(p, n)| data.collision_rates(p.clone(), 100.0).into_iter().map(move |r| (r, *n)))
+            .filter(|(r, _)| (r.up, r.low) == (up, low))
+            .map(|(r, n)| n * r.rate)
+            .sum::<f64>();
+        assert!((total[&(up, low)] / expected - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,82 @@
+use ndarray::{Array1, Array2, Array3};
+
+use super::{CollisionPartnerId, ElementData};
+
+// Arrays indexed by the position of the levels in `energy_levels`, which
+// may differ from the level numbers of the file. Matrices have the upper
+// level first, so `m[[u, l]]` is for the transition from u down to l, and
+// are zero for level pairs without data.
+impl ElementData {
+    // Level energies, cm^-1
+    pub fn level_energies(&self) -> Array1<f64> {
+        self.energy_levels.iter().map(|el| el.energy).collect()
+    }
+
+    pub fn level_weights(&self) -> Array1<f64> {
+        self.energy_levels.iter().map(|el| el.stat_weight).collect()
+    }
+
+    // Einstein A coefficients, s^-1
+    pub fn einstein_a_matrix(&self) -> Array2<f64> {
+        let index = self.level_index();
+        let mut matrix = Array2::zeros((index.len(), index.len()));
+        for rt in self.radiative_transitions.iter() {
+            if let (Some(&u), Some(&l)) = (index.get(&rt.up), index.get(&rt.low)) {
+                matrix[[u, l]] = rt.aeinst;
+            }
+        }
+
+        matrix
+    }
+
+    // Downward rate coefficients with `name` in cm^3 s^-1, one matrix per
+    // temperature of the partner: `m[[t, u, l]]`; see `rate_table`
+    pub fn rate_matrices(&self, name: CollisionPartnerId) -> Option<Array3<f64>> {
+        let table = self.rate_table(name)?;
+        let ntemp = table.temperatures().len();
+        let mut matrices = Array3::zeros((ntemp, table.levels(), table.levels()));
+        for ((u, l), rates) in table.iter() {
+            for (t, rate) in rates.iter().take(ntemp).enumerate() {
+                matrices[[t, u, l]] = *rate;
+            }
+        }
+
+        Some(matrices)
+    }
+
+    // Downward rate coefficients with `name` at its `temperature`-th
+    // temperature, cm^3 s^-1
+    pub fn rate_matrix(&self, name: CollisionPartnerId, temperature: usize) -> Option<Array2<f64>> {
+        let matrices = self.rate_matrices(name)?;
+        (temperature < matrices.shape()[0]).then(|| matrices.index_axis_move(ndarray::Axis(0), temperature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn level_arrays() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let partner = &data.collision_partners()[0];
+        let rate = &partner.rates()[0];
+
+        assert_eq!(data.level_energies().to_vec(), data.energy_levels().iter().map(|el| el.energy).collect::<Vec<_>>());
+        assert_eq!(data.level_weights()[0], data.energy_levels()[0].stat_weight);
+
+        let a = data.einstein_a_matrix();
+        let rt = &data.radiative_transitions()[0];
+        assert_eq!(a.shape(), &[3, 3]);
+        assert_eq!(a[[rt.up as usize - 1, rt.low as usize - 1]], rt.aeinst);
+        assert_eq!(a[[rt.low as usize - 1, rt.up as usize - 1]], 0.0);
+
+        let matrices = data.rate_matrices(partner.name()).unwrap();
+        assert_eq!(matrices.shape(), &[partner.temperatures().len(), 3, 3]);
+        let last = partner.temperatures().len() - 1;
+        let matrix = data.rate_matrix(partner.name(), last).unwrap();
+        assert_eq!(matrix[[rate.up as usize - 1, rate.low as usize - 1]], rate.rates[last], "Wrong result for rate matrix");
+        assert_eq!(data.rate_matrix(partner.name(), last + 1), None);
+    }
+}
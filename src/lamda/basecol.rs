@@ -0,0 +1,214 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{
+    CollisionPartnerData,
+    CollisionPartnerId,
+    CollisionalRates,
+    CollisionalTemperatures,
+    field_error,
+    FieldError,
+    ParseError,
+    Span,
+};
+
+// BASECOL rate tables are exported as plain text: `#` or `!` header lines
+// (with an optional `collider:` entry), one line of temperatures (an optional
+// leading label such as `T(K):` is skipped) and one line per transition with
+// upper level, lower level and downward rate coefficients in cm3 s-1.
+pub fn parse(s: &str) -> Result<CollisionPartnerData, ParseError> {
+    parse_with_partner(s, None)
+}
+
+pub fn parse_with_partner(
+    s: &str,
+    partner: Option<CollisionPartnerId>
+) -> Result<CollisionPartnerData, ParseError> {
+    parse_table(s, partner).map_err(|e| e.locate(s))
+}
+
+fn parse_table(s: &str, partner: Option<CollisionPartnerId>) -> Result<CollisionPartnerData, ParseError> {
+    let mut lines = s
+        .lines()
+        .enumerate()
+        .filter(|el| !el.1.trim().is_empty());
+
+    let mut name = partner;
+    let mut header: Vec<String> = vec!();
+    let mut last_line_number = 0;
+
+    let temperatures = loop {
+        let line = lines.next().ok_or(ParseError::end_of_input(last_line_number + 1))?;
+        last_line_number = line.0;
+
+        let trimmed = line.1.trim();
+        if trimmed.starts_with('#') || trimmed.starts_with('!') {
+            let content = trimmed.trim_matches(|c: char| c.is_whitespace() || c == '!' || c == '#');
+            match content.split_once(':') {
+                Some((key, value)) if key.trim().eq_ignore_ascii_case("collider") => {
+                    let id = collision_partner_from_name(value.trim()).ok_or(
+                        ParseError::UnknownCollisionPartner {
+                            line_number: line.0,
+                            span: Span::in_line(line.1.rfind(value.trim()).unwrap_or(0), value.trim().len()),
+                            line: String::from(line.1),
+                            note: format!("Unknown collision partner `{}`", value.trim()),
+                            source: None,
+                        }
+                    )?;
+                    name.get_or_insert(id);
+                },
+                _ => header.push(String::from(content)),
+            }
+            continue;
+        }
+
+        let values = match trimmed.split_once(':') {
+            Some((_, values)) => values,
+            None => trimmed.trim_start_matches(|c: char| !c.is_ascii_digit() && c != '.'),
+        };
+
+        break match values.parse::<CollisionalTemperatures>() {
+            Ok(temps) => temps.0,
+            Err(e) => return Err(ParseError::UnknownItem {
+                line_number: line.0,
+                span: Span::in_line(line.1.find(&e.value).unwrap_or(0), e.value.len()),
+                line: String::from(line.1),
+                note: e.to_string(),
+                source: Some(Box::new(FieldError::CollisionalTemperatures(e))),
+            })
+        };
+    };
+
+    let name = name.ok_or(ParseError::UnknownCollisionPartner {
+        line_number: last_line_number,
+        span: Span::in_line(0, 0),
+        line: String::new(),
+        note: String::from("Collision partner is neither given nor specified with a `collider:` header line"),
+        source: None,
+    })?;
+
+    let rates = lines
+        .zip(1..)
+        .map(|(el, transition)| {
+            let rate = format!("{} {}", transition, el.1).parse::<CollisionalRates>();
+            match rate {
+                Ok(rate) => match rate.rates.len() == temperatures.len() {
+                    true => Ok(rate),
+                    false => Err(ParseError::MissingField {
+                        line_number: el.0,
+                        span: Span::in_line(el.1.len(), 0),
+                        line: String::from(el.1),
+                        note: format!(
+                            "Expected {} rate coefficients, found {}",
+                            temperatures.len(),
+                            rate.rates.len()
+                        ),
+                        source: None,
+                    }),
+                },
+                Err(e) => Err(field_error(el, e, FieldError::CollisionalRates)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CollisionPartnerData {
+        name,
+        information: header.join(" "),
+        temperatures,
+        rates,
+    })
+}
+
+pub fn collision_partner_from_name(s: &str) -> Option<CollisionPartnerId> {
+    match s.to_ascii_lowercase().as_str() {
+        "h2" => Some(CollisionPartnerId::H2),
+        "p-h2" | "ph2" | "para-h2" => Some(CollisionPartnerId::pH2),
+        "o-h2" | "oh2" | "ortho-h2" => Some(CollisionPartnerId::oH2),
+        "e" | "e-" | "electron" | "electrons" => Some(CollisionPartnerId::electrons),
+        "h" => Some(CollisionPartnerId::HI),
+        "he" => Some(CollisionPartnerId::He),
+        "h+" => Some(CollisionPartnerId::HII),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_basecol_table() {
+        let s = r#"# BASECOL rate coefficients
+        # collider: p-H2
+        # Yang et al. 2010, ApJ 718, 1062
+        T(K):  10.0  20.0  30.0
+           2  1   3.1e-11  3.3e-11  3.5e-11
+           3  1   1.1e-11  1.2e-11  1.3e-11
+           3  2   4.4e-11  4.6e-11  4.8e-11
+        "#;
+
+        let result = parse(s);
+
+        assert_eq!(
+            result,
+            Ok(CollisionPartnerData {
+                name: CollisionPartnerId::pH2,
+                information: String::from("BASECOL rate coefficients Yang et al. 2010, ApJ 718, 1062"),
+                temperatures: vec!(10.0, 20.0, 30.0),
+                rates: vec!(
+                    CollisionalRates { transition: 1, up: 2, low: 1, rates: vec!(3.1e-11, 3.3e-11, 3.5e-11) },
+                    CollisionalRates { transition: 2, up: 3, low: 1, rates: vec!(1.1e-11, 1.2e-11, 1.3e-11) },
+                    CollisionalRates { transition: 3, up: 3, low: 2, rates: vec!(4.4e-11, 4.6e-11, 4.8e-11) },
+                ),
+            }),
+            "Wrong result for BASECOL table `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_basecol_table_without_collider() {
+        let s = "10.0 20.0\n2 1 1e-11 2e-11\n";
+
+        assert!(
+            matches!(parse(s), Err(ParseError::UnknownCollisionPartner { .. })),
+            "Collision partner should be required for `{}`",
+            s
+        );
+        assert_eq!(
+            parse_with_partner(s, Some(CollisionPartnerId::He)).map(|cp| cp.name),
+            Ok(CollisionPartnerId::He),
+        );
+    }
+
+    #[test]
+    fn merge_basecol_table() {
+        let mut ed = crate::lamda::ElementData {
+            energy_levels: vec!(
+                crate::lamda::EnergyLevel { level: 1, ..Default::default() },
+                crate::lamda::EnergyLevel { level: 2, ..Default::default() },
+            ),
+            ..Default::default()
+        };
+
+        let partner = parse("# collider: He\n10.0 20.0\n2 1 1e-11 2e-11\n").unwrap();
+        assert_eq!(ed.merge_collision_partner(partner), Ok(()));
+        assert_eq!(ed.collision_partners.len(), 1);
+
+        let partner = parse("# collider: He\n10.0 20.0\n2 1 1e-11 2e-11\n").unwrap();
+        assert_eq!(
+            ed.merge_collision_partner(partner),
+            Err(crate::lamda::MergeError::DuplicateCollisionPartner { name: CollisionPartnerId::He })
+        );
+
+        let partner = parse("# collider: e-\n10.0 20.0\n3 1 1e-11 2e-11\n").unwrap();
+        assert_eq!(
+            ed.merge_collision_partner(partner),
+            Err(crate::lamda::MergeError::UnknownLevel { transition: 1, level: 3 })
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{CollisionPartnerData, ElementData, EnergyLevel, FormatFeatures, MergeError, RadiativeTransition};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    MissingName,
+    InvalidWeight {
+        weight: f64,
+    },
+    NoEnergyLevels,
+    InvalidLevel {
+        level: u32,
+        note: &'static str,
+    },
+    InvalidTransition {
+        transition: u32,
+        note: &'static str,
+    },
+    RateCount {
+        transition: u32,
+        rates: usize,
+        temperatures: usize,
+    },
+    Merge(MergeError),
+}
+
+impl core::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingName => write!(f, "The species has no name"),
+            Self::InvalidWeight { weight } => write!(f, "Molecular weight {} is not positive", weight),
+            Self::NoEnergyLevels => write!(f, "No energy levels were added"),
+            Self::InvalidLevel { level, note } => write!(f, "Energy level {}: {}", level, note),
+            Self::InvalidTransition { transition, note } => write!(f, "Radiative transition {}: {}", transition, note),
+            Self::RateCount { transition, rates, temperatures } => write!(
+                f,
+                "Collisional transition {} has {} rates for {} temperatures",
+                transition,
+                rates,
+                temperatures
+            ),
+            Self::Merge(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+impl From<MergeError> for BuildError {
+    fn from(e: MergeError) -> Self {
+        BuildError::Merge(e)
+    }
+}
+
+// Molecular data assembled in code, e.g. from a quantum chemistry pipeline.
+// Levels and radiative transitions are numbered from 1 in the order they are
+// added.
+#[derive(Debug, Default)]
+pub struct ElementDataBuilder {
+    name: String,
+    information: String,
+    weight: f64,
+    energy_levels: Vec<EnergyLevel>,
+    radiative_transitions: Vec<RadiativeTransition>,
+    collision_partners: Vec<CollisionPartnerData>,
+}
+
+impl ElementData {
+    pub fn builder(name: &str) -> ElementDataBuilder {
+        ElementDataBuilder { name: String::from(name), ..Default::default() }
+    }
+}
+
+impl ElementDataBuilder {
+    pub fn information(mut self, information: &str) -> Self {
+        self.information = String::from(information);
+        self
+    }
+
+    // Molecular weight, atomic mass units
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    // Level with its energy in cm-1, statistical weight and quantum number label
+    pub fn level(mut self, energy: f64, stat_weight: f64, qnums: &str) -> Self {
+        let level = self.energy_levels.len() as u32 + 1;
+        self.energy_levels.push(EnergyLevel { level, energy, stat_weight, qnums: String::from(qnums) });
+        self
+    }
+
+    // Transition between level numbers `up` and `low` with its Einstein A, s-1
+    pub fn transition(mut self, up: u32, low: u32, aeinst: f64) -> Self {
+        let transition = self.radiative_transitions.len() as u32 + 1;
+        self.radiative_transitions.push(RadiativeTransition { transition, up, low, aeinst, ..Default::default() });
+        self
+    }
+
+    pub fn collision_partner(mut self, partner: CollisionPartnerData) -> Self {
+        self.collision_partners.push(partner);
+        self
+    }
+
+    pub fn build(self) -> Result<ElementData, BuildError> {
+        if self.name.trim().is_empty() {
+            return Err(BuildError::MissingName);
+        }
+        if !(self.weight.is_finite() && self.weight > 0.0) {
+            return Err(BuildError::InvalidWeight { weight: self.weight });
+        }
+        if self.energy_levels.is_empty() {
+            return Err(BuildError::NoEnergyLevels);
+        }
+
+        for l in self.energy_levels.iter() {
+            let invalid = |note| Err(BuildError::InvalidLevel { level: l.level, note });
+            if !(l.energy.is_finite() && l.energy >= 0.0) {
+                return invalid("the energy must not be negative");
+            }
+            if !(l.stat_weight.is_finite() && l.stat_weight > 0.0) {
+                return invalid("the statistical weight must be positive");
+            }
+        }
+
+        let energy = |level: u32| self.energy_levels.get((level as usize).wrapping_sub(1)).map(|l| l.energy);
+        for t in self.radiative_transitions.iter() {
+            let invalid = |note| Err(BuildError::InvalidTransition { transition: t.transition, note });
+            match (energy(t.up), energy(t.low)) {
+                (Some(up), Some(low)) if up > low => (),
+                (Some(_), Some(_)) => return invalid("the upper level must lie above the lower level"),
+                _ => return invalid("it refers to a level that was not added"),
+            }
+            if !(t.aeinst.is_finite() && t.aeinst >= 0.0) {
+                return invalid("the Einstein A coefficient must not be negative");
+            }
+        }
+
+        let features = FormatFeatures {
+            quantum_numbers: self.energy_levels.iter().any(|l| !l.qnums.is_empty()),
+            hyperfine: self.energy_levels.iter().any(|l| l.qnums.contains("F=")),
+            ..Default::default()
+        };
+        let mut data = ElementData {
+            name: self.name,
+            information: self.information,
+            weight: self.weight,
+            energy_levels: self.energy_levels,
+            radiative_transitions: self.radiative_transitions,
+            collision_partners: vec!(),
+            features,
+            ..Default::default()
+        };
+
+        for partner in self.collision_partners {
+            if let Some(rate) = partner.rates.iter().find(|r| r.rates.len() != partner.temperatures.len()) {
+                return Err(BuildError::RateCount {
+                    transition: rate.transition,
+                    rates: rate.rates.len(),
+                    temperatures: partner.temperatures.len(),
+                });
+            }
+            data.merge_collision_partner(partner)?;
+        }
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{CollisionPartnerId, CollisionalRates};
+
+    fn partner(rates: Vec<f64>) -> CollisionPartnerData {
+        let rates = vec!(CollisionalRates { transition: 1, up: 2, low: 1, rates });
+        CollisionPartnerData::new(CollisionPartnerId::pH2, "CO-pH2", vec!(10.0, 20.0), rates)
+    }
+
+    #[test]
+    fn build_element_data() {
+        let builder = ElementData::builder("CO")
+            .weight(28.0)
+            .level(0.0, 1.0, "0")
+            .level(3.845, 3.0, "1")
+            .transition(2, 1, 7.2e-8);
+
+        let data = builder.collision_partner(partner(vec!(3.0e-11, 3.5e-11))).build().unwrap();
+        assert_eq!(data.energy_level(2).map(|l| l.stat_weight), Some(3.0));
+        assert!(data.features().quantum_numbers);
+        let reparsed = data.to_lamda_string().parse::<ElementData>().unwrap();
+        assert_eq!(reparsed.radiative_transitions(), data.radiative_transitions(), "Wrong result for written data");
+
+        let levels = || ElementData::builder("CO").weight(28.0).level(0.0, 1.0, "").level(3.845, 3.0, "");
+        assert_eq!(
+            levels().transition(1, 2, 7.2e-8).build(),
+            Err(BuildError::InvalidTransition { transition: 1, note: "the upper level must lie above the lower level" })
+        );
+
+        assert_eq!(
+            levels().collision_partner(partner(vec!(3.0e-11))).build(),
+            Err(BuildError::RateCount { transition: 1, rates: 1, temperatures: 2 }),
+            "Wrong result for missing rates"
+        );
+        assert_eq!(ElementData::builder("").build(), Err(BuildError::MissingName));
+    }
+}
@@ -0,0 +1,453 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use super::{CollisionPartnerId, Decoding, ElementData, ParseError, ReadError};
+use crate::progress::{CancellationToken, Progress, ProgressCallback};
+
+#[derive(Debug)]
+pub enum DatabaseError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        error: ParseError,
+    },
+    UnknownSpecies {
+        name: String,
+    },
+    AmbiguousSpecies {
+        name: String,
+        candidates: Vec<String>,
+    },
+    Cancelled,
+}
+
+impl std::fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseError::Io { path, error } => write!(f, "cannot read `{}`: {}", path.display(), error),
+            DatabaseError::Parse { path, error } => {
+                let line = error.diagnostic().line;
+                write!(f, "{}:{}: not a valid LAMDA datafile\n{}", path.display(), line, error)
+            }
+            DatabaseError::UnknownSpecies { name } => write!(f, "species `{}` is not in the database", name),
+            DatabaseError::AmbiguousSpecies { name, candidates } => {
+                write!(f, "species `{}` may be any of `{}`", name, candidates.join("`, `"))
+            }
+            DatabaseError::Cancelled => write!(f, "loading the database was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DatabaseError::Io { error, .. } => Some(error),
+            DatabaseError::Parse { error, .. } => Some(error),
+            DatabaseError::UnknownSpecies { .. } | DatabaseError::AmbiguousSpecies { .. } | DatabaseError::Cancelled => None,
+        }
+    }
+}
+
+impl ElementData {
+    // Reads the datafile at `path` as it is parsed. Errors name the file, so
+    // that they can be told apart when many files are read.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, DatabaseError> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(|error| DatabaseError::Io { path: path.to_path_buf(), error })?;
+
+        Self::from_reader(std::io::BufReader::new(file), Decoding::Latin1Fallback).map_err(|e| match e {
+            ReadError::Io(error) => DatabaseError::Io { path: path.to_path_buf(), error },
+            ReadError::Parse(error) => DatabaseError::Parse { path: path.to_path_buf(), error },
+        })
+    }
+}
+
+// Parses each of `paths` with `ElementData::from_path`, in parallel with the
+// `parallel` feature. Results are in the order of `paths`.
+pub fn parse_all<P: AsRef<Path> + Sync>(paths: &[P]) -> Vec<Result<ElementData, DatabaseError>> {
+    parse_all_with_progress(paths, &|_| ())
+}
+
+pub fn parse_all_with_progress<P: AsRef<Path> + Sync>(
+    paths: &[P],
+    progress: ProgressCallback<'_, Path>,
+) -> Vec<Result<ElementData, DatabaseError>> {
+    parse_all_cancellable(paths, progress, &CancellationToken::new())
+}
+
+// Once `cancel` is raised the remaining files are skipped with
+// `DatabaseError::Cancelled`; the files parsed before are kept.
+pub fn parse_all_cancellable<P: AsRef<Path> + Sync>(
+    paths: &[P],
+    progress: ProgressCallback<'_, Path>,
+    cancel: &CancellationToken,
+) -> Vec<Result<ElementData, DatabaseError>> {
+    let completed = AtomicUsize::new(0);
+    let parse = |path: &P| {
+        if cancel.is_cancelled() {
+            return Err(DatabaseError::Cancelled);
+        }
+        let data = ElementData::from_path(path);
+        let completed = completed.fetch_add(1, Ordering::Relaxed) + 1;
+        progress(Progress { completed, total: paths.len(), current: path.as_ref() });
+        data
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        paths.par_iter().map(parse).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        paths.iter().map(parse).collect()
+    }
+}
+
+// Other names of species in LAMDA, as (alias, species)
+const ALIASES: [(&str, &str); 8] = [
+    ("12co", "co"),
+    ("12c16o", "co"),
+    ("13c16o", "13co"),
+    ("12c17o", "c17o"),
+    ("12c18o", "c18o"),
+    ("hcop", "hco+"),
+    ("n2hp", "n2h+"),
+    ("hcsp", "hcs+"),
+];
+
+// Form in which species names are compared: case, a `.dat` extension and
+// `-`/`_` separators are ignored, so `p-H2O@Daniel`, `ph2o@daniel.dat` and
+// `pH2O@daniel` are the same name. The `@reference` suffix is kept.
+fn normalize(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    let name = name.strip_suffix(".dat").unwrap_or(&name);
+
+    name.chars().filter(|&c| c != '-' && c != '_').collect()
+}
+
+// Species of a normalized name, without its `@reference`
+fn species_part(name: &str) -> &str {
+    name.split('@').next().unwrap_or(name)
+}
+
+#[derive(Debug)]
+struct Entry {
+    path: PathBuf,
+    data: OnceLock<Arc<ElementData>>,
+}
+
+// Collection of LAMDA datafiles keyed by species name. Datafiles are parsed
+// on first request and kept; clones share the parsed data, so one database
+// can serve requests from many threads.
+#[derive(Debug, Clone)]
+pub struct LamdaDatabase {
+    entries: Arc<BTreeMap<String, Entry>>,
+    // Normalized alias to species name
+    aliases: Arc<BTreeMap<String, String>>,
+}
+
+impl Default for LamdaDatabase {
+    fn default() -> Self {
+        Self::from_paths(Vec::<(String, PathBuf)>::new())
+    }
+}
+
+impl LamdaDatabase {
+    pub fn from_paths<I, S, P>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = (S, P)>,
+        S: Into<String>,
+        P: Into<PathBuf>,
+    {
+        let entries = paths
+            .into_iter()
+            .map(|(name, path)| (name.into(), Entry { path: path.into(), data: OnceLock::new() }))
+            .collect();
+
+        let aliases = ALIASES
+            .iter()
+            .map(|(alias, species)| (String::from(*alias), String::from(*species)))
+            .collect();
+
+        Self { entries: Arc::new(entries), aliases: Arc::new(aliases) }
+    }
+
+    // Every `.dat` file of `dir`, named by its file stem (`co.dat` is `co`).
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, DatabaseError> {
+        let dir = dir.as_ref();
+        let io_error = |error| DatabaseError::Io { path: dir.to_path_buf(), error };
+
+        let mut paths = vec!();
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if path.extension().is_some_and(|e| e == "dat") {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    paths.push((String::from(name), path.clone()));
+                }
+            }
+        }
+
+        Ok(Self::from_paths(paths))
+    }
+
+    pub fn species(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.resolve(name).is_ok()
+    }
+
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.resolve(name)
+            .ok()
+            .and_then(|name| self.entries.get(name))
+            .is_some_and(|e| e.data.get().is_some())
+    }
+
+    // Makes `alias` another name of `species`, taking precedence over the
+    // built-in aliases. Only this database and its later clones see it.
+    pub fn add_alias(&mut self, alias: &str, species: &str) {
+        Arc::make_mut(&mut self.aliases).insert(normalize(alias), String::from(species));
+    }
+
+    // Name of the entry `name` refers to: an exact entry name, an alias, or a
+    // name equal to an entry up to case, extension and separators. A name
+    // without a reference also finds the one datafile of the species under
+    // any reference; with several of them the name is ambiguous.
+    pub fn resolve(&self, name: &str) -> Result<&str, DatabaseError> {
+        if let Some((key, _)) = self.entries.get_key_value(name) {
+            return Ok(key);
+        }
+
+        let mut target = normalize(name);
+        if let Some(species) = self.aliases.get(&target) {
+            if let Some((key, _)) = self.entries.get_key_value(species.as_str()) {
+                return Ok(key);
+            }
+            target = normalize(species);
+        }
+
+        let mut candidates = self.entries.keys().filter(|k| normalize(k) == target).collect::<Vec<_>>();
+        if candidates.is_empty() && !target.contains('@') {
+            candidates = self.entries.keys().filter(|k| species_part(&normalize(k)) == target).collect();
+        }
+
+        match candidates[..] {
+            [key] => Ok(key),
+            [] => Err(DatabaseError::UnknownSpecies { name: String::from(name) }),
+            _ => Err(DatabaseError::AmbiguousSpecies {
+                name: String::from(name),
+                candidates: candidates.into_iter().cloned().collect(),
+            }),
+        }
+    }
+
+    // Parses the datafile on the first call. Concurrent first calls may both
+    // parse it, but all of them get the same shared data back.
+    pub fn get(&self, name: &str) -> Result<Arc<ElementData>, DatabaseError> {
+        let key = self.resolve(name)?;
+        let entry = self.entries.get(key).ok_or_else(|| DatabaseError::UnknownSpecies { name: String::from(name) })?;
+
+        if let Some(data) = entry.data.get() {
+            return Ok(Arc::clone(data));
+        }
+
+        let data = ElementData::from_path(&entry.path)?;
+
+        Ok(Arc::clone(entry.data.get_or_init(|| Arc::new(data))))
+    }
+
+    // Species whose name contains `query`, compared like `resolve` does,
+    // e.g. `co` finds `co`, `13co` and `co@neufeld`. No datafile is parsed.
+    pub fn search(&self, query: &str) -> Vec<&str> {
+        let query = normalize(query);
+        self.species().filter(|name| normalize(name).contains(&query)).collect()
+    }
+
+    // Species whose data satisfies `predicate`. Every datafile is parsed, so
+    // a file that cannot be read fails the search.
+    pub fn find<F: Fn(&ElementData) -> bool>(&self, predicate: F) -> Result<Vec<&str>, DatabaseError> {
+        self.find_with_progress(predicate, &|_| ())
+    }
+
+    pub fn find_with_progress<F: Fn(&ElementData) -> bool>(
+        &self,
+        predicate: F,
+        progress: ProgressCallback<'_, str>,
+    ) -> Result<Vec<&str>, DatabaseError> {
+        self.find_cancellable(predicate, progress, &CancellationToken::new())
+    }
+
+    // Fails with `DatabaseError::Cancelled` once `cancel` is raised; the
+    // datafiles parsed before stay loaded.
+    pub fn find_cancellable<F: Fn(&ElementData) -> bool>(
+        &self,
+        predicate: F,
+        progress: ProgressCallback<'_, str>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<&str>, DatabaseError> {
+        let mut found = vec!();
+        for (i, name) in self.species().enumerate() {
+            if cancel.is_cancelled() {
+                return Err(DatabaseError::Cancelled);
+            }
+            let data = self.get(name)?;
+            if predicate(&data) {
+                found.push(name);
+            }
+            progress(Progress { completed: i + 1, total: self.entries.len(), current: name });
+        }
+
+        Ok(found)
+    }
+
+    // Species with a molecular weight from `min` to `max` amu
+    pub fn with_weight(&self, min: f64, max: f64) -> Result<Vec<&str>, DatabaseError> {
+        self.find(|data| min <= data.weight() && data.weight() <= max)
+    }
+
+    // Species with collisional rates for `partner`
+    pub fn with_collision_partner(&self, partner: CollisionPartnerId) -> Result<Vec<&str>, DatabaseError> {
+        self.find(|data| data.collision_partner(partner.clone()).is_some())
+    }
+
+    // Species whose main isotopologue is `parent`, e.g. `CO` for 13CO and C18O
+    pub fn isotopologues(&self, parent: &str) -> Result<Vec<&str>, DatabaseError> {
+        self.find(|data| data.species().is_some_and(|s| s.parent == parent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn shared_lazy_loading() {
+        let dir = std::env::temp_dir().join(format!("ism-database-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let s = "!MOLECULE\nO\n!MOLECULAR WEIGHT\n16.0\n!NUMBER OF ENERGY LEVELS\n1\n!LEVEL\n1 0.0 5.0 3_P_2\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n0\n!TRANS\n!NUMBER OF COLL PARTNERS\n0\n";
+        std::fs::write(dir.join("o.dat"), s).unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let db = LamdaDatabase::open(&dir).unwrap();
+        let shared = db.clone();
+
+        assert_eq!(db.species().collect::<Vec<_>>(), vec!("o"));
+        assert!(!db.is_loaded("o"));
+
+        let a = std::thread::spawn(move || shared.get("o").unwrap()).join().unwrap();
+        let b = db.get("o").unwrap();
+
+        assert!(Arc::ptr_eq(&a, &b), "Wrong result for repeated lookups");
+        assert!(db.is_loaded("o"));
+        assert!(matches!(db.get("co"), Err(DatabaseError::UnknownSpecies { .. })));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolve_aliases() {
+        let mut db = LamdaDatabase::from_paths([
+            ("co", "co.dat"),
+            ("co@neufeld", "co@neufeld.dat"),
+            ("ph2o@daniel", "ph2o@daniel.dat"),
+            ("hco+@xpol", "hco+@xpol.dat"),
+        ]);
+
+        assert_eq!(db.resolve("CO").ok(), Some("co"));
+        assert_eq!(db.resolve("co.dat").ok(), Some("co"));
+        assert_eq!(db.resolve("12CO").ok(), Some("co"));
+        assert_eq!(db.resolve("co@neufeld").ok(), Some("co@neufeld"));
+        assert_eq!(db.resolve("CO@Neufeld.dat").ok(), Some("co@neufeld"));
+        assert_eq!(db.resolve("p-H2O").ok(), Some("ph2o@daniel"));
+        assert_eq!(db.resolve("HCOp").ok(), Some("hco+@xpol"));
+        assert!(matches!(db.resolve("water"), Err(DatabaseError::UnknownSpecies { .. })));
+        assert!(matches!(db.resolve("hco+@ypol"), Err(DatabaseError::UnknownSpecies { .. })));
+
+        db.add_alias("Water", "ph2o@daniel");
+        db.add_alias("12CO", "co@neufeld");
+
+        assert_eq!(db.resolve("water").ok(), Some("ph2o@daniel"));
+        assert_eq!(db.resolve("12co").ok(), Some("co@neufeld"));
+    }
+
+    #[test]
+    fn ambiguous_names() {
+        let db = LamdaDatabase::from_paths([
+            ("co@neufeld", "co@neufeld.dat"),
+            ("co@yang", "co@yang.dat"),
+            ("CO", "CO.dat"),
+            ("co", "co.dat"),
+        ]);
+
+        assert_eq!(db.resolve("co@yang").ok(), Some("co@yang"));
+        match db.resolve("Co") {
+            Err(DatabaseError::AmbiguousSpecies { candidates, .. }) => assert_eq!(candidates, vec!("CO", "co")),
+            other => panic!("Wrong result for ambiguous name: {:?}", other),
+        }
+
+        let db = LamdaDatabase::from_paths([("co@neufeld", "co@neufeld.dat"), ("co@yang", "co@yang.dat")]);
+        let e = db.get("co").unwrap_err();
+        assert_eq!(e.to_string(), "species `co` may be any of `co@neufeld`, `co@yang`");
+    }
+
+    #[test]
+    fn search_species() {
+        let dir = std::env::temp_dir().join(format!("ism-database-search-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("oatom.dat"), include_str!("../../data/oatom.dat")).unwrap();
+        let co = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n1\n!LEVEL\n1 0.0 1.0 0\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n0\n!TRANS\n!NUMBER OF COLL PARTNERS\n0\n";
+        std::fs::write(dir.join("co.dat"), co).unwrap();
+        std::fs::write(dir.join("co@neufeld.dat"), co).unwrap();
+
+        let db = LamdaDatabase::open(&dir).unwrap();
+        assert_eq!(db.search("CO"), vec!("co", "co@neufeld"));
+        assert!(!db.is_loaded("co"), "Search by name parsed a datafile");
+        assert_eq!(db.with_weight(20.0, 30.0).unwrap(), vec!("co", "co@neufeld"));
+        assert_eq!(db.with_collision_partner(CollisionPartnerId::He).unwrap(), vec!("oatom"));
+        assert_eq!(db.isotopologues("CO").unwrap(), vec!("co", "co@neufeld"));
+
+        let parsed = parse_all(&[dir.join("co.dat"), dir.join("missing.dat"), dir.join("oatom.dat")]);
+        let names = parsed.iter().map(|r| r.as_ref().map(|data| data.name())).collect::<Vec<_>>();
+        assert!(matches!(names[..], [Ok("CO"), Err(DatabaseError::Io { .. }), Ok("O")]), "Wrong result for {:?}", names);
+
+        let paths = [dir.join("co.dat"), dir.join("oatom.dat")];
+        let cancel = CancellationToken::new();
+        let done = AtomicUsize::new(0);
+        let progress = |p: Progress<'_, Path>| {
+            done.fetch_add(1, Ordering::Relaxed);
+            assert!(paths.iter().any(|path| path == p.current) && p.total == 2);
+        };
+        assert!(parse_all_with_progress(&paths, &progress).iter().all(Result::is_ok));
+        assert_eq!(done.load(Ordering::Relaxed), 2, "Wrong number of progress calls");
+        cancel.cancel();
+        assert!(parse_all_cancellable(&paths, &progress, &cancel).iter().all(|r| matches!(r, Err(DatabaseError::Cancelled))));
+
+        let names = std::sync::Mutex::new(vec!());
+        let progress = |p: Progress<'_, str>| names.lock().unwrap().push((p.completed, String::from(p.current)));
+        assert_eq!(db.find_with_progress(|_| true, &progress).unwrap().len(), 3);
+        assert_eq!(names.lock().unwrap().last(), Some(&(3, String::from("oatom"))));
+        assert!(matches!(db.find_cancellable(|_| true, &progress, &cancel), Err(DatabaseError::Cancelled)));
+
+        std::fs::write(dir.join("broken.dat"), "!MOLECULE\n").unwrap();
+        let db = LamdaDatabase::open(&dir).unwrap();
+        assert!(matches!(db.with_weight(0.0, 100.0), Err(DatabaseError::Parse { .. })));
+
+        let e = ElementData::from_path(dir.join("broken.dat")).unwrap_err();
+        let message = e.to_string();
+        assert!(message.starts_with(&format!("{}:2: ", dir.join("broken.dat").display())), "Wrong file context in `{}`", message);
+        assert!(matches!(ElementData::from_path(dir.join("missing.dat")), Err(DatabaseError::Io { .. })));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::ParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// Structured form of a problem in a datafile for editors and web services.
+// Lines and columns are 1-based, as editors count them; both are 0 for
+// problems that are not tied to a line, such as solver warnings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Diagnostic {
+    pub code: String,
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    // Number of characters the problem spans, starting at `column`
+    pub width: usize,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn warning(code: &str, message: String) -> Self {
+        Self { code: String::from(code), severity: Severity::Warning, line: 0, column: 0, width: 0, message, suggestion: None }
+    }
+
+    pub fn error(code: &str, message: String) -> Self {
+        Self { severity: Severity::Error, ..Self::warning(code, message) }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+// Non-fatal findings collected while parsing and solving. A run that
+// succeeds may still have put warnings here, e.g. about rates clamped to the
+// tabulated temperatures.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    warnings: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.warnings.push(diagnostic);
+    }
+
+    pub fn warn(&mut self, code: &str, message: String) {
+        self.push(Diagnostic::warning(code, message));
+    }
+
+    pub fn warnings(&self) -> &[Diagnostic] {
+        &self.warnings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.warnings.len()
+    }
+
+    pub fn into_vec(self) -> Vec<Diagnostic> {
+        self.warnings
+    }
+}
+
+impl ParseError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotEnoughInput { .. } => "not-enough-input",
+            Self::WrongCommentFormat { .. } => "wrong-comment-format",
+            Self::MissingField { .. } => "missing-field",
+            Self::NotFloat { .. } => "not-float",
+            Self::NotInt { .. } => "not-int",
+            Self::UnknownItem { .. } => "unknown-item",
+            Self::UnknownCollisionPartner { .. } => "unknown-collision-partner",
+            Self::InvalidEncoding { .. } => "invalid-encoding",
+            Self::LimitExceeded { .. } => "limit-exceeded",
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = |line_number: usize, column: usize, width: usize, message: &str, suggestion: Option<&str>| {
+            Diagnostic {
+                code: String::from(self.code()),
+                severity: Severity::Error,
+                line: line_number + 1,
+                column: column + 1,
+                width,
+                message: String::from(message),
+                suggestion: suggestion.map(String::from),
+            }
+        };
+
+        match self {
+            Self::NotEnoughInput { line_number, .. } => diagnostic(
+                *line_number, 0, 0,
+                "Unexpected end of input",
+                Some("Check that the numbers of levels, transitions and collision partners match the data"),
+            ),
+            Self::WrongCommentFormat { line_number, line, note, .. } => diagnostic(
+                *line_number, 0, line.len().min(1), note,
+                Some("Start the line with `!`, or check the counts of the preceding section"),
+            ),
+            Self::MissingField { line_number, line, note, .. } => diagnostic(
+                *line_number, line.len(), 1, note,
+                Some("Add the missing column"),
+            ),
+            Self::NotFloat { line_number, line, note, .. } => diagnostic(
+                *line_number, 0, line.len(), note,
+                Some("Write the value as a decimal or exponent number, e.g. `28.0` or `2.8e1`"),
+            ),
+            Self::NotInt { line_number, line, note, .. } => diagnostic(
+                *line_number, 0, line.len(), note,
+                Some("Write the value as a whole number without a decimal point"),
+            ),
+            Self::UnknownItem { line_number, span, note, .. } => diagnostic(
+                *line_number, span.column - 1, span.len, note,
+                None,
+            ),
+            Self::UnknownCollisionPartner { line_number, line, note, .. } => diagnostic(
+                *line_number,
+                line.find(char::is_alphanumeric).unwrap_or(0),
+                line.split_whitespace().next().unwrap_or("").len(),
+                note,
+                Some("Use one of the LAMDA collision partner ids 1 to 7"),
+            ),
+            Self::InvalidEncoding { line_number, column, note, .. } => diagnostic(
+                *line_number, *column, 1, note,
+                Some("Save the file as UTF-8, or read it with Latin-1 fallback decoding"),
+            ),
+            Self::LimitExceeded { line_number, line, note, .. } => diagnostic(
+                *line_number, 0, line.len(), note,
+                Some("Check the count, or parse with larger `ParseLimits` if the file is trusted"),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{ElementData, Span};
+
+    #[test]
+    fn unknown_item_diagnostic() {
+        let e = ParseError::UnknownItem {
+            line_number: 7,
+            span: Span::in_line(5, 3),
+            line: String::from("    1 abc  1.0  0"),
+            note: String::from("Value `abc` from field `energy` has wrong type (should be floating point number)"),
+            source: None,
+        };
+
+        let d = e.diagnostic();
+
+        assert_eq!((d.code.as_str(), d.line, d.column, d.width), ("unknown-item", 8, 6, 3));
+        assert_eq!(d.severity, Severity::Error);
+    }
+
+    #[test]
+    fn parse_warnings() {
+        let s = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n2\n!LEVEL\n1 0.0 1.0\n2 3.845 3.0\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS\n1 2 1 7.2e-8\n!NUMBER OF COLL PARTNERS\n1\n!PARTNER\n\
+            2 CO-pH2\n!NUMBER OF COLL TRANS\n1\n!NUMBER OF COLL TEMPS\n3\n!COLL TEMPS\n10.0 20.0\n!TRANS\n1 2 1 3.0e-11\n";
+        let mut diagnostics = Diagnostics::new();
+
+        assert!(ElementData::parse_with_diagnostics(s, &mut diagnostics).is_ok());
+
+        let found = diagnostics.warnings().iter().map(|d| (d.code.as_str(), d.line)).collect::<Vec<_>>();
+        assert_eq!(found, vec!(("temperature-count", 23), ("rate-count", 25)));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn diagnostic_json() {
+        let d = ParseError::end_of_input(3).diagnostic();
+
+        let json: serde_json::Value = serde_json::from_str(&d.to_json().unwrap()).unwrap();
+
+        assert_eq!(json["code"], "not-enough-input");
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["line"], 4);
+    }
+}
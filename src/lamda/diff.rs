@@ -0,0 +1,202 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{CollisionPartnerData, CollisionPartnerId, CollisionalRates, ElementData, EnergyLevel, RadiativeTransition};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+pub enum Change<T> {
+    Added(T),
+    Removed(T),
+    Changed { old: T, new: T },
+}
+
+// Changes of the rates with one collision partner that both versions have
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct PartnerDiff {
+    pub partner: CollisionPartnerId,
+    // Old and new temperatures, when they differ
+    pub temperatures: Option<(Vec<f64>, Vec<f64>)>,
+    pub rates: Vec<Change<CollisionalRates>>,
+}
+
+// Differences between two versions of a datafile. Levels are matched by
+// their number and transitions and rates by their upper and lower levels, so
+// that renumbered transitions are not reported; values are compared exactly,
+// as they were written in the files.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Diff {
+    pub weight: Option<(f64, f64)>,
+    pub levels: Vec<Change<EnergyLevel>>,
+    pub transitions: Vec<Change<RadiativeTransition>>,
+    pub added_partners: Vec<CollisionPartnerId>,
+    pub removed_partners: Vec<CollisionPartnerId>,
+    pub partners: Vec<PartnerDiff>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.weight.is_none()
+            && self.levels.is_empty()
+            && self.transitions.is_empty()
+            && self.added_partners.is_empty()
+            && self.removed_partners.is_empty()
+            && self.partners.is_empty()
+    }
+}
+
+// Items of `old` that are missing or differ in `new`, in the order of `old`,
+// followed by the items only `new` has
+fn changes<T: Clone, K: Ord>(
+    old: &[T],
+    new: &[T],
+    key: impl Fn(&T) -> K,
+    same: impl Fn(&T, &T) -> bool,
+) -> Vec<Change<T>> {
+    let in_new = new.iter().map(|n| (key(n), n)).collect::<BTreeMap<_, _>>();
+    let in_old = old.iter().map(|o| (key(o), o)).collect::<BTreeMap<_, _>>();
+
+    let mut changes = vec!();
+    for o in old.iter() {
+        match in_new.get(&key(o)) {
+            None => changes.push(Change::Removed(o.clone())),
+            Some(n) if !same(o, n) => changes.push(Change::Changed { old: o.clone(), new: (*n).clone() }),
+            Some(_) => (),
+        }
+    }
+    changes.extend(new.iter().filter(|n| !in_old.contains_key(&key(n))).map(|n| Change::Added(n.clone())));
+
+    changes
+}
+
+fn partner_diff(old: &CollisionPartnerData, new: &CollisionPartnerData) -> Option<PartnerDiff> {
+    let temperatures = (old.temperatures != new.temperatures)
+        .then(|| (old.temperatures.clone(), new.temperatures.clone()));
+    let rates = changes(&old.rates, &new.rates, |cr| (cr.up, cr.low), |o, n| o.rates == n.rates);
+
+    (temperatures.is_some() || !rates.is_empty())
+        .then(|| PartnerDiff { partner: old.name.clone(), temperatures, rates })
+}
+
+impl ElementData {
+    // What changed from `self` to `other`, e.g. between two releases of the
+    // database
+    pub fn diff(&self, other: &ElementData) -> Diff {
+        let partner = |data: &ElementData, name: &CollisionPartnerId| {
+            data.collision_partners.iter().any(|cp| cp.name == *name)
+        };
+
+        Diff {
+            weight: (self.weight != other.weight).then_some((self.weight, other.weight)),
+            levels: changes(
+                &self.energy_levels,
+                &other.energy_levels,
+                |el| el.level,
+                |o, n| o.energy == n.energy && o.stat_weight == n.stat_weight && o.qnums == n.qnums,
+            ),
+            transitions: changes(
+                &self.radiative_transitions,
+                &other.radiative_transitions,
+                |rt| (rt.up, rt.low),
+                |o, n| o.aeinst == n.aeinst && o.frequency == n.frequency && o.upper_energy == n.upper_energy,
+            ),
+            added_partners: other.collision_partners
+                .iter()
+                .filter(|cp| !partner(self, &cp.name))
+                .map(|cp| cp.name.clone())
+                .collect(),
+            removed_partners: self.collision_partners
+                .iter()
+                .filter(|cp| !partner(other, &cp.name))
+                .map(|cp| cp.name.clone())
+                .collect(),
+            partners: self.collision_partners
+                .iter()
+                .filter_map(|old| {
+                    let new = other.collision_partners.iter().find(|cp| cp.name == old.name)?;
+                    partner_diff(old, new)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl core::fmt::Display for Diff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fn sign<T>(change: &Change<T>) -> char {
+            match change {
+                Change::Added(_) => '+',
+                Change::Removed(_) => '-',
+                Change::Changed { .. } => '~',
+            }
+        }
+        fn item<T>(change: &Change<T>) -> &T {
+            match change {
+                Change::Added(item) | Change::Removed(item) | Change::Changed { new: item, .. } => item,
+            }
+        }
+
+        if let Some((old, new)) = self.weight {
+            writeln!(f, "~ weight {} -> {}", old, new)?;
+        }
+        for change in self.levels.iter() {
+            let el = item(change);
+            writeln!(f, "{} level {} ({})", sign(change), el.level, el.qnums)?;
+        }
+        for change in self.transitions.iter() {
+            let rt = item(change);
+            writeln!(f, "{} transition {} -> {}", sign(change), rt.up, rt.low)?;
+        }
+        for name in self.added_partners.iter() {
+            writeln!(f, "+ partner {}", name)?;
+        }
+        for name in self.removed_partners.iter() {
+            writeln!(f, "- partner {}", name)?;
+        }
+        for partner in self.partners.iter() {
+            if partner.temperatures.is_some() {
+                writeln!(f, "~ partner {} temperatures", partner.partner)?;
+            }
+            for change in partner.rates.iter() {
+                let cr = item(change);
+                writeln!(f, "{} partner {} rates {} -> {}", sign(change), partner.partner, cr.up, cr.low)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn diff_versions() {
+        let old = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        assert!(old.diff(&old).is_empty(), "Wrong result for equal data");
+
+        let mut new = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        new.energy_levels_mut()[1].energy *= 1.01;
+        new.radiative_transitions.pop();
+        new.radiative_transitions_mut()[0].transition += 10;
+        new.collision_partners_mut()[0].rates[0].rates[0] *= 2.0;
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.weight, None);
+        assert!(matches!(&diff.levels[..], [Change::Changed { old, new }] if old.level == 2 && new.energy > old.energy));
+        assert!(matches!(&diff.transitions[..], [Change::Removed(_)]), "Wrong result for transitions: {:?}", diff.transitions);
+        assert_eq!(diff.partners.len(), 1);
+        assert_eq!(diff.partners[0].partner, old.collision_partners()[0].name());
+        assert_eq!(diff.partners[0].rates.len(), 1, "Wrong result for rates");
+        assert_eq!(diff.to_string().lines().count(), 3);
+
+        let reverse = new.diff(&old);
+        assert!(matches!(&reverse.transitions[..], [Change::Added(_)]));
+    }
+}
@@ -0,0 +1,88 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+use super::{ElementData, ParseError, Span};
+
+// How input that is not valid UTF-8 is read. Archive datafiles often have
+// Latin-1 characters in author names and notes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Decoding {
+    // Reject input that is not UTF-8
+    Strict,
+    // Replace invalid bytes with U+FFFD
+    Lossy,
+    // Read invalid bytes as Latin-1 (ISO 8859-1), keeping valid UTF-8 as is
+    #[default]
+    Latin1Fallback,
+}
+
+fn latin1_fallback(mut bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len());
+
+    loop {
+        match core::str::from_utf8(bytes) {
+            Ok(valid) => {
+                s.push_str(valid);
+                return s;
+            }
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                s.push_str(core::str::from_utf8(valid).unwrap_or_default());
+                let invalid = e.error_len().unwrap_or(rest.len());
+                s.extend(rest[..invalid].iter().map(|&b| b as char));
+                bytes = &rest[invalid..];
+            }
+        }
+    }
+}
+
+fn invalid_encoding(bytes: &[u8], offset: usize) -> ParseError {
+    let start = bytes[..offset].iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let end = bytes[offset..].iter().position(|&b| b == b'\n').map_or(bytes.len(), |i| offset + i);
+
+    ParseError::InvalidEncoding {
+        line_number: bytes[..offset].iter().filter(|&&b| b == b'\n').count(),
+        span: Span { column: offset - start + 1, len: 1, byte_offset: offset },
+        column: String::from_utf8_lossy(&bytes[start..offset]).chars().count(),
+        line: String::from_utf8_lossy(&bytes[start..end]).trim_end_matches('\r').into(),
+        note: String::from("Byte sequence is not valid UTF-8"),
+    }
+}
+
+pub fn decode(bytes: &[u8], decoding: Decoding) -> Result<Cow<'_, str>, ParseError> {
+    match core::str::from_utf8(bytes) {
+        Ok(s) => Ok(Cow::Borrowed(s)),
+        Err(e) => match decoding {
+            Decoding::Strict => Err(invalid_encoding(bytes, e.valid_up_to())),
+            Decoding::Lossy => Ok(String::from_utf8_lossy(bytes)),
+            Decoding::Latin1Fallback => Ok(Cow::Owned(latin1_fallback(bytes))),
+        },
+    }
+}
+
+impl ElementData {
+    pub fn from_bytes(bytes: &[u8], decoding: Decoding) -> Result<Self, ParseError> {
+        decode(bytes, decoding)?.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn decode_latin1_comments() {
+        let bytes = b"!MOLECULE\nO\n!MOLECULAR WEIGHT\n16.0\n!NUMBER OF ENERGY LEVELS\n1\n!LEVEL\n1 0.0 5.0 3_P_2\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n0\n!TRANS\n!NUMBER OF COLL PARTNERS\n0\n! Sch\xf6ier, \xc3\xa5 2005\n";
+
+        assert_eq!(decode(bytes, Decoding::Latin1Fallback).unwrap().lines().last(), Some("! Schöier, å 2005"));
+        assert_eq!(decode(bytes, Decoding::Lossy).unwrap().lines().last(), Some("! Sch\u{fffd}ier, å 2005"));
+        assert!(ElementData::from_bytes(bytes, Decoding::default()).is_ok());
+
+        match ElementData::from_bytes(bytes, Decoding::Strict) {
+            Err(ParseError::InvalidEncoding { line_number, column, .. }) => assert_eq!((line_number, column), (13, 5)),
+            other => panic!("Wrong result for strict decoding: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use super::ElementDataRef;
+
+// Optional parts of the LAMDA format found in a datafile. Files from
+// different eras of the database differ in these, so code that needs one of
+// them should check here instead of guessing from the species.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormatFeatures {
+    // Radiative transitions list frequency (GHz) and upper level energy (K)
+    // after the Einstein A coefficient
+    pub transition_frequencies: bool,
+    // Energy levels carry quantum number labels
+    pub quantum_numbers: bool,
+    // Levels are hyperfine components, labelled with F quantum numbers
+    pub hyperfine: bool,
+    // Free-text notes follow the collisional data
+    pub notes: bool,
+}
+
+// A level header such as `!LEVEL + ENERGIES(cm^-1) + WEIGHT + J + F1 + F`
+// names the F quantum numbers of hyperfine datafiles.
+fn is_hyperfine_header(header: &str) -> bool {
+    header
+        .trim_start_matches(['!', ' '])
+        .split(|c: char| c == '+' || c.is_whitespace())
+        .any(|token| token.strip_prefix('F').is_some_and(|n| n.chars().all(|c| c.is_ascii_digit() || c == '_')))
+}
+
+impl FormatFeatures {
+    pub(crate) fn detect(level_header: &str, data: &ElementDataRef<'_>) -> Self {
+        let transition_frequencies = !data.radiative_transitions.is_empty()
+            && data.radiative_transitions.iter().all(|t| t.frequency.is_some() && t.upper_energy.is_some());
+
+        Self {
+            transition_frequencies,
+            quantum_numbers: data.energy_levels.iter().any(|l| !l.qnums.is_empty()),
+            hyperfine: is_hyperfine_header(level_header) || data.energy_levels.iter().any(|l| l.qnums.contains("F=")),
+            notes: !data.comments.is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn detect_features() {
+        let modern = "!MOLECULE\nHCN hyperfine\n!MOLECULAR WEIGHT\n27.0\n!NUMBER OF ENERGY LEVELS\n2\n\
+            !LEVEL + ENERGIES(cm^-1) + WEIGHT + J + F\n1 0.0 3.0 0_1\n2 2.9564 1.0 1_0\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS + UP + LOW + EINSTEINA(s^-1) + FREQ(GHz) + E_u(K)\n\
+            1 2 1 2.4e-5 88.6304 4.25\n!NUMBER OF COLL PARTNERS\n0\n! Hyperfine splitting from the CDMS\n";
+        let legacy = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n2\n!LEVEL + ENERGIES(cm^-1) + WEIGHT\n\
+            1 0.0 1.0\n2 3.845 3.0\n!NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS + UP + LOW + EINSTEINA(s^-1)\n\
+            1 2 1 7.2e-8\n!NUMBER OF COLL PARTNERS\n0\n";
+
+        let features = |s: &str| ElementDataRef::parse(s).unwrap().features();
+
+        assert_eq!(
+            features(modern),
+            FormatFeatures { transition_frequencies: true, quantum_numbers: true, hyperfine: true, notes: true },
+        );
+        assert_eq!(features(legacy), FormatFeatures::default());
+    }
+}
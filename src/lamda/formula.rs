@@ -0,0 +1,99 @@
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::species::{atoms, Species};
+use super::{Diagnostic, ElementData};
+
+// Standard atomic weights, amu, of the elements of interstellar molecules
+const ATOMIC_WEIGHTS: &[(&str, f64)] = &[
+    ("H", 1.008), ("He", 4.0026), ("C", 12.011), ("N", 14.007), ("O", 15.999), ("F", 18.998),
+    ("Ne", 20.180), ("Na", 22.990), ("Mg", 24.305), ("Al", 26.982), ("Si", 28.085), ("P", 30.974),
+    ("S", 32.06), ("Cl", 35.45), ("Ar", 39.948), ("K", 39.098), ("Ca", 40.078), ("Ti", 47.867),
+    ("Fe", 55.845),
+];
+
+// Masses, amu, of the isotopes found in datafiles; others are taken at their
+// mass number
+const ISOTOPE_MASSES: &[(&str, u32, f64)] = &[
+    ("H", 1, 1.007825), ("H", 2, 2.014102), ("He", 3, 3.016029), ("C", 12, 12.0), ("C", 13, 13.003355),
+    ("N", 14, 14.003074), ("N", 15, 15.000109), ("O", 16, 15.994915), ("O", 17, 16.999132),
+    ("O", 18, 17.999160), ("Si", 29, 28.976495), ("Si", 30, 29.973770), ("S", 33, 32.971459),
+    ("S", 34, 33.967867), ("Cl", 37, 36.965903),
+];
+
+// Tolerance of `validate` for the stated molecular weight, amu. Most files
+// give the weight rounded to the mass number.
+pub const WEIGHT_TOLERANCE: f64 = 0.5;
+
+fn atomic_weight(element: &str) -> Option<f64> {
+    ATOMIC_WEIGHTS.iter().find(|(e, _)| *e == element).map(|(_, w)| *w)
+}
+
+// Molecular weight of a formula or species name such as `H2C3O`, `C-13-O`
+// or `DCO+`, amu; None for names that are not a formula or have an element
+// without a known weight
+pub fn molecular_weight(formula: &str) -> Option<f64> {
+    let species = Species::parse(formula)?;
+    let parent = atoms(species.parent.trim_end_matches(['+', '-']))?
+        .iter()
+        .map(|(element, count, _)| Some(atomic_weight(element)? * *count as f64))
+        .sum::<Option<f64>>()?;
+    let isotopes = species.substitutions
+        .iter()
+        .map(|isotope| {
+            let mass = ISOTOPE_MASSES
+                .iter()
+                .find(|(e, a, _)| *e == isotope.element && *a == isotope.mass)
+                .map_or(isotope.mass as f64, |(_, _, m)| *m);
+            Some(mass - atomic_weight(&isotope.element)?)
+        })
+        .sum::<Option<f64>>()?;
+
+    Some(parent + isotopes)
+}
+
+impl ElementData {
+    pub fn formula_weight(&self) -> Option<f64> {
+        molecular_weight(&self.name)
+    }
+
+    // Warning when the stated molecular weight is more than `tolerance` amu
+    // from that of the species name
+    pub fn check_molecular_weight(&self, tolerance: f64) -> Vec<Diagnostic> {
+        match self.formula_weight() {
+            Some(expected) if (self.weight - expected).abs() > tolerance => vec!(Diagnostic::warning(
+                "weight-mismatch",
+                format!("Molecular weight {} differs from {:.3} of {}", self.weight, expected, self.name),
+            )),
+            _ => vec!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn formula_weights() {
+        let weight = |f: &str| molecular_weight(f).unwrap_or(f64::NAN);
+
+        assert!((weight("H2C3O") - (2.0 * 1.008 + 3.0 * 12.011 + 15.999)).abs() < 1e-9);
+        assert!((weight("C-13-O") - (13.003355 + 15.999)).abs() < 1e-9, "Wrong result for a dashed isotope {}", weight("C-13-O"));
+        assert_eq!(weight("13CO"), weight("C-13-O"));
+        assert!((weight("HCO+") - 29.018).abs() < 1e-9);
+        assert!((weight("CN-") - 26.018).abs() < 1e-9);
+        assert!((weight("o-H2CO") - 30.026).abs() < 1e-9);
+        assert!((weight("D2CO") - (2.0 * 2.014102 + 12.011 + 15.999)).abs() < 1e-9);
+        assert!((weight("C18O") - (12.011 + 17.999160)).abs() < 1e-9);
+        assert_eq!(molecular_weight("e-CH3OH"), None);
+        assert_eq!(molecular_weight("Xy"), None);
+
+        let mut data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        assert!(data.check_molecular_weight(WEIGHT_TOLERANCE).is_empty());
+        data.weight = 18.0;
+        assert_eq!(data.check_molecular_weight(WEIGHT_TOLERANCE)[0].code, "weight-mismatch", "Wrong result for a wrong weight");
+    }
+}
@@ -0,0 +1,184 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use super::generate::{self, PARTNERS};
+use super::{CollisionPartnerData, CollisionPartnerId, CollisionalRates, ElementData, EnergyLevel, RadiativeTransition};
+
+// `Arbitrary` values that are structurally valid molecular data, for fuzzers
+// and property tests, with the invariants of `generate`; every value is
+// finite.
+
+fn float(u: &mut Unstructured<'_>, min: f64, max: f64) -> Result<f64> {
+    let x = u.arbitrary::<u32>()? as f64 / u32::MAX as f64;
+    Ok(min + x * (max - min))
+}
+
+// Powers of ten, as Einstein A and rate coefficients span many decades
+fn power(u: &mut Unstructured<'_>, min: f64, max: f64) -> Result<f64> {
+    Ok(10f64.powf(float(u, min, max)?))
+}
+
+fn text(u: &mut Unstructured<'_>, first: &[u8], rest: &[u8], max: usize) -> Result<String> {
+    let mut s = String::new();
+    if !first.is_empty() {
+        s.push(*u.choose(first)? as char);
+    }
+    for _ in 0..u.int_in_range(0..=max)? {
+        s.push(*u.choose(rest)? as char);
+    }
+    Ok(s)
+}
+
+fn qnums(u: &mut Unstructured<'_>) -> Result<String> {
+    text(u, b"", b"0123456789_", 6)
+}
+
+fn level_pair(u: &mut Unstructured<'_>, nlev: u32) -> Result<(u32, u32)> {
+    let up = u.int_in_range(2..=nlev)?;
+    Ok((up, u.int_in_range(1..=up - 1)?))
+}
+
+fn temperatures(u: &mut Unstructured<'_>) -> Result<Vec<f64>> {
+    let steps = (0..u.int_in_range(1..=8)?).map(|_| float(u, 1.0, 100.0)).collect::<Result<Vec<_>>>()?;
+    Ok(generate::increasing(steps))
+}
+
+fn rates(u: &mut Unstructured<'_>, ntemp: usize) -> Result<Vec<f64>> {
+    (0..ntemp).map(|_| power(u, -14.0, -9.0)).collect()
+}
+
+// Rates of `name` for some of the pairs of `nlev` levels
+fn collision_partner(u: &mut Unstructured<'_>, name: CollisionPartnerId, nlev: u32) -> Result<CollisionPartnerData> {
+    let temperatures = temperatures(u)?;
+    let mut pairs = vec!();
+    for pair in generate::level_pairs(nlev) {
+        if u.arbitrary()? {
+            pairs.push((pair, rates(u, temperatures.len())?));
+        }
+    }
+
+    Ok(generate::collision_partner(name, temperatures, pairs))
+}
+
+impl<'a> Arbitrary<'a> for CollisionPartnerId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&PARTNERS).cloned()
+    }
+}
+
+impl<'a> Arbitrary<'a> for EnergyLevel {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(EnergyLevel {
+            level: u.int_in_range(1..=1000)?,
+            energy: float(u, 0.0, 1e4)?,
+            stat_weight: u.int_in_range(1..=99)? as f64,
+            qnums: qnums(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for RadiativeTransition {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let (up, low) = level_pair(u, 1000)?;
+        let frequency = u.arbitrary::<bool>()?.then(|| float(u, 1.0, 1e4)).transpose()?;
+        let upper_energy = frequency.map(|_| float(u, 1.0, 1e4)).transpose()?;
+        Ok(RadiativeTransition {
+            transition: u.int_in_range(1..=1000)?,
+            up,
+            low,
+            aeinst: power(u, -10.0, -2.0)?,
+            frequency,
+            upper_energy,
+            extra: String::new(),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for CollisionalRates {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let (transition, (up, low), ntemp) = (u.int_in_range(1..=1000)?, level_pair(u, 1000)?, u.int_in_range(1..=8)?);
+        Ok(CollisionalRates { transition, up, low, rates: rates(u, ntemp)? })
+    }
+}
+
+impl<'a> Arbitrary<'a> for CollisionPartnerData {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let (name, nlev) = (u.arbitrary()?, u.int_in_range(2..=12)?);
+        collision_partner(u, name, nlev)
+    }
+}
+
+impl<'a> Arbitrary<'a> for ElementData {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let name = text(u, b"ABCHNOS", b"abcdeCHNOS0123456789+", 5)?;
+        let note = text(u, b"", b"abcdefghij", 8)?;
+        let weight = float(u, 1.0, 200.0)?;
+
+        let nlev = u.int_in_range(1..=12)?;
+        let levels = (0..nlev)
+            .map(|_| Ok((float(u, 0.1, 500.0)?, u.int_in_range(1..=99)? as f64, qnums(u)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let frequencies = u.arbitrary::<bool>()?;
+        let mut lines = vec!();
+        for pair in generate::level_pairs(nlev) {
+            if u.arbitrary()? {
+                let frequency = match frequencies {
+                    true => Some((float(u, 1.0, 1e4)?, float(u, 1.0, 1e4)?)),
+                    false => None,
+                };
+                lines.push((pair, power(u, -10.0, -2.0)?, frequency));
+            }
+        }
+
+        let mut collision_partners = vec!();
+        for name in PARTNERS {
+            if u.arbitrary()? {
+                collision_partners.push(collision_partner(u, name, nlev)?);
+            }
+        }
+
+        Ok(generate::element_data(
+            name,
+            &note,
+            weight,
+            generate::energy_levels(levels),
+            generate::radiative_transitions(lines),
+            collision_partners,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // Deterministic bytes standing in for fuzzer input
+    fn bytes(seed: u64, len: usize) -> Vec<u8> {
+        let mut x = seed;
+        (0..len)
+            .map(|_| {
+                x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (x >> 33) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arbitrary_round_trip() {
+        for seed in 0..200 {
+            let bytes = bytes(seed, 4096);
+            let data = ElementData::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert!(data.energy_levels().windows(2).all(|w| w[0].energy <= w[1].energy));
+            let parsed = data.to_lamda_string().parse::<ElementData>();
+            assert_eq!(parsed.as_ref(), Ok(&data), "Wrong result for re-parsed data of seed {}", seed);
+
+            let level = EnergyLevel::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert!(level.level >= 1 && level.energy >= 0.0);
+            let transition = RadiativeTransition::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert!(transition.up > transition.low && transition.low >= 1);
+            let partner = CollisionPartnerData::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert!(partner.rates().iter().all(|cr| cr.rates.len() == partner.temperatures().len()));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use super::{
+    CollisionPartnerData, CollisionPartnerId, CollisionalRates, ElementData, EnergyLevel, FormatFeatures,
+    RadiativeTransition,
+};
+
+// Structurally valid molecular data out of drawn values, shared by the
+// proptest strategies of `crate::testing` and the `Arbitrary` impls. Levels
+// are numbered from 1 in order of increasing energy, transitions and rates
+// refer to existing levels with the upper one first, rate tables have one
+// rate per temperature and temperatures increase. An `ElementData` writes to
+// a datafile that parses back to it.
+
+pub(crate) const PARTNERS: [CollisionPartnerId; 7] = [
+    CollisionPartnerId::H2,
+    CollisionPartnerId::pH2,
+    CollisionPartnerId::oH2,
+    CollisionPartnerId::electrons,
+    CollisionPartnerId::HI,
+    CollisionPartnerId::He,
+    CollisionPartnerId::HII,
+];
+
+// Every (up, low) pair of `nlev` levels, ordered by upper level
+pub(crate) fn level_pairs(nlev: u32) -> Vec<(u32, u32)> {
+    (2..=nlev).flat_map(|up| (1..up).map(move |low| (up, low))).collect()
+}
+
+// Running sum of positive `steps`, e.g. temperatures of a rate table
+pub(crate) fn increasing(steps: impl IntoIterator<Item = f64>) -> Vec<f64> {
+    steps
+        .into_iter()
+        .scan(0.0, |x, step| {
+            *x += step;
+            Some(*x)
+        })
+        .collect()
+}
+
+// Levels from (energy above the previous level, statistical weight, quantum
+// numbers); the first level is the ground level at zero energy
+pub(crate) fn energy_levels(levels: impl IntoIterator<Item = (f64, f64, String)>) -> Vec<EnergyLevel> {
+    let mut energy = 0.0;
+    levels
+        .into_iter()
+        .enumerate()
+        .map(|(i, (step, stat_weight, qnums))| {
+            if i > 0 {
+                energy += step;
+            }
+            EnergyLevel { level: i as u32 + 1, energy, stat_weight, qnums }
+        })
+        .collect()
+}
+
+// Lines from (level pair, Einstein A, frequency and upper level energy),
+// numbered in order
+pub(crate) fn radiative_transitions(
+    lines: impl IntoIterator<Item = ((u32, u32), f64, Option<(f64, f64)>)>,
+) -> Vec<RadiativeTransition> {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, ((up, low), aeinst, frequency))| RadiativeTransition {
+            transition: i as u32 + 1,
+            up,
+            low,
+            aeinst,
+            frequency: frequency.map(|(f, _)| f),
+            upper_energy: frequency.map(|(_, e)| e),
+            extra: String::new(),
+        })
+        .collect()
+}
+
+// Rate table of `name` from (level pair, one rate per temperature), numbered
+// in order
+pub(crate) fn collision_partner(
+    name: CollisionPartnerId,
+    temperatures: Vec<f64>,
+    rates: impl IntoIterator<Item = ((u32, u32), Vec<f64>)>,
+) -> CollisionPartnerData {
+    let rates = rates
+        .into_iter()
+        .enumerate()
+        .map(|(i, ((up, low), rates))| CollisionalRates { transition: i as u32 + 1, up, low, rates })
+        .collect();
+
+    CollisionPartnerData { name, information: String::new(), temperatures, rates }
+}
+
+// Molecule with the format features its data needs
+pub(crate) fn element_data(
+    name: String,
+    note: &str,
+    weight: f64,
+    energy_levels: Vec<EnergyLevel>,
+    radiative_transitions: Vec<RadiativeTransition>,
+    collision_partners: Vec<CollisionPartnerData>,
+) -> ElementData {
+    let features = FormatFeatures {
+        transition_frequencies: radiative_transitions.iter().any(|rt| rt.frequency.is_some()),
+        quantum_numbers: energy_levels.iter().any(|el| !el.qnums.is_empty()),
+        ..Default::default()
+    };
+
+    ElementData {
+        name,
+        // Parsed data keeps notes after `. ` in the information, see `into_owned`
+        information: format!("{}. ", note),
+        weight,
+        energy_levels,
+        radiative_transitions,
+        collision_partners,
+        features,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,241 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{CollisionPartnerData, CollisionalRates, ElementData, EnergyLevel, FormatFeatures, RadiativeTransition};
+
+// Hyperfine component of a rotational line with its share of the line
+// strength g_u A_ul / nu^3, which is the optically thin intensity ratio
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct HyperfineComponent {
+    pub transition: u32,
+    // Rest frequency, GHz
+    pub frequency: f64,
+    pub relative_intensity: f64,
+}
+
+// Hyperfine components of one rotational transition, labelled with the
+// rotational quantum numbers of the upper and lower level
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct HyperfineMultiplet {
+    pub up: String,
+    pub low: String,
+    pub components: Vec<HyperfineComponent>,
+}
+
+fn is_f_label(token: &str) -> bool {
+    let name = token.split('=').next().unwrap_or("");
+    name.strip_prefix('F').is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+}
+
+// Quantum numbers of the rotational level a hyperfine level belongs to: the
+// ones other than F, F1, ... for labels such as `J=1 F=2`, and the first one
+// for the positional `J_F1_F` labels of the LAMDA hyperfine files.
+fn rotational_label(qnums: &str) -> String {
+    let qnums = qnums.split('!').next().unwrap_or("").trim();
+    match qnums.contains('=') {
+        true => qnums
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty() && !is_f_label(token))
+            .collect::<Vec<_>>()
+            .join(" "),
+        false => String::from(qnums.split('_').next().unwrap_or(qnums)),
+    }
+}
+
+// Value kept for `key`, added with `init` on first use; the list keeps the
+// order in which keys first appear
+fn entry<K: PartialEq, T>(list: &mut Vec<(K, T)>, key: K, init: impl FnOnce() -> T) -> &mut T {
+    let index = match list.iter().position(|(k, _)| *k == key) {
+        Some(index) => index,
+        None => {
+            list.push((key, init()));
+            list.len() - 1
+        }
+    };
+    &mut list[index].1
+}
+
+impl ElementData {
+    // Radiative transitions grouped by the rotational line they split, in
+    // the order of the file. Transitions between components of the same
+    // rotational level are left out.
+    pub fn hyperfine_multiplets(&self) -> Vec<HyperfineMultiplet> {
+        // Components keep their line strength until the multiplet is complete
+        let mut multiplets: Vec<((String, String), Vec<HyperfineComponent>)> = vec!();
+        for t in self.radiative_transitions.iter() {
+            let (up, low, frequency) = match (self.energy_level(t.up), self.energy_level(t.low), self.frequency(t)) {
+                (Some(up), Some(low), Some(frequency)) => (up, low, frequency),
+                _ => continue,
+            };
+            let key = (rotational_label(&up.qnums), rotational_label(&low.qnums));
+            if key.0 == key.1 {
+                continue;
+            }
+            let strength = up.stat_weight * t.aeinst / (frequency * frequency * frequency);
+            entry(&mut multiplets, key, Vec::new).push(HyperfineComponent {
+                transition: t.transition,
+                frequency,
+                relative_intensity: strength,
+            });
+        }
+
+        multiplets
+            .into_iter()
+            .map(|((up, low), mut components)| {
+                let total = components.iter().map(|c| c.relative_intensity).sum::<f64>();
+                components.iter_mut().for_each(|c| c.relative_intensity /= total);
+                HyperfineMultiplet { up, low, components }
+            })
+            .collect()
+    }
+
+    // Data with the hyperfine components of each rotational level merged,
+    // as if the hyperfine levels were thermally populated: a level has the
+    // summed statistical weight and the weighted mean energy, and A_UL and
+    // C_UL are sums of g_u A_ul and g_u C_ul over the components divided by
+    // g_U. Files without hyperfine structure come back unchanged apart from
+    // the frequency columns.
+    pub fn collapse_hyperfine(&self) -> ElementData {
+        // Rotational label, summed weight and summed g E of each level
+        let mut parents: Vec<(String, (f64, f64))> = vec!();
+        let mut parent_of = vec!();
+        for level in self.energy_levels.iter() {
+            let label = rotational_label(&level.qnums);
+            let sums = entry(&mut parents, label.clone(), || (0.0, 0.0));
+            sums.0 += level.stat_weight;
+            sums.1 += level.stat_weight * level.energy;
+            parent_of.push((level.level, parents.iter().position(|p| p.0 == label)));
+        }
+        let parent = |level: u32| parent_of.iter().find(|p| p.0 == level).and_then(|p| p.1);
+        let weight = |level: u32| self.energy_level(level).map(|l| l.stat_weight).unwrap_or(0.0);
+        let parent_weight = |parent: usize| parents[parent].1.0;
+
+        let energy_levels = parents
+            .iter()
+            .enumerate()
+            .map(|(i, (label, (g, ge)))| EnergyLevel {
+                level: i as u32 + 1,
+                energy: ge / g,
+                stat_weight: *g,
+                qnums: label.clone(),
+            })
+            .collect();
+
+        let mut radiative: Vec<((usize, usize), f64)> = vec!();
+        for t in self.radiative_transitions.iter() {
+            if let (Some(up), Some(low)) = (parent(t.up), parent(t.low)) {
+                if up != low {
+                    *entry(&mut radiative, (up, low), || 0.0) += weight(t.up) * t.aeinst;
+                }
+            }
+        }
+        let radiative_transitions = radiative
+            .into_iter()
+            .enumerate()
+            .map(|(i, ((up, low), sum))| RadiativeTransition {
+                transition: i as u32 + 1,
+                up: up as u32 + 1,
+                low: low as u32 + 1,
+                aeinst: sum / parent_weight(up),
+                ..Default::default()
+            })
+            .collect();
+
+        let collision_partners = self.collision_partners
+            .iter()
+            .map(|partner| {
+                let mut rates: Vec<((usize, usize), Vec<f64>)> = vec!();
+                for rate in partner.rates.iter() {
+                    if let (Some(up), Some(low)) = (parent(rate.up), parent(rate.low)) {
+                        if up != low {
+                            let sums = entry(&mut rates, (up, low), || vec!(0.0; partner.temperatures.len()));
+                            for (sum, r) in sums.iter_mut().zip(rate.rates.iter()) {
+                                *sum += weight(rate.up) * r;
+                            }
+                        }
+                    }
+                }
+                let rates = rates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, ((up, low), sums))| CollisionalRates {
+                        transition: i as u32 + 1,
+                        up: up as u32 + 1,
+                        low: low as u32 + 1,
+                        rates: sums.into_iter().map(|s| s / parent_weight(up)).collect(),
+                    })
+                    .collect();
+                CollisionPartnerData::new(partner.name.clone(), &partner.information, partner.temperatures.clone(), rates)
+            })
+            .collect();
+
+        ElementData {
+            name: self.name.clone(),
+            information: self.information.clone(),
+            weight: self.weight,
+            energy_levels,
+            radiative_transitions,
+            collision_partners,
+            features: FormatFeatures {
+                quantum_numbers: self.features.quantum_numbers,
+                notes: self.features.notes,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::CollisionPartnerId;
+
+    fn hcn() -> ElementData {
+        let rates = (2..5).map(|up| CollisionalRates { transition: up - 1, up, low: 1, rates: vec!(3.0e-11, 6.0e-11) });
+        ElementData::builder("HCN")
+            .weight(27.0)
+            .level(0.0, 3.0, "0_1")
+            .level(2.956_3, 3.0, "1_1")
+            .level(2.956_4, 5.0, "1_2")
+            .level(2.956_5, 1.0, "1_0")
+            .transition(2, 1, 2.4e-5)
+            .transition(3, 1, 2.4e-5)
+            .transition(4, 1, 2.4e-5)
+            .collision_partner(CollisionPartnerData::new(CollisionPartnerId::pH2, "", vec!(10.0, 20.0), rates.collect()))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn hyperfine_multiplets() {
+        assert_eq!(rotational_label("1_2  ! J F"), "1");
+        assert_eq!(rotational_label("J=1 F1=2 F=3"), "J=1");
+
+        let multiplets = hcn().hyperfine_multiplets();
+        assert_eq!(multiplets.len(), 1);
+        assert_eq!((multiplets[0].up.as_str(), multiplets[0].low.as_str()), ("1", "0"));
+        let intensities = multiplets[0].components.iter().map(|c| c.relative_intensity).collect::<Vec<_>>();
+        for (found, expected) in intensities.iter().zip([3.0 / 9.0, 5.0 / 9.0, 1.0 / 9.0]) {
+            assert!((found - expected).abs() < 1e-4, "Wrong result for relative intensities {:?}", intensities);
+        }
+    }
+
+    #[test]
+    fn collapse_hyperfine() {
+        let collapsed = hcn().collapse_hyperfine();
+
+        let levels = collapsed.energy_levels().iter().map(|l| (l.stat_weight, l.qnums.as_str())).collect::<Vec<_>>();
+        assert_eq!(levels, vec!((3.0, "0"), (9.0, "1")));
+        assert!((collapsed.energy_levels()[1].energy - 2.956_38).abs() < 1e-4);
+        assert_eq!(collapsed.radiative_transitions().len(), 1);
+        assert!((collapsed.radiative_transitions()[0].aeinst - 2.4e-5).abs() < 1e-18);
+        let rates = &collapsed.collision_partners()[0].rates()[0];
+        assert!((rates.rates[1] - 6.0e-11).abs() < 1e-24, "Wrong result for collapsed rates {:?}", rates.rates);
+        assert!(!collapsed.features().hyperfine);
+    }
+}
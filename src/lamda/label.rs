@@ -0,0 +1,52 @@
+use alloc::format;
+use alloc::string::String;
+
+use super::{ElementData, RadiativeTransition};
+
+impl RadiativeTransition {
+    // Label such as `CO J=1-0 115.271 GHz` for plots and tables. Levels with
+    // a bare number as quantum numbers are taken as rotational levels J;
+    // other quantum numbers are given as in the file, without comments, and
+    // level numbers where the file gives none. The frequency is left out
+    // when unknown.
+    pub fn label(&self, data: &ElementData) -> String {
+        let qnums = |n: u32| {
+            let el = data.energy_level(n)?;
+            Some(el.qnums.split('!').next()?.trim()).filter(|q| !q.is_empty())
+        };
+        let mut label = match (qnums(self.up), qnums(self.low)) {
+            (Some(up), Some(low)) if up.parse::<u32>().is_ok() && low.parse::<u32>().is_ok() => {
+                format!("{} J={}-{}", data.name, up, low)
+            }
+            (Some(up), Some(low)) => format!("{} {}-{}", data.name, up, low),
+            _ => format!("{} {}-{}", data.name, self.up, self.low),
+        };
+        if let Some(frequency) = data.frequency(self) {
+            label.push_str(&format!(" {:.3} GHz", frequency));
+        }
+
+        label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn transition_labels() {
+        let mut data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let transition = data.radiative_transitions()[0].clone();
+        assert_eq!(transition.label(&data), "O 3_P_1-3_P_2 4744.777 GHz");
+
+        data.name = String::from("CO");
+        for (i, el) in data.energy_levels_mut().iter_mut().enumerate() {
+            el.qnums = format!("{}", i);
+        }
+        assert_eq!(transition.label(&data), "CO J=1-0 4744.777 GHz", "Wrong result for rotational levels");
+
+        data.energy_levels_mut()[1].qnums = String::new();
+        assert_eq!(transition.label(&data), "CO 2-1 4744.777 GHz", "Wrong result for a level without quantum numbers");
+    }
+}
@@ -0,0 +1,202 @@
+use core::ops::Range;
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::limits;
+use super::{
+    shift, CollisionPartnerDataRef, Diagnostics, ElementDataRef, FormatFeatures, Lines, NumberOfCollisionalTransitions,
+    ParseError, ParseLimits, Span,
+};
+
+// Collision partner block of a datafile, from its `!COLLISIONS BETWEEN`
+// comment to its last rate line
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Block {
+    first_line: usize,
+    bytes: Range<usize>,
+}
+
+// Datafile with the levels and radiative transitions parsed and the
+// collision partner blocks only located, for uses such as LTE spectra that
+// never need the rates. Locating a block only reads its header lines, so
+// the rates of heavy molecules cost next to nothing until they are parsed.
+#[derive(Debug)]
+pub struct LazyElementData<'a> {
+    source: &'a str,
+    data: ElementDataRef<'a>,
+    level_header: &'a str,
+    partners: Vec<Block>,
+    // Line number and byte offset of what follows the last block
+    notes: (usize, usize),
+    limits: ParseLimits,
+}
+
+// Byte offset of `line`, a slice of `source`, in `source`
+fn offset(source: &str, line: &str) -> usize {
+    line.as_ptr() as usize - source.as_ptr() as usize
+}
+
+impl<'a> ElementDataRef<'a> {
+    pub fn parse_lazy(s: &'a str) -> Result<LazyElementData<'a>, ParseError> {
+        LazyElementData::parse_with_limits(s, &ParseLimits::default())
+    }
+}
+
+impl<'a> LazyElementData<'a> {
+    pub fn parse_with_limits(s: &'a str, limits: &ParseLimits) -> Result<Self, ParseError> {
+        Self::parse_source(s, limits).map_err(|e| e.locate(s))
+    }
+
+    // Block and note offsets are into `input`, byte order mark included
+    fn parse_source(input: &'a str, limits: &ParseLimits) -> Result<Self, ParseError> {
+        let s = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let mut lines = s.lines().enumerate();
+        let mut line = (0, "");
+
+        let (name, weight) = ElementDataRef::parse_header(&mut lines, &mut line)?;
+        let (energy_levels, level_header) = ElementDataRef::parse_energy_levels(&mut lines, &mut line, limits, None, None)?;
+        let radiative_transitions = ElementDataRef::parse_radiative_transitions(&mut lines, &mut line, limits, None, None)?;
+        let npart = ElementDataRef::parse_number_of_collision_partners(&mut lines, &mut line, limits)?;
+
+        let mut partners = vec!();
+        for _ in 0..npart {
+            partners.push(Self::locate_block(input, &mut lines, &mut line, limits)?);
+        }
+        let notes = match lines.next() {
+            Some(next) => (next.0, offset(input, next.1)),
+            None => (line.0 + 1, input.len()),
+        };
+
+        let mut data = ElementDataRef {
+            name: name.name,
+            information: name.information,
+            weight,
+            energy_levels,
+            radiative_transitions,
+            ..Default::default()
+        };
+        data.features = FormatFeatures::detect(level_header, &data);
+
+        Ok(Self { source: input, data, level_header, partners, notes, limits: *limits })
+    }
+
+    // Skips the header lines and as many rate lines as the block gives
+    fn locate_block(
+        s: &'a str,
+        lines: &mut Lines<'a>,
+        line: &mut (usize, &'a str),
+        limits: &ParseLimits,
+    ) -> Result<Block, ParseError> {
+        let mut next = |line: &mut (usize, &'a str)| -> Result<(), ParseError> {
+            *line = lines.next().ok_or_else(|| ParseError::end_of_input(line.0 + 1))?;
+            Ok(())
+        };
+
+        next(line)?;
+        let first = *line;
+        next(line)?;
+        next(line)?;
+        next(line)?;
+        let ncol = match line.1.parse::<NumberOfCollisionalTransitions>() {
+            Ok(n) => limits::check(n.0, limits.max_collisional_transitions, "collisional transitions", *line)?,
+            Err(_) => return Err(ParseError::NotInt {
+                line_number: line.0,
+                span: Span::in_line(0, line.1.len()),
+                line: String::from(line.1),
+                note: String::from("Expected integer"),
+            }),
+        };
+        for _ in 0..5 + ncol {
+            next(line)?;
+        }
+
+        Ok(Block { first_line: first.0, bytes: offset(s, first.1)..offset(s, line.1) + line.1.len() })
+    }
+
+    // Levels, transitions and header of the datafile, without collision
+    // partners
+    pub fn data(&self) -> &ElementDataRef<'a> {
+        &self.data
+    }
+
+    pub fn collision_partner_count(&self) -> usize {
+        self.partners.len()
+    }
+
+    // Byte ranges of the collision partner blocks in the input
+    pub fn collision_partner_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.partners.iter().map(|block| block.bytes.clone())
+    }
+
+    // The `n`-th collision partner, parsed now. Errors and diagnostics have
+    // the line numbers of the whole input.
+    pub fn collision_partner(
+        &self,
+        n: usize,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<Result<CollisionPartnerDataRef<'a>, ParseError>> {
+        let block = self.partners.get(n)?;
+        let text = &self.source[block.bytes.clone()];
+        let mut lines = text.lines().enumerate();
+        let mut found = Diagnostics::new();
+
+        let partner = ElementDataRef::parse_collision_partner(&mut lines, &mut (0, ""), &self.limits, None, &mut found, None)
+            .map_err(|e| shift(e.locate(text), block.first_line, block.bytes.start));
+        for mut diagnostic in found.into_vec() {
+            diagnostic.line += block.first_line;
+            diagnostics.push(diagnostic);
+        }
+
+        Some(partner)
+    }
+
+    // The whole datafile, as `ElementDataRef::parse_with_diagnostics` gives it
+    pub fn into_data(self, diagnostics: &mut Diagnostics) -> Result<ElementDataRef<'a>, ParseError> {
+        let partners = (0..self.partners.len())
+            .filter_map(|n| self.collision_partner(n, diagnostics))
+            .collect::<Result<Vec<_>, _>>()?;
+        let text = &self.source[self.notes.1..];
+        let comments = ElementDataRef::parse_notes(text.lines().enumerate(), self.partners.len() as u32, None)
+            .map_err(|e| shift(e.locate(text), self.notes.0, self.notes.1))?;
+
+        let mut data = self.data;
+        data.collision_partners = partners;
+        data.comments = comments;
+        data.features = FormatFeatures::detect(self.level_header, &data);
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_lazily() {
+        let s = include_str!("../../data/oatom.dat");
+        let full = ElementDataRef::parse(s).unwrap();
+
+        let lazy = ElementDataRef::parse_lazy(s).unwrap();
+        assert_eq!(lazy.data().energy_levels(), full.energy_levels());
+        assert_eq!(lazy.data().radiative_transitions(), full.radiative_transitions());
+        assert_eq!(lazy.collision_partner_count(), full.collision_partners().len());
+        let range = lazy.collision_partner_ranges().next().unwrap();
+        assert!(s[range].starts_with("!COLLISIONS BETWEEN"));
+
+        let partner = lazy.collision_partner(0, &mut Diagnostics::new()).unwrap().unwrap();
+        assert_eq!(&partner, &full.collision_partners()[0], "Wrong result for a partner parsed on demand");
+        assert_eq!(lazy.into_data(&mut Diagnostics::new()).unwrap(), full, "Wrong result for the whole datafile");
+
+        // Broken rates are only found when the partner is parsed
+        let rates = s.find("COLLRATES").unwrap();
+        let rate = s[rates..].lines().nth(1).unwrap();
+        let broken = s.replacen(rate, "    1     2     1  x", 1);
+        let expected = ElementDataRef::parse(&broken).unwrap_err();
+        let lazy = ElementDataRef::parse_lazy(&broken).unwrap();
+        assert_eq!(lazy.collision_partner(0, &mut Diagnostics::new()).unwrap().unwrap_err(), expected);
+    }
+}
@@ -0,0 +1,79 @@
+use alloc::format;
+use alloc::string::String;
+
+use super::{ParseError, Span};
+
+// Largest counts a datafile may give, checked as soon as a count is read so
+// that a broken or hostile file cannot make the parser allocate for billions
+// of lines. The defaults are far above those of any LAMDA datafile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    pub max_levels: u32,
+    pub max_transitions: u32,
+    pub max_collision_partners: u32,
+    pub max_collisional_transitions: u32,
+    pub max_temperatures: u32,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_levels: 100_000,
+            max_transitions: 1_000_000,
+            max_collision_partners: 256,
+            max_collisional_transitions: 10_000_000,
+            max_temperatures: 1_000,
+        }
+    }
+}
+
+impl ParseLimits {
+    // No limits, for trusted input
+    pub fn unlimited() -> Self {
+        Self {
+            max_levels: u32::MAX,
+            max_transitions: u32::MAX,
+            max_collision_partners: u32::MAX,
+            max_collisional_transitions: u32::MAX,
+            max_temperatures: u32::MAX,
+        }
+    }
+}
+
+// `count` of `what` read from `line`, or the error when it is above `limit`
+pub(super) fn check(count: u32, limit: u32, what: &str, line: (usize, &str)) -> Result<u32, ParseError> {
+    match count <= limit {
+        true => Ok(count),
+        false => Err(ParseError::LimitExceeded {
+            line_number: line.0,
+            span: Span::in_line(0, line.1.len()),
+            line: String::from(line.1),
+            note: format!("{} {} are given, but at most {} are allowed", count, what, limit),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::ElementData;
+
+    #[test]
+    fn limits_exceeded() {
+        let s = include_str!("../../data/oatom.dat");
+        let first = s.find("!NUMBER OF ENERGY LEVELS\n").unwrap() + "!NUMBER OF ENERGY LEVELS\n".len();
+        let line = &s[first..first + s[first..].find('\n').unwrap()];
+
+        let huge = s.replacen(line, "4000000000", 1);
+        assert!(
+            matches!(huge.parse::<ElementData>(), Err(ParseError::LimitExceeded { .. })),
+            "Wrong result for a huge number of levels",
+        );
+
+        let limits = ParseLimits { max_temperatures: 1, ..Default::default() };
+        let e = ElementData::parse_with_limits(s, &limits).unwrap_err();
+        assert_eq!(e.code(), "limit-exceeded");
+        assert!(ElementData::parse_with_limits(s, &ParseLimits::unlimited()).is_ok());
+    }
+}
@@ -0,0 +1,161 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::writer::split_information;
+use super::{ElementData, ParseError};
+
+// Text a datafile was parsed from, kept by `parse_lossless` to write the file
+// back as it was. Two datafiles are equal whatever text they came from.
+#[derive(Debug, Default)]
+pub(crate) struct Original(pub(super) Option<String>);
+
+impl PartialEq for Original {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+// What a line before the notes holds. Comments and the counts of levels,
+// transitions and partners are fixed by the layout and kept as they were.
+#[derive(Debug, Clone, Copy)]
+enum Line {
+    Kept,
+    Name,
+    Weight,
+    Level(usize),
+    Transition(usize),
+    Partner(usize),
+    TemperatureCount(usize),
+    Temperatures(usize),
+    Rates(usize, usize),
+}
+
+fn layout(data: &ElementData) -> Vec<Line> {
+    let mut lines = vec!(Line::Kept, Line::Name, Line::Kept, Line::Weight, Line::Kept, Line::Kept, Line::Kept);
+    lines.extend((0..data.energy_levels.len()).map(Line::Level));
+    lines.extend([Line::Kept; 3]);
+    lines.extend((0..data.radiative_transitions.len()).map(Line::Transition));
+    lines.extend([Line::Kept; 2]);
+    for (p, partner) in data.collision_partners.iter().enumerate() {
+        lines.extend([Line::Kept, Line::Partner(p), Line::Kept, Line::Kept, Line::Kept]);
+        lines.extend([Line::TemperatureCount(p), Line::Kept, Line::Temperatures(p), Line::Kept]);
+        lines.extend((0..partner.rates.len()).map(|k| Line::Rates(p, k)));
+    }
+
+    lines
+}
+
+// Whether `data` has as many items in each section as `original`, so that
+// every line of the original text has its counterpart
+fn same_layout(original: &ElementData, data: &ElementData) -> bool {
+    original.energy_levels.len() == data.energy_levels.len()
+        && original.radiative_transitions.len() == data.radiative_transitions.len()
+        && original.collision_partners.len() == data.collision_partners.len()
+        && original.collision_partners
+            .iter()
+            .zip(data.collision_partners.iter())
+            .all(|(a, b)| a.rates.len() == b.rates.len())
+}
+
+fn changed(line: Line, original: &ElementData, data: &ElementData) -> bool {
+    let partners = |p: usize| (&original.collision_partners[p], &data.collision_partners[p]);
+    match line {
+        Line::Kept => false,
+        Line::Name => {
+            original.name != data.name
+                || split_information(&original.information).0 != split_information(&data.information).0
+        }
+        Line::Weight => original.weight != data.weight,
+        Line::Level(k) => original.energy_levels[k] != data.energy_levels[k],
+        Line::Transition(k) => original.radiative_transitions[k] != data.radiative_transitions[k],
+        Line::Partner(p) => {
+            let (a, b) = partners(p);
+            a.name != b.name || a.information != b.information
+        }
+        Line::TemperatureCount(p) => {
+            let (a, b) = partners(p);
+            a.temperatures.len() != b.temperatures.len()
+        }
+        Line::Temperatures(p) => {
+            let (a, b) = partners(p);
+            a.temperatures != b.temperatures
+        }
+        Line::Rates(p, k) => {
+            let (a, b) = partners(p);
+            a.rates[k] != b.rates[k]
+        }
+    }
+}
+
+impl ElementData {
+    // Parses a datafile and keeps its text, so that writing the data back
+    // gives the same bytes: comments, spacing, line ends and notes stay as
+    // they were, and only the lines of edited items are formatted anew.
+    pub fn parse_lossless(s: &str) -> Result<Self, ParseError> {
+        let mut data: Self = s.parse()?;
+        data.original = Original(Some(String::from(s)));
+        Ok(data)
+    }
+
+    // The original text with the lines of the items that differ from it
+    // replaced by the ones of `formatted`, or nothing when the data has no
+    // original text or items were added or removed since
+    pub(super) fn write_over_original(&self, formatted: &str) -> Option<String> {
+        let text = self.original.0.as_deref()?;
+        let original: ElementData = text.parse().ok()?;
+        if !same_layout(&original, self) {
+            return None;
+        }
+
+        let layout = layout(self);
+        let mut kept = text.split_inclusive('\n');
+        let mut fresh = formatted.lines();
+        let mut s = String::with_capacity(text.len());
+        for line in layout.iter() {
+            let (old, new) = (kept.next()?, fresh.next()?);
+            match changed(*line, &original, self) {
+                true => {
+                    s.push_str(new);
+                    s.push_str(&old[old.trim_end_matches(['\n', '\r']).len()..]);
+                }
+                false => s.push_str(old),
+            }
+        }
+
+        match split_information(&original.information).1 == split_information(&self.information).1 {
+            true => kept.for_each(|line| s.push_str(line)),
+            false => fresh.for_each(|line| {
+                s.push_str(line);
+                s.push('\n');
+            }),
+        }
+
+        Some(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn lossless_round_trip() {
+        let s = include_str!("../../data/oatom.dat");
+        let mut data = ElementData::parse_lossless(s).unwrap();
+        assert_eq!(data.to_string(), s, "Wrong result for an unedited datafile");
+        assert_eq!(data, s.parse().unwrap());
+
+        let crlf = s.replace('\n', "\r\n");
+        assert_eq!(ElementData::parse_lossless(&crlf).unwrap().to_string(), crlf);
+
+        data.energy_levels_mut()[1].energy = 158.5;
+        let written = data.to_string();
+        let diff = s.lines().zip(written.lines()).filter(|(a, b)| a != b).collect::<Vec<_>>();
+        assert_eq!(diff.len(), 1, "Wrong result for an edited datafile: {:?}", diff);
+        assert_eq!(written.lines().count(), s.lines().count());
+        assert_eq!(written.parse::<ElementData>().unwrap(), data);
+    }
+}
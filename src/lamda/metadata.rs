@@ -0,0 +1,195 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{ElementData, ElementDataRef, ParseError};
+
+// Line of a datafile that metadata was captured from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum MetadataTarget {
+    EnergyLevel(u32),
+    RadiativeTransition(u32),
+    // Comments after the collisional data, about the whole file
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum MetadataValue {
+    Number(f64),
+    Text(String),
+}
+
+impl MetadataValue {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            MetadataValue::Number(x) => Some(*x),
+            MetadataValue::Text(_) => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            MetadataValue::Number(_) => None,
+            MetadataValue::Text(s) => Some(s),
+        }
+    }
+}
+
+// Values captured by `MetadataHook`s while parsing, keyed by line and name.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metadata {
+    entries: Vec<(MetadataTarget, String, MetadataValue)>,
+}
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, target: MetadataTarget, key: &str) -> Option<&MetadataValue> {
+        self.entries.iter().find(|(t, k, _)| *t == target && k == key).map(|(_, _, value)| value)
+    }
+
+    // Everything captured from one line, in the order the hooks returned it
+    pub fn of(&self, target: MetadataTarget) -> impl Iterator<Item = (&str, &MetadataValue)> {
+        self.entries.iter().filter(move |(t, _, _)| *t == target).map(|(_, k, value)| (k.as_str(), value))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(MetadataTarget, String, MetadataValue)> {
+        self.entries.iter()
+    }
+
+    fn extend(&mut self, target: MetadataTarget, values: Vec<(String, MetadataValue)>) {
+        self.entries.extend(values.into_iter().map(|(key, value)| (target, key, value)));
+    }
+
+    // Entries of the levels and transitions that are kept under new numbers,
+    // for data that was cut down or reordered
+    pub(super) fn renumbered(&self, level: impl Fn(u32) -> Option<u32>, transition: impl Fn(u32) -> Option<u32>) -> Self {
+        let entries = self.entries
+            .iter()
+            .filter_map(|(target, key, value)| {
+                let target = match *target {
+                    MetadataTarget::EnergyLevel(n) => MetadataTarget::EnergyLevel(level(n)?),
+                    MetadataTarget::RadiativeTransition(n) => MetadataTarget::RadiativeTransition(transition(n)?),
+                    MetadataTarget::File => MetadataTarget::File,
+                };
+                Some((target, key.clone(), value.clone()))
+            })
+            .collect();
+
+        Self { entries }
+    }
+}
+
+// Handler for conventions the LAMDA format leaves open, such as uncertainty
+// columns after the Einstein A coefficients or `! ref:` comments. Hooks see
+// the free text of a line after its numeric fields, return named values and
+// cut what they understood from `text`, so that it does not end up in the
+// quantum numbers or the `extra` column. A comment that a hook returns
+// values for is left out of the file information.
+pub trait MetadataHook {
+    fn energy_level(&self, _level: u32, _text: &mut String) -> Vec<(String, MetadataValue)> {
+        vec!()
+    }
+
+    fn radiative_transition(&self, _transition: u32, _text: &mut String) -> Vec<(String, MetadataValue)> {
+        vec!()
+    }
+
+    fn comment(&self, _comment: &str) -> Vec<(String, MetadataValue)> {
+        vec!()
+    }
+}
+
+impl ElementData {
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    // Parses like `from_str`, running every hook on the levels, transitions
+    // and trailing comments in turn.
+    pub fn parse_with_hooks(s: &str, hooks: &[&dyn MetadataHook]) -> Result<Self, ParseError> {
+        let mut parsed = ElementDataRef::parse(s)?;
+        let mut metadata = Metadata::default();
+
+        parsed.comments.retain(|comment| {
+            let values = hooks.iter().flat_map(|hook| hook.comment(comment)).collect::<Vec<_>>();
+            let keep = values.is_empty();
+            metadata.extend(MetadataTarget::File, values);
+            keep
+        });
+
+        let mut data = parsed.into_owned();
+        for level in data.energy_levels.iter_mut() {
+            for hook in hooks.iter() {
+                let values = hook.energy_level(level.level, &mut level.qnums);
+                metadata.extend(MetadataTarget::EnergyLevel(level.level), values);
+            }
+        }
+        for transition in data.radiative_transitions.iter_mut() {
+            for hook in hooks.iter() {
+                let values = hook.radiative_transition(transition.transition, &mut transition.extra);
+                metadata.extend(MetadataTarget::RadiativeTransition(transition.transition), values);
+            }
+        }
+        data.metadata = metadata;
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use alloc::string::ToString;
+
+    // Relative uncertainty of A in the last column, and `! ref: <tag>` comments
+    struct Uncertainties;
+
+    impl MetadataHook for Uncertainties {
+        fn radiative_transition(&self, _transition: u32, text: &mut String) -> Vec<(String, MetadataValue)> {
+            let (rest, last) = text.rsplit_once(' ').unwrap_or(("", text.as_str()));
+            match last.strip_suffix('%').and_then(|v| v.parse::<f64>().ok()) {
+                Some(x) => {
+                    *text = rest.to_string();
+                    vec!((String::from("aeinst_error"), MetadataValue::Number(x / 100.0)))
+                }
+                None => vec!(),
+            }
+        }
+
+        fn comment(&self, comment: &str) -> Vec<(String, MetadataValue)> {
+            match comment.strip_prefix("ref:") {
+                Some(tag) => vec!((String::from("reference"), MetadataValue::Text(tag.trim().to_string()))),
+                None => vec!(),
+            }
+        }
+    }
+
+    #[test]
+    fn capture_trailing_columns_and_comments() {
+        let s = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n2\n!LEVEL + ENERGIES(cm^-1) + WEIGHT + J\n\
+            1 0.0 1.0 0\n2 3.845 3.0 1\n!NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS + UP + LOW + EINSTEINA(s^-1) + FREQ(GHz) + E_u(K)\n\
+            1 2 1 7.2e-8 115.2712 5.53 3%\n!NUMBER OF COLL PARTNERS\n0\n! ref: Winnewisser1997\n! Kept as information\n";
+
+        let data = ElementData::parse_with_hooks(s, &[&Uncertainties]).unwrap();
+        let metadata = data.metadata();
+
+        assert_eq!(
+            metadata.get(MetadataTarget::RadiativeTransition(1), "aeinst_error").and_then(MetadataValue::as_number),
+            Some(0.03),
+        );
+        let t = &data.radiative_transitions[0];
+        assert_eq!((t.frequency, t.upper_energy, t.extra.as_str()), (Some(115.2712), Some(5.53), ""), "Wrong result for columns after the hook");
+        assert_eq!(metadata.get(MetadataTarget::File, "reference").and_then(MetadataValue::as_text), Some("Winnewisser1997"));
+        assert!(!data.information.contains("ref:") && data.information.contains("Kept as information"));
+        assert_eq!(metadata.of(MetadataTarget::EnergyLevel(1)).count(), 0);
+
+        assert!(s.parse::<ElementData>().unwrap().metadata().is_empty(), "Wrong result without hooks");
+    }
+}
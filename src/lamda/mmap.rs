@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use super::{Decoding, ElementData, ParseError, ReadError};
+
+#[derive(Debug)]
+pub enum MappedParseError {
+    Io(std::io::Error),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for MappedParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MappedParseError::Io(e) => write!(f, "{}", e),
+            MappedParseError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MappedParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MappedParseError::Io(e) => e.source(),
+            MappedParseError::Parse(e) => e.source(),
+        }
+    }
+}
+
+// Datafile mapped into memory. Pages are read by the OS on first access and
+// `parse` reads the mapping section by section, so parsing starts
+// immediately and the file is never wholly in memory or copied into a String.
+pub struct MappedDatafile {
+    map: Mmap,
+}
+
+impl MappedDatafile {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, std::io::Error> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapping is read-only; modifying the file while it is
+        // mapped is undefined behaviour, as with every memory-mapped reader.
+        let map = unsafe { Mmap::map(&file)? };
+
+        Ok(Self { map })
+    }
+
+    pub fn as_str(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.map)
+    }
+
+    pub fn parse(&self, decoding: Decoding) -> Result<ElementData, MappedParseError> {
+        ElementData::from_reader(&self.map[..], decoding).map_err(|e| match e {
+            ReadError::Io(e) => MappedParseError::Io(e),
+            ReadError::Parse(e) => MappedParseError::Parse(e),
+        })
+    }
+}
+
+// Reads Latin-1 datafiles too, as `ElementData::from_path` does
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<ElementData, MappedParseError> {
+    let file = MappedDatafile::open(path).map_err(MappedParseError::Io)?;
+    file.parse(Decoding::Latin1Fallback)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_mapped_file() {
+        let path = std::env::temp_dir().join(format!("ism-mmap-{}.dat", std::process::id()));
+        let s = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n1\n!LEVEL\n1 0.0 1.0 0\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n0\n!TRANS\n!NUMBER OF COLL PARTNERS\n0\n";
+        std::fs::write(&path, s).unwrap();
+
+        let result = parse_file(&path);
+        assert_eq!(result.unwrap(), s.parse::<ElementData>().unwrap());
+
+        // Latin-1 comment, as in some older LAMDA files
+        let mut bytes = s.replace("1 0.0 1.0 0", "1 0.0 1.0 0 ! X").into_bytes();
+        let x = bytes.iter().position(|&b| b == b'X').unwrap();
+        bytes[x] = 0xb0;
+        std::fs::write(&path, &bytes).unwrap();
+        let file = MappedDatafile::open(&path).unwrap();
+        assert!(file.parse(Decoding::Latin1Fallback).is_ok(), "Wrong result for a Latin-1 datafile");
+        assert!(matches!(file.parse(Decoding::Strict), Err(MappedParseError::Parse(_))));
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,2350 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::constants::{SPEED_OF_LIGHT, WAVENUMBER_TO_KELVIN};
+use crate::progress::ProgressCallback;
+
+#[cfg(feature = "std")]
+mod abundances;
+#[cfg(feature = "ndarray")]
+mod arrays;
+pub mod basecol;
+mod builder;
+#[cfg(feature = "std")]
+mod database;
+mod diagnostic;
+mod diff;
+mod encoding;
+mod format;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod formula;
+#[cfg(any(feature = "testing", feature = "arbitrary"))]
+pub(crate) mod generate;
+mod hyperfine;
+mod label;
+mod lazy;
+mod limits;
+mod lossless;
+mod metadata;
+mod orthopara;
+mod overview;
+mod partners;
+mod progress;
+#[cfg(feature = "f64")]
+mod quantities;
+mod rates;
+#[cfg(feature = "std")]
+mod physics;
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+pub mod mmap;
+mod section;
+mod span;
+mod species;
+#[cfg(feature = "std")]
+mod stream;
+mod subset;
+mod summary;
+mod validate;
+mod writer;
+
+#[cfg(feature = "std")]
+pub use abundances::{thermal_ortho_para_ratio, PartnerAbundances};
+pub use builder::{BuildError, ElementDataBuilder};
+#[cfg(feature = "std")]
+pub use database::{parse_all, parse_all_cancellable, parse_all_with_progress, DatabaseError, LamdaDatabase};
+pub use diagnostic::{Diagnostic, Diagnostics, Severity};
+pub use diff::{Change, Diff, PartnerDiff};
+pub use encoding::{decode, Decoding};
+pub use format::FormatFeatures;
+pub use formula::{molecular_weight, WEIGHT_TOLERANCE};
+pub use hyperfine::{HyperfineComponent, HyperfineMultiplet};
+pub use lazy::LazyElementData;
+pub use limits::ParseLimits;
+pub use metadata::{Metadata, MetadataHook, MetadataTarget, MetadataValue};
+pub use orthopara::OrthoParaRatio;
+pub use overview::{parse_overview, MoleculeEntry};
+#[cfg(all(feature = "std", feature = "f64"))]
+pub use physics::EinsteinB;
+pub use rates::{RateMatrix, RateTable};
+pub use section::Section;
+pub use span::Span;
+pub use species::{Isotope, Species};
+#[cfg(feature = "std")]
+pub use stream::ReadError;
+pub use summary::{CollisionPartnerSummary, Summary, TransitionSummary};
+#[cfg(all(feature = "fetch", not(target_arch = "wasm32")))]
+pub use crate::fetch::fetch;
+
+use lossless::Original;
+use progress::Reporter;
+
+type Lines<'a> = core::iter::Enumerate<core::str::Lines<'a>>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    NotEnoughInput { line_number: usize, span: Span },
+    WrongCommentFormat {
+        line_number: usize,
+        span: Span,
+        line: String,
+        note: String
+    },
+    MissingField {
+        line_number: usize,
+        span: Span,
+        line: String,
+        note: String,
+        source: Option<Box<FieldError>>,
+    },
+    NotFloat {
+        line_number: usize,
+        span: Span,
+        line: String,
+        note: String
+    },
+    NotInt {
+        line_number: usize,
+        span: Span,
+        line: String,
+        note: String,
+    },
+    UnknownItem {
+        line_number: usize,
+        span: Span,
+        line: String,
+        note: String,
+        source: Option<Box<FieldError>>,
+    },
+    UnknownCollisionPartner {
+        line_number: usize,
+        span: Span,
+        line: String,
+        note: String,
+        source: Option<Box<FieldError>>,
+    },
+    InvalidEncoding {
+        line_number: usize,
+        span: Span,
+        column: usize,
+        line: String,
+        note: String,
+    },
+    LimitExceeded {
+        line_number: usize,
+        span: Span,
+        line: String,
+        note: String,
+    },
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let linenum_width = 6;
+
+        match self {
+            Self::NotEnoughInput { line_number, .. } => {
+                write!(f, "{:>linenum_width$} |\n", line_number)?;
+                write!(f, "{:>linenum_width$} | {:^<linenum_width$}\n", " ", "^")?;
+                write!(f, "{:>linenum_width$} = Line {} is empty, but there should be more input.\n", " ", line_number)?;
+
+                Ok(())
+            },
+            Self::WrongCommentFormat { line_number, line, note, .. } => {
+                write!(f, "{:>linenum_width$} | {}\n", line_number, line)?;
+                write!(f, "{:>linenum_width$} | ^\n", " ")?;
+                write!(f, "{:>linenum_width$} = {}.\n", " ", note)?;
+
+                Ok(())
+            },
+            Self::MissingField { line_number, line, note, .. } => {
+                let line_len = line.len();
+                write!(f, "{:>linenum_width$} | {}\n", line_number, line)?;
+                write!(f, "{:>linenum_width$} | {:>line_len$} {:^<linenum_width$}\n", " ", " ", "^")?;
+                write!(f, "{:>linenum_width$} = {}.\n", " ", note)?;
+
+                Ok(())
+            },
+            Self::NotFloat { line_number, line, note, .. } => {
+                let line_len = line.len();
+                write!(f, "{:>linenum_width$} | {}\n", line_number, line)?;
+                write!(f, "{:>linenum_width$} | {:^<line_len$}\n", " ", "^")?;
+                write!(f, "{:>linenum_width$} = {}.\n", " ", note)?;
+
+                Ok(())
+            },
+            Self::NotInt { line_number, line, note, .. } => {
+                let line_len = line.len();
+                write!(f, "{:>linenum_width$} | {}\n", line_number, line)?;
+                write!(f, "{:>linenum_width$} | {:^<line_len$}\n", " ", "^")?;
+                write!(f, "{:>linenum_width$} = {}.\n", " ", note)?;
+
+                Ok(())
+            },
+            Self::UnknownItem { line_number, span, line, note, .. } => {
+                write!(f, "{:>linenum_width$} | {}\n", line_number, line.replace("\t", " "))?;
+                write!(f, "{:>linenum_width$} | {:>column$}{:^<width$}\n", " ", " ", "^", column = span.column - 1, width = span.len)?;
+                write!(f, "{:>linenum_width$} = {}.\n", " ", note)?;
+
+                Ok(())
+            },
+            Self::UnknownCollisionPartner { line_number, line, note, .. } => {
+                let skip = line.find(char::is_alphanumeric).unwrap_or(0);
+                let item_len = line.split_whitespace().next().unwrap_or("").len();
+                write!(f, "{:>linenum_width$} | {}\n", line_number, line)?;
+                write!(f, "{:>linenum_width$} | {:>skip$}{:^<item_len$}\n", " ", " ", "^")?;
+                write!(f, "{:>linenum_width$} = {}.\n", " ", note)?;
+
+                Ok(())
+            },
+            Self::InvalidEncoding { line_number, column, line, note, .. } => {
+                write!(f, "{:>linenum_width$} | {}\n", line_number, line)?;
+                write!(f, "{:>linenum_width$} | {:>column$}^\n", " ", " ")?;
+                write!(f, "{:>linenum_width$} = {}.\n", " ", note)?;
+
+                Ok(())
+            },
+            Self::LimitExceeded { line_number, line, note, .. } => {
+                let line_len = line.len();
+                write!(f, "{:>linenum_width$} | {}\n", line_number, line)?;
+                write!(f, "{:>linenum_width$} | {:^<line_len$}\n", " ", "^")?;
+                write!(f, "{:>linenum_width$} = {}.\n", " ", note)?;
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::MissingField { source, .. }
+            | Self::UnknownItem { source, .. }
+            | Self::UnknownCollisionPartner { source, .. } => source.as_deref().map(|e| e as _),
+            _ => None,
+        }
+    }
+}
+
+// Error of a single field, kept as the source of the `ParseError` of its line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+    EnergyLevel(SplittedFieldParseError<EnergyLevelField>),
+    RadiativeTransition(SplittedFieldParseError<RadiativeTransitionField>),
+    CollisionalRates(SplittedFieldParseError<CollisionalRatesField>),
+    CollisionalTemperatures(CollisionalTemperaturesParseError),
+    CollisionPartnerId(CollisionPartnerIdParseError),
+}
+
+impl core::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::EnergyLevel(e) => write!(f, "Energy level: {}", e),
+            Self::RadiativeTransition(e) => write!(f, "Radiative transition: {}", e),
+            Self::CollisionalRates(e) => write!(f, "Collisional rates: {}", e),
+            Self::CollisionalTemperatures(e) => write!(f, "Collisional temperatures: {}", e),
+            Self::CollisionPartnerId(e) => write!(f, "Collision partner: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FieldError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::EnergyLevel(e) => Some(e),
+            Self::RadiativeTransition(e) => Some(e),
+            Self::CollisionalRates(e) => Some(e),
+            Self::CollisionalTemperatures(e) => Some(e),
+            Self::CollisionPartnerId(e) => Some(e),
+        }
+    }
+}
+
+// Span of the first word of a line, the partner id of `!COLLISIONS BETWEEN`
+fn partner_span(line: &str) -> Span {
+    let word = line.split_whitespace().next().unwrap_or("");
+    Span::in_line(line.len() - line.trim_start().len(), word.len())
+}
+
+// Error of a line of data whose fields do not parse
+fn field_error<F>(
+    (line_number, line): (usize, &str),
+    e: SplittedFieldParseError<F>,
+    source: fn(SplittedFieldParseError<F>) -> FieldError,
+) -> ParseError
+where
+    SplittedFieldParseError<F>: core::fmt::Display,
+{
+    let note = e.to_string();
+    match &e {
+        SplittedFieldParseError::MissingField { .. } => ParseError::MissingField {
+            line_number,
+            span: Span::in_line(line.len(), 0),
+            line: String::from(line),
+            note,
+            source: Some(Box::new(source(e))),
+        },
+        SplittedFieldParseError::UnknownFormat { value, .. } => ParseError::UnknownItem {
+            line_number,
+            span: Span::in_line(line.find(value.as_str()).unwrap_or(0), value.len()),
+            line: String::from(line),
+            note,
+            source: Some(Box::new(source(e))),
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    DuplicateCollisionPartner { name: CollisionPartnerId },
+    UnknownLevel { transition: u32, level: u32 },
+    MissingCollisionPartner { name: CollisionPartnerId },
+    NoCommonTemperatures,
+    LevelMismatch { level: u32 },
+    LevelCountMismatch { levels: usize, other: usize },
+}
+
+impl core::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::DuplicateCollisionPartner { name } => {
+                write!(f, "Collision partner {:?} is already present in the data", name)
+            },
+            Self::UnknownLevel { transition, level } => {
+                write!(f, "Collisional transition {} refers to unknown energy level {}", transition, level)
+            }
+            Self::MissingCollisionPartner { name } => {
+                write!(f, "Collision partner {:?} is not present in the data", name)
+            }
+            Self::NoCommonTemperatures => write!(f, "The collision partners have no temperature range in common"),
+            Self::LevelMismatch { level } => {
+                write!(f, "Energy level {} differs between the datafiles or is missing from one of them", level)
+            }
+            Self::LevelCountMismatch { levels, other } => {
+                write!(f, "The datafiles have {} and {} energy levels", levels, other)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MergeError {}
+
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollisionPartnerData {
+    pub(crate) name: CollisionPartnerId,
+    pub(crate) information: String,
+    pub(crate) temperatures: Vec<f64>,
+    pub(crate) rates: Vec<CollisionalRates>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElementData {
+    pub(crate) name: String,
+    pub(crate) information: String,
+    pub(crate) weight: f64,
+    pub(crate) energy_levels: Vec<EnergyLevel>,
+    pub(crate) radiative_transitions: Vec<RadiativeTransition>,
+    pub(crate) collision_partners: Vec<CollisionPartnerData>,
+    pub(crate) features: FormatFeatures,
+    pub(crate) metadata: Metadata,
+    #[cfg_attr(feature = "json", serde(skip))]
+    pub(crate) original: Original,
+}
+
+// Borrowed counterpart of `CollisionPartnerData`, see `ElementDataRef`.
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct CollisionPartnerDataRef<'a> {
+    pub(crate) name: CollisionPartnerId,
+    pub(crate) information: &'a str,
+    pub(crate) temperatures: Vec<f64>,
+    pub(crate) rates: Vec<CollisionalRates>,
+}
+
+// Parsed datafile that borrows names, comments and quantum numbers from the
+// input instead of allocating them, for scanning many files quickly.
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct ElementDataRef<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) information: &'a str,
+    pub(crate) comments: Vec<&'a str>,
+    pub(crate) weight: f64,
+    pub(crate) energy_levels: Vec<EnergyLevelRef<'a>>,
+    pub(crate) radiative_transitions: Vec<RadiativeTransitionRef<'a>>,
+    pub(crate) collision_partners: Vec<CollisionPartnerDataRef<'a>>,
+    pub(crate) features: FormatFeatures,
+}
+
+impl CollisionPartnerData {
+    pub fn new(name: CollisionPartnerId, information: &str, temperatures: Vec<f64>, rates: Vec<CollisionalRates>) -> Self {
+        Self { name, information: String::from(information), temperatures, rates }
+    }
+
+    pub fn name(&self) -> CollisionPartnerId {
+        self.name.clone()
+    }
+
+    pub fn information(&self) -> &str {
+        &self.information
+    }
+
+    // Temperatures of the rate columns, K
+    pub fn temperatures(&self) -> &[f64] {
+        &self.temperatures
+    }
+
+    pub fn rates(&self) -> &[CollisionalRates] {
+        &self.rates
+    }
+
+    // Rate coefficients can be edited in place; the number of transitions
+    // and temperatures stays as parsed.
+    pub fn rates_mut(&mut self) -> &mut [CollisionalRates] {
+        &mut self.rates
+    }
+}
+
+impl<'a> CollisionPartnerDataRef<'a> {
+    pub fn name(&self) -> CollisionPartnerId {
+        self.name.clone()
+    }
+
+    pub fn information(&self) -> &'a str {
+        self.information
+    }
+
+    pub fn temperatures(&self) -> &[f64] {
+        &self.temperatures
+    }
+
+    pub fn rates(&self) -> &[CollisionalRates] {
+        &self.rates
+    }
+}
+
+// Quantum numbers of a level without comments and a leading `name=`
+fn quantum_number_key(qnums: &str) -> &str {
+    let qnums = qnums.split('!').next().unwrap_or("").trim();
+    match qnums.split_once('=') {
+        Some((name, rest)) if !name.is_empty() && name.chars().all(char::is_alphabetic) => rest.trim(),
+        _ => qnums,
+    }
+}
+
+impl ElementData {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn information(&self) -> &str {
+        &self.information
+    }
+
+    // Molecular weight, atomic mass units
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub fn energy_levels(&self) -> &[EnergyLevel] {
+        &self.energy_levels
+    }
+
+    // Level by its number in the datafile, which counts from 1
+    pub fn energy_level(&self, level: u32) -> Option<&EnergyLevel> {
+        self.level(level)
+    }
+
+    // Level `n` is at index n - 1 when the levels are numbered 1, 2, ... as
+    // in LAMDA files, which makes the lookup O(1); other numberings are
+    // searched.
+    pub fn level(&self, n: u32) -> Option<&EnergyLevel> {
+        let index = (n as usize).checked_sub(1)?;
+        match self.energy_levels.get(index) {
+            Some(level) if level.level == n => Some(level),
+            _ => self.energy_levels.iter().find(|l| l.level == n),
+        }
+    }
+
+    // Energy of level `n`, cm^-1
+    pub fn level_energy(&self, n: u32) -> Option<f64> {
+        self.level(n).map(|l| l.energy)
+    }
+
+    // Whether the levels are numbered 1, 2, ... in order, so that the up and
+    // low numbers of transitions are indices into `energy_levels`
+    pub fn has_contiguous_levels(&self) -> bool {
+        self.energy_levels.iter().enumerate().all(|(i, l)| l.level as usize == i + 1)
+    }
+
+    pub fn radiative_transitions(&self) -> &[RadiativeTransition] {
+        &self.radiative_transitions
+    }
+
+    pub fn radiative_transition(&self, transition: u32) -> Option<&RadiativeTransition> {
+        self.radiative_transitions.iter().find(|t| t.transition == transition)
+    }
+
+    // Radiative transition by the quantum numbers of its levels, written as
+    // `up-low`, e.g. `J=2-1` or `2-1` for CO(2-1) and `3_P_1-3_P_2` for
+    // [OI] 63 um. Comments in the level lines and a leading `J=` style label
+    // are ignored; quantum numbers with a `-` of their own still match.
+    pub fn find_transition(&self, label: &str) -> Option<&RadiativeTransition> {
+        let label = label.trim();
+        let qnums = |n: u32| self.energy_level(n).map(|l| quantum_number_key(&l.qnums));
+        let matches = |t: &&RadiativeTransition, up: &str, low: &str| {
+            qnums(t.up) == Some(quantum_number_key(up)) && qnums(t.low) == Some(quantum_number_key(low))
+        };
+
+        label
+            .match_indices('-')
+            .map(|(i, _)| (&label[..i], label[i + 1..].trim_start_matches('>')))
+            .find_map(|(up, low)| self.radiative_transitions.iter().find(|t| matches(t, up, low)))
+    }
+
+    // Radiative transitions with a rest frequency from `f_min` to `f_max`
+    // GHz, both included, in the order of the file
+    pub fn transitions_in_range(&self, f_min: f64, f_max: f64) -> Vec<&RadiativeTransition> {
+        self.radiative_transitions
+            .iter()
+            .filter(|t| self.frequency(t).is_some_and(|f| f_min <= f && f <= f_max))
+            .collect()
+    }
+
+    // Rest frequency of `transition`, GHz: the frequency column where the
+    // file has one, otherwise from the level energies.
+    pub fn frequency(&self, transition: &RadiativeTransition) -> Option<f64> {
+        transition.frequency.or_else(|| self.level_frequency(transition))
+    }
+
+    // Energy of the upper level of `transition`, K, likewise
+    pub fn upper_energy(&self, transition: &RadiativeTransition) -> Option<f64> {
+        transition.upper_energy.or_else(|| self.level_upper_energy(transition))
+    }
+
+    // Frequency and E_u from the energy levels, which RADEX uses whatever
+    // the columns of the transition say
+    fn level_frequency(&self, transition: &RadiativeTransition) -> Option<f64> {
+        let (up, low) = (self.energy_level(transition.up)?, self.energy_level(transition.low)?);
+        Some((up.energy - low.energy) * SPEED_OF_LIGHT * 1e-9)
+    }
+
+    fn level_upper_energy(&self, transition: &RadiativeTransition) -> Option<f64> {
+        self.energy_level(transition.up).map(|l| l.energy * WAVENUMBER_TO_KELVIN)
+    }
+
+    pub fn collision_partners(&self) -> &[CollisionPartnerData] {
+        &self.collision_partners
+    }
+
+    pub fn collision_partner(&self, name: CollisionPartnerId) -> Option<&CollisionPartnerData> {
+        self.collision_partners.iter().find(|cp| cp.name == name)
+    }
+
+    // Mutable views for editing values before writing the data back with
+    // `to_lamda_string`; sections keep their length, see `reparse_section`
+    // and `merge_collision_partner` to change it.
+    pub fn energy_levels_mut(&mut self) -> &mut [EnergyLevel] {
+        &mut self.energy_levels
+    }
+
+    pub fn radiative_transitions_mut(&mut self) -> &mut [RadiativeTransition] {
+        &mut self.radiative_transitions
+    }
+
+    pub fn collision_partners_mut(&mut self) -> &mut [CollisionPartnerData] {
+        &mut self.collision_partners
+    }
+
+    // Optional parts of the format the datafile was parsed from
+    pub fn features(&self) -> FormatFeatures {
+        self.features
+    }
+
+    pub fn parse_with_diagnostics(s: &str, diagnostics: &mut Diagnostics) -> Result<Self, ParseError> {
+        ElementDataRef::parse_with_diagnostics(s, diagnostics).map(|data| data.into_owned())
+    }
+
+    pub fn parse_with_limits(s: &str, limits: &ParseLimits) -> Result<Self, ParseError> {
+        ElementDataRef::parse_with_limits(s, limits, &mut Diagnostics::new()).map(|data| data.into_owned())
+    }
+
+    pub fn parse_with_progress(s: &str, progress: ProgressCallback<'_, Option<Section>>) -> Result<Self, ParseError> {
+        ElementDataRef::parse_with_progress(s, &mut Diagnostics::new(), progress).map(|data| data.into_owned())
+    }
+
+    pub fn parse_lenient(s: &str) -> (Self, Vec<ParseError>) {
+        let (data, errors) = ElementDataRef::parse_lenient(s, &mut Diagnostics::new());
+        (data.into_owned(), errors)
+    }
+
+    pub fn merge_collision_partner(&mut self, partner: CollisionPartnerData) -> Result<(), MergeError> {
+        if self.collision_partners.iter().any(|cp| cp.name == partner.name) {
+            return Err(MergeError::DuplicateCollisionPartner { name: partner.name });
+        }
+
+        let nlev = self.energy_levels.len() as u32;
+        for rate in partner.rates.iter() {
+            for level in [rate.up, rate.low] {
+                if level == 0 || level > nlev {
+                    return Err(MergeError::UnknownLevel { transition: rate.transition, level });
+                }
+            }
+        }
+
+        self.collision_partners.push(partner);
+
+        Ok(())
+    }
+
+    // Adds the collision partners of `other`, another datafile of the same
+    // species, e.g. one with the rates for H and He next to one for H2. Both
+    // must have the same levels, with the same weights and, up to the
+    // rounding of the files, the same energies. Nothing is added if a level
+    // differs or a partner is in both.
+    pub fn merge(&mut self, other: &ElementData) -> Result<(), MergeError> {
+        if self.energy_levels.len() != other.energy_levels.len() {
+            return Err(MergeError::LevelCountMismatch {
+                levels: self.energy_levels.len(),
+                other: other.energy_levels.len(),
+            });
+        }
+        for level in other.energy_levels.iter() {
+            let same = self.level(level.level).is_some_and(|el| {
+                el.stat_weight == level.stat_weight && (el.energy - level.energy).abs() <= 1e-4 * el.energy.abs().max(1.0)
+            });
+            if !same {
+                return Err(MergeError::LevelMismatch { level: level.level });
+            }
+        }
+        if let Some(partner) = other.collision_partners.iter().find(|cp| self.collision_partner(cp.name.clone()).is_some()) {
+            return Err(MergeError::DuplicateCollisionPartner { name: partner.name.clone() });
+        }
+
+        for partner in other.collision_partners.iter() {
+            self.collision_partners.push(CollisionPartnerData::new(
+                partner.name.clone(),
+                &partner.information,
+                partner.temperatures.clone(),
+                partner.rates.clone(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl core::str::FromStr for ElementData {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ElementDataRef::parse(s).map(|data| data.into_owned())
+    }
+}
+
+impl<'a> ElementDataRef<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn information(&self) -> &'a str {
+        self.information
+    }
+
+    // Comment lines after the last collision partner
+    pub fn comments(&self) -> &[&'a str] {
+        &self.comments
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub fn energy_levels(&self) -> &[EnergyLevelRef<'a>] {
+        &self.energy_levels
+    }
+
+    pub fn radiative_transitions(&self) -> &[RadiativeTransitionRef<'a>] {
+        &self.radiative_transitions
+    }
+
+    pub fn collision_partners(&self) -> &[CollisionPartnerDataRef<'a>] {
+        &self.collision_partners
+    }
+
+    pub fn features(&self) -> FormatFeatures {
+        self.features
+    }
+
+    pub fn into_owned(self) -> ElementData {
+        let additional_info = self.comments
+            .iter()
+            .map(|comment| String::from(*comment) + " ")
+            .collect::<String>();
+
+        ElementData {
+            name: String::from(self.name),
+            information: format!("{}. {}", self.information, additional_info),
+            weight: self.weight,
+            energy_levels: self.energy_levels.into_iter().map(EnergyLevelRef::into_owned).collect(),
+            radiative_transitions: self.radiative_transitions.into_iter().map(RadiativeTransitionRef::into_owned).collect(),
+            collision_partners: self.collision_partners
+                .into_iter()
+                .map(|cp| CollisionPartnerData {
+                    name: cp.name,
+                    information: String::from(cp.information),
+                    temperatures: cp.temperatures,
+                    rates: cp.rates,
+                })
+                .collect(),
+            features: self.features,
+            metadata: Metadata::default(),
+            original: Original::default(),
+        }
+    }
+
+    fn validate_and_parse_comment(line_number: usize, line: &'a str) -> Result<Comment<'a>, ParseError> {
+        match line.trim().starts_with("!") {
+            true => Ok(Comment::parse(line)),
+            false => Err(ParseError::WrongCommentFormat {
+                line_number,
+                span: Span::in_line(0, line.len().min(1)),
+                line: String::from(line),
+                note: String::from("Comment should begin with `!` character")
+            })
+        }
+    }
+
+    // Molecule name and information, and the molecular weight, from the
+    // `!MOLECULE` comment on
+    fn parse_header(lines: &mut Lines<'a>, line: &mut (usize, &'a str)) -> Result<(ElementName<'a>, f64), ParseError> {
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        let name = ElementName::parse(line.1);
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        let weight: f64 = match line.1.trim().parse() {
+            Ok(w) => w,
+            Err(_) => return Err(ParseError::NotFloat {
+                line_number: line.0,
+                span: Span::in_line(0, line.1.len()),
+                line: String::from(line.1),
+                note: String::from("Expected floating point number")
+            })
+        };
+
+        Ok((name, weight))
+    }
+
+    fn parse_number_of_collision_partners(
+        lines: &mut Lines<'a>,
+        line: &mut (usize, &'a str),
+        limits: &ParseLimits,
+    ) -> Result<u32, ParseError> {
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        match line.1.parse::<NumberOfCollisionPartners>() {
+            Ok(n) => limits::check(n.0, limits.max_collision_partners, "collision partners", *line),
+            Err(_) => Err(ParseError::NotInt {
+                line_number: line.0,
+                span: Span::in_line(0, line.1.len()),
+                line: String::from(line.1),
+                note: String::from("Expected integer")
+            })
+        }
+    }
+
+    // Comments with additional information after the collisional data
+    fn parse_notes(lines: Lines<'a>, npart: u32, mut errors: Option<&mut Vec<ParseError>>) -> Result<Vec<&'a str>, ParseError> {
+        lines
+            .filter(|el| !el.1.trim().is_empty())
+            .map(|el| match Self::validate_and_parse_comment(el.0, el.1) {
+                Ok(comment) => Ok(comment.0),
+                Err(_) => Err(ParseError::WrongCommentFormat {
+                    line_number: el.0,
+                    span: Span::in_line(0, el.1.len().min(1)),
+                    line: String::from(el.1),
+                    note: format!(
+                        "{} collision partners were read, only comments with additional information should be left",
+                        npart
+                    )
+                })
+            })
+            .filter_map(|r| skip_line(r, &mut errors).transpose())
+            .collect()
+    }
+
+    // Energy levels from the `!NUMBER OF ENERGY LEVELS` comment on, with the
+    // header comment of the level lines.
+    fn parse_energy_levels(
+        lines: &mut Lines<'a>,
+        line: &mut (usize, &'a str),
+        limits: &ParseLimits,
+        mut progress: Option<&mut Reporter<'_>>,
+        mut errors: Option<&mut Vec<ParseError>>,
+    ) -> Result<(Vec<EnergyLevelRef<'a>>, &'a str), ParseError> {
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        let nlev = match line.1.parse::<NumberOfEnergyLevels>() {
+            Ok(n) => limits::check(n.0, limits.max_levels, "energy levels", *line)?,
+            Err(_) => return Err(ParseError::NotInt {
+                line_number: line.0,
+                span: Span::in_line(0, line.1.len()),
+                line: String::from(line.1),
+                note: String::from("Expected integer")
+            })
+        };
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+        let level_header = line.1;
+
+        let energy_level_lines = lines.by_ref().take(nlev as usize);
+        let energy_levels = energy_level_lines
+            .inspect(|el| progress.iter_mut().for_each(|p| p.line(el.0)))
+            .map(|el| Ok(match EnergyLevelRef::parse(el.1) {
+                Ok(enlev) => enlev,
+                Err(e) => return Err(field_error(el, e, FieldError::EnergyLevel)),
+            }))
+            .filter_map(|r| skip_line(r, &mut errors).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((energy_levels, level_header))
+    }
+
+    fn parse_radiative_transitions(
+        lines: &mut Lines<'a>,
+        line: &mut (usize, &'a str),
+        limits: &ParseLimits,
+        mut progress: Option<&mut Reporter<'_>>,
+        mut errors: Option<&mut Vec<ParseError>>,
+    ) -> Result<Vec<RadiativeTransitionRef<'a>>, ParseError> {
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        let nlin = match line.1.parse::<NumberOfRadiativeTransitions>() {
+            Ok(n) => limits::check(n.0, limits.max_transitions, "radiative transitions", *line)?,
+            Err(_) => return Err(ParseError::NotInt {
+                line_number: line.0,
+                span: Span::in_line(0, line.1.len()),
+                line: String::from(line.1),
+                note: String::from("Expected integer")
+            })
+        };
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        let radiative_transition_lines = lines.by_ref().take(nlin as usize);
+        radiative_transition_lines
+            .inspect(|el| progress.iter_mut().for_each(|p| p.line(el.0)))
+            .map(|el| Ok(match RadiativeTransitionRef::parse(el.1) {
+                Ok(enlev) => enlev,
+                Err(e) => return Err(field_error(el, e, FieldError::RadiativeTransition)),
+            }))
+            .filter_map(|r| skip_line(r, &mut errors).transpose())
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    // One collision partner from the `!COLLISIONS BETWEEN` comment on
+    fn parse_collision_partner(
+        lines: &mut Lines<'a>,
+        line: &mut (usize, &'a str),
+        limits: &ParseLimits,
+        mut progress: Option<&mut Reporter<'_>>,
+        diagnostics: &mut Diagnostics,
+        mut errors: Option<&mut Vec<ParseError>>,
+    ) -> Result<CollisionPartnerDataRef<'a>, ParseError> {
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        let (name, information) = match CollisionPartnerName::parse(line.1) {
+            Ok(cp_name) => (cp_name.name, cp_name.information),
+            Err(e) => return Err(ParseError::UnknownCollisionPartner {
+                line_number: line.0,
+                span: partner_span(line.1),
+                line: String::from(line.1),
+                note: e.to_string(),
+                source: Some(Box::new(FieldError::CollisionPartnerId(e))),
+            })
+        };
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        let ncol = match line.1.parse::<NumberOfCollisionalTransitions>() {
+            Ok(n) => limits::check(n.0, limits.max_collisional_transitions, "collisional transitions", *line)?,
+            Err(_) => return Err(ParseError::NotInt {
+                line_number: line.0,
+                span: Span::in_line(0, line.1.len()),
+                line: String::from(line.1),
+                note: String::from("Expected integer")
+            })
+        };
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        let ntemp = match line.1.parse::<NumberOfCollisionalTemperatures>() {
+            Ok(n) => limits::check(n.0, limits.max_temperatures, "temperatures", *line)?,
+            Err(_) => return Err(ParseError::NotInt {
+                line_number: line.0,
+                span: Span::in_line(0, line.1.len()),
+                line: String::from(line.1),
+                note: String::from("Expected integer")
+            })
+        };
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        let temperatures = match line.1.parse::<CollisionalTemperatures>() {
+            Ok(temps) => temps.0,
+            Err(e) => return Err(ParseError::UnknownItem {
+                line_number: line.0,
+                span: Span::in_line(line.1.find(&e.value).unwrap_or(0), e.value.len()),
+                line: String::from(line.1),
+                note: e.to_string(),
+                source: Some(Box::new(FieldError::CollisionalTemperatures(e))),
+            })
+        };
+        if temperatures.len() != ntemp as usize {
+            diagnostics.push(Diagnostic {
+                line: line.0 + 1,
+                column: 1,
+                width: line.1.len(),
+                ..Diagnostic::warning(
+                    "temperature-count",
+                    format!("{} temperatures are listed, but the header gives {}", temperatures.len(), ntemp),
+                )
+            });
+        }
+
+        *line = lines.next().ok_or(ParseError::end_of_input(line.0 + 1))?;
+        Self::validate_and_parse_comment(line.0, line.1)?;
+
+        let collisional_rates_lines = lines.by_ref().take(ncol as usize);
+        let rates = collisional_rates_lines
+            .inspect(|el| progress.iter_mut().for_each(|p| p.line(el.0)))
+            .map(|el| Ok(match el.1.parse::<CollisionalRates>() {
+                Ok(colrate) => {
+                    if colrate.rates.len() != temperatures.len() {
+                        diagnostics.push(Diagnostic {
+                            line: el.0 + 1,
+                            column: 1,
+                            width: el.1.len(),
+                            ..Diagnostic::warning(
+                                "rate-count",
+                                format!(
+                                    "Transition {} has {} rates for {} temperatures",
+                                    colrate.transition,
+                                    colrate.rates.len(),
+                                    temperatures.len(),
+                                ),
+                            )
+                        });
+                    }
+                    colrate
+                },
+                Err(e) => return Err(field_error(el, e, FieldError::CollisionalRates)),
+            }))
+            .filter_map(|r| skip_line(r, &mut errors).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(CollisionPartnerDataRef {name, information, temperatures, rates})
+    }
+
+    pub fn parse(s: &'a str) -> Result<Self, ParseError> {
+        Self::parse_with_diagnostics(s, &mut Diagnostics::new())
+    }
+
+    // Parses like `parse`, adding what the format tolerates but is likely a
+    // mistake in the datafile to `diagnostics`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", name = "parse_lamda", skip_all, fields(bytes = s.len())))]
+    pub fn parse_with_diagnostics(s: &'a str, diagnostics: &mut Diagnostics) -> Result<Self, ParseError> {
+        Self::parse_with_limits(s, &ParseLimits::default(), diagnostics)
+    }
+
+    // Parses like `parse_with_diagnostics`, failing with
+    // `ParseError::LimitExceeded` for counts above `limits`
+    pub fn parse_with_limits(s: &'a str, limits: &ParseLimits, diagnostics: &mut Diagnostics) -> Result<Self, ParseError> {
+        let mut data = Self::default();
+        Self::parse_into(s, &mut data, limits, None, diagnostics, None)?;
+
+        Ok(data)
+    }
+
+    // Parses like `parse_with_diagnostics`, calling `progress` as sections
+    // start and lines are read, e.g. for a progress bar on large files
+    pub fn parse_with_progress(
+        s: &'a str,
+        diagnostics: &mut Diagnostics,
+        progress: ProgressCallback<'_, Option<Section>>,
+    ) -> Result<Self, ParseError> {
+        let mut data = Self::default();
+        let mut reporter = Reporter::new(s, progress);
+        Self::parse_into(s, &mut data, &ParseLimits::default(), Some(&mut reporter), diagnostics, None)?;
+
+        Ok(data)
+    }
+
+    // Parses past lines that do not parse and returns what could be read
+    // with every error found, e.g. to fix a hand-edited datafile in one go.
+    // Only a broken line of data is skipped; when the structure of the file
+    // is broken, e.g. a count is not a number or a section comment is
+    // missing, parsing stops there with that error last.
+    pub fn parse_lenient(s: &'a str, diagnostics: &mut Diagnostics) -> (Self, Vec<ParseError>) {
+        let mut data = Self::default();
+        let mut errors = vec!();
+
+        if let Err(e) = Self::parse_into(s, &mut data, &ParseLimits::default(), None, diagnostics, Some(&mut errors)) {
+            errors.push(e);
+            data.features = FormatFeatures::detect("", &data);
+        }
+
+        (data, errors)
+    }
+
+    // Fills `data` section by section, so that it keeps what was read when
+    // parsing stops. Errors have their spans in `s`.
+    fn parse_into(
+        s: &'a str,
+        data: &mut Self,
+        limits: &ParseLimits,
+        progress: Option<&mut Reporter<'_>>,
+        diagnostics: &mut Diagnostics,
+        mut errors: Option<&mut Vec<ParseError>>,
+    ) -> Result<(), ParseError> {
+        let found = errors.as_ref().map_or(0, |errors| errors.len());
+        let result = Self::parse_sections(s, data, limits, progress, diagnostics, errors.as_deref_mut());
+        if let Some(errors) = errors {
+            let located = errors.drain(found..).map(|e| e.locate(s)).collect::<Vec<_>>();
+            errors.extend(located);
+        }
+
+        result.map_err(|e| e.locate(s))
+    }
+
+    fn parse_sections(
+        s: &'a str,
+        data: &mut Self,
+        limits: &ParseLimits,
+        mut progress: Option<&mut Reporter<'_>>,
+        diagnostics: &mut Diagnostics,
+        mut errors: Option<&mut Vec<ParseError>>,
+    ) -> Result<(), ParseError> {
+        // Editors on Windows may start the file with a byte order mark
+        let s = s.strip_prefix('\u{feff}').unwrap_or(s);
+        let mut lines = s.lines().enumerate();
+        let mut line = (0, "");
+
+        let (ElementName { name, information }, weight) = Self::parse_header(&mut lines, &mut line)?;
+        data.name = name;
+        data.information = information;
+        data.weight = weight;
+
+        progress.iter_mut().for_each(|p| p.section(Some(Section::EnergyLevels), line.0 + 1));
+        let (energy_levels, level_header) =
+            Self::parse_energy_levels(&mut lines, &mut line, limits, progress.as_deref_mut(), errors.as_deref_mut())?;
+        trace_event!(trace, species = %name, levels = energy_levels.len(), "energy levels parsed");
+        data.energy_levels = energy_levels;
+
+        progress.iter_mut().for_each(|p| p.section(Some(Section::RadiativeTransitions), line.0 + 1));
+        let radiative_transitions =
+            Self::parse_radiative_transitions(&mut lines, &mut line, limits, progress.as_deref_mut(), errors.as_deref_mut())?;
+        trace_event!(trace, species = %name, transitions = radiative_transitions.len(), "radiative transitions parsed");
+        data.radiative_transitions = radiative_transitions;
+
+        let npart = Self::parse_number_of_collision_partners(&mut lines, &mut line, limits)?;
+
+        data.collision_partners = Vec::with_capacity(npart as usize);
+        for _ in 1..(npart + 1) {
+            progress.iter_mut().for_each(|p| p.section(Some(Section::CollisionPartner), line.0 + 1));
+            let partner =
+                Self::parse_collision_partner(&mut lines, &mut line, limits, progress.as_deref_mut(), diagnostics, errors.as_deref_mut())?;
+            trace_event!(trace, partner = %partner.name, transitions = partner.rates.len(), temperatures = partner.temperatures.len(), "collision rates parsed");
+            data.collision_partners.push(partner);
+        }
+
+        progress.iter_mut().for_each(|p| p.section(None, line.0 + 1));
+        data.comments = Self::parse_notes(lines, npart, errors)?;
+        trace_event!(debug, species = %name, partners = data.collision_partners.len(), "datafile parsed");
+
+        data.features = FormatFeatures::detect(level_header, data);
+        progress.iter_mut().for_each(|p| p.finish());
+
+        Ok(())
+    }
+}
+
+// Moves an error found in a part of the input, `lines` lines and `bytes`
+// bytes into the whole input, to its place in the whole input
+pub(crate) fn shift(mut error: ParseError, lines: usize, bytes: usize) -> ParseError {
+    match &mut error {
+        ParseError::NotEnoughInput { line_number, .. }
+        | ParseError::WrongCommentFormat { line_number, .. }
+        | ParseError::MissingField { line_number, .. }
+        | ParseError::NotFloat { line_number, .. }
+        | ParseError::NotInt { line_number, .. }
+        | ParseError::UnknownItem { line_number, .. }
+        | ParseError::UnknownCollisionPartner { line_number, .. }
+        | ParseError::InvalidEncoding { line_number, .. }
+        | ParseError::LimitExceeded { line_number, .. } => *line_number += lines,
+    }
+    error.span_mut().byte_offset += bytes;
+
+    error
+}
+
+// Line that does not parse: strict parsing fails with its error, lenient
+// parsing keeps the error in `errors` and skips the line
+fn skip_line<T>(result: Result<T, ParseError>, errors: &mut Option<&mut Vec<ParseError>>) -> Result<Option<T>, ParseError> {
+    match (result, errors) {
+        (Ok(value), _) => Ok(Some(value)),
+        (Err(e), Some(errors)) => {
+            errors.push(e);
+            Ok(None)
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Comment<'a>(&'a str);
+
+impl<'a> Comment<'a> {
+    fn parse(s: &'a str) -> Self {
+        Self(s.trim_matches(|c: char| c.is_whitespace() || c == '!'))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ElementName<'a> {
+    name: &'a str,
+    information: &'a str,
+}
+
+impl<'a> ElementName<'a> {
+    fn parse(s: &'a str) -> Self {
+        let s = s.trim();
+        let (name, comment_str) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let information = comment_str.trim_matches(|c: char| c.is_whitespace() || c == '!');
+
+        Self { name, information }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ElementWeight(f64);
+
+impl core::str::FromStr for ElementWeight {
+    type Err = core::num::ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<f64>() {
+            Ok(n) => Ok(ElementWeight(n)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct NumberOfEnergyLevels(u32);
+
+impl core::str::FromStr for NumberOfEnergyLevels {
+    type Err = core::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<u32>() {
+            Ok(n) => Ok(Self(n)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectedFieldValue {
+    Integer,
+    Float,
+}
+
+impl core::fmt::Display for ExpectedFieldValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ExpectedFieldValue::Integer => write!(f, "integer"),
+            ExpectedFieldValue::Float => write!(f, "floating point number"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplittedFieldParseError<F> {
+    MissingField {
+        field: F,
+        expected: ExpectedFieldValue,
+    },
+    UnknownFormat {
+        field: F,
+        value: String,
+        expected: ExpectedFieldValue,
+    },
+}
+
+impl<F: core::fmt::Display> core::fmt::Display for SplittedFieldParseError<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingField { field, expected } => {
+                write!(f, "Missing field `{}` with value of {} type", field, expected)
+            },
+            Self::UnknownFormat { field, value, expected } => {
+                write!(f, "Value `{}` from field `{}` has wrong type (should be {})", value, field, expected)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: core::fmt::Debug + core::fmt::Display> std::error::Error for SplittedFieldParseError<F> {}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnergyLevel {
+    pub level: u32,
+    pub energy: f64,
+    pub stat_weight: f64,
+    pub qnums: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnergyLevelField {
+    Level = 0,
+    Energy,
+    StatisticalWeight,
+}
+
+impl core::fmt::Display for EnergyLevelField {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EnergyLevelField::Level => write!(f, "level"),
+            EnergyLevelField::Energy => write!(f, "energy [cm-1]"),
+            EnergyLevelField::StatisticalWeight => write!(f, "statistical weight")
+        }
+    }
+}
+
+type EnergyLevelParseError = SplittedFieldParseError<EnergyLevelField>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct EnergyLevelRef<'a> {
+    pub level: u32,
+    pub energy: f64,
+    pub stat_weight: f64,
+    pub qnums: &'a str,
+}
+
+impl core::str::FromStr for EnergyLevel {
+    type Err = EnergyLevelParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EnergyLevelRef::parse(s).map(EnergyLevelRef::into_owned)
+    }
+}
+
+// Free text after the numeric fields of a line, starting at `token`.
+fn remainder<'a>(s: &'a str, token: Option<&'a str>) -> &'a str {
+    let rest = match token {
+        Some(token) => &s[token.as_ptr() as usize - s.as_ptr() as usize..],
+        None => "",
+    };
+
+    rest.trim_matches(|c: char| c.is_whitespace() || c == '!' || c == '\'')
+}
+
+// Frequency and upper level energy columns at the start of the text after
+// the Einstein A, and the text after them. E_u is only read after a
+// frequency, as a single number is a frequency in the RADEX layout.
+pub(crate) fn transition_columns(s: &str) -> (Option<f64>, Option<f64>, &str) {
+    let mut values = s.split_whitespace();
+    let mut next = values.next();
+
+    let frequency = next.and_then(scan_f64);
+    if frequency.is_some() {
+        next = values.next();
+    }
+    let upper_energy = frequency.and(next.and_then(scan_f64));
+    if upper_energy.is_some() {
+        next = values.next();
+    }
+
+    (frequency, upper_energy, remainder(s, next))
+}
+
+// Owned form of a `remainder`, with runs of whitespace collapsed.
+fn normalize_remainder(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c| c == ' ' || c == '!' || c == '\'' || c == '\n')
+        .to_string()
+}
+
+impl<'a> EnergyLevelRef<'a> {
+    pub(crate) fn into_owned(self) -> EnergyLevel {
+        EnergyLevel {
+            level: self.level,
+            energy: self.energy,
+            stat_weight: self.stat_weight,
+            qnums: normalize_remainder(self.qnums),
+        }
+    }
+
+    pub(crate) fn parse(s: &'a str) -> Result<Self, EnergyLevelParseError> {
+        let mut values = s.split_whitespace();
+        let mut values_beg = values.clone();
+
+        let level = values
+            .next()
+            .ok_or(EnergyLevelParseError::MissingField {
+                field: EnergyLevelField::Level,
+                expected: ExpectedFieldValue::Integer,
+            })?
+            .parse::<u32>();
+
+        let level = match level {
+            Ok(n) => n,
+            Err(_) => return Err(EnergyLevelParseError::UnknownFormat {
+                field: EnergyLevelField::Level,
+                value: String::from(values_beg.nth(EnergyLevelField::Level as usize).unwrap()),
+                expected: ExpectedFieldValue::Integer,
+            })
+        };
+
+        let energy = values
+            .next()
+            .ok_or(EnergyLevelParseError::MissingField {
+                field: EnergyLevelField::Energy,
+                expected: ExpectedFieldValue::Float,
+            })?
+            .parse::<f64>();
+
+        let energy = match energy {
+            Ok(n) => n,
+            Err(_) => return Err(EnergyLevelParseError::UnknownFormat {
+                field: EnergyLevelField::Energy,
+                value: String::from(values_beg.nth(EnergyLevelField::Energy as usize).unwrap()),
+                expected: ExpectedFieldValue::Float,
+            })
+        };
+
+        let stat_weight = values
+            .next()
+            .ok_or(EnergyLevelParseError::MissingField {
+                field: EnergyLevelField::StatisticalWeight,
+                expected: ExpectedFieldValue::Float,
+            })?
+            .parse::<f64>();
+
+        let stat_weight = match stat_weight {
+            Ok(n) => n,
+            Err(_) => return Err(EnergyLevelParseError::UnknownFormat {
+                field: EnergyLevelField::StatisticalWeight,
+                value: String::from(values_beg.nth(EnergyLevelField::StatisticalWeight as usize).unwrap()),
+                expected: ExpectedFieldValue::Float,
+            })
+        };
+
+        let qnums = remainder(s, values.next());
+
+        Ok(Self {
+            level,
+            energy,
+            stat_weight,
+            qnums
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct NumberOfRadiativeTransitions(u32);
+
+impl core::str::FromStr for NumberOfRadiativeTransitions {
+    type Err = core::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<u32>() {
+            Ok(n) => Ok(Self(n)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct RadiativeTransition {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub aeinst: f64,
+    // Rest frequency, GHz, and upper level energy, K, from the columns most
+    // files add after the Einstein A
+    pub frequency: Option<f64>,
+    pub upper_energy: Option<f64>,
+    pub extra: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadiativeTransitionField {
+    Transition = 0,
+    UpperLevel,
+    LowerLevel,
+    SpontaneousDecayRate,
+}
+
+impl core::fmt::Display for RadiativeTransitionField {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RadiativeTransitionField::Transition => write!(f, "transition"),
+            RadiativeTransitionField::UpperLevel => write!(f, "upper level"),
+            RadiativeTransitionField::LowerLevel => write!(f, "lower level"),
+            RadiativeTransitionField::SpontaneousDecayRate => write!(f, "spontaneous decay rate [s-1]")
+        }
+    }
+}
+
+type RadiativeTransitionParseError = SplittedFieldParseError<RadiativeTransitionField>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct RadiativeTransitionRef<'a> {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub aeinst: f64,
+    pub frequency: Option<f64>,
+    pub upper_energy: Option<f64>,
+    pub extra: &'a str,
+}
+
+impl core::str::FromStr for RadiativeTransition {
+    type Err = RadiativeTransitionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RadiativeTransitionRef::parse(s).map(RadiativeTransitionRef::into_owned)
+    }
+}
+
+impl<'a> RadiativeTransitionRef<'a> {
+    pub(crate) fn into_owned(self) -> RadiativeTransition {
+        RadiativeTransition {
+            transition: self.transition,
+            up: self.up,
+            low: self.low,
+            aeinst: self.aeinst,
+            frequency: self.frequency,
+            upper_energy: self.upper_energy,
+            extra: normalize_remainder(self.extra),
+        }
+    }
+
+    pub(crate) fn parse(s: &'a str) -> Result<Self, RadiativeTransitionParseError> {
+        let mut values = s.split_whitespace();
+        let mut values_beg = values.clone();
+
+        let transition = values
+            .next()
+            .ok_or(RadiativeTransitionParseError::MissingField {
+                field: RadiativeTransitionField::Transition,
+                expected: ExpectedFieldValue::Integer,
+            })?
+            .parse::<u32>();
+
+        let transition = match transition {
+            Ok(n) => n,
+            Err(_) => return Err(RadiativeTransitionParseError::UnknownFormat {
+                field: RadiativeTransitionField::Transition,
+                value: String::from(values_beg.nth(RadiativeTransitionField::Transition as usize).unwrap()),
+                expected: ExpectedFieldValue::Integer,
+            })
+        };
+
+        let up = values
+            .next()
+            .ok_or(RadiativeTransitionParseError::MissingField {
+                field: RadiativeTransitionField::UpperLevel,
+                expected: ExpectedFieldValue::Integer,
+            })?
+            .parse::<u32>();
+
+        let up = match up {
+            Ok(n) => n,
+            Err(_) => return Err(RadiativeTransitionParseError::UnknownFormat {
+                field: RadiativeTransitionField::UpperLevel,
+                value: String::from(values_beg.nth(RadiativeTransitionField::UpperLevel as usize).unwrap()),
+                expected: ExpectedFieldValue::Integer,
+            })
+        };
+
+        let low = values
+            .next()
+            .ok_or(RadiativeTransitionParseError::MissingField {
+                field: RadiativeTransitionField::LowerLevel,
+                expected: ExpectedFieldValue::Integer,
+            })?
+            .parse::<u32>();
+
+        let low = match low {
+            Ok(n) => n,
+            Err(_) => return Err(RadiativeTransitionParseError::UnknownFormat {
+                field: RadiativeTransitionField::LowerLevel,
+                value: String::from(values_beg.nth(RadiativeTransitionField::LowerLevel as usize).unwrap()),
+                expected: ExpectedFieldValue::Integer,
+            })
+        };
+
+        let aeinst = values
+            .next()
+            .ok_or(RadiativeTransitionParseError::MissingField {
+                field: RadiativeTransitionField::SpontaneousDecayRate,
+                expected: ExpectedFieldValue::Float,
+            })?
+            .parse::<f64>();
+
+        let aeinst = match aeinst {
+            Ok(n) => n,
+            Err(_) => return Err(RadiativeTransitionParseError::UnknownFormat {
+                field: RadiativeTransitionField::SpontaneousDecayRate,
+                value: String::from(values_beg.nth(RadiativeTransitionField::SpontaneousDecayRate as usize).unwrap()),
+                expected: ExpectedFieldValue::Float,
+            })
+        };
+
+        let (frequency, upper_energy, extra) = transition_columns(remainder(s, values.next()));
+
+        Ok(Self {
+            transition,
+            up,
+            low,
+            aeinst,
+            frequency,
+            upper_energy,
+            extra
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct NumberOfCollisionPartners(u32);
+
+impl core::str::FromStr for NumberOfCollisionPartners {
+    type Err = core::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<u32>() {
+            Ok(n) => Ok(Self(n)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum CollisionPartnerId {
+    #[default]
+    H2,
+    pH2,
+    oH2,
+    electrons,
+    HI,
+    He,
+    HII,
+    // Code beyond 1-7 with its name, empty when the code is not registered
+    Other(u32, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollisionPartnerIdParseError;
+
+impl core::fmt::Display for CollisionPartnerIdParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Unknown collision partner id (1=H2, 2=para-H2, 3=ortho-H2, 4=electrons, 5=H, 6=He, 7=H+)")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CollisionPartnerIdParseError {}
+
+impl core::convert::From<core::num::ParseIntError> for CollisionPartnerIdParseError {
+    fn from(_item: core::num::ParseIntError) -> Self {
+        Self
+    }
+}
+
+impl TryFrom<u32> for CollisionPartnerId {
+    type Error = CollisionPartnerIdParseError;
+
+    fn try_from(item: u32) -> Result<Self, Self::Error> {
+        match item {
+            1 => Ok(CollisionPartnerId::H2),
+            2 => Ok(CollisionPartnerId::pH2),
+            3 => Ok(CollisionPartnerId::oH2),
+            4 => Ok(CollisionPartnerId::electrons),
+            5 => Ok(CollisionPartnerId::HI),
+            6 => Ok(CollisionPartnerId::He),
+            7 => Ok(CollisionPartnerId::HII),
+            _ => Err(CollisionPartnerIdParseError),
+        }
+    }
+}
+
+impl core::fmt::Display for CollisionPartnerId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CollisionPartnerId::H2 => write!(f, "H2"),
+            CollisionPartnerId::pH2 => write!(f, "p-H2"),
+            CollisionPartnerId::oH2 => write!(f, "o-H2"),
+            CollisionPartnerId::electrons => write!(f, "e"),
+            CollisionPartnerId::HI => write!(f, "H"),
+            CollisionPartnerId::He => write!(f, "He"),
+            CollisionPartnerId::HII => write!(f, "H+"),
+            CollisionPartnerId::Other(code, name) if name.is_empty() => write!(f, "partner {}", code),
+            CollisionPartnerId::Other(_, name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct CollisionPartnerName<'a> {
+    name: CollisionPartnerId,
+    information: &'a str,
+}
+
+impl<'a> CollisionPartnerName<'a> {
+    fn parse(s: &'a str) -> Result<Self, CollisionPartnerIdParseError> {
+        let s = s.trim();
+        let (data_str, comment_str) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+
+        let name = match data_str.parse::<u32>() {
+            Ok(n) => CollisionPartnerId::from_code(n),
+            Err(_) => CollisionPartnerId::from_name(data_str).ok_or(CollisionPartnerIdParseError)?,
+        };
+
+        let information = comment_str.trim_matches(|c: char| c.is_whitespace() || c == '!');
+
+        Ok(Self { name, information })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct NumberOfCollisionalTransitions(u32);
+
+impl core::str::FromStr for NumberOfCollisionalTransitions {
+    type Err = core::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<u32>() {
+            Ok(n) => Ok(Self(n)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct NumberOfCollisionalTemperatures(u32);
+
+impl core::str::FromStr for NumberOfCollisionalTemperatures {
+    type Err = core::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<u32>() {
+            Ok(n) => Ok(Self(n)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// Number scanning for the collisional sections, which hold most of the numbers
+// of a datafile and dominate load time for large molecules.
+#[cfg(feature = "fast-float")]
+fn scan_f64(s: &str) -> Option<f64> {
+    lexical_core::parse(s.as_bytes()).ok()
+}
+
+#[cfg(not(feature = "fast-float"))]
+fn scan_f64(s: &str) -> Option<f64> {
+    s.parse().ok()
+}
+
+#[cfg(feature = "fast-float")]
+fn scan_u32(s: &str) -> Option<u32> {
+    lexical_core::parse(s.as_bytes()).ok()
+}
+
+#[cfg(not(feature = "fast-float"))]
+fn scan_u32(s: &str) -> Option<u32> {
+    s.parse().ok()
+}
+
+#[derive(Debug, PartialEq)]
+struct CollisionalTemperatures(Vec<f64>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollisionalTemperaturesParseError {
+    pub value: String,
+}
+
+impl core::fmt::Display for CollisionalTemperaturesParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Value `{}` has wrong type (should be floating point number)", self.value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CollisionalTemperaturesParseError {}
+
+impl core::str::FromStr for CollisionalTemperatures {
+    type Err = CollisionalTemperaturesParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result: Vec<f64> = vec!();
+
+        for i in s.split_whitespace() {
+            let item = match scan_f64(i) {
+                Some(n) => n,
+                None => return Err(Self::Err { value: String::from(i) }),
+            };
+
+            result.push(item);
+        }
+
+        Ok(Self(result))
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollisionalRates {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub rates: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionalRatesField {
+    Transition = 0,
+    UpperLevel,
+    LowerLevel,
+    RateCoefficients,
+}
+
+impl core::fmt::Display for CollisionalRatesField {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CollisionalRatesField::Transition => write!(f, "transition"),
+            CollisionalRatesField::UpperLevel => write!(f, "upper level"),
+            CollisionalRatesField::LowerLevel => write!(f, "lower level"),
+            CollisionalRatesField::RateCoefficients => write!(f, "rate coefficients [cm3 s-1]")
+        }
+    }
+}
+
+type CollisionalRatesParseError = SplittedFieldParseError<CollisionalRatesField>;
+
+impl core::str::FromStr for CollisionalRates {
+    type Err = CollisionalRatesParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = s.split_whitespace();
+        let mut values_beg = values.clone();
+
+        let transition = values
+            .next()
+            .ok_or(Self::Err::MissingField {
+                field: CollisionalRatesField::Transition,
+                expected: ExpectedFieldValue::Integer,
+            })
+            .map(scan_u32)?
+            .ok_or(());
+
+        let transition = match transition {
+            Ok(n) => n,
+            Err(_) => return Err(Self::Err::UnknownFormat {
+                field: CollisionalRatesField::Transition,
+                value: String::from(values_beg.nth(CollisionalRatesField::Transition as usize).unwrap()),
+                expected: ExpectedFieldValue::Integer,
+            })
+        };
+
+        let up = values
+            .next()
+            .ok_or(Self::Err::MissingField {
+                field: CollisionalRatesField::UpperLevel,
+                expected: ExpectedFieldValue::Integer,
+            })
+            .map(scan_u32)?
+            .ok_or(());
+
+        let up = match up {
+            Ok(n) => n,
+            Err(_) => return Err(Self::Err::UnknownFormat {
+                field: CollisionalRatesField::UpperLevel,
+                value: String::from(values_beg.nth(CollisionalRatesField::UpperLevel as usize).unwrap()),
+                expected: ExpectedFieldValue::Integer,
+            })
+        };
+
+        let low = values
+            .next()
+            .ok_or(Self::Err::MissingField {
+                field: CollisionalRatesField::LowerLevel,
+                expected: ExpectedFieldValue::Integer,
+            })
+            .map(scan_u32)?
+            .ok_or(());
+
+        let low = match low {
+            Ok(n) => n,
+            Err(_) => return Err(Self::Err::UnknownFormat {
+                field: CollisionalRatesField::LowerLevel,
+                value: String::from(values_beg.nth(CollisionalRatesField::LowerLevel as usize).unwrap()),
+                expected: ExpectedFieldValue::Integer,
+            })
+        };
+
+        let mut rates: Vec<f64> = vec!();
+        for i in values {
+            let item = match scan_f64(i) {
+                Some(n) => n,
+                None => return Err(Self::Err::UnknownFormat {
+                    field: CollisionalRatesField::RateCoefficients,
+                    value: String::from(i),
+                    expected: ExpectedFieldValue::Float,
+                })
+            };
+
+            rates.push(item);
+        }
+
+        Ok(Self {
+            transition,
+            up,
+            low,
+            rates
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_comment() {
+        let s = "! Comment      ";
+        let expected = Comment("Comment");
+
+        assert_eq!(
+            Comment::parse(s),
+            expected,
+            "Wrong result for default comment string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_u32() {
+        let s = "  65 ";
+        let expected_number_of_energy_levels = Ok(NumberOfEnergyLevels(65));
+        let expected_number_of_radiative_transitions = Ok(NumberOfRadiativeTransitions(65));
+        let expected_number_of_collision_partners = Ok(NumberOfCollisionPartners(65));
+        let expected_number_of_collisional_transitions = Ok(NumberOfCollisionalTransitions(65));
+        let expected_number_of_collisional_temperatures = Ok(NumberOfCollisionalTemperatures(65));
+
+        assert_eq!(
+            s.parse::<NumberOfEnergyLevels>(),
+            expected_number_of_energy_levels,
+            "Cannot parse u32 value from string `{}`",
+            s
+        );
+        assert_eq!(
+            s.parse::<NumberOfRadiativeTransitions>(),
+            expected_number_of_radiative_transitions,
+            "Cannot parse u32 value from string `{}`",
+            s
+        );
+        assert_eq!(
+            s.parse::<NumberOfCollisionPartners>(),
+            expected_number_of_collision_partners,
+            "Cannot parse u32 value from string `{}`",
+            s
+        );
+        assert_eq!(
+            s.parse::<NumberOfCollisionalTransitions>(),
+            expected_number_of_collisional_transitions,
+            "Cannot parse u32 value from string `{}`",
+            s
+        );
+        assert_eq!(
+            s.parse::<NumberOfCollisionalTemperatures>(),
+            expected_number_of_collisional_temperatures,
+            "Cannot parse u32 value from string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_element_name() {
+        let s = "  TEST ! Additional information  ";
+        let expected = ElementName {
+            name: "TEST",
+            information: "Additional information",
+        };
+
+        assert_eq!(
+            ElementName::parse(s),
+            expected,
+            "Wrong result for default format string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_element_weight() {
+        let s = " 32.3  ";
+        let expected = Ok(ElementWeight(32.3));
+
+        assert_eq!(
+            s.parse::<ElementWeight>(),
+            expected,
+            "Cannot parse f32 value from string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_energy_level() {
+        let s = "   32  32.4    1e-12   ! ' 3 5 6'";
+        let expected = Ok(EnergyLevel {
+            level: 32,
+            energy: 32.4,
+            stat_weight: 1e-12,
+            qnums: String::from("3 5 6")
+        });
+
+        assert_eq!(
+            s.parse::<EnergyLevel>(),
+            expected,
+            "Wrong result for default format string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_radiative_transition() {
+        let s = "  45 32 9  1e-14     345.32    Additional";
+        let expected = Ok(RadiativeTransition {
+            transition: 45,
+            up: 32,
+            low: 9,
+            aeinst: 1e-14,
+            frequency: Some(345.32),
+            upper_energy: None,
+            extra: String::from("Additional"),
+        });
+
+        assert_eq!(
+            s.parse::<RadiativeTransition>(),
+            expected,
+            "Wrong result for default format string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_collision_partner_name() {
+        let s = "2 ! Additional info ";
+        let expected = Ok(CollisionPartnerName {
+            name: CollisionPartnerId::pH2,
+            information: "Additional info"
+        });
+
+        assert_eq!(
+            CollisionPartnerName::parse(s),
+            expected,
+            "Wrong result for default format string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_collisional_temperatures() {
+        let s = "10.1 20.2  30.3     40.4   ";
+        let expected = Ok(CollisionalTemperatures(vec!(10.1, 20.2, 30.3, 40.4)));
+
+        assert_eq!(
+            s.parse::<CollisionalTemperatures>(),
+            expected,
+            "Wrong result for default format string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn parse_collisional_rates() {
+        let s = "65 42 13    12e-12 13e-13 14e-14";
+        let expected = Ok(CollisionalRates {
+            transition: 65,
+            up: 42,
+            low: 13,
+            rates: vec!(12e-12, 13e-13, 14e-14),
+        });
+
+        assert_eq!(
+            s.parse::<CollisionalRates>(),
+            expected,
+            "Wrong result for default format string `{}`",
+            s
+        );
+    }
+
+    #[test]
+    fn scan_numbers_like_std() {
+        for s in ["0", "0.0", "1.", ".5", "2.730", "1.2E-10", "3.4e+02", "-7.5e-3", "12345678"] {
+            assert_eq!(scan_f64(s), s.parse::<f64>().ok(), "Wrong result for `{}`", s);
+        }
+        for s in ["", "1,5", "e5", "1.2.3", "0x10", "1e"] {
+            assert_eq!(scan_f64(s), None, "Wrong result for `{}`", s);
+        }
+        assert_eq!(scan_u32("42"), Some(42), "Wrong result for `42`");
+        assert_eq!(scan_u32("4.2"), None, "Wrong result for `4.2`");
+    }
+
+    #[test]
+    fn parse_lamda_file_contents() -> Result<(), ParseError> {
+        let s = r#"!MOLECULE
+        O (neutral atom)
+        !MOLECULAR WEIGHT
+        16.0
+        !NUMBER OF ENERGY LEVELS
+        3
+        !LEVEL + ENERGIES(cm^-1) + WEIGHT + Qnum
+           1    0.000000000   5.0  3_P_2  ! 2S+1  L  J = 3 P 2
+           2  158.2687410     3.0  3_P_1  ! 2S+1  L  J = 3 P 1
+           3  226.9852492     1.0  3_P_0  ! 2S+1  L  J = 3 P 0
+        !NUMBER OF RADIATIVE TRANSITIONS
+        3
+        !TRANS + UP + LOW + EINSTEINA(s^-1) + FREQ(GHz) + E_u(K)
+            1     2     1   8.910E-05  4744.77749   227.712
+            2     3     1   1.340E-10  6804.84658   326.579
+            3     3     2   1.750E-05  2060.06909   326.579
+        !NUMBER OF COLL PARTNERS
+        6
+        !COLLISIONS BETWEEN
+        5 O + H  ! Lique et al. 2018, MNRAS 474, 2313 corrected, transmitted by M. Wolfire. T-points selected by E. Roueff
+        !NUMBER OF COLL TRANS
+        3
+        !NUMBER OF COLL TEMPS
+        19
+        !COLL TEMPS
+           10.000      20.000      30.000      40.000      60.000      80.000      110.00      160.00      220.00      320.00      450.00      630.00      890.00      1260.0      1780.0      2510.0      3550.0      5010.0      8000.0
+        !TRANS + UP + LOW + COLLRATES(cm^3 s^-1)
+            1     2     1   7.0204e-11  8.2028e-11  9.0584e-11  9.8459e-11  1.1421e-10  1.3039e-10  1.5488e-10  1.9425e-10  2.3747e-10  2.9974e-10  3.6597e-10  4.3801e-10  5.1576e-10  5.9551e-10  6.7682e-10  7.6338e-10  8.6209e-10  9.7867e-10  1.1762e-09
+            2     3     1   7.3118e-11  6.9519e-11  7.1053e-11  7.4232e-11  8.2569e-11  9.2191e-11  1.0783e-10  1.3515e-10  1.6763e-10  2.1768e-10  2.7340e-10  3.3530e-10  4.0291e-10  4.7408e-10  5.5063e-10  6.3692e-10  7.3695e-10  8.4933e-10  1.0160e-09
+            3     3     2   1.2258e-10  1.1282e-10  1.1049e-10  1.1007e-10  1.1069e-10  1.1194e-10  1.1472e-10  1.2189e-10  1.3383e-10  1.5806e-10  1.9211e-10  2.3911e-10  3.0442e-10  3.9226e-10  5.0606e-10  6.4819e-10  8.2175e-10  1.0238e-09  1.3390e-09
+        !COLLISIONS BETWEEN
+        6 O + He  ! Lique et al. 2018, MNRAS 474, 2313 corrected, transmitted by M. Wolfire. T-points selected by E. Roueff
+        !NUMBER OF COLL TRANS
+        3
+        !NUMBER OF COLL TEMPS
+        19
+        !COLL TEMPS
+           10.000      20.000      30.000      40.000      60.000      80.000      110.00      160.00      220.00      320.00      450.00      630.00      890.00      1260.0      1780.0      2510.0      3550.0      5010.0      8000.0
+        !TRANS + UP + LOW + COLLRATES(cm^3 s^-1)
+            1     2     1   1.6482e-11  1.8573e-11  2.1463e-11  2.4598e-11  3.0966e-11  3.7104e-11  4.5611e-11  5.7945e-11  7.0394e-11  8.7752e-11  1.0736e-10  1.3238e-10  1.6591e-10  2.0812e-10  2.5733e-10  3.1253e-10  3.7520e-10  4.4631e-10  5.5849e-10
+            2     3     1   2.7998e-11  2.9454e-11  3.3389e-11  3.8068e-11  4.8082e-11  5.8091e-11  7.2279e-11  9.3025e-11  1.1352e-10  1.4031e-10  1.6777e-10  2.0034e-10  2.4300e-10  2.9705e-10  3.6009e-10  4.2922e-10  5.0444e-10  5.8567e-10  7.0673e-10
+            3     3     2   1.0152e-13  1.6305e-13  2.5139e-13  3.6504e-13  6.6603e-13  1.0595e-12  1.8033e-12  3.3720e-12  5.6364e-12  9.9193e-12  1.5822e-11  2.3904e-11  3.4853e-11  4.9010e-11  6.6911e-11  8.9450e-11  1.1803e-10  1.5344e-10  2.1404e-10
+        !COLLISIONS BETWEEN
+        2 O + p-H2  ! Lique et al. 2018, MNRAS 474, 2313 corrected, transmitted by M. Wolfire. T-points selected by E. Roueff
+        !NUMBER OF COLL TRANS
+        3
+        !NUMBER OF COLL TEMPS
+        19
+        !COLL TEMPS
+           10.000      20.000      30.000      40.000      60.000      80.000      110.00      160.00      220.00      320.00      450.00      630.00      890.00      1260.0      1780.0      2510.0      3550.0      5010.0      8000.0
+        !TRANS + UP + LOW + COLLRATES(cm^3 s^-1)
+            1     2     1   1.1818e-10  1.2795e-10  1.3314e-10  1.3766e-10  1.4621e-10  1.5405e-10  1.6409e-10  1.7678e-10  1.8771e-10  2.0139e-10  2.1690e-10  2.3769e-10  2.6604e-10  3.0174e-10  3.4447e-10  3.9528e-10  4.5692e-10  5.2977e-10  6.3237e-10
+            2     3     1   8.1964e-11  1.0202e-10  1.1233e-10  1.2001e-10  1.3180e-10  1.4038e-10  1.4902e-10  1.5630e-10  1.5920e-10  1.6019e-10  1.6294e-10  1.7246e-10  1.9279e-10  2.2497e-10  2.6829e-10  3.2276e-10  3.9034e-10  4.7055e-10  5.8402e-10
+            3     3     2   7.9957e-14  1.5935e-13  2.6308e-13  3.9685e-13  6.6622e-13  8.6942e-13  1.0522e-12  1.1780e-12  1.2352e-12  1.3677e-12  1.7490e-12  2.6158e-12  4.2863e-12  7.0486e-12  1.1151e-11  1.6878e-11  2.4697e-11  3.4836e-11  5.0910e-11
+        !COLLISIONS BETWEEN
+        3 O + o-H2  ! Lique et al. 2018, MNRAS 474, 2313 corrected, transmitted by M. Wolfire. T-points selected by E. Roueff
+        !NUMBER OF COLL TRANS
+        3
+        !NUMBER OF COLL TEMPS
+        19
+        !COLL TEMPS
+           10.000      20.000      30.000      40.000      60.000      80.000      110.00      160.00      220.00      320.00      450.00      630.00      890.00      1260.0      1780.0      2510.0      3550.0      5010.0      8000.0
+        !TRANS + UP + LOW + COLLRATES(cm^3 s^-1)
+            1     2     1   1.3258e-10  1.3972e-10  1.4475e-10  1.4972e-10  1.5993e-10  1.7007e-10  1.8457e-10  2.0635e-10  2.2904e-10  2.6107e-10  2.9598e-10  3.3664e-10  3.8519e-10  4.4088e-10  5.0314e-10  5.7270e-10  6.5175e-10  7.3905e-10  8.5173e-10
+            2     3     1   6.5072e-11  7.6028e-11  8.2408e-11  8.7995e-11  9.8545e-11  1.0856e-10  1.2254e-10  1.4300e-10  1.6361e-10  1.9165e-10  2.2195e-10  2.5888e-10  3.0675e-10  3.6650e-10  4.3772e-10  5.2065e-10  6.1746e-10  7.2668e-10  8.7341e-10
+            3     3     2   2.6483e-12  3.0795e-12  3.3059e-12  3.4907e-12  3.8261e-12  4.1442e-12  4.6053e-12  5.3504e-12  6.2198e-12  7.6176e-12  9.3446e-12  1.1600e-11  1.4670e-11  1.8803e-11  2.4297e-11  3.1535e-11  4.1039e-11  5.2862e-11  7.0467e-11
+        !COLLISIONS BETWEEN
+        7 O + H+  !  computed from xsections of Spirko et al. J. Phys B 36, 1645, 2003 by E. Roueff, oct 2019 at same temperatures
+        !NUMBER OF COLL TRANS
+        3
+        !NUMBER OF COLL TEMPS
+        19
+        !COLL TEMPS
+           10.000      20.000      30.000      40.000      60.000      80.000      110.00      160.00      220.00      320.00      450.00      630.00      890.00      1260.0      1780.0      2510.0      3550.0      5010.0      8000.0
+        !TRANS + UP + LOW + COLLRATES(cm^3 s^-1)
+            1     2     1   2.4006e-11	4.4688e-11	6.4277e-11	8.3187e-11	1.1965e-10	1.5485e-10	2.0602e-10	2.8826e-10	3.8350e-10	5.3660e-10	7.2842e-10	9.8488e-10	1.3424e-09	1.8334e-09	2.4990e-09	3.4007e-09	4.6401e-09	6.3190e-09	9.6131e-09
+            2     3     1   4.1218e-12	8.8188e-12	1.3761e-11	1.8868e-11	2.9441e-11	4.0369e-11	5.7255e-11	8.6371e-11	1.2250e-10	1.8480e-10	2.6863e-10	3.8860e-10	5.6775e-10	8.3143e-10	1.2147e-09	1.7711e-09	2.5909e-09	3.7811e-09	6.3190e-09
+            3     3     2   1.7356e-10	2.2838e-10	2.6815e-10	3.0050e-10	3.5283e-10	3.9540e-10	4.4854e-10	5.2028e-10	5.9020e-10	6.8459e-10	7.8354e-10	8.9520e-10	1.0264e-09	1.1779e-09	1.3506e-09	1.5475e-09	1.7752e-09	2.0346e-09	2.4488e-09
+        !COLLISIONS BETWEEN
+        4 O + e  ! Bell et al. 1998, MNRAS, 293, L83
+        !NUMBER OF COLL TRANS
+        3
+        !NUMBER OF COLL TEMPS
+        5
+        !COLL TEMPS
+        50.0 100.0 500.0 1000. 3000.
+        !TRANS + UP + LOW + COLLRATES(cm^3 s^-1)
+            1     2     1   3.4E-10  3.6E-10  3.3E-10  3.1E-10  3.1E-10
+            2     3     1   3.9E-10  4.3E-10  4.3E-10  4.1E-10  4.2E-10
+            3     3     2   3.3E-13  7.7E-13  4.1E-12  6.5E-12  1.1E-11
+        !NOTES
+        ! A-values are from the NIST database.
+        ! Accurate transition frequencies measured by Zink et al. 1991, ApJ 371, L85.
+        ! Transition frequencies for the 17O and 18O isotopes can be found
+        ! in Brown, Evenson, Zink (1993, Phys. Rev. A, 48, 3761) and in
+        ! DeNatale et al. (1993, Phys. Rev. A, 48, 3757). The latter reference
+        ! presents more precise values.
+        "#;
+
+        let result = s.parse::<ElementData>();
+
+        match result {
+            Ok(ed) => {
+                assert_eq!(ed.radiative_transitions.len(), 3);
+                assert_eq!(ed.collision_partners.len(), 6);
+                Ok(())
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    #[test]
+    fn element_data_accessors() {
+        let s = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n2\n!LEVEL\n1 0.0 1.0 0\n2 3.845 3.0 1\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS\n1 2 1 7.2e-8\n!NUMBER OF COLL PARTNERS\n1\n!COLLISIONS BETWEEN\n\
+            2 CO-pH2\n!NUMBER OF COLL TRANS\n1\n!NUMBER OF COLL TEMPS\n2\n!COLL TEMPS\n10.0 20.0\n!TRANS\n1 2 1 3.0e-11 3.5e-11\n";
+        let mut data = s.parse::<ElementData>().unwrap();
+
+        assert_eq!((data.name(), data.weight()), ("CO", 28.0));
+        assert_eq!(data.energy_level(2).map(|l| l.qnums.as_str()), Some("1"), "Wrong result for level 2");
+        assert_eq!(data.radiative_transitions().iter().map(|t| t.aeinst).sum::<f64>(), 7.2e-8);
+
+        let partner = data.collision_partner(CollisionPartnerId::pH2).unwrap();
+        assert_eq!((partner.information(), partner.temperatures()), ("CO-pH2", &[10.0, 20.0][..]));
+        assert!(data.collision_partner(CollisionPartnerId::He).is_none());
+
+        data.collision_partners_mut()[0].rates_mut()[0].rates[1] = 4.0e-11;
+        assert_eq!(data.collision_partners()[0].rates()[0].rates, vec!(3.0e-11, 4.0e-11));
+
+        assert_eq!((data.level_energy(2), data.level(0), data.level(3)), (Some(3.845), None, None));
+        assert!(data.has_contiguous_levels());
+        data.energy_levels_mut()[0].level = 5;
+        assert_eq!(data.level(5).map(|l| l.energy), Some(0.0), "Wrong result for levels numbered out of order");
+        assert!(data.level(1).is_none() && !data.has_contiguous_levels());
+    }
+
+    #[test]
+    fn derived_transition_values() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let t = data.radiative_transition(1).unwrap();
+
+        let frequency = data.frequency(t).unwrap();
+        assert!((frequency - 4744.777).abs() < 1e-2, "Wrong result for frequency {}", frequency);
+        assert!((data.upper_energy(t).unwrap() - 227.712).abs() < 1e-2, "Wrong result for E_up");
+        assert!(data.radiative_transition(100).is_none());
+
+        let far_infrared = data.transitions_in_range(2000.0, 5000.0).iter().map(|t| t.transition).collect::<Vec<_>>();
+        assert_eq!(far_infrared, vec!(1, 3), "Wrong result for transitions in range");
+        assert!(data.transitions_in_range(5000.0, 2000.0).is_empty());
+    }
+
+    #[test]
+    fn find_transition_by_quantum_numbers() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        assert_eq!(data.find_transition("3_P_1-3_P_2").map(|t| t.transition), Some(1));
+        assert_eq!(data.find_transition("3_P_0 -> 3_P_1").map(|t| t.transition), Some(3));
+        assert!(data.find_transition("3_P_2-3_P_1").is_none(), "Wrong result for reversed levels");
+
+        let co = ElementData::builder("CO")
+            .weight(28.0)
+            .level(0.0, 1.0, "J=0")
+            .level(3.845, 3.0, "J=1")
+            .level(11.535, 5.0, "J=2")
+            .transition(2, 1, 7.2e-8)
+            .transition(3, 2, 6.9e-7)
+            .build()
+            .unwrap();
+        assert_eq!(co.find_transition("J=2-1").map(|t| t.transition), Some(2));
+        assert_eq!(co.find_transition("1-0").map(|t| t.transition), Some(1));
+
+        let h2co = ElementData::builder("H2CO")
+            .weight(30.0)
+            .level(0.0, 1.0, "1_-1")
+            .level(1.0, 3.0, "2_-1")
+            .transition(2, 1, 1.0e-6)
+            .build()
+            .unwrap();
+        assert_eq!(h2co.find_transition("2_-1-1_-1").map(|t| t.transition), Some(1));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trip() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let json = serde_json::to_string(&data).unwrap();
+        let cached = serde_json::from_str::<ElementData>(&json).unwrap();
+
+        assert_eq!(cached, data, "Wrong result for cached data");
+        assert!(json.contains("\"name\":\"HI\""), "Wrong collision partner in `{}`", json);
+    }
+
+    #[test]
+    fn parse_borrowed_lamda_file() -> Result<(), ParseError> {
+        let s = "!MOLECULE\nCO ! carbon monoxide\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n2\n\
+            !LEVEL + ENERGIES(cm^-1) + WEIGHT + J\n1 0.0 1.0 0\n2 3.845033413 3.0 1   ! J=1\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS + UP + LOW + EINSTEINA(s^-1) + FREQ(GHz)\n1 2 1 7.203e-08 115.2712018\n\
+            !NUMBER OF COLL PARTNERS\n1\n!COLLISIONS BETWEEN\n2 CO-pH2 from Yang et al. (2010)\n!NUMBER OF COLL TRANS\n1\n\
+            !NUMBER OF COLL TEMPS\n2\n!COLL TEMPS\n10.0 20.0\n!TRANS + UP + LOW + COLLRATES(cm^3 s^-1)\n1 2 1 3.3e-11 3.4e-11\n\
+            ! Reference: LAMDA\n";
+
+        let borrowed = ElementDataRef::parse(s)?;
+        let qnums = borrowed.energy_levels()[1].qnums;
+
+        assert_eq!(borrowed.name(), "CO");
+        assert_eq!(qnums, "1   ! J=1");
+        assert!(s.as_ptr() <= qnums.as_ptr() && qnums.as_ptr() < s[s.len()..].as_ptr(), "Quantum numbers are not borrowed");
+        assert_eq!(borrowed.comments(), &["Reference: LAMDA"]);
+        assert_eq!(borrowed.into_owned(), s.parse::<ElementData>()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_datafiles() {
+        let s = include_str!("../../data/oatom.dat");
+        let mut data = s.parse::<ElementData>().unwrap();
+        let partners = data.collision_partners.len();
+        let first = s.find("!NUMBER OF COLL PARTNERS").unwrap();
+        let levels_only = format!("{}!NUMBER OF COLL PARTNERS\n0\n", &s[..first]);
+        let mut other = levels_only.parse::<ElementData>().unwrap();
+        other.merge_collision_partner(CollisionPartnerData::new(
+            CollisionPartnerId::Other(8, String::from("D2")),
+            "",
+            vec!(10.0),
+            vec!(CollisionalRates { transition: 1, up: 2, low: 1, rates: vec!(1e-11) }),
+        )).unwrap();
+
+        data.merge(&other).unwrap();
+        assert_eq!(data.collision_partners.len(), partners + 1, "Wrong result for merged partners");
+        assert_eq!(
+            data.merge(&other),
+            Err(MergeError::DuplicateCollisionPartner { name: CollisionPartnerId::Other(8, String::from("D2")) }),
+        );
+
+        other.energy_levels[1].stat_weight += 1.0;
+        assert_eq!(data.merge(&other), Err(MergeError::LevelMismatch { level: 2 }));
+
+        other.energy_levels[1].stat_weight -= 1.0;
+        let levels = other.energy_levels.len();
+        other.energy_levels.truncate(2);
+        assert_eq!(data.merge(&other), Err(MergeError::LevelCountMismatch { levels, other: 2 }));
+    }
+
+    #[test]
+    fn parse_windows_and_spreadsheet_files() {
+        let s = include_str!("../../data/oatom.dat");
+        let expected = s.parse::<ElementData>().unwrap();
+
+        let variants = [
+            s.replace('\n', "\r\n"),
+            format!("\u{feff}{}", s),
+            s.lines().map(|l| l.split_whitespace().collect::<Vec<_>>().join("\t") + "\t\r\n").collect(),
+            format!("\u{feff}{}", s.replace(' ', "\t").replace('\n', "\r\n")),
+        ];
+        for variant in variants.iter() {
+            let data = variant.parse::<ElementData>().unwrap_or_else(|e| panic!("Cannot parse {:?}:\n{}", &variant[..40], e));
+            assert_eq!(data.name(), expected.name());
+            assert_eq!(data.energy_levels(), expected.energy_levels(), "Wrong result for levels of {:?}", &variant[..40]);
+            assert_eq!(data.radiative_transitions(), expected.radiative_transitions());
+            assert_eq!(data.collision_partners().len(), expected.collision_partners().len());
+            for (partner, expected) in data.collision_partners().iter().zip(expected.collision_partners()) {
+                assert_eq!((partner.name(), partner.temperatures(), partner.rates()), (expected.name(), expected.temperatures(), expected.rates()));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let s = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n3\n!LEVEL\n1 0.0 1.0\n2 3.845 x\n3 11.535 5.0\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n2\n!TRANS\n1 2 1 7.2e-8\n2 3 2\n!NUMBER OF COLL PARTNERS\ntwo\n";
+
+        let (data, errors) = ElementData::parse_lenient(s);
+
+        let levels = data.energy_levels().iter().map(|l| l.level).collect::<Vec<_>>();
+        assert_eq!(levels, vec!(1, 3), "Wrong result for skipped level");
+        assert_eq!(data.radiative_transitions().len(), 1);
+        let found = errors.iter().map(|e| (e.code(), e.diagnostic().line)).collect::<Vec<_>>();
+        assert_eq!(found, vec!(("unknown-item", 9), ("missing-field", 15), ("not-int", 17)));
+
+        let (strict, errors) = ElementData::parse_lenient(include_str!("../../data/oatom.dat"));
+        assert!(errors.is_empty());
+        assert_eq!(strict, include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn parse_error_source() {
+        use std::error::Error;
+
+        let s = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n1\n!LEVEL\n1 zero 1.0\n";
+        let e = s.parse::<ElementData>().unwrap_err();
+
+        let source = e.source().expect("No source for a field error");
+        assert_eq!(source.to_string(), "Energy level: Value `zero` from field `energy [cm-1]` has wrong type (should be floating point number)");
+        assert!(source.source().is_some_and(|e| e.is::<SplittedFieldParseError<EnergyLevelField>>()));
+        assert!(ParseError::end_of_input(0).source().is_none());
+
+        let boxed: Box<dyn Error> = Box::new(CollisionPartnerIdParseError);
+        assert!(boxed.to_string().starts_with("Unknown collision partner id"));
+    }
+}
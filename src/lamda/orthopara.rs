@@ -0,0 +1,147 @@
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::molecular::interpolate;
+
+use super::{CollisionPartnerData, CollisionPartnerId, CollisionalRates, ElementData, MergeError};
+
+// Ortho-to-para ratio of H2 for `ElementData::combine_ortho_para_h2`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrthoParaRatio {
+    Fixed(f64),
+    // Ratio in thermal equilibrium at each temperature of the rates, see
+    // `thermal_ortho_para_ratio`
+    #[cfg(feature = "std")]
+    Thermal,
+}
+
+impl OrthoParaRatio {
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub fn at(&self, temperature: f64) -> f64 {
+        match self {
+            OrthoParaRatio::Fixed(ratio) => *ratio,
+            #[cfg(feature = "std")]
+            OrthoParaRatio::Thermal => super::thermal_ortho_para_ratio(temperature),
+        }
+    }
+}
+
+impl core::fmt::Display for OrthoParaRatio {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OrthoParaRatio::Fixed(ratio) => write!(f, "{}", ratio),
+            #[cfg(feature = "std")]
+            OrthoParaRatio::Thermal => write!(f, "thermal"),
+        }
+    }
+}
+
+impl ElementData {
+    // Replaces the o-H2 and p-H2 partners with one H2 partner with rates
+    // (opr k_o + k_p) / (1 + opr). The rates of both are interpolated to
+    // their temperatures within the range both cover; a transition that only
+    // one of them lists has no rate for the other.
+    pub fn combine_ortho_para_h2(&mut self, ratio: OrthoParaRatio) -> Result<(), MergeError> {
+        if self.collision_partner(CollisionPartnerId::H2).is_some() {
+            return Err(MergeError::DuplicateCollisionPartner { name: CollisionPartnerId::H2 });
+        }
+        let partner = |name: CollisionPartnerId| self.collision_partner(name.clone()).ok_or(MergeError::MissingCollisionPartner { name });
+        let (ortho, para) = (partner(CollisionPartnerId::oH2)?, partner(CollisionPartnerId::pH2)?);
+
+        let range = |p: &CollisionPartnerData| {
+            let first = p.temperatures.iter().copied().fold(f64::INFINITY, f64::min);
+            let last = p.temperatures.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            (first, last)
+        };
+        let (first, last) = (range(ortho).0.max(range(para).0), range(ortho).1.min(range(para).1));
+        let mut temperatures = ortho.temperatures
+            .iter()
+            .chain(para.temperatures.iter())
+            .copied()
+            .filter(|&t| first <= t && t <= last)
+            .collect::<Vec<_>>();
+        temperatures.sort_by(f64::total_cmp);
+        temperatures.dedup();
+        if temperatures.is_empty() {
+            return Err(MergeError::NoCommonTemperatures);
+        }
+
+        let rate = |p: &CollisionPartnerData, up: u32, low: u32, t: f64| {
+            match p.rates.iter().find(|r| r.up == up && r.low == low) {
+                Some(r) if !r.rates.is_empty() => interpolate(&p.temperatures, &r.rates, t),
+                _ => 0.0,
+            }
+        };
+        let mut transitions: Vec<(u32, u32)> = vec!();
+        for r in para.rates.iter().chain(ortho.rates.iter()) {
+            if !transitions.contains(&(r.up, r.low)) {
+                transitions.push((r.up, r.low));
+            }
+        }
+        let rates = transitions
+            .into_iter()
+            .enumerate()
+            .map(|(i, (up, low))| CollisionalRates {
+                transition: i as u32 + 1,
+                up,
+                low,
+                rates: temperatures
+                    .iter()
+                    .map(|&t| {
+                        let opr = ratio.at(t);
+                        (opr * rate(ortho, up, low, t) + rate(para, up, low, t)) / (1.0 + opr)
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let information = format!("H2 from o-H2 and p-H2 with ortho-to-para ratio {}", ratio);
+        let h2 = CollisionPartnerData::new(CollisionPartnerId::H2, &information, temperatures, rates);
+        self.collision_partners.retain(|p| p.name != CollisionPartnerId::oH2 && p.name != CollisionPartnerId::pH2);
+        self.collision_partners.push(h2);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn combine_ortho_para_h2() {
+        let partner = |name, temperatures: Vec<f64>, rates: Vec<f64>| {
+            let rates = vec!(CollisionalRates { transition: 1, up: 2, low: 1, rates });
+            CollisionPartnerData::new(name, "", temperatures, rates)
+        };
+        let mut combined = ElementData::builder("CO")
+            .weight(28.0)
+            .level(0.0, 1.0, "0")
+            .level(3.845, 3.0, "1")
+            .collision_partner(partner(CollisionPartnerId::pH2, vec!(10.0, 20.0), vec!(1.0e-11, 2.0e-11)))
+            .collision_partner(partner(CollisionPartnerId::oH2, vec!(5.0, 15.0, 30.0), vec!(2.0e-11, 4.0e-11, 6.0e-11)))
+            .collision_partner(partner(CollisionPartnerId::He, vec!(10.0), vec!(1.0e-11)))
+            .build()
+            .unwrap();
+
+        combined.combine_ortho_para_h2(OrthoParaRatio::Fixed(3.0)).unwrap();
+        let names = combined.collision_partners().iter().map(|p| p.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!(CollisionPartnerId::He, CollisionPartnerId::H2));
+
+        let h2 = combined.collision_partner(CollisionPartnerId::H2).unwrap();
+        assert_eq!(h2.temperatures(), &[10.0, 15.0, 20.0][..], "Wrong result for the common grid");
+        let expected = [(3.0 * 3.0e-11 + 1.0e-11) / 4.0, (3.0 * 4.0e-11 + 1.5e-11) / 4.0, (3.0 * (4.0e-11 + 2.0e-11 / 3.0) + 2.0e-11) / 4.0];
+        for (found, expected) in h2.rates()[0].rates.iter().zip(expected) {
+            assert!((found - expected).abs() < 1e-24, "Wrong result for rates {:?}", h2.rates()[0].rates);
+        }
+
+        assert_eq!(
+            combined.combine_ortho_para_h2(OrthoParaRatio::Thermal),
+            Err(MergeError::DuplicateCollisionPartner { name: CollisionPartnerId::H2 }),
+        );
+        assert!((OrthoParaRatio::Thermal.at(50.0) - 0.297).abs() < 1e-3, "Wrong result for the thermal ratio");
+        assert_eq!(OrthoParaRatio::Thermal.at(1000.0), 3.0);
+    }
+}
@@ -0,0 +1,157 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+// Datafile listed on the molecular data pages of the LAMDA website, which
+// give each species in a table row with links to its datafiles, the number
+// of levels and the references of the data.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct MoleculeEntry {
+    pub species: String,
+    // File name as on the server, e.g. `co.dat`
+    pub file: String,
+    pub levels: Option<u32>,
+    pub references: String,
+}
+
+// Text of an HTML fragment without tags, with the common entities decoded
+// and runs of whitespace collapsed. Only line breaks separate words, so
+// that e.g. `HCO<sup>+</sup>` stays one name.
+fn text(html: &str) -> String {
+    let mut s = String::new();
+    let mut tag = None;
+    for (i, c) in html.char_indices() {
+        match (c, tag) {
+            ('<', None) => tag = Some(i + 1),
+            ('>', Some(start)) => {
+                if html[start..i].to_ascii_lowercase().starts_with("br") {
+                    s.push(' ');
+                }
+                tag = None;
+            }
+            (c, None) => s.push(c),
+            _ => (),
+        }
+    }
+    let s = s
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#43;", "+")
+        .replace("&amp;", "&");
+
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Link targets of the `.dat` files in an HTML fragment, as file names
+fn datafiles(html: &str) -> Vec<String> {
+    html.to_ascii_lowercase()
+        .match_indices("href=")
+        .filter_map(|(i, _)| {
+            let rest = &html[i + "href=".len()..];
+            let rest = rest.trim_start_matches(['"', '\'']);
+            let link = &rest[..rest.find(['"', '\'', ' ', '>']).unwrap_or(rest.len())];
+            let file = link.rsplit('/').next()?;
+            file.to_ascii_lowercase().ends_with(".dat").then(|| String::from(file))
+        })
+        .collect()
+}
+
+// Parts of `html` from each opening tag named one of `tags` to the next
+fn split_tags<'a>(html: &'a str, tags: &[&str]) -> Vec<&'a str> {
+    let lower = html.to_ascii_lowercase();
+    let starts = lower
+        .match_indices('<')
+        .map(|(i, _)| i)
+        .filter(|&i| {
+            let name = &lower[i + 1..];
+            let end = name.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(name.len());
+            tags.contains(&&name[..end])
+        })
+        .collect::<Vec<_>>();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &i)| &html[i..starts.get(n + 1).copied().unwrap_or(html.len())])
+        .collect()
+}
+
+// Datafiles listed in the tables of a LAMDA molecular data page. Columns
+// are found by the words `level` and `reference` in a header row; without
+// one, only the species and file names are read.
+pub fn parse_overview(html: &str) -> Vec<MoleculeEntry> {
+    let mut entries = vec!();
+    let mut levels_column = None;
+    let mut references_column = None;
+
+    for row in split_tags(html, &["tr"]) {
+        let row = &row[..row.to_ascii_lowercase().find("</table").unwrap_or(row.len())];
+        let cells = split_tags(row, &["td", "th"]);
+        let files = datafiles(row);
+
+        if files.is_empty() {
+            let header = cells.iter().map(|c| text(c).to_lowercase()).collect::<Vec<_>>();
+            if let Some(i) = header.iter().position(|h| h.contains("level")) {
+                levels_column = Some(i);
+                references_column = header.iter().position(|h| h.contains("reference"));
+            }
+            continue;
+        }
+
+        let cell = |i: Option<usize>| i.and_then(|i| cells.get(i)).map(|c| text(c)).unwrap_or_default();
+        let species = cells.iter().map(|c| text(c)).find(|t| !t.is_empty()).unwrap_or_default();
+        let levels = cell(levels_column)
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|n| !n.is_empty())
+            .and_then(|n| n.parse().ok());
+        let references = cell(references_column);
+
+        entries.extend(files.into_iter().map(|file| MoleculeEntry {
+            species: species.clone(),
+            file,
+            levels,
+            references: references.clone(),
+        }));
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_molecule_list() {
+        let html = r#"<html><body><h2>Molecular datafiles</h2>
+            <TABLE border=1>
+            <TR><TH>Molecule</TH><TH>Datafile</TH><TH>Levels</TH><TH>References</TH></TR>
+            <TR><TD>CO</TD><TD><A HREF="datafiles/co.dat">co.dat</A></TD><TD>41</TD><TD>Yang et al. 2010</TD></TR>
+            <tr><td>HCO<sup>+</sup></td><td><a href='datafiles/hco+@xpol.dat'>hco+@xpol.dat</a></td>
+                <td>31&nbsp;levels</td><td>Flower 1999 &amp; Schöier et al. 2005</td></tr>
+            <tr><td colspan=4>Last update 2020</td></tr>
+            </TABLE></body></html>"#;
+
+        let entries = parse_overview(html);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0],
+            MoleculeEntry {
+                species: String::from("CO"),
+                file: String::from("co.dat"),
+                levels: Some(41),
+                references: String::from("Yang et al. 2010"),
+            },
+            "Wrong result for the CO row",
+        );
+        assert_eq!((entries[1].species.as_str(), entries[1].file.as_str()), ("HCO+", "hco+@xpol.dat"));
+        assert_eq!(entries[1].levels, Some(31));
+        assert_eq!(entries[1].references, "Flower 1999 & Schöier et al. 2005");
+        assert_eq!(parse_overview("<p>no tables</p>"), vec!());
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+use super::basecol::collision_partner_from_name;
+use super::CollisionPartnerId;
+
+// Partner codes and names beyond the LAMDA ones, used by every parser of the
+// process. Without `std` there is no registry and other codes have no name.
+#[cfg(feature = "std")]
+static REGISTRY: std::sync::RwLock<Vec<(u32, String)>> = std::sync::RwLock::new(Vec::new());
+
+impl CollisionPartnerId {
+    // Code of the partner in datafiles, 1-7 for the LAMDA partners
+    pub fn code(&self) -> u32 {
+        match self {
+            CollisionPartnerId::H2 => 1,
+            CollisionPartnerId::pH2 => 2,
+            CollisionPartnerId::oH2 => 3,
+            CollisionPartnerId::electrons => 4,
+            CollisionPartnerId::HI => 5,
+            CollisionPartnerId::He => 6,
+            CollisionPartnerId::HII => 7,
+            CollisionPartnerId::Other(code, _) => *code,
+        }
+    }
+
+    // Partner of a datafile code; codes beyond 1-7 give `Other` with the
+    // name registered for them, if any
+    pub fn from_code(code: u32) -> Self {
+        let name = || registered(|(c, _)| *c == code).map(|(_, name)| name).unwrap_or_default();
+        Self::try_from(code).unwrap_or_else(|_| Self::Other(code, name()))
+    }
+
+    // Partner of a name such as `p-H2`, `e` or a registered one
+    pub fn from_name(name: &str) -> Option<Self> {
+        collision_partner_from_name(name).or_else(|| {
+            let (code, name) = registered(|(_, n)| n.eq_ignore_ascii_case(name))?;
+            Some(Self::Other(code, name))
+        })
+    }
+
+    // Registers `name` for partner `code`, so that datafiles may give either
+    // of them. Codes 1-7 belong to the LAMDA partners and are not changed.
+    #[cfg(feature = "std")]
+    pub fn register(code: u32, name: &str) -> bool {
+        if Self::try_from(code).is_ok() {
+            return false;
+        }
+
+        let mut registry = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+        registry.retain(|(c, _)| *c != code);
+        registry.push((code, String::from(name)));
+        true
+    }
+}
+
+#[cfg(feature = "std")]
+fn registered(find: impl Fn(&(u32, String)) -> bool) -> Option<(u32, String)> {
+    let registry = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+    registry.iter().find(|entry| find(entry)).cloned()
+}
+
+#[cfg(not(feature = "std"))]
+fn registered(_find: impl Fn(&(u32, String)) -> bool) -> Option<(u32, String)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::ElementData;
+
+    #[test]
+    fn other_collision_partners() {
+        assert_eq!(CollisionPartnerId::from_code(2), CollisionPartnerId::pH2);
+        assert_eq!(CollisionPartnerId::from_code(2).code(), 2);
+        assert!(!CollisionPartnerId::register(2, "D2"));
+
+        let s = include_str!("../../data/oatom.dat");
+        let first = s.find("!COLLISIONS BETWEEN\n").unwrap() + "!COLLISIONS BETWEEN\n".len();
+        let line = &s[first..first + s[first..].find('\n').unwrap()];
+        let code = line.split_whitespace().next().unwrap();
+
+        let data: ElementData = s.replacen(line, &line.replacen(code, "41", 1), 1).parse().unwrap();
+        assert_eq!(data.collision_partners()[0].name(), CollisionPartnerId::Other(41, String::new()));
+        assert!(data.to_string().contains("\n41 "), "Wrong result for written partner code");
+
+        assert!(CollisionPartnerId::register(42, "CO2"));
+        assert_eq!(CollisionPartnerId::from_name("co2"), Some(CollisionPartnerId::Other(42, String::from("CO2"))));
+        let data: ElementData = s.replacen(line, &line.replacen(code, "CO2", 1), 1).parse().unwrap();
+        assert_eq!(data.collision_partners()[0].name().code(), 42, "Wrong result for a registered partner name");
+        assert_eq!(CollisionPartnerId::from_code(42).to_string(), "CO2");
+
+        let unknown = s.replacen(line, &line.replacen(code, "XY", 1), 1);
+        assert!(matches!(unknown.parse::<ElementData>(), Err(crate::lamda::ParseError::UnknownCollisionPartner { .. })));
+    }
+}
@@ -0,0 +1,151 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+#[cfg(feature = "f64")]
+use uom::typenum::{N1, P1, Z0};
+
+use crate::constants::{PLANCK, SPEED_OF_LIGHT, WAVENUMBER_TO_KELVIN};
+use crate::molecular::{Extrapolation, ExtrapolationError};
+
+use super::{CollisionPartnerId, ElementData, RadiativeTransition};
+
+// Einstein B coefficient, cm2 erg-1 s-1 in CGS, which is g-1 s
+#[cfg(feature = "f64")]
+pub type EinsteinB = crate::iau::Quantity<crate::iau::IAUQ<Z0, N1, P1>, crate::iau::IAU<f64>, f64>;
+
+impl ElementData {
+    // Partition function Q(T) = sum g_i exp(-E_i / k T) over the levels in
+    // the file, at `temperature` K. Files truncate the level ladder, so Q is
+    // too small at temperatures that populate the highest levels.
+    pub fn partition_function(&self, temperature: f64) -> f64 {
+        self.energy_levels
+            .iter()
+            .map(|l| l.stat_weight * (-l.energy * WAVENUMBER_TO_KELVIN / temperature).exp())
+            .sum()
+    }
+
+    // Partition function at each of `temperatures`, K
+    pub fn partition_functions(&self, temperatures: &[f64]) -> Vec<f64> {
+        temperatures.iter().map(|&t| self.partition_function(t)).collect()
+    }
+
+    // Einstein B coefficients B_ul and B_lu of `transition` for intensities
+    // per unit frequency, cm2 erg-1 s-1: B_ul = A_ul c^2 / (2 h nu^3) and
+    // g_l B_lu = g_u B_ul.
+    pub fn einstein_b(&self, transition: &RadiativeTransition) -> Option<(f64, f64)> {
+        let frequency = self.frequency(transition)? * 1e9;
+        let (up, low) = (self.energy_level(transition.up)?, self.energy_level(transition.low)?);
+        let b_ul = transition.aeinst * SPEED_OF_LIGHT.powi(2) / (2.0 * PLANCK * frequency.powi(3));
+
+        Some((b_ul, b_ul * up.stat_weight / low.stat_weight))
+    }
+
+    // `einstein_b` as quantities
+    #[cfg(feature = "f64")]
+    pub fn einstein_b_quantities(&self, transition: &RadiativeTransition) -> Option<(EinsteinB, EinsteinB)> {
+        use crate::iau::f64::{Mass, Time};
+        use crate::iau::{mass::gram, time::second};
+
+        let cgs = |b: f64| Time::new::<second>(b) / Mass::new::<gram>(1.0);
+        self.einstein_b(transition).map(|(b_ul, b_lu)| (cgs(b_ul), cgs(b_lu)))
+    }
+
+    // Critical density n_crit = A_ul / sum_l K_ul(T) of each radiative
+    // transition, cm-3, keyed by transition number. The rates of all
+    // `partners` are added, as for a gas of equal parts of each; upper levels
+    // without collisional rates get an infinite critical density.
+    pub fn critical_densities(
+        &self,
+        tkin: f64,
+        partners: &[CollisionPartnerId],
+        extrapolation: Extrapolation,
+    ) -> Result<BTreeMap<u32, f64>, ExtrapolationError> {
+        let mut depopulation = BTreeMap::new();
+        for partner in partners {
+            for rate in self.collision_rates_with(partner.clone(), tkin, extrapolation)? {
+                *depopulation.entry(rate.up).or_insert(0.0) += rate.rate;
+            }
+        }
+
+        Ok(self.radiative_transitions
+            .iter()
+            .map(|t| match depopulation.get(&t.up) {
+                Some(&k) if k > 0.0 => (t.transition, t.aeinst / k),
+                _ => (t.transition, f64::INFINITY),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{CollisionPartnerData, CollisionalRates};
+
+    #[test]
+    fn partition_function() {
+        let data = ElementData::builder("CO")
+            .weight(28.0)
+            .level(0.0, 1.0, "0")
+            .level(3.845_033, 3.0, "1")
+            .build()
+            .unwrap();
+
+        let expected = 1.0 + 3.0 * (-3.845_033 * WAVENUMBER_TO_KELVIN / 10.0).exp();
+        assert!((data.partition_function(10.0) - expected).abs() < 1e-12, "Wrong result for Q(10 K)");
+        let grid = data.partition_functions(&[0.5, 1e6]);
+        assert!(grid[0] < 1.001 && (grid[1] - 4.0).abs() < 1e-3, "Wrong result for limits {:?}", grid);
+    }
+
+    #[test]
+    fn critical_densities() {
+        let rates = |k: f64| vec!(
+            CollisionalRates { transition: 1, up: 2, low: 1, rates: vec!(k) },
+            CollisionalRates { transition: 2, up: 3, low: 1, rates: vec!(k) },
+            CollisionalRates { transition: 3, up: 3, low: 2, rates: vec!(k) },
+        );
+        let data = ElementData::builder("CO")
+            .weight(28.0)
+            .level(0.0, 1.0, "0")
+            .level(3.845, 3.0, "1")
+            .level(11.535, 5.0, "2")
+            .transition(2, 1, 7.2e-8)
+            .transition(3, 2, 6.9e-7)
+            .collision_partner(CollisionPartnerData::new(CollisionPartnerId::pH2, "", vec!(10.0), rates(3.0e-11)))
+            .collision_partner(CollisionPartnerData::new(CollisionPartnerId::oH2, "", vec!(10.0), rates(1.0e-11)))
+            .build()
+            .unwrap();
+
+        let para = data.critical_densities(10.0, &[CollisionPartnerId::pH2], Extrapolation::Clamp).unwrap();
+        assert!((para[&1] - 2.4e3).abs() < 1e-9, "Wrong result for transition 1: {}", para[&1]);
+        assert!((para[&2] - 1.15e4).abs() < 1e-9, "Wrong result for transition 2: {}", para[&2]);
+
+        let both = [CollisionPartnerId::pH2, CollisionPartnerId::oH2];
+        assert!((data.critical_densities(10.0, &both, Extrapolation::Clamp).unwrap()[&1] - 1.8e3).abs() < 1e-9);
+        assert_eq!(data.critical_densities(10.0, &[], Extrapolation::Clamp).unwrap()[&1], f64::INFINITY);
+    }
+
+    #[test]
+    fn einstein_b() {
+        let data = ElementData::builder("CO")
+            .weight(28.0)
+            .level(0.0, 1.0, "0")
+            .level(3.845_033_413, 3.0, "1")
+            .transition(2, 1, 7.203e-8)
+            .build()
+            .unwrap();
+        let t = &data.radiative_transitions()[0];
+
+        let (b_ul, b_lu) = data.einstein_b(t).unwrap();
+        assert!((b_ul / 3.189_380e6 - 1.0).abs() < 1e-6, "Wrong result for B_ul {}", b_ul);
+        assert!((b_lu - 3.0 * b_ul).abs() < 1e-6, "Wrong result for B_lu {}", b_lu);
+
+        #[cfg(feature = "f64")]
+        {
+            let (quantity, _) = data.einstein_b_quantities(t).unwrap();
+            let per_gram = quantity * crate::iau::f64::Mass::new::<crate::iau::mass::gram>(1.0);
+            assert!((per_gram.get::<crate::iau::time::second>() / b_ul - 1.0).abs() < 1e-9);
+        }
+    }
+}
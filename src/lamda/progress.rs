@@ -0,0 +1,75 @@
+use crate::progress::{Progress, ProgressCallback};
+
+use super::Section;
+
+// Calls the callback of `parse_with_progress` at each section and about a
+// hundred times over the file, so that it costs little on large files. The
+// progress is in lines of the file; the current section is None for the
+// header and the closing notes.
+pub(super) struct Reporter<'p> {
+    callback: ProgressCallback<'p, Option<Section>>,
+    total: usize,
+    step: usize,
+    next: usize,
+    lines: usize,
+    section: Option<Section>,
+}
+
+impl<'p> Reporter<'p> {
+    pub(super) fn new(s: &str, callback: ProgressCallback<'p, Option<Section>>) -> Self {
+        let total = s.lines().count();
+        Self { callback, total, step: (total / 100).max(1), next: 0, lines: 0, section: None }
+    }
+
+    fn report(&mut self, lines: usize) {
+        self.lines = self.lines.max(lines).min(self.total);
+        (self.callback)(Progress { completed: self.lines, total: self.total, current: &self.section });
+        self.next = self.lines + self.step;
+    }
+
+    // At least `lines` lines are read and `section` starts
+    pub(super) fn section(&mut self, section: Option<Section>, lines: usize) {
+        self.section = section;
+        self.report(lines);
+    }
+
+    // Line `line_number`, counted from 0, is read
+    pub(super) fn line(&mut self, line_number: usize) {
+        match line_number + 1 >= self.next {
+            true => self.report(line_number + 1),
+            false => self.lines = self.lines.max(line_number + 1),
+        }
+    }
+
+    pub(super) fn finish(&mut self) {
+        self.section = None;
+        self.report(self.total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::ElementData;
+    use alloc::vec;
+    use std::sync::Mutex;
+
+    #[test]
+    fn parse_with_progress() {
+        let s = include_str!("../../data/oatom.dat");
+        let calls = Mutex::new(vec!());
+        let data = ElementData::parse_with_progress(s, &|p| calls.lock().unwrap().push((p.completed, p.total, *p.current)))
+            .unwrap();
+        assert_eq!(data, s.parse::<ElementData>().unwrap());
+
+        let calls = calls.into_inner().unwrap();
+        let total = s.lines().count();
+        assert!(calls.iter().all(|&(lines, n, _)| n == total && lines <= total));
+        assert!(calls.windows(2).all(|w| w[0].0 <= w[1].0), "Wrong result for line counts {:?}", calls);
+        assert_eq!(calls.last(), Some(&(total, total, None)));
+        let partner = calls.iter().find(|c| c.2 == Some(Section::CollisionPartner)).unwrap();
+        assert_eq!(s.lines().nth(partner.0).map(str::trim), Some("!COLLISIONS BETWEEN"), "Wrong result for section start");
+        assert!(calls.iter().filter(|c| c.2 == Some(Section::CollisionPartner)).count() > data.collision_partners().len());
+    }
+}
@@ -0,0 +1,81 @@
+use alloc::vec::Vec;
+
+use uom::si::energy::erg;
+use uom::si::f64::{Energy, Frequency, Mass, ThermodynamicTemperature, VolumeRate};
+use uom::si::frequency::{gigahertz, hertz};
+use uom::si::mass::dalton;
+use uom::si::thermodynamic_temperature::kelvin;
+use uom::si::volume_rate::cubic_centimeter_per_second;
+
+use crate::constants::{PLANCK, SPEED_OF_LIGHT};
+
+use super::{CollisionPartnerData, CollisionalRates, ElementData, EnergyLevel, RadiativeTransition};
+
+// Values of the datafiles as quantities, in the units LAMDA gives them. The
+// IAU system has no temperature dimension, so these are SI quantities.
+
+impl ElementData {
+    pub fn weight_quantity(&self) -> Mass {
+        Mass::new::<dalton>(self.weight)
+    }
+}
+
+impl EnergyLevel {
+    // Energy above the ground level, E = h c times the wavenumber in the file
+    pub fn energy_quantity(&self) -> Energy {
+        Energy::new::<erg>(PLANCK * SPEED_OF_LIGHT * self.energy)
+    }
+}
+
+impl RadiativeTransition {
+    pub fn einstein_a_quantity(&self) -> Frequency {
+        Frequency::new::<hertz>(self.aeinst)
+    }
+
+    // Frequency and E_u of the transition as given in the file, see
+    // `ElementData::frequency` for the ones computed from the levels
+    pub fn frequency_quantity(&self) -> Option<Frequency> {
+        self.frequency.map(Frequency::new::<gigahertz>)
+    }
+
+    pub fn upper_energy_quantity(&self) -> Option<ThermodynamicTemperature> {
+        self.upper_energy.map(ThermodynamicTemperature::new::<kelvin>)
+    }
+}
+
+impl CollisionPartnerData {
+    pub fn temperature_quantities(&self) -> Vec<ThermodynamicTemperature> {
+        self.temperatures.iter().map(|&t| ThermodynamicTemperature::new::<kelvin>(t)).collect()
+    }
+}
+
+impl CollisionalRates {
+    // Rate coefficients at the temperatures of the partner
+    pub fn rate_quantities(&self) -> Vec<VolumeRate> {
+        self.rates.iter().map(|&r| VolumeRate::new::<cubic_centimeter_per_second>(r)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use uom::si::energy::joule;
+    use uom::si::volume_rate::cubic_meter_per_second;
+
+    #[test]
+    fn quantities() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let level = &data.energy_levels()[1];
+        let transition = &data.radiative_transitions()[0];
+        let partner = &data.collision_partners()[0];
+
+        assert!((data.weight_quantity().get::<dalton>() - 16.0).abs() < 1e-12);
+        let energy = level.energy_quantity().get::<joule>();
+        assert!((energy / (level.energy * 1.986_445_857e-23) - 1.0).abs() < 1e-9, "Wrong result for level energy {}", energy);
+        assert_eq!(transition.einstein_a_quantity().get::<hertz>(), transition.aeinst);
+        assert_eq!(partner.temperature_quantities()[0].get::<kelvin>(), partner.temperatures()[0]);
+        let rate = partner.rates()[0].rate_quantities()[0].get::<cubic_meter_per_second>();
+        assert!((rate / (partner.rates()[0].rates[0] * 1e-6) - 1.0).abs() < 1e-12);
+    }
+}
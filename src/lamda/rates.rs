@@ -0,0 +1,169 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{CollisionPartnerData, CollisionPartnerId, ElementData};
+
+// Rate coefficients of one partner by (upper, lower) level instead of in
+// file order. Levels are positions in `energy_levels`, as in the arrays of
+// the `ndarray` feature. Where the file lists a level pair twice the first
+// line is kept, as RADEX does; lines with a level that is not a level of
+// the molecule are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateTable<'a> {
+    levels: usize,
+    temperatures: &'a [f64],
+    rates: BTreeMap<(usize, usize), &'a [f64]>,
+    skipped: usize,
+}
+
+// Rates at one temperature as a dense `levels` x `levels` matrix, row major
+// with the upper level as row, zero for level pairs without data
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateMatrix {
+    levels: usize,
+    values: Vec<f64>,
+}
+
+impl<'a> RateTable<'a> {
+    // Table of `partner` for the levels of `index`, level number to position
+    fn new(partner: &'a CollisionPartnerData, index: &BTreeMap<u32, usize>) -> Self {
+        let mut rates = BTreeMap::new();
+        let mut skipped = 0;
+        for cr in partner.rates.iter() {
+            match (index.get(&cr.up), index.get(&cr.low)) {
+                (Some(&u), Some(&l)) => {
+                    rates.entry((u, l)).or_insert(cr.rates.as_slice());
+                }
+                _ => skipped += 1,
+            }
+        }
+
+        Self { levels: index.len(), temperatures: &partner.temperatures, rates, skipped }
+    }
+
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    // Temperatures of the rates, K
+    pub fn temperatures(&self) -> &'a [f64] {
+        self.temperatures
+    }
+
+    // Rate lines left out because a level is not a level of the molecule
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    // Rates from `up` to `low` at every temperature, cm^3 s^-1
+    pub fn get(&self, up: usize, low: usize) -> Option<&'a [f64]> {
+        self.rates.get(&(up, low)).copied()
+    }
+
+    // Rate from `up` to `low` at the `temperature`-th temperature
+    pub fn rate(&self, up: usize, low: usize, temperature: usize) -> Option<f64> {
+        self.get(up, low)?.get(temperature).copied()
+    }
+
+    // Level pairs with their rates, by upper and then lower level
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &'a [f64])> + '_ {
+        self.rates.iter().map(|(&pair, &rates)| (pair, rates))
+    }
+
+    pub fn len(&self) -> usize {
+        self.rates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    // Dense matrix at the `temperature`-th temperature
+    pub fn matrix(&self, temperature: usize) -> Option<RateMatrix> {
+        if temperature >= self.temperatures.len() {
+            return None;
+        }
+
+        let n = self.levels;
+        let mut values = vec!(0.0; n * n);
+        for (&(u, l), rates) in self.rates.iter() {
+            if let Some(&rate) = rates.get(temperature) {
+                values[u * n + l] = rate;
+            }
+        }
+
+        Some(RateMatrix { levels: n, values })
+    }
+}
+
+impl RateMatrix {
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    // Rate from `up` to `low`
+    pub fn get(&self, up: usize, low: usize) -> Option<f64> {
+        if up >= self.levels || low >= self.levels {
+            return None;
+        }
+        Some(self.values[up * self.levels + low])
+    }
+
+    // Every level pair with its rate, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), f64)> + '_ {
+        let n = self.levels;
+        self.values.iter().enumerate().map(move |(i, &rate)| ((i / n, i % n), rate))
+    }
+
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+impl ElementData {
+    // Position in `energy_levels` of every level number
+    pub(crate) fn level_index(&self) -> BTreeMap<u32, usize> {
+        self.energy_levels.iter().enumerate().map(|(i, el)| (el.level, i)).collect()
+    }
+
+    pub fn rate_table(&self, name: CollisionPartnerId) -> Option<RateTable<'_>> {
+        let partner = self.collision_partner(name)?;
+        Some(RateTable::new(partner, &self.level_index()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn rate_tables() {
+        let mut data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let partner = &data.collision_partners()[0];
+        let (name, cr, last) = (partner.name(), partner.rates()[0].clone(), partner.temperatures().len() - 1);
+        let (u, l) = (cr.up as usize - 1, cr.low as usize - 1);
+
+        let table = data.rate_table(name.clone()).unwrap();
+        assert_eq!((table.levels(), table.len(), table.skipped()), (3, partner.rates().len(), 0));
+        assert_eq!(table.get(u, l), Some(cr.rates.as_slice()));
+        assert_eq!(table.rate(u, l, 1), Some(cr.rates[1]));
+        assert_eq!(table.get(l, u), None, "Wrong result for an upward pair");
+        assert!(table.iter().map(|(pair, _)| pair).collect::<Vec<_>>().windows(2).all(|w| w[0] < w[1]));
+
+        let matrix = table.matrix(last).unwrap();
+        assert_eq!(matrix.as_slice().len(), 9);
+        assert_eq!(matrix.get(u, l), Some(cr.rates[last]), "Wrong result for dense matrix");
+        assert_eq!(matrix.get(l, u), Some(0.0));
+        assert_eq!(matrix.get(3, 0), None);
+        assert_eq!(matrix.iter().find(|&(pair, _)| pair == (u, l)).map(|(_, rate)| rate), Some(cr.rates[last]));
+        assert_eq!(table.matrix(last + 1), None);
+
+        // A level beyond the molecule does not size the table
+        data.collision_partners_mut()[0].rates_mut()[0].up = 4_000_000;
+        let table = data.rate_table(name).unwrap();
+        assert_eq!((table.levels(), table.skipped(), table.get(u, l)), (3, 1, None));
+        assert_eq!(table.matrix(0).unwrap().as_slice().len(), 9);
+    }
+}
@@ -0,0 +1,143 @@
+use alloc::format;
+use alloc::string::String;
+
+use super::{
+    CollisionPartnerData, Diagnostics, ElementData, ElementDataRef, EnergyLevelRef, FormatFeatures, Lines,
+    ParseError, ParseLimits, RadiativeTransitionRef, Span,
+};
+
+// Part of a datafile that can be parsed on its own, from its leading comment
+// on, e.g. `!NUMBER OF ENERGY LEVELS` or `!COLLISIONS BETWEEN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    EnergyLevels,
+    RadiativeTransitions,
+    // Replaces the partner with the id given in the block, or adds it
+    CollisionPartner,
+}
+
+// Only blank lines and comments may follow a section.
+fn check_end(lines: Lines<'_>, section: Section) -> Result<(), ParseError> {
+    for (line_number, line) in lines.filter(|el| !el.1.trim().is_empty()) {
+        if !line.trim().starts_with('!') {
+            return Err(ParseError::WrongCommentFormat {
+                line_number,
+                span: Span::in_line(0, line.len().min(1)),
+                line: String::from(line),
+                note: format!("{:?} section was read, only comments should be left", section),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+impl ElementData {
+    pub fn reparse_section(&mut self, section: Section, s: &str) -> Result<(), ParseError> {
+        self.reparse_section_with_diagnostics(section, s, &mut Diagnostics::new())
+    }
+
+    // Parses `s`, one section as written in a datafile, and patches it into
+    // the data without parsing the rest of the file again. Line numbers in
+    // errors and diagnostics count from the start of `s`. Nothing is changed
+    // if `s` does not parse.
+    pub fn reparse_section_with_diagnostics(
+        &mut self,
+        section: Section,
+        s: &str,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<(), ParseError> {
+        self.patch_section(section, s, diagnostics).map_err(|e| e.locate(s))
+    }
+
+    fn patch_section(&mut self, section: Section, s: &str, diagnostics: &mut Diagnostics) -> Result<(), ParseError> {
+        let mut lines = s.lines().enumerate();
+        let mut line = (0, "");
+
+        match section {
+            Section::EnergyLevels => {
+                let (energy_levels, header) = ElementDataRef::parse_energy_levels(&mut lines, &mut line, &ParseLimits::default(), None, None)?;
+                check_end(lines, section)?;
+
+                let levels = ElementDataRef { energy_levels: energy_levels.clone(), ..Default::default() };
+                let detected = FormatFeatures::detect(header, &levels);
+                self.features.quantum_numbers = detected.quantum_numbers;
+                self.features.hyperfine = detected.hyperfine;
+                self.energy_levels = energy_levels.into_iter().map(EnergyLevelRef::into_owned).collect();
+            }
+            Section::RadiativeTransitions => {
+                let radiative_transitions = ElementDataRef::parse_radiative_transitions(&mut lines, &mut line, &ParseLimits::default(), None, None)?;
+                check_end(lines, section)?;
+
+                let transitions =
+                    ElementDataRef { radiative_transitions: radiative_transitions.clone(), ..Default::default() };
+                let detected = FormatFeatures::detect("", &transitions);
+                self.features.transition_frequencies = detected.transition_frequencies;
+                self.radiative_transitions =
+                    radiative_transitions.into_iter().map(RadiativeTransitionRef::into_owned).collect();
+            }
+            Section::CollisionPartner => {
+                let partner = ElementDataRef::parse_collision_partner(&mut lines, &mut line, &ParseLimits::default(), None, diagnostics, None)?;
+                check_end(lines, section)?;
+
+                let partner = CollisionPartnerData {
+                    name: partner.name,
+                    information: String::from(partner.information),
+                    temperatures: partner.temperatures,
+                    rates: partner.rates,
+                };
+                match self.collision_partners.iter_mut().find(|cp| cp.name == partner.name) {
+                    Some(existing) => *existing = partner,
+                    None => self.collision_partners.push(partner),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::CollisionPartnerId;
+
+    const DATA: &str = "!MOLECULE\nCO\n!MOLECULAR WEIGHT\n28.0\n!NUMBER OF ENERGY LEVELS\n2\n!LEVEL + ENERGIES(cm^-1) + WEIGHT\n\
+        1 0.0 1.0\n2 3.845 3.0\n!NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS + UP + LOW + EINSTEINA(s^-1)\n\
+        1 2 1 7.2e-8\n!NUMBER OF COLL PARTNERS\n1\n!COLLISIONS BETWEEN\n2 CO-pH2\n!NUMBER OF COLL TRANS\n1\n\
+        !NUMBER OF COLL TEMPS\n2\n!COLL TEMPS\n10.0 20.0\n!TRANS + UP + LOW + COLLRATES(cm^3 s^-1)\n1 2 1 3.0e-11 3.5e-11\n";
+
+    #[test]
+    fn reparse_collision_partner() {
+        let mut data: ElementData = DATA.parse().unwrap();
+        let rates = "!COLLISIONS BETWEEN\n2 CO-pH2 new rates\n!NUMBER OF COLL TRANS\n1\n!NUMBER OF COLL TEMPS\n3\n\
+            !COLL TEMPS\n10.0 20.0 30.0\n!TRANS + UP + LOW + COLLRATES(cm^3 s^-1)\n1 2 1 3.1e-11 3.6e-11 4.0e-11\n";
+
+        data.reparse_section(Section::CollisionPartner, rates).unwrap();
+        assert_eq!(data.collision_partners.len(), 1, "Wrong result for replaced partner");
+        assert_eq!(data.collision_partners[0].temperatures, vec!(10.0, 20.0, 30.0));
+        assert_eq!(data.collision_partners[0].rates[0].rates[2], 4.0e-11);
+
+        data.reparse_section(Section::CollisionPartner, &rates.replace("\n2 CO-pH2", "\n3 CO-oH2")).unwrap();
+        assert_eq!(data.collision_partners[1].name, CollisionPartnerId::oH2, "Wrong result for added partner");
+    }
+
+    #[test]
+    fn reparse_levels_and_transitions() {
+        let mut data: ElementData = DATA.parse().unwrap();
+        let transitions = "!NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS + UP + LOW + EINSTEINA(s^-1) + FREQ(GHz) + E_u(K)\n\
+            1 2 1 7.203e-8 115.2712 5.53\n";
+
+        data.reparse_section(Section::RadiativeTransitions, transitions).unwrap();
+        assert_eq!(data.radiative_transitions[0].aeinst, 7.203e-8);
+        assert!(data.features().transition_frequencies, "Wrong result for detected frequencies");
+
+        let broken = "!NUMBER OF ENERGY LEVELS\n2\n!LEVEL + ENERGIES(cm^-1) + WEIGHT\n1 0.0 1.0\n2 3.845 3.0\n3 11.535 5.0\n";
+        assert!(matches!(
+            data.reparse_section(Section::EnergyLevels, broken),
+            Err(ParseError::WrongCommentFormat { line_number: 5, .. })
+        ));
+        assert_eq!(data.energy_levels.len(), 2, "Wrong result for energy levels after a failed re-parse");
+    }
+}
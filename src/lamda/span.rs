@@ -0,0 +1,157 @@
+use alloc::format;
+use alloc::string::String;
+
+use super::ParseError;
+
+// Place of an error in the source it was parsed from, on the line of its
+// `line_number`. `column` is 1-based like that of `Diagnostic`; `column`,
+// `len` and `byte_offset` count bytes, so that
+// `&source[byte_offset..byte_offset + len]` is the part of the source the
+// error is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Span {
+    pub column: usize,
+    pub len: usize,
+    pub byte_offset: usize,
+}
+
+impl Span {
+    // `len` bytes from byte `column` of the line, counted from 0 as the
+    // parsers do. `byte_offset` counts from the start of the line until the
+    // parse function places the line in its input, see `locate`.
+    pub(crate) fn in_line(column: usize, len: usize) -> Self {
+        Span { column: column + 1, len, byte_offset: column }
+    }
+}
+
+impl ParseError {
+    pub(crate) fn end_of_input(line_number: usize) -> Self {
+        ParseError::NotEnoughInput { line_number, span: Span::in_line(0, 0) }
+    }
+
+    // Span of the error in the input it was parsed from. Errors past the end
+    // of the input have an empty span at its end.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::NotEnoughInput { span, .. }
+            | ParseError::WrongCommentFormat { span, .. }
+            | ParseError::MissingField { span, .. }
+            | ParseError::NotFloat { span, .. }
+            | ParseError::NotInt { span, .. }
+            | ParseError::UnknownItem { span, .. }
+            | ParseError::UnknownCollisionPartner { span, .. }
+            | ParseError::InvalidEncoding { span, .. }
+            | ParseError::LimitExceeded { span, .. } => *span,
+        }
+    }
+
+    // Line of the error, counted from 0
+    pub(crate) fn line_number(&self) -> usize {
+        match self {
+            ParseError::NotEnoughInput { line_number, .. }
+            | ParseError::WrongCommentFormat { line_number, .. }
+            | ParseError::MissingField { line_number, .. }
+            | ParseError::NotFloat { line_number, .. }
+            | ParseError::NotInt { line_number, .. }
+            | ParseError::UnknownItem { line_number, .. }
+            | ParseError::UnknownCollisionPartner { line_number, .. }
+            | ParseError::InvalidEncoding { line_number, .. }
+            | ParseError::LimitExceeded { line_number, .. } => *line_number,
+        }
+    }
+
+    pub(crate) fn span_mut(&mut self) -> &mut Span {
+        match self {
+            ParseError::NotEnoughInput { span, .. }
+            | ParseError::WrongCommentFormat { span, .. }
+            | ParseError::MissingField { span, .. }
+            | ParseError::NotFloat { span, .. }
+            | ParseError::NotInt { span, .. }
+            | ParseError::UnknownItem { span, .. }
+            | ParseError::UnknownCollisionPartner { span, .. }
+            | ParseError::InvalidEncoding { span, .. }
+            | ParseError::LimitExceeded { span, .. } => span,
+        }
+    }
+
+    // Moves the span of an error found in `source` from the start of its
+    // line to its place in `source`
+    pub(crate) fn locate(mut self, source: &str) -> Self {
+        let line_number = self.line_number();
+        let bom = match source.starts_with('\u{feff}') && line_number == 0 {
+            true => '\u{feff}'.len_utf8(),
+            false => 0,
+        };
+        self.span_mut().byte_offset += bom + source
+            .split_inclusive('\n')
+            .take(line_number)
+            .map(str::len)
+            .sum::<usize>();
+
+        self
+    }
+
+    // The error as compilers show them, with its line of `source` and the
+    // span marked below it:
+    //
+    //   error[not-int]: Expected integer
+    //     --> 6:1
+    //      |
+    //    6 | 4.5
+    //      | ^^^
+    //      = help: Write the value as a whole number without a decimal point
+    pub fn render(&self, source: &str) -> String {
+        let d = self.diagnostic();
+        let span = self.span();
+        let text = source[span.byte_offset + 1 - span.column..].lines().next().unwrap_or("");
+        let number = format!("{}", d.line);
+        let width = number.len();
+
+        let mut s = format!("error[{}]: {}\n", d.code, d.message);
+        s.push_str(&format!("{:>width$}--> {}:{}\n", " ", d.line, span.column));
+        s.push_str(&format!("{:>width$} |\n", " "));
+        s.push_str(&format!("{} | {}\n", number, text));
+        s.push_str(&format!("{:>width$} | {:>skip$}{:^<len$}\n", " ", "", "^", skip = span.column - 1, len = span.len.max(1)));
+        if let Some(help) = d.suggestion {
+            s.push_str(&format!("{:>width$} = help: {}\n", " ", help));
+        }
+
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::ElementData;
+
+    #[test]
+    fn error_span() {
+        let s = include_str!("../../data/oatom.dat");
+        let first = s.find("!NUMBER OF ENERGY LEVELS\n").unwrap() + "!NUMBER OF ENERGY LEVELS\n".len();
+        let line = &s[first..first + s[first..].find('\n').unwrap()];
+        let broken = s.replacen(line, "4.5", 1);
+
+        let e = broken.parse::<ElementData>().unwrap_err();
+        let span = e.span();
+        assert_eq!(e.line_number(), s[..first].lines().count());
+        assert_eq!((span.column, span.byte_offset), (1, first));
+        assert_eq!(&broken[span.byte_offset..span.byte_offset + span.len], "4.5", "Wrong result for span");
+
+        let rendered = e.render(&broken);
+        assert!(rendered.starts_with("error[not-int]: Expected integer\n"));
+        assert!(rendered.contains(&format!("{} | 4.5\n", e.line_number() + 1)));
+        assert!(rendered.contains(" | ^^^\n"), "Wrong result for rendered error:\n{}", rendered);
+
+        #[cfg(feature = "std")]
+        match ElementData::from_reader(broken.as_bytes(), crate::lamda::Decoding::Strict) {
+            Err(crate::lamda::ReadError::Parse(e)) => assert_eq!(e.span(), span, "Wrong result for read span"),
+            other => panic!("Wrong result for read error: {:?}", other.map(|_| ())),
+        }
+
+        let end = s[..first].parse::<ElementData>().unwrap_err().span();
+        assert_eq!((end.byte_offset, end.len), (first, 0), "Wrong result for end of input span");
+    }
+}
@@ -0,0 +1,144 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::ElementData;
+
+// Isotope replacing the main one of `element`, e.g. 13C or D, which is 2H
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Isotope {
+    pub mass: u32,
+    pub element: String,
+}
+
+// Species of a molecule name such as `13CO`, `DCO+` or `o-H2CO`: the formula
+// of the main isotopologue, the isotopes that differ from it, the charge and
+// the ortho/para or A/E form the name gives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Species {
+    pub parent: String,
+    pub substitutions: Vec<Isotope>,
+    pub charge: i32,
+    pub form: Option<String>,
+}
+
+fn digits(chars: &mut core::iter::Peekable<core::str::Chars>) -> String {
+    let mut n = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        n.push(c);
+    }
+    n
+}
+
+// Element, count and mass number of each atom group of a formula. Names put
+// mass numbers before the element, so that in `H13CN` or `C18O` a number of
+// two or more digits before an element is its mass number, not a count.
+// Mass numbers may also follow the element between dashes, as in `C-13-O`.
+pub(super) fn atoms(formula: &str) -> Option<Vec<(String, u32, Option<u32>)>> {
+    let mut atoms = vec!();
+    let mut chars = formula.chars().peekable();
+    let mut mass = digits(&mut chars).parse().ok();
+    while chars.peek().is_some() {
+        let mut element = String::from(chars.next().filter(char::is_ascii_uppercase)?);
+        element.extend(chars.next_if(char::is_ascii_lowercase));
+        if chars.next_if_eq(&'-').is_some() {
+            mass = Some(digits(&mut chars).parse().ok()?);
+            chars.next_if_eq(&'-');
+        }
+        let n = digits(&mut chars);
+        let (count, next) = match n.len() >= 2 && chars.peek().is_some() {
+            true => (1, n.parse().ok()),
+            false => (n.parse().unwrap_or(1), None),
+        };
+        atoms.push((element, count, mass));
+        mass = next;
+    }
+
+    Some(atoms)
+}
+
+impl Species {
+    // None for names that are not a formula, e.g. of a test molecule
+    pub fn parse(name: &str) -> Option<Self> {
+        let (form, name) = match name.split_once('-') {
+            Some((prefix @ ("o" | "p" | "ortho" | "para"), rest)) => (Some(&prefix[..1]), rest),
+            _ => (None, name),
+        };
+        let (name, form) = match name.rsplit_once('-') {
+            Some((rest, suffix @ ("A" | "E"))) if form.is_none() => (rest, Some(suffix)),
+            _ => (name, form),
+        };
+
+        let formula = name.trim_end_matches(['+', '-']);
+        let sign = &name[formula.len()..];
+        let charge = match sign.chars().all(|c| c == '+') {
+            true => sign.len() as i32,
+            false if sign.chars().all(|c| c == '-') => -(sign.len() as i32),
+            false => return None,
+        };
+
+        let mut parent: Vec<(String, u32)> = vec!();
+        let mut substitutions = vec!();
+        for (element, count, mass) in atoms(formula)? {
+            let (element, mass) = match element.as_str() {
+                "D" => (String::from("H"), Some(2)),
+                _ => (element, mass),
+            };
+            if let Some(mass) = mass {
+                substitutions.extend((0..count).map(|_| Isotope { mass, element: element.clone() }));
+            }
+            match parent.last_mut() {
+                Some((last, n)) if *last == element => *n += count,
+                _ => parent.push((element, count)),
+            }
+        }
+        let mut formula = parent
+            .into_iter()
+            .map(|(element, n)| match n {
+                1 => element,
+                n => format!("{}{}", element, n),
+            })
+            .collect::<String>();
+        formula.push_str(sign);
+
+        Some(Self { parent: formula, substitutions, charge, form: form.map(String::from) })
+    }
+
+    pub fn is_main_isotopologue(&self) -> bool {
+        self.substitutions.is_empty()
+    }
+}
+
+impl ElementData {
+    pub fn species(&self) -> Option<Species> {
+        Species::parse(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_species() {
+        let parents = ["CO", "13CO", "C18O", "H13CN", "DCO+", "HDO", "NH2D", "o-H2CO", "CH3OH-E", "HCS+", "e-CH3OH"]
+            .map(|name| Species::parse(name).map(|s| s.parent));
+        assert_eq!(
+            parents.map(|p| p.unwrap_or_default()),
+            ["CO", "CO", "CO", "HCN", "HCO+", "H2O", "NH3", "H2CO", "CH3OH", "HCS+", ""],
+            "Wrong result for parent molecules",
+        );
+
+        let s = Species::parse("D2CO").unwrap();
+        assert_eq!(s.substitutions, vec!(Isotope { mass: 2, element: String::from("H") }; 2));
+        assert_eq!(Species::parse("13CO").unwrap().substitutions, vec!(Isotope { mass: 13, element: String::from("C") }));
+        assert_eq!((Species::parse("HCO+").unwrap().charge, Species::parse("CN-").unwrap().charge), (1, -1));
+        assert_eq!(Species::parse("p-NH3").unwrap().form.as_deref(), Some("p"));
+        assert!(Species::parse("SiO").unwrap().is_main_isotopologue());
+        assert_eq!(Species::parse("HC-13-N"), Species::parse("H13CN"), "Wrong result for a dashed mass number");
+    }
+}
@@ -0,0 +1,268 @@
+use std::io::BufRead;
+
+use super::{
+    decode, shift, CollisionPartnerData, Decoding, Diagnostics, ElementData, ElementDataRef, ElementName,
+    EnergyLevelRef, FormatFeatures, Lines, ParseError, ParseLimits, RadiativeTransitionRef,
+};
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(std::io::Error),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read datafile: {}", e),
+            Self::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for ReadError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+// Lines of one section, the number of the first of them and the byte
+// offset in the input of each, followed by the offset of the section end
+struct Chunk {
+    first: usize,
+    lines: String,
+    offsets: Vec<usize>,
+}
+
+impl Chunk {
+    fn new(first: usize) -> Self {
+        Chunk { first, lines: String::new(), offsets: vec!() }
+    }
+
+    fn push(&mut self, (offset, line): (usize, String)) {
+        self.lines.push_str(&line);
+        self.lines.push('\n');
+        self.offsets.push(offset);
+    }
+
+    fn parse<'a, T>(
+        &'a self,
+        parse: impl FnOnce(&mut Lines<'a>, &mut (usize, &'a str)) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let end = self.offsets.last().copied().unwrap_or(0);
+        if self.lines.is_empty() {
+            return Err(shift(ParseError::end_of_input(0), self.first, end));
+        }
+
+        let mut lines = self.lines.lines().enumerate();
+        parse(&mut lines, &mut (0, "")).map_err(|e| {
+            let offset = self.offsets.get(e.line_number()).copied().unwrap_or(end);
+            shift(e, self.first, offset)
+        })
+    }
+}
+
+struct SectionReader<R> {
+    reader: R,
+    decoding: Decoding,
+    line_number: usize,
+    // Bytes read so far
+    offset: usize,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> SectionReader<R> {
+    // The next line with the byte offset of its start
+    fn line(&mut self) -> Result<Option<(usize, String)>, ReadError> {
+        self.buf.clear();
+        let read = self.reader.read_until(b'\n', &mut self.buf)?;
+        if read == 0 {
+            return Ok(None);
+        }
+
+        let mut start = self.offset;
+        self.offset += read;
+        let line = decode(&self.buf, self.decoding).map_err(|e| shift(e, self.line_number, start))?;
+        let line = match self.line_number {
+            0 => match line.strip_prefix('\u{feff}') {
+                Some(rest) => {
+                    start += '\u{feff}'.len_utf8();
+                    rest
+                }
+                None => &line,
+            },
+            _ => &line,
+        };
+        self.line_number += 1;
+
+        Ok(Some((start, String::from(line.trim_end_matches(['\n', '\r'])))))
+    }
+
+    // The next `head` lines, followed by as many lines as the count on line
+    // `count_at` of them gives. Stops early at the end of the input and
+    // leaves the error to the section parser, as for a bad count.
+    fn section(&mut self, head: usize, count_at: Option<usize>) -> Result<Chunk, ReadError> {
+        let mut chunk = Chunk::new(self.line_number);
+        let mut body = 0;
+
+        for i in 0..head {
+            let line = match self.line()? {
+                Some(line) => line,
+                None => break,
+            };
+            if count_at == Some(i) {
+                body = line.1.trim().parse().unwrap_or(0);
+            }
+            chunk.push(line);
+        }
+        for _ in 0..body {
+            match self.line()? {
+                Some(line) => chunk.push(line),
+                None => break,
+            }
+        }
+        chunk.offsets.push(self.offset);
+
+        Ok(chunk)
+    }
+
+    fn rest(&mut self) -> Result<Chunk, ReadError> {
+        let mut chunk = Chunk::new(self.line_number);
+        while let Some(line) = self.line()? {
+            chunk.push(line);
+        }
+        chunk.offsets.push(self.offset);
+
+        Ok(chunk)
+    }
+}
+
+impl ElementData {
+    pub fn from_reader<R: BufRead>(reader: R, decoding: Decoding) -> Result<Self, ReadError> {
+        Self::from_reader_with_diagnostics(reader, decoding, &mut Diagnostics::new())
+    }
+
+    // Parses a datafile as it is read, e.g. from a file, a socket or a gzip
+    // decoder, keeping only the lines of the current section in memory.
+    // Errors and diagnostics have the same line numbers as for `parse`.
+    pub fn from_reader_with_diagnostics<R: BufRead>(
+        reader: R,
+        decoding: Decoding,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Self, ReadError> {
+        let mut reader = SectionReader { reader, decoding, line_number: 0, offset: 0, buf: vec!() };
+        let limits = ParseLimits::default();
+
+        let (name, information, weight) = reader.section(4, None)?.parse(|lines, line| {
+            let (ElementName { name, information }, weight) = ElementDataRef::parse_header(lines, line)?;
+            Ok((String::from(name), String::from(information), weight))
+        })?;
+
+        let (energy_levels, level_features) = reader
+            .section(3, Some(1))?
+            .parse(|lines, line| {
+                let (energy_levels, header) = ElementDataRef::parse_energy_levels(lines, line, &limits, None, None)?;
+                let levels = ElementDataRef { energy_levels, ..Default::default() };
+                let features = FormatFeatures::detect(header, &levels);
+                Ok((levels.energy_levels.into_iter().map(EnergyLevelRef::into_owned).collect(), features))
+            })?;
+
+        let (radiative_transitions, transition_features) = reader
+            .section(3, Some(1))?
+            .parse(|lines, line| {
+                let radiative_transitions = ElementDataRef::parse_radiative_transitions(lines, line, &limits, None, None)?;
+                let transitions = ElementDataRef { radiative_transitions, ..Default::default() };
+                let features = FormatFeatures::detect("", &transitions);
+                Ok((
+                    transitions.radiative_transitions.into_iter().map(RadiativeTransitionRef::into_owned).collect(),
+                    features,
+                ))
+            })?;
+
+        let npart = reader.section(2, None)?.parse(|lines, line| ElementDataRef::parse_number_of_collision_partners(lines, line, &limits))?;
+
+        let mut collision_partners = Vec::with_capacity(npart as usize);
+        for _ in 0..npart {
+            let mut found = Diagnostics::new();
+            let chunk = reader.section(9, Some(3))?;
+            let partner = chunk.parse(|lines, line| {
+                let partner = ElementDataRef::parse_collision_partner(lines, line, &limits, None, &mut found, None)?;
+                Ok(CollisionPartnerData {
+                    name: partner.name,
+                    information: String::from(partner.information),
+                    temperatures: partner.temperatures,
+                    rates: partner.rates,
+                })
+            })?;
+            for mut diagnostic in found.into_vec() {
+                diagnostic.line += chunk.first;
+                diagnostics.push(diagnostic);
+            }
+            collision_partners.push(partner);
+        }
+
+        let notes = reader.rest()?;
+        let comments = match notes.lines.is_empty() {
+            true => vec!(),
+            false => notes.parse(|lines, _| ElementDataRef::parse_notes(lines.clone(), npart, None))?,
+        };
+
+        let features = FormatFeatures {
+            transition_frequencies: transition_features.transition_frequencies,
+            notes: !comments.is_empty(),
+            ..level_features
+        };
+        let data = ElementDataRef { name: &name, information: &information, comments, weight, features, ..Default::default() };
+
+        Ok(ElementData { energy_levels, radiative_transitions, collision_partners, ..data.into_owned() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn read_datafile() {
+        let s = include_str!("../../data/oatom.dat");
+        let expected: ElementData = s.parse().unwrap();
+
+        let data = ElementData::from_reader(std::io::BufReader::new(s.as_bytes()), Decoding::default()).unwrap();
+        assert_eq!(data, expected, "Wrong result for a datafile read line by line");
+
+        let crlf = s.replace('\n', "\r\n");
+        let data = ElementData::from_reader(crlf.as_bytes(), Decoding::default()).unwrap();
+        assert_eq!(data, expected, "Wrong result for a datafile with CRLF line ends");
+        let bom = format!("\u{feff}{}", crlf);
+        assert_eq!(ElementData::from_reader(bom.as_bytes(), Decoding::default()).unwrap(), expected);
+
+        let broken = s.replacen("!NUMBER OF COLL TRANS", "NUMBER OF COLL TRANS", 1);
+        let (found, expected) = match (ElementData::from_reader(broken.as_bytes(), Decoding::Strict), broken.parse::<ElementData>()) {
+            (Err(ReadError::Parse(found)), Err(expected)) => (found, expected),
+            other => panic!("Wrong result for a broken datafile: {:?}", other),
+        };
+        assert_eq!(found, expected);
+
+        let truncated = &s[..s.len() / 2];
+        assert!(matches!(
+            ElementData::from_reader(truncated.as_bytes(), Decoding::default()),
+            Err(ReadError::Parse(ParseError::NotEnoughInput { .. }))
+        ));
+    }
+}
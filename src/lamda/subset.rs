@@ -0,0 +1,147 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use super::{CollisionPartnerData, CollisionalRates, ElementData, EnergyLevel, RadiativeTransition};
+
+impl ElementData {
+    // Data with only the `n` lowest levels by energy, numbered 1 to `n` in
+    // order of energy. Radiative and collisional transitions between the
+    // kept levels are renumbered in their order in the file; the others are
+    // dropped. Data with at most `n` levels comes back with the levels in
+    // order of energy.
+    pub fn truncate_levels(&self, n: usize) -> ElementData {
+        let mut lowest = self.by_energy();
+        lowest.truncate(n);
+
+        self.with_levels(lowest)
+    }
+
+    // Sorts the levels by energy and numbers them 1 to N in that order,
+    // with the levels of every transition and the transitions renumbered to
+    // match, as `truncate_levels` does. Levels of equal energy keep their
+    // order. Returns whether any level moved.
+    pub fn sort_levels_by_energy(&mut self) -> bool {
+        let sorted = self.by_energy();
+        let in_order = sorted.iter().zip(self.energy_levels.iter()).all(|(a, b)| core::ptr::eq(*a, b));
+        if in_order && self.has_contiguous_levels() {
+            return false;
+        }
+
+        *self = self.with_levels(sorted);
+        true
+    }
+
+    fn by_energy(&self) -> Vec<&EnergyLevel> {
+        let mut levels = self.energy_levels.iter().collect::<Vec<_>>();
+        levels.sort_by(|a, b| a.energy.total_cmp(&b.energy));
+        levels
+    }
+
+    // Data with `levels`, a selection of the levels of `self`, numbered 1 to
+    // N in their order, and the transitions between them
+    fn with_levels(&self, lowest: Vec<&EnergyLevel>) -> ElementData {
+        let numbers = lowest
+            .iter()
+            .enumerate()
+            .map(|(i, el)| (el.level, i as u32 + 1))
+            .collect::<BTreeMap<_, _>>();
+        let new_level = |level: u32| numbers.get(&level).copied();
+
+        let energy_levels = lowest
+            .iter()
+            .map(|el| EnergyLevel { level: new_level(el.level).unwrap_or(0), ..(*el).clone() })
+            .collect();
+
+        let kept = self.radiative_transitions
+            .iter()
+            .filter_map(|rt| Some((rt, new_level(rt.up)?, new_level(rt.low)?)))
+            .collect::<Vec<_>>();
+        let transitions = kept
+            .iter()
+            .enumerate()
+            .map(|(i, (rt, _, _))| (rt.transition, i as u32 + 1))
+            .collect::<BTreeMap<_, _>>();
+        let radiative_transitions = kept
+            .into_iter()
+            .enumerate()
+            .map(|(i, (rt, up, low))| RadiativeTransition { transition: i as u32 + 1, up, low, ..rt.clone() })
+            .collect();
+
+        let collision_partners = self.collision_partners
+            .iter()
+            .map(|partner| {
+                let rates = partner.rates
+                    .iter()
+                    .filter_map(|cr| Some((cr, new_level(cr.up)?, new_level(cr.low)?)))
+                    .enumerate()
+                    .map(|(i, (cr, up, low))| CollisionalRates { transition: i as u32 + 1, up, low, rates: cr.rates.clone() })
+                    .collect();
+                CollisionPartnerData::new(partner.name.clone(), &partner.information, partner.temperatures.clone(), rates)
+            })
+            .collect();
+
+        ElementData {
+            name: self.name.clone(),
+            information: self.information.clone(),
+            weight: self.weight,
+            energy_levels,
+            radiative_transitions,
+            collision_partners,
+            features: self.features,
+            metadata: self.metadata.renumbered(new_level, |t| transitions.get(&t).copied()),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn truncate_levels() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let truncated = data.truncate_levels(2);
+
+        assert_eq!(truncated.energy_levels().len(), 2);
+        assert_eq!(truncated.energy_levels()[1].energy, data.energy_levels()[1].energy);
+        assert!(truncated.radiative_transitions().iter().all(|rt| rt.up <= 2 && rt.low <= 2));
+        assert_eq!(
+            truncated.radiative_transitions().iter().map(|rt| rt.transition).collect::<Vec<_>>(),
+            (1..=truncated.radiative_transitions().len() as u32).collect::<Vec<_>>(),
+            "Wrong result for renumbered transitions",
+        );
+        let rates = truncated.collision_partners()[0].rates();
+        assert_eq!(rates.len(), data.collision_partners()[0].rates().iter().filter(|cr| cr.up <= 2).count());
+        assert!(truncated.validate().is_empty(), "Wrong result for truncated data: {:?}", truncated.validate());
+
+        assert_eq!(data.truncate_levels(10).energy_levels(), data.energy_levels());
+    }
+
+    #[test]
+    fn sort_levels_by_energy() {
+        let mut data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        let expected = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        assert!(!data.sort_levels_by_energy());
+
+        data.energy_levels.swap(1, 2);
+        for level in data.energy_levels.iter_mut() {
+            level.level = [1, 3, 2][level.level as usize - 1];
+        }
+        for rt in data.radiative_transitions.iter_mut() {
+            (rt.up, rt.low) = ([1, 3, 2][rt.up as usize - 1], [1, 3, 2][rt.low as usize - 1]);
+        }
+        assert_eq!(data.check_energy_order().len(), 1, "Wrong result for unsorted levels");
+
+        assert!(data.sort_levels_by_energy());
+        assert!(data.check_energy_order().is_empty());
+        assert_eq!(data.energy_levels(), expected.energy_levels(), "Wrong result for sorted levels");
+        let pairs = |d: &ElementData| d.radiative_transitions().iter().map(|rt| (rt.up, rt.low, rt.aeinst)).collect::<Vec<_>>();
+        let mut sorted = pairs(&data);
+        sorted.sort_by(|a, b| a.2.total_cmp(&b.2));
+        let mut original = pairs(&expected);
+        original.sort_by(|a, b| a.2.total_cmp(&b.2));
+        assert_eq!(sorted, original, "Wrong result for remapped transitions");
+    }
+}
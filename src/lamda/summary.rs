@@ -0,0 +1,161 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use super::ElementData;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Summary {
+    pub species: String,
+    pub weight: f64,
+    pub levels: usize,
+    // Range of the level energies, cm^-1, and of the transition frequencies,
+    // GHz; NaN without levels or transitions
+    pub energy_min: f64,
+    pub energy_max: f64,
+    pub frequency_min: f64,
+    pub frequency_max: f64,
+    pub transitions: Vec<TransitionSummary>,
+    pub collision_partners: Vec<CollisionPartnerSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct TransitionSummary {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub qnums_up: String,
+    pub qnums_low: String,
+    pub aeinst: f64,
+    pub frequency_ghz: f64,
+    pub e_up_kelvin: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct CollisionPartnerSummary {
+    pub partner: String,
+    pub transitions: usize,
+    pub temperatures: usize,
+    pub temperature_min: f64,
+    pub temperature_max: f64,
+}
+
+impl ElementData {
+    pub fn summary(&self) -> Summary {
+        let qnums = |n: u32| self.energy_level(n).map(|el| el.qnums.clone()).unwrap_or_default();
+
+        let transitions = self.radiative_transitions
+            .iter()
+            .map(|rt| TransitionSummary {
+                transition: rt.transition,
+                up: rt.up,
+                low: rt.low,
+                qnums_up: qnums(rt.up),
+                qnums_low: qnums(rt.low),
+                aeinst: rt.aeinst,
+                frequency_ghz: self.frequency(rt).unwrap_or(f64::NAN),
+                e_up_kelvin: self.upper_energy(rt).unwrap_or(f64::NAN),
+            })
+            .collect::<Vec<_>>();
+
+        let collision_partners = self.collision_partners
+            .iter()
+            .map(|cp| CollisionPartnerSummary {
+                partner: cp.name.to_string(),
+                transitions: cp.rates.len(),
+                temperatures: cp.temperatures.len(),
+                temperature_min: cp.temperatures.iter().copied().fold(f64::NAN, f64::min),
+                temperature_max: cp.temperatures.iter().copied().fold(f64::NAN, f64::max),
+            })
+            .collect();
+
+        let energies = || self.energy_levels.iter().map(|el| el.energy);
+        let frequencies = || transitions.iter().map(|t| t.frequency_ghz);
+
+        Summary {
+            species: self.name.clone(),
+            weight: self.weight,
+            levels: self.energy_levels.len(),
+            energy_min: energies().fold(f64::NAN, f64::min),
+            energy_max: energies().fold(f64::NAN, f64::max),
+            frequency_min: frequencies().fold(f64::NAN, f64::min),
+            frequency_max: frequencies().fold(f64::NAN, f64::max),
+            transitions,
+            collision_partners,
+        }
+    }
+}
+
+impl core::fmt::Display for Summary {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "Species:          {}", self.species)?;
+        writeln!(f, "Molecular weight: {}", self.weight)?;
+        writeln!(f, "Energy levels:    {} ({} - {} cm-1)", self.levels, self.energy_min, self.energy_max)?;
+        writeln!(
+            f,
+            "Radiative transitions: {} ({:.6} - {:.6} GHz)",
+            self.transitions.len(),
+            self.frequency_min,
+            self.frequency_max,
+        )?;
+        writeln!(f, "  trans    up   low      freq [GHz]     E_u [K]      A [s-1]  qnums")?;
+        for t in self.transitions.iter() {
+            writeln!(
+                f,
+                "  {:>5} {:>5} {:>5}  {:>14.6}  {:>10.3}  {:>11.4e}  {} -> {}",
+                t.transition, t.up, t.low, t.frequency_ghz, t.e_up_kelvin, t.aeinst, t.qnums_up, t.qnums_low
+            )?;
+        }
+        writeln!(f, "Collision partners: {}", self.collision_partners.len())?;
+        for cp in self.collision_partners.iter() {
+            writeln!(
+                f,
+                "  {:<5} {} transitions, {} temperatures ({} - {} K)",
+                cp.partner, cp.transitions, cp.temperatures, cp.temperature_min, cp.temperature_max
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{CollisionPartnerData, CollisionPartnerId, EnergyLevel, RadiativeTransition};
+
+    #[test]
+    fn element_data_summary() {
+        let ed = ElementData {
+            name: String::from("CO"),
+            weight: 28.0,
+            energy_levels: vec!(
+                EnergyLevel { level: 1, energy: 0.0, stat_weight: 1.0, qnums: String::from("0") },
+                EnergyLevel { level: 2, energy: 3.845033413, stat_weight: 3.0, qnums: String::from("1") },
+            ),
+            radiative_transitions: vec!(
+                RadiativeTransition { transition: 1, up: 2, low: 1, aeinst: 7.203e-08, ..Default::default() },
+            ),
+            collision_partners: vec!(CollisionPartnerData {
+                name: CollisionPartnerId::pH2,
+                temperatures: vec!(2.0, 10.0, 3000.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let summary = ed.summary();
+
+        assert_eq!(summary.levels, 2);
+        assert_eq!((summary.energy_min, summary.energy_max), (0.0, 3.845033413));
+        assert_eq!(summary.frequency_min, summary.transitions[0].frequency_ghz, "Wrong result for frequency coverage");
+        assert!((summary.transitions[0].frequency_ghz - 115.271).abs() < 1e-3);
+        assert!((summary.transitions[0].e_up_kelvin - 5.532).abs() < 1e-3);
+        assert_eq!(summary.collision_partners[0].partner, "p-H2");
+        assert_eq!(summary.collision_partners[0].temperature_min, 2.0);
+        assert_eq!(summary.collision_partners[0].temperature_max, 3000.0);
+    }
+}
@@ -0,0 +1,237 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{Diagnostic, ElementData, EnergyLevel, WEIGHT_TOLERANCE};
+
+impl ElementData {
+    // Consistency of the data across sections, which the parser does not
+    // check line by line. Errors mark data RADEX would solve wrongly or not
+    // at all; unsorted energies are only a warning.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut found = vec!();
+
+        if !(self.weight.is_finite() && self.weight > 0.0) {
+            found.push(Diagnostic::error(
+                "molecular-weight",
+                format!("Molecular weight {} is not positive", self.weight),
+            ));
+        }
+
+        for (i, level) in self.energy_levels.iter().enumerate() {
+            if level.level as usize != i + 1 {
+                found.push(Diagnostic::error(
+                    "level-index",
+                    format!("Energy level {} is listed as number {}", i + 1, level.level),
+                ));
+            }
+            if !(level.energy.is_finite() && level.energy >= 0.0) {
+                found.push(Diagnostic::error(
+                    "negative-energy",
+                    format!("Energy level {} has energy {}", level.level, level.energy),
+                ));
+            }
+            if !(level.stat_weight.is_finite() && level.stat_weight > 0.0) {
+                found.push(Diagnostic::error(
+                    "statistical-weight",
+                    format!("Energy level {} has statistical weight {}", level.level, level.stat_weight),
+                ));
+            }
+        }
+        found.extend(self.check_energy_order());
+
+        let levels = self.energy_levels.len() as u32;
+        let unknown = |up: u32, low: u32| [up, low].into_iter().find(|&l| l == 0 || l > levels);
+
+        for t in self.radiative_transitions.iter() {
+            if let Some(level) = unknown(t.up, t.low) {
+                found.push(Diagnostic::error(
+                    "unknown-level",
+                    format!("Radiative transition {} refers to level {} of {}", t.transition, level, levels),
+                ));
+            }
+            if !(t.aeinst.is_finite() && t.aeinst >= 0.0) {
+                found.push(Diagnostic::error(
+                    "einstein-a",
+                    format!("Radiative transition {} has Einstein A coefficient {}", t.transition, t.aeinst),
+                ));
+            }
+            // The columns are rounded in most files, but agree with the levels
+            // to a fraction of a percent unless they belong to other levels
+            if let (Some(frequency), Some(expected)) = (t.frequency, self.level_frequency(t)) {
+                if (frequency - expected).abs() > 1e-3 * expected.abs() {
+                    found.push(Diagnostic::warning(
+                        "frequency-mismatch",
+                        format!("Radiative transition {} is at {} GHz, its levels give {:.4} GHz", t.transition, frequency, expected),
+                    ));
+                }
+            }
+            if let (Some(energy), Some(expected)) = (t.upper_energy, self.level_upper_energy(t)) {
+                if (energy - expected).abs() > 1e-2 * expected.abs().max(1.0) {
+                    found.push(Diagnostic::warning(
+                        "upper-energy-mismatch",
+                        format!("Radiative transition {} has E_u {} K, its upper level gives {:.2} K", t.transition, energy, expected),
+                    ));
+                }
+            }
+        }
+
+        for partner in self.collision_partners.iter() {
+            for rate in partner.rates.iter() {
+                if let Some(level) = unknown(rate.up, rate.low) {
+                    found.push(Diagnostic::error(
+                        "unknown-level",
+                        format!(
+                            "Collisional transition {} with {} refers to level {} of {}",
+                            rate.transition, partner.name, level, levels,
+                        ),
+                    ));
+                }
+                if rate.rates.len() != partner.temperatures.len() {
+                    found.push(Diagnostic::error(
+                        "rate-count",
+                        format!(
+                            "Collisional transition {} with {} has {} rates for {} temperatures",
+                            rate.transition, partner.name, rate.rates.len(), partner.temperatures.len(),
+                        ),
+                    ));
+                }
+            }
+        }
+
+        found.extend(self.check_einstein_a_scaling());
+        found.extend(self.check_molecular_weight(WEIGHT_TOLERANCE));
+
+        found
+    }
+
+    // Levels that lie below a level listed before them. Solvers that take
+    // the levels as sorted by energy go wrong on these; see
+    // `sort_levels_by_energy`.
+    pub fn check_energy_order(&self) -> Vec<Diagnostic> {
+        let mut found = vec!();
+        let mut highest: Option<&EnergyLevel> = None;
+        for level in self.energy_levels.iter() {
+            match highest {
+                Some(h) if level.energy < h.energy => found.push(Diagnostic::warning(
+                    "unsorted-energies",
+                    format!("Energy level {} lies below level {}", level.level, h.level),
+                )),
+                _ => highest = Some(level),
+            }
+        }
+
+        found
+    }
+
+    // Transitions whose A_ul / nu^3 is far off that of their neighbours in
+    // the same band, as from a wrong exponent in a hand-edited file. A band
+    // is the transitions that step down the same number of levels, e.g.
+    // J -> J-1 of a linear molecule, in order of the upper level; its line
+    // strengths vary slowly, so a transition is only flagged when it
+    // differs by more than a factor of 10 from neighbours that agree among
+    // themselves.
+    pub fn check_einstein_a_scaling(&self) -> Vec<Diagnostic> {
+        let mut bands = BTreeMap::new();
+        for t in self.radiative_transitions.iter().filter(|t| t.up > t.low && t.aeinst > 0.0) {
+            let (Some(frequency), Some(up)) = (self.level_frequency(t), self.energy_level(t.up)) else {
+                continue;
+            };
+            if frequency <= 0.0 {
+                continue;
+            }
+            let ratio = t.aeinst / (frequency * frequency * frequency);
+            bands.entry(t.up - t.low).or_insert_with(Vec::new).push((t, up.energy, ratio));
+        }
+
+        let mut found = vec!();
+        for band in bands.values_mut() {
+            band.sort_by(|a, b| a.1.total_cmp(&b.1));
+            for (i, (t, _, ratio)) in band.iter().enumerate() {
+                let mut neighbours = band[i.saturating_sub(2)..(i + 3).min(band.len())]
+                    .iter()
+                    .filter(|n| n.0.transition != t.transition)
+                    .map(|n| n.2)
+                    .collect::<Vec<_>>();
+                if neighbours.len() < 2 {
+                    continue;
+                }
+                neighbours.sort_by(f64::total_cmp);
+                let (min, max) = (neighbours[0], neighbours[neighbours.len() - 1]);
+                let median = neighbours[neighbours.len() / 2];
+                if max <= 10.0 * min && !(0.1..=10.0).contains(&(ratio / median)) {
+                    found.push(Diagnostic::warning(
+                        "einstein-a-scaling",
+                        format!(
+                            "Radiative transition {} has Einstein A coefficient {}, {:.1e} times what its neighbours give",
+                            t.transition, t.aeinst, ratio / median,
+                        ),
+                    ));
+                }
+            }
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{CollisionPartnerData, CollisionPartnerId, CollisionalRates, Severity};
+
+    #[test]
+    fn validate_element_data() {
+        let data = include_str!("../../data/oatom.dat").parse::<ElementData>().unwrap();
+        assert_eq!(data.validate(), vec!(), "Wrong result for a LAMDA datafile");
+
+        let mut data = ElementData::builder("CO")
+            .weight(28.0)
+            .level(0.0, 1.0, "0")
+            .level(3.845, 3.0, "1")
+            .transition(2, 1, 7.2e-8)
+            .build()
+            .unwrap();
+        data.energy_levels_mut()[0].energy = 5.0;
+        data.radiative_transitions_mut()[0].aeinst = f64::NAN;
+        data.radiative_transitions_mut()[0].upper_energy = Some(55.3);
+        data.collision_partners.push(CollisionPartnerData::new(
+            CollisionPartnerId::pH2,
+            "CO-pH2",
+            vec!(10.0, 20.0),
+            vec!(CollisionalRates { transition: 1, up: 3, low: 1, rates: vec!(3.0e-11) }),
+        ));
+
+        let found = data.validate().into_iter().map(|d| (d.code, d.severity)).collect::<Vec<_>>();
+        let expected = vec!(
+            ("unsorted-energies", Severity::Warning),
+            ("einstein-a", Severity::Error),
+            ("upper-energy-mismatch", Severity::Warning),
+            ("unknown-level", Severity::Error),
+            ("rate-count", Severity::Error),
+        );
+        assert_eq!(found, expected.into_iter().map(|(c, s)| (String::from(c), s)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn einstein_a_scaling() {
+        // Rotational ladder of CO with A_ul proportional to nu^3 J/(2J+1)
+        let mut builder = ElementData::builder("CO").weight(28.0);
+        for j in 0..8 {
+            builder = builder.level(1.9225 * (j * (j + 1)) as f64, (2 * j + 1) as f64, &j.to_string());
+        }
+        for j in 1..8 {
+            let nu = 2.0 * 1.9225 * j as f64;
+            builder = builder.transition(j + 1, j, 1e-10 * nu.powi(3) * j as f64 / (2 * j + 1) as f64);
+        }
+        let mut data = builder.build().unwrap();
+        assert_eq!(data.check_einstein_a_scaling(), vec!(), "Wrong result for a consistent band");
+
+        data.radiative_transitions_mut()[3].aeinst *= 100.0;
+        let found = data.check_einstein_a_scaling();
+        assert_eq!(found.len(), 1, "Wrong result for a mistyped coefficient: {:?}", found);
+        assert!(found[0].message.starts_with("Radiative transition 4 "));
+    }
+}
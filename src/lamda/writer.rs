@@ -0,0 +1,171 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+
+use super::{ElementData, RadiativeTransition};
+
+// Number as Rust prints it, the shortest form that reads back to the same
+// value, with a decimal point as in the LAMDA files.
+fn float(x: f64) -> String {
+    let s = x.to_string();
+    match s.contains(['.', 'e', 'N', 'i']) {
+        true => s,
+        false => s + ".0",
+    }
+}
+
+// Exponent form of LAMDA rates, e.g. `7.203e-08`: at least three decimals
+// and a two digit exponent, without losing digits of the value.
+fn exponent(x: f64) -> String {
+    if !x.is_finite() {
+        return x.to_string();
+    }
+
+    let s = format!("{:e}", x);
+    let (mantissa, power) = s.split_once('e').unwrap_or((&s, "0"));
+    let decimals = mantissa.split_once('.').map(|(_, d)| d.len()).unwrap_or(0);
+    let mantissa = match decimals {
+        0 => format!("{}.000", mantissa),
+        n if n < 3 => format!("{}{:0<width$}", mantissa, "", width = 3 - n),
+        _ => String::from(mantissa),
+    };
+    let power = power.parse::<i32>().unwrap_or(0);
+
+    format!("{}e{}{:02}", mantissa, if power < 0 { '-' } else { '+' }, power.abs())
+}
+
+impl RadiativeTransition {
+    // Text after the Einstein A: frequency, E_u and `extra`. E_u is written
+    // only after a frequency, where the parser reads it back.
+    pub(crate) fn columns(&self) -> String {
+        let mut columns = vec!();
+        if let Some(frequency) = self.frequency {
+            columns.push(float(frequency));
+            columns.extend(self.upper_energy.map(float));
+        }
+        if !self.extra.is_empty() {
+            columns.push(self.extra.clone());
+        }
+
+        columns.join(" ")
+    }
+}
+
+// The information is the text after the molecule name, then the notes
+pub(super) fn split_information(information: &str) -> (&str, &str) {
+    information.split_once(". ").unwrap_or((information, ""))
+}
+
+impl ElementData {
+    // The data as a LAMDA datafile that RADEX reads, see `Display`
+    pub fn to_lamda_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ElementData {
+    fn write_lamda<W: core::fmt::Write>(&self, f: &mut W) -> core::fmt::Result {
+        let features = self.features;
+        let (information, notes) = split_information(&self.information);
+
+        writeln!(f, "!MOLECULE")?;
+        writeln!(f, "{}", format!("{} {}", self.name, information).trim_end())?;
+        writeln!(f, "!MOLECULAR WEIGHT")?;
+        writeln!(f, "{}", float(self.weight))?;
+
+        writeln!(f, "!NUMBER OF ENERGY LEVELS")?;
+        writeln!(f, "{}", self.energy_levels.len())?;
+        let labels = match (features.hyperfine, features.quantum_numbers) {
+            (true, _) => " + J + F",
+            (false, true) => " + J",
+            (false, false) => "",
+        };
+        writeln!(f, "!LEVEL + ENERGIES(cm^-1) + WEIGHT{}", labels)?;
+        for level in self.energy_levels.iter() {
+            let (energy, weight) = (float(level.energy), float(level.stat_weight));
+            let line = format!("{:>5} {:>16} {:>7}   {}", level.level, energy, weight, level.qnums);
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        writeln!(f, "!NUMBER OF RADIATIVE TRANSITIONS")?;
+        writeln!(f, "{}", self.radiative_transitions.len())?;
+        let columns = if features.transition_frequencies { " + FREQ(GHz) + E_u(K)" } else { "" };
+        writeln!(f, "!TRANS + UP + LOW + EINSTEINA(s^-1){}", columns)?;
+        for t in self.radiative_transitions.iter() {
+            let line = format!("{:>5} {:>5} {:>5}  {:>10}   {}", t.transition, t.up, t.low, exponent(t.aeinst), t.columns());
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        writeln!(f, "!NUMBER OF COLL PARTNERS")?;
+        writeln!(f, "{}", self.collision_partners.len())?;
+        for partner in self.collision_partners.iter() {
+            writeln!(f, "!COLLISIONS BETWEEN")?;
+            writeln!(f, "{}", format!("{} {}", partner.name.code(), partner.information).trim_end())?;
+            writeln!(f, "!NUMBER OF COLL TRANS")?;
+            writeln!(f, "{}", partner.rates.len())?;
+            writeln!(f, "!NUMBER OF COLL TEMPS")?;
+            writeln!(f, "{}", partner.temperatures.len())?;
+            writeln!(f, "!COLL TEMPS")?;
+            let temperatures = partner.temperatures.iter().map(|t| format!(" {:>9}", float(*t))).collect::<String>();
+            writeln!(f, "{}", temperatures)?;
+            writeln!(f, "!TRANS + UP + LOW + COLLRATES(cm^3 s^-1)")?;
+            for rate in partner.rates.iter() {
+                let rates = rate.rates.iter().map(|r| format!(" {:>10}", exponent(*r))).collect::<String>();
+                writeln!(f, "{:>5} {:>5} {:>5} {}", rate.transition, rate.up, rate.low, rates)?;
+            }
+        }
+
+        if !notes.trim().is_empty() {
+            writeln!(f, "! {}", notes.trim())?;
+        }
+
+        Ok(())
+    }
+}
+
+// Writes the datafile layout of LAMDA: section comments, right-aligned
+// columns and rates in exponent form. Notes that followed the collisional
+// data are written back there as a single comment. Data read with
+// `parse_lossless` is written as its original text instead, with only the
+// edited lines in this layout.
+impl core::fmt::Display for ElementData {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.original.0.is_none() {
+            return self.write_lamda(f);
+        }
+
+        let mut formatted = String::new();
+        self.write_lamda(&mut formatted)?;
+        match self.write_over_original(&formatted) {
+            Some(s) => f.write_str(&s),
+            None => f.write_str(&formatted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn format_numbers() {
+        assert_eq!(exponent(7.203e-8), "7.203e-08");
+        assert_eq!(exponent(3.3e-11), "3.300e-11");
+        assert_eq!(exponent(2e5), "2.000e+05");
+        assert_eq!(exponent(1.234_567e-10), "1.234567e-10");
+        assert_eq!(float(28.0), "28.0");
+        assert_eq!(float(3.845_033_413), "3.845033413");
+    }
+
+    #[test]
+    fn lamda_round_trip() {
+        let s = include_str!("../../data/oatom.dat");
+        let data = s.parse::<ElementData>().unwrap();
+        let written = data.to_lamda_string();
+
+        assert!(written.starts_with("!MOLECULE\nO (neutral atom)\n!MOLECULAR WEIGHT\n16.0\n"), "Wrong header in `{}`", written);
+        assert_eq!(written.parse::<ElementData>().unwrap(), data, "Wrong result for re-parsed datafile");
+        assert_eq!(written.parse::<ElementData>().unwrap().to_lamda_string(), written);
+    }
+}
@@ -0,0 +1,51 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+#[macro_use]
+extern crate uom;
+
+#[cfg(all(target_arch = "wasm32", any(feature = "hdf5", feature = "python")))]
+compile_error!("features `hdf5` and `python` link native libraries and are not available on wasm32");
+
+// Emits a `tracing` event with the `tracing` feature and expands to nothing otherwise.
+macro_rules! trace_event {
+    ($level:ident, $($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)*);
+    };
+}
+
+#[cfg(feature = "std")]
+pub mod catalog;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod constants;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(all(feature = "fetch", not(target_arch = "wasm32")))]
+pub mod fetch;
+pub mod lamda;
+mod cgs;
+pub mod iau;
+#[cfg(feature = "std")]
+pub mod interop;
+#[cfg(feature = "std")]
+pub mod model;
+pub mod molecular;
+pub mod prelude;
+pub mod progress;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "std")]
+pub mod radex;
+#[cfg(feature = "samples")]
+pub mod samples;
+#[cfg(feature = "std")]
+pub mod spectrum;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "std")]
+pub use error::IsmError;
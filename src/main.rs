@@ -1,9 +1,15 @@
-#[macro_use]
-extern crate uom;
+use clap::Parser;
 
-mod lamda;
-mod cgs;
-mod iau;
+mod cli;
 
-fn main() {
+fn main() -> std::process::ExitCode {
+    let args = cli::Cli::parse();
+
+    match cli::run(args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use crate::lamda::{CollisionPartnerId, Diagnostics, ElementData};
+use crate::radex::{self, Conditions, Geometry, Solution, SolverError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelError {
+    MissingSpecies,
+    MissingParameter {
+        name: &'static str,
+    },
+    InvalidParameter {
+        name: &'static str,
+        value: f64,
+        note: &'static str,
+    },
+    NoRadiativeTransitions {
+        species: String,
+    },
+    // None of the given densities is for a partner with rates in the datafile
+    MissingDensity {
+        species: String,
+        partners: Vec<CollisionPartnerId>,
+    },
+    UnknownCollider {
+        species: String,
+        partner: CollisionPartnerId,
+    },
+    EmptyFrequencyRange {
+        fmin: f64,
+        fmax: f64,
+    },
+}
+
+fn partner_list(partners: &[CollisionPartnerId]) -> String {
+    partners.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+impl std::fmt::Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelError::MissingSpecies => write!(f, "no molecular data was given for the model"),
+            ModelError::MissingParameter { name } => write!(f, "parameter `{}` is required", name),
+            ModelError::InvalidParameter { name, value, note } => {
+                write!(f, "parameter `{}` is {}, but {}", name, value, note)
+            }
+            ModelError::NoRadiativeTransitions { species } => {
+                write!(f, "datafile of {} has no radiative transitions", species)
+            }
+            ModelError::MissingDensity { species, partners } => write!(
+                f,
+                "datafile of {} has rates for {}, but no density is given for any of them",
+                species,
+                partner_list(partners)
+            ),
+            ModelError::UnknownCollider { species, partner } => {
+                write!(f, "a density is given for {}, but the datafile of {} has no rates for it", partner, species)
+            }
+            ModelError::EmptyFrequencyRange { fmin, fmax } => {
+                write!(f, "frequency range {}-{} GHz is empty", fmin, fmax)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+// Single-zone excitation model whose parameters were checked by
+// `ModelBuilder::build`.
+#[derive(Debug, Clone)]
+pub struct Model {
+    data: Arc<ElementData>,
+    conditions: Conditions,
+    // Frequency window of the reported lines, GHz
+    fmin: f64,
+    fmax: f64,
+}
+
+impl Model {
+    pub fn builder() -> ModelBuilder {
+        ModelBuilder::default()
+    }
+
+    pub fn data(&self) -> &ElementData {
+        &self.data
+    }
+
+    pub fn conditions(&self) -> &Conditions {
+        &self.conditions
+    }
+
+    pub fn solve(&self) -> Result<Solution, SolverError> {
+        self.solve_with_diagnostics(&mut Diagnostics::new())
+    }
+
+    // Solution with only the lines inside the frequency window.
+    pub fn solve_with_diagnostics(&self, diagnostics: &mut Diagnostics) -> Result<Solution, SolverError> {
+        let mut solution = radex::solve_with_diagnostics(self.data.as_ref(), &self.conditions, diagnostics)?;
+        solution.lines.retain(|l| l.frequency >= self.fmin && l.frequency <= self.fmax);
+
+        Ok(solution)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelBuilder {
+    data: Option<Arc<ElementData>>,
+    tkin: Option<f64>,
+    cdmol: Option<f64>,
+    linewidth: f64,
+    tbg: f64,
+    geometry: Geometry,
+    densities: Vec<(CollisionPartnerId, f64)>,
+    fmin: f64,
+    fmax: f64,
+}
+
+impl Default for ModelBuilder {
+    fn default() -> Self {
+        let conditions = Conditions::default();
+
+        Self {
+            data: None,
+            tkin: None,
+            cdmol: None,
+            linewidth: conditions.linewidth,
+            tbg: conditions.tbg,
+            geometry: conditions.geometry,
+            densities: vec!(),
+            fmin: 0.0,
+            fmax: f64::INFINITY,
+        }
+    }
+}
+
+impl ModelBuilder {
+    // Molecular data of the species, owned or shared with a `LamdaDatabase`
+    pub fn species(mut self, data: impl Into<Arc<ElementData>>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    // Kinetic temperature, K
+    pub fn kinetic_temperature(mut self, tkin: f64) -> Self {
+        self.tkin = Some(tkin);
+        self
+    }
+
+    // Column density of the species, cm-2
+    pub fn column_density(mut self, cdmol: f64) -> Self {
+        self.cdmol = Some(cdmol);
+        self
+    }
+
+    // Line width (FWHM), km s-1
+    pub fn linewidth(mut self, linewidth: f64) -> Self {
+        self.linewidth = linewidth;
+        self
+    }
+
+    // Background radiation temperature, K
+    pub fn background(mut self, tbg: f64) -> Self {
+        self.tbg = tbg;
+        self
+    }
+
+    pub fn geometry(mut self, geometry: Geometry) -> Self {
+        self.geometry = geometry;
+        self
+    }
+
+    // Density of a collision partner, cm-3; a later call for the same
+    // partner replaces the density
+    pub fn density(mut self, partner: CollisionPartnerId, density: f64) -> Self {
+        self.densities.retain(|(p, _)| *p != partner);
+        self.densities.push((partner, density));
+        self
+    }
+
+    // Only lines between `fmin` and `fmax` GHz are reported
+    pub fn frequency_range(mut self, fmin: f64, fmax: f64) -> Self {
+        self.fmin = fmin;
+        self.fmax = fmax;
+        self
+    }
+
+    pub fn build(self) -> Result<Model, ModelError> {
+        let data = self.data.ok_or(ModelError::MissingSpecies)?;
+        let tkin = self.tkin.ok_or(ModelError::MissingParameter { name: "kinetic temperature" })?;
+        let cdmol = self.cdmol.ok_or(ModelError::MissingParameter { name: "column density" })?;
+
+        let invalid = |name, value, note| Err(ModelError::InvalidParameter { name, value, note });
+        let positive = [("kinetic temperature", tkin), ("line width", self.linewidth)];
+        let non_negative = [("column density", cdmol), ("background temperature", self.tbg)];
+        for (name, value) in positive {
+            if !(value.is_finite() && value > 0.0) {
+                return invalid(name, value, "it must be positive");
+            }
+        }
+        for (name, value) in non_negative {
+            if !(value.is_finite() && value >= 0.0) {
+                return invalid(name, value, "it must not be negative");
+            }
+        }
+        for &(_, density) in self.densities.iter() {
+            if !(density.is_finite() && density >= 0.0) {
+                return invalid("density", density, "it must not be negative");
+            }
+        }
+        if self.fmin.is_nan() || self.fmax.is_nan() || self.fmin > self.fmax {
+            return Err(ModelError::EmptyFrequencyRange { fmin: self.fmin, fmax: self.fmax });
+        }
+
+        let species = data.name.clone();
+        if data.radiative_transitions.is_empty() {
+            return Err(ModelError::NoRadiativeTransitions { species });
+        }
+
+        let conditions = Conditions {
+            tkin,
+            tbg: self.tbg,
+            densities: self.densities,
+            cdmol,
+            linewidth: self.linewidth,
+            geometry: self.geometry,
+        };
+
+        // Total H2 stands for its ortho and para forms when the datafile has
+        // only those, as in the solver
+        let available = data.collision_partners.iter().map(|cp| cp.name.clone()).collect::<Vec<_>>();
+        let has_ortho_para = available.contains(&CollisionPartnerId::pH2) || available.contains(&CollisionPartnerId::oH2);
+        for (partner, _) in conditions.densities.iter() {
+            let split = *partner == CollisionPartnerId::H2 && has_ortho_para;
+            if !available.contains(partner) && !split {
+                return Err(ModelError::UnknownCollider { species, partner: partner.clone() });
+            }
+        }
+        let used = radex::partner_densities(data.as_ref(), &conditions)
+            .iter()
+            .any(|(partner, density)| *density > 0.0 && available.contains(partner));
+        if !used {
+            return Err(ModelError::MissingDensity { species, partners: available });
+        }
+
+        Ok(Model { data, conditions, fmin: self.fmin, fmax: self.fmax })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn oxygen() -> ElementData {
+        let s = "!MOLECULE\nO\n!MOLECULAR WEIGHT\n16.0\n!NUMBER OF ENERGY LEVELS\n2\n!LEVEL\n1 0.0 5.0\n2 158.265 3.0\n\
+            !NUMBER OF RADIATIVE TRANSITIONS\n1\n!TRANS\n1 2 1 8.91e-05\n!NUMBER OF COLL PARTNERS\n2\n\
+            !PARTNER\n2 O-pH2\n!NUMBER OF COLL TRANS\n1\n!NUMBER OF COLL TEMPS\n2\n!COLL TEMPS\n20.0 100.0\n!TRANS\n1 2 1 3.0e-11 4.0e-11\n\
+            !PARTNER\n3 O-oH2\n!NUMBER OF COLL TRANS\n1\n!NUMBER OF COLL TEMPS\n2\n!COLL TEMPS\n20.0 100.0\n!TRANS\n1 2 1 3.0e-11 4.0e-11\n";
+        s.parse().unwrap()
+    }
+
+    fn builder() -> ModelBuilder {
+        Model::builder().species(oxygen()).kinetic_temperature(50.0).column_density(1e15)
+    }
+
+    #[test]
+    fn build_checks_colliders() {
+        assert!(builder().density(CollisionPartnerId::H2, 1e4).build().is_ok());
+
+        match builder().density(CollisionPartnerId::electrons, 1e2).build() {
+            Err(ModelError::UnknownCollider { partner, .. }) => assert_eq!(partner, CollisionPartnerId::electrons),
+            other => panic!("Wrong result for unknown collider: {:?}", other.map(|_| ())),
+        }
+        match builder().build() {
+            Err(ModelError::MissingDensity { partners, .. }) => {
+                assert_eq!(partners, vec!(CollisionPartnerId::pH2, CollisionPartnerId::oH2))
+            }
+            other => panic!("Wrong result for missing density: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn build_checks_parameters() {
+        let missing = Model::builder().species(oxygen()).column_density(1e15).build();
+        assert_eq!(missing.map(|_| ()), Err(ModelError::MissingParameter { name: "kinetic temperature" }));
+
+        let negative = builder().density(CollisionPartnerId::pH2, 1e4).linewidth(-1.0).build();
+        assert!(matches!(negative, Err(ModelError::InvalidParameter { name: "line width", .. })));
+
+        let window = builder().density(CollisionPartnerId::pH2, 1e4).frequency_range(5.0, 1.0).build();
+        assert!(matches!(window, Err(ModelError::EmptyFrequencyRange { .. })));
+    }
+
+    #[test]
+    fn solve_in_window() {
+        let model = builder().density(CollisionPartnerId::pH2, 1e4).frequency_range(0.0, 1000.0).build().unwrap();
+
+        assert!(model.solve().unwrap().lines.is_empty(), "Wrong result for lines outside the window");
+    }
+}
@@ -0,0 +1,387 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::constants::WAVENUMBER_TO_KELVIN;
+use crate::lamda::{CollisionPartnerId, ElementData, EnergyLevelRef, RadiativeTransitionRef};
+
+// Downward collisional rate coefficient at one temperature, cm3 s-1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionRate {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub rate: f64,
+}
+
+// Molecular data the excitation solver works with, whatever its source. Level
+// numbers in the transitions and rates refer to `EnergyLevelRef::level`.
+// Sources without collisional data, such as line catalogs, have no partners.
+pub trait MolecularData {
+    fn species(&self) -> &str;
+
+    fn energy_levels(&self) -> Vec<EnergyLevelRef<'_>>;
+
+    fn radiative_transitions(&self) -> Vec<RadiativeTransitionRef<'_>>;
+
+    fn collision_partners(&self) -> Vec<CollisionPartnerId>;
+
+    // Lowest and highest tabulated temperature of `partner`, K
+    fn collision_temperature_range(&self, partner: CollisionPartnerId) -> Option<(f64, f64)>;
+
+    // Rates of `partner` at `temperature` K, empty for an unknown partner
+    fn collision_rates(&self, partner: CollisionPartnerId, temperature: f64) -> Vec<CollisionRate>;
+}
+
+// How rates are taken outside the tabulated temperatures
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Extrapolation {
+    // Rates at the nearest tabulated temperature
+    #[default]
+    Clamp,
+    // Power law k ~ T^a through the two outermost temperatures
+    #[cfg(feature = "std")]
+    PowerLaw,
+    // `ExtrapolationError` for any temperature outside the table
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtrapolationError {
+    OutOfRange {
+        temperature: f64,
+        first: f64,
+        last: f64,
+    },
+}
+
+impl core::fmt::Display for ExtrapolationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ExtrapolationError::OutOfRange { temperature, first, last } => {
+                write!(f, "temperature {} K is outside the tabulated {}-{} K", temperature, first, last)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ExtrapolationError {}
+
+// Linear interpolation in temperature, clamped to the tabulated range. Rate
+// lines with fewer values than temperatures, which the parser only warns
+// about, use the temperatures they have values for; without any the rate is 0.
+pub(crate) fn interpolate(temperatures: &[f64], rates: &[f64], t: f64) -> f64 {
+    let n = temperatures.len().min(rates.len());
+    let (temperatures, rates) = (&temperatures[..n], &rates[..n]);
+    match temperatures.iter().position(|&x| x >= t) {
+        _ if n == 0 => 0.0,
+        _ if n == 1 => rates[0],
+        Some(0) => rates[0],
+        None => rates[rates.len() - 1],
+        Some(i) => {
+            let w = (t - temperatures[i - 1]) / (temperatures[i] - temperatures[i - 1]);
+            rates[i - 1] + w * (rates[i] - rates[i - 1])
+        }
+    }
+}
+
+// Linear interpolation in temperature, with `extrapolation` outside the
+// tabulated range.
+pub fn interpolate_with(
+    temperatures: &[f64],
+    rates: &[f64],
+    t: f64,
+    extrapolation: Extrapolation,
+) -> Result<f64, ExtrapolationError> {
+    let n = temperatures.len().min(rates.len());
+    if n == 0 {
+        return Ok(0.0);
+    }
+    let (temperatures, rates) = (&temperatures[..n], &rates[..n]);
+    let (first, last) = (temperatures[0], temperatures[n - 1]);
+    if first <= t && t <= last {
+        return Ok(interpolate(temperatures, rates, t));
+    }
+
+    match extrapolation {
+        Extrapolation::Clamp => Ok(interpolate(temperatures, rates, t)),
+        #[cfg(feature = "std")]
+        Extrapolation::PowerLaw => {
+            if n == 1 {
+                return Ok(rates[0]);
+            }
+            let (i, j) = if t < first { (0, 1) } else { (n - 1, n - 2) };
+            let (k0, k1) = (rates[i], rates[j]);
+            match k0 > 0.0 && k1 > 0.0 {
+                true => {
+                    let exponent = (k1 / k0).ln() / (temperatures[j] / temperatures[i]).ln();
+                    Ok(k0 * (t / temperatures[i]).powf(exponent))
+                }
+                false => Ok(k0),
+            }
+        }
+        Extrapolation::Error => Err(ExtrapolationError::OutOfRange { temperature: t, first, last }),
+    }
+}
+
+impl ElementData {
+    // Rates of `partner` at `temperature` K like `MolecularData::collision_rates`,
+    // which clamps, but with `extrapolation` outside the tabulated range
+    pub fn collision_rates_with(
+        &self,
+        partner: CollisionPartnerId,
+        temperature: f64,
+        extrapolation: Extrapolation,
+    ) -> Result<Vec<CollisionRate>, ExtrapolationError> {
+        let cp = match self.collision_partners.iter().find(|cp| cp.name == partner) {
+            Some(cp) => cp,
+            None => return Ok(vec!()),
+        };
+
+        cp.rates
+            .iter()
+            .map(|cr| Ok(CollisionRate {
+                transition: cr.transition,
+                up: cr.up,
+                low: cr.low,
+                rate: interpolate_with(&cp.temperatures, &cr.rates, temperature, extrapolation)?,
+            }))
+            .collect()
+    }
+}
+
+// Downward rate coefficient of a collisional transition and the upward one
+// from detailed balance, cm3 s-1
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BalancedRate {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub downward: f64,
+    pub upward: f64,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetailedBalanceError {
+    Extrapolation(ExtrapolationError),
+    UnknownLevel {
+        transition: u32,
+        level: u32,
+    },
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for DetailedBalanceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DetailedBalanceError::Extrapolation(e) => write!(f, "{}", e),
+            DetailedBalanceError::UnknownLevel { transition, level } => {
+                write!(f, "collisional transition {} refers to unknown level {}", transition, level)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DetailedBalanceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DetailedBalanceError::Extrapolation(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ExtrapolationError> for DetailedBalanceError {
+    fn from(e: ExtrapolationError) -> Self {
+        DetailedBalanceError::Extrapolation(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ElementData {
+    // Downward and upward rates of `partner` at kinetic temperature `tkin` K.
+    // LAMDA tabulates downward rates only; the upward ones follow from
+    // C_lu = C_ul g_u / g_l exp(-(E_u - E_l) / k T).
+    pub fn balanced_collision_rates(
+        &self,
+        partner: CollisionPartnerId,
+        tkin: f64,
+        extrapolation: Extrapolation,
+    ) -> Result<Vec<BalancedRate>, DetailedBalanceError> {
+        let rates = self.collision_rates_with(partner, tkin, extrapolation)?;
+
+        rates
+            .into_iter()
+            .map(|cr| {
+                let level = |n: u32| {
+                    self.energy_level(n).ok_or(DetailedBalanceError::UnknownLevel { transition: cr.transition, level: n })
+                };
+                let (up, low) = (level(cr.up)?, level(cr.low)?);
+                let boltzmann = (-(up.energy - low.energy) * WAVENUMBER_TO_KELVIN / tkin).exp();
+
+                Ok(BalancedRate {
+                    transition: cr.transition,
+                    up: cr.up,
+                    low: cr.low,
+                    downward: cr.rate,
+                    upward: cr.rate * up.stat_weight / low.stat_weight * boltzmann,
+                })
+            })
+            .collect()
+    }
+}
+
+impl MolecularData for ElementData {
+    fn species(&self) -> &str {
+        &self.name
+    }
+
+    fn energy_levels(&self) -> Vec<EnergyLevelRef<'_>> {
+        self.energy_levels
+            .iter()
+            .map(|el| EnergyLevelRef { level: el.level, energy: el.energy, stat_weight: el.stat_weight, qnums: &el.qnums })
+            .collect()
+    }
+
+    fn radiative_transitions(&self) -> Vec<RadiativeTransitionRef<'_>> {
+        self.radiative_transitions
+            .iter()
+            .map(|rt| RadiativeTransitionRef {
+                transition: rt.transition,
+                up: rt.up,
+                low: rt.low,
+                aeinst: rt.aeinst,
+                frequency: rt.frequency,
+                upper_energy: rt.upper_energy,
+                extra: &rt.extra,
+            })
+            .collect()
+    }
+
+    fn collision_partners(&self) -> Vec<CollisionPartnerId> {
+        self.collision_partners.iter().map(|cp| cp.name.clone()).collect()
+    }
+
+    fn collision_temperature_range(&self, partner: CollisionPartnerId) -> Option<(f64, f64)> {
+        let cp = self.collision_partners.iter().find(|cp| cp.name == partner)?;
+        Some((*cp.temperatures.first()?, *cp.temperatures.last()?))
+    }
+
+    fn collision_rates(&self, partner: CollisionPartnerId, temperature: f64) -> Vec<CollisionRate> {
+        match self.collision_partners.iter().find(|cp| cp.name == partner) {
+            Some(cp) => cp.rates
+                .iter()
+                .map(|cr| CollisionRate {
+                    transition: cr.transition,
+                    up: cr.up,
+                    low: cr.low,
+                    rate: interpolate(&cp.temperatures, &cr.rates, temperature),
+                })
+                .collect(),
+            None => vec!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{CollisionPartnerData, CollisionalRates};
+
+    #[test]
+    fn interpolate_clamps() {
+        let temperatures = [10.0, 20.0, 40.0];
+        let rates = [1.0, 2.0, 4.0];
+
+        assert_eq!(interpolate(&temperatures, &rates, 5.0), 1.0);
+        assert_eq!(interpolate(&temperatures, &rates, 30.0), 3.0);
+        assert_eq!(interpolate(&temperatures, &rates, 100.0), 4.0);
+    }
+
+    #[test]
+    fn extrapolation_policies() {
+        let temperatures = [10.0, 20.0, 40.0];
+        let rates = [1.0, 2.0, 4.0];
+        let at = |t, extrapolation| interpolate_with(&temperatures, &rates, t, extrapolation);
+
+        assert_eq!(at(30.0, Extrapolation::Error), Ok(3.0));
+        assert_eq!(at(100.0, Extrapolation::Clamp), Ok(4.0));
+        assert_eq!(
+            at(5.0, Extrapolation::Error),
+            Err(ExtrapolationError::OutOfRange { temperature: 5.0, first: 10.0, last: 40.0 }),
+        );
+        let high = at(80.0, Extrapolation::PowerLaw).unwrap();
+        let low = at(5.0, Extrapolation::PowerLaw).unwrap();
+        assert!((high - 8.0).abs() < 1e-12 && (low - 0.5).abs() < 1e-12, "Wrong result for power law {} {}", low, high);
+    }
+
+    #[test]
+    fn detailed_balance() {
+        let rates = vec!(CollisionalRates { transition: 1, up: 2, low: 1, rates: vec!(3.0e-11, 4.0e-11) });
+        let data = ElementData::builder("CO")
+            .weight(28.0)
+            .level(0.0, 1.0, "0")
+            .level(3.845, 3.0, "1")
+            .collision_partner(CollisionPartnerData::new(CollisionPartnerId::pH2, "", vec!(10.0, 20.0), rates))
+            .build()
+            .unwrap();
+
+        let balanced = data.balanced_collision_rates(CollisionPartnerId::pH2, 20.0, Extrapolation::Clamp).unwrap();
+        let expected = 4.0e-11 * 3.0 * (-3.845 * WAVENUMBER_TO_KELVIN / 20.0).exp();
+        assert_eq!(balanced[0].downward, 4.0e-11);
+        assert!((balanced[0].upward - expected).abs() < 1e-24, "Wrong result for upward rate {}", balanced[0].upward);
+
+        assert!(matches!(
+            data.balanced_collision_rates(CollisionPartnerId::pH2, 50.0, Extrapolation::Error),
+            Err(DetailedBalanceError::Extrapolation(_)),
+        ));
+    }
+
+    #[test]
+    fn short_rate_lines() {
+        let s = r#"!MOLECULE
+        CO
+        !MOLECULAR WEIGHT
+        28.0
+        !NUMBER OF ENERGY LEVELS
+        2
+        !LEVEL + ENERGIES(cm^-1) + WEIGHT + J
+            1     0.000000000  1.0     0
+            2     3.845033413  3.0     1
+        !NUMBER OF RADIATIVE TRANSITIONS
+        1
+        !TRANS + UP + LOW + EINSTEINA(s^-1) + FREQ(GHz) + E_u(K)
+            1     2     1  7.203e-08          115.2712018     5.53
+        !NUMBER OF COLL PARTNERS
+        1
+        !COLLISIONS BETWEEN
+        2 CO-pH2 from Yang et al. (2010)
+        !NUMBER OF COLL TRANS
+        1
+        !NUMBER OF COLL TEMPS
+        3
+        !COLL TEMPS
+            10.0  20.0  6000.0
+        !TRANS + UP + LOW + COLLRATES(cm^3 s^-1)
+            1     2     1  3.3e-11  3.4e-11
+        "#;
+        let data = s.parse::<ElementData>().unwrap();
+
+        let rates = data.collision_rates(CollisionPartnerId::pH2, 6000.0);
+        assert_eq!(rates[0].rate, 3.4e-11, "Wrong result for rate beyond the values of a short line");
+        assert_eq!(interpolate_with(&[10.0, 20.0, 40.0], &[], 15.0, Extrapolation::Error), Ok(0.0));
+
+        let conditions = crate::radex::Conditions {
+            tkin: 6000.0,
+            densities: vec!((CollisionPartnerId::pH2, 1.0e4)),
+            ..Default::default()
+        };
+        assert!(crate::radex::solve(&data, &conditions).is_ok());
+    }
+}
@@ -0,0 +1,40 @@
+// Commonly used types, for `use ism::prelude::*;`. Modules stay the place for
+// everything else, e.g. `ism::interop` for the table formats.
+
+pub use crate::lamda::{
+    CollisionPartnerData,
+    CollisionPartnerId,
+    CollisionalRates,
+    Decoding,
+    Diagnostic,
+    Diagnostics,
+    ElementData,
+    ElementDataRef,
+    EnergyLevel,
+    FormatFeatures,
+    ParseError,
+    RadiativeTransition,
+    Severity,
+    Summary,
+};
+pub use crate::progress::{CancellationToken, Progress, ProgressCallback};
+
+// IAU quantities, with the units in their modules, e.g. `length::parsec`
+pub use crate::iau::{length, mass, time};
+#[cfg(feature = "f64")]
+pub use crate::iau::f64::{Length, Mass, Time};
+
+#[cfg(feature = "std")]
+pub use crate::catalog::{CatalogLine, LineList};
+#[cfg(feature = "std")]
+pub use crate::lamda::LamdaDatabase;
+#[cfg(feature = "std")]
+pub use crate::model::{Model, ModelBuilder, ModelError};
+#[cfg(feature = "std")]
+pub use crate::radex::{Conditions, Geometry, LineResult, Solution, SolverError};
+#[cfg(feature = "std")]
+pub use crate::spectrum::{Axis, Cube, Observation, Spectrum, SpectrumError};
+#[cfg(feature = "std")]
+pub use crate::IsmError;
+#[cfg(feature = "config")]
+pub use crate::config::ModelConfig;
@@ -0,0 +1,66 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+// Progress of a long computation, passed to the callback after each finished item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress<'a, T: ?Sized> {
+    pub completed: usize,
+    pub total: usize,
+    // The item that has just been finished
+    pub current: &'a T,
+}
+
+impl<T: ?Sized> Progress<'_, T> {
+    pub fn fraction(&self) -> f64 {
+        match self.total {
+            0 => 1.0,
+            total => self.completed as f64 / total as f64,
+        }
+    }
+}
+
+// Callbacks may be invoked from several threads at once, and `completed`
+// may arrive out of order when they are.
+pub type ProgressCallback<'a, T> = &'a (dyn Fn(Progress<'_, T>) + Sync);
+
+// Flag a frontend raises to stop a long computation early. Clones share the
+// flag, so one clone can be handed to the worker and another kept to cancel it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn progress_fraction() {
+        let progress = Progress { completed: 3, total: 4, current: "item" };
+
+        assert_eq!(progress.fraction(), 0.75);
+        assert_eq!(Progress { completed: 0, total: 0, current: &() }.fraction(), 1.0);
+    }
+    #[test]
+    fn cancellation_is_shared() {
+        let token = CancellationToken::new();
+        let worker = token.clone();
+
+        assert!(!worker.is_cancelled());
+        token.cancel();
+        assert!(worker.is_cancelled());
+    }
+}
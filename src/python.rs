@@ -0,0 +1,284 @@
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::iau;
+use crate::lamda::{CollisionPartnerId, Decoding, ElementData, ParseError};
+use crate::radex::{self, Conditions, Geometry, Solution};
+
+#[pyclass(name = "ElementData", module = "ism", frozen)]
+struct PyElementData {
+    inner: ElementData,
+}
+
+#[pymethods]
+impl PyElementData {
+    #[staticmethod]
+    fn parse(s: &str) -> PyResult<Self> {
+        s.parse::<ElementData>()
+            .map(|inner| Self { inner })
+            .map_err(|e: ParseError| PyValueError::new_err(format!("\n{}", e)))
+    }
+
+    #[staticmethod]
+    fn from_file(path: &str) -> PyResult<Self> {
+        let bytes = std::fs::read(path)?;
+        ElementData::from_bytes(&bytes, Decoding::Latin1Fallback)
+            .map(|inner| Self { inner })
+            .map_err(|e: ParseError| PyValueError::new_err(format!("\n{}", e)))
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    #[getter]
+    fn information(&self) -> &str {
+        &self.inner.information
+    }
+
+    #[getter]
+    fn weight(&self) -> f64 {
+        self.inner.weight
+    }
+
+    #[getter]
+    fn levels(&self) -> Vec<(u32, f64, f64, String)> {
+        self.inner.energy_levels
+            .iter()
+            .map(|el| (el.level, el.energy, el.stat_weight, el.qnums.clone()))
+            .collect()
+    }
+
+    #[getter]
+    fn radiative_transitions(&self) -> Vec<(u32, u32, u32, f64)> {
+        self.inner.radiative_transitions
+            .iter()
+            .map(|rt| (rt.transition, rt.up, rt.low, rt.aeinst))
+            .collect()
+    }
+
+    #[getter]
+    fn collision_partners(&self) -> Vec<String> {
+        self.inner.collision_partners
+            .iter()
+            .map(|cp| cp.name.to_string())
+            .collect()
+    }
+
+    fn collision_temperatures(&self, partner: &str) -> PyResult<Vec<f64>> {
+        self.inner.collision_partners
+            .iter()
+            .find(|cp| cp.name.to_string().eq_ignore_ascii_case(partner))
+            .map(|cp| cp.temperatures.clone())
+            .ok_or_else(|| PyValueError::new_err(format!("no collision data for partner `{}`", partner)))
+    }
+
+    fn collision_rates(&self, partner: &str) -> PyResult<Vec<(u32, u32, Vec<f64>)>> {
+        self.inner.collision_partners
+            .iter()
+            .find(|cp| cp.name.to_string().eq_ignore_ascii_case(partner))
+            .map(|cp| cp.rates.iter().map(|cr| (cr.up, cr.low, cr.rates.clone())).collect())
+            .ok_or_else(|| PyValueError::new_err(format!("no collision data for partner `{}`", partner)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ElementData(name='{}', levels={}, radiative_transitions={}, collision_partners={})",
+            self.inner.name,
+            self.inner.energy_levels.len(),
+            self.inner.radiative_transitions.len(),
+            self.inner.collision_partners.len()
+        )
+    }
+}
+
+#[pyclass(name = "Conditions", module = "ism", frozen)]
+struct PyConditions {
+    inner: Conditions,
+}
+
+#[pymethods]
+impl PyConditions {
+    // Densities map partner names such as "p-H2" to cm-3, in the order given
+    #[new]
+    #[pyo3(signature = (tkin=30.0, tbg=2.73, densities=None, cdmol=1.0e13, linewidth=1.0, geometry="sphere"))]
+    fn new(
+        tkin: f64,
+        tbg: f64,
+        densities: Option<&Bound<'_, PyDict>>,
+        cdmol: f64,
+        linewidth: f64,
+        geometry: &str,
+    ) -> PyResult<Self> {
+        let geometry = match geometry.to_ascii_lowercase().as_str() {
+            "uni" | "sphere" => Geometry::Sphere,
+            "lvg" => Geometry::LargeVelocityGradient,
+            "slab" => Geometry::Slab,
+            _ => return Err(PyValueError::new_err(format!("unknown geometry `{}`", geometry))),
+        };
+        let densities = match densities {
+            Some(densities) => densities
+                .iter()
+                .map(|(name, density)| {
+                    let name = name.extract::<String>()?;
+                    let partner = CollisionPartnerId::from_name(&name)
+                        .ok_or_else(|| PyValueError::new_err(format!("unknown collision partner `{}`", name)))?;
+                    Ok((partner, density.extract::<f64>()?))
+                })
+                .collect::<PyResult<Vec<_>>>()?,
+            None => Conditions::default().densities,
+        };
+
+        Ok(Self { inner: Conditions { tkin, tbg, densities, cdmol, linewidth, geometry } })
+    }
+
+    #[getter]
+    fn tkin(&self) -> f64 {
+        self.inner.tkin
+    }
+
+    #[getter]
+    fn tbg(&self) -> f64 {
+        self.inner.tbg
+    }
+
+    #[getter]
+    fn densities(&self) -> Vec<(String, f64)> {
+        self.inner.densities
+            .iter()
+            .map(|(partner, density)| (partner.to_string(), *density))
+            .collect()
+    }
+
+    #[getter]
+    fn cdmol(&self) -> f64 {
+        self.inner.cdmol
+    }
+
+    #[getter]
+    fn linewidth(&self) -> f64 {
+        self.inner.linewidth
+    }
+
+    #[getter]
+    fn geometry(&self) -> String {
+        self.inner.geometry.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Conditions(tkin={}, tbg={}, densities={:?}, cdmol={}, linewidth={}, geometry='{}')",
+            self.inner.tkin,
+            self.inner.tbg,
+            self.densities(),
+            self.inner.cdmol,
+            self.inner.linewidth,
+            self.inner.geometry
+        )
+    }
+}
+
+#[pyclass(name = "Solution", module = "ism", frozen)]
+struct PySolution {
+    inner: Solution,
+}
+
+#[pymethods]
+impl PySolution {
+    #[getter]
+    fn populations(&self) -> Vec<f64> {
+        self.inner.populations.clone()
+    }
+
+    #[getter]
+    fn iterations(&self) -> usize {
+        self.inner.iterations
+    }
+
+    // (transition, up, low, frequency [GHz], e_up [K], tex [K], tau, t_r [K],
+    // pop_up, pop_low, flux [K km s-1], flux [erg cm-2 s-1]) of every line
+    #[getter]
+    #[allow(clippy::type_complexity)]
+    fn lines(&self) -> Vec<(u32, u32, u32, f64, f64, f64, f64, f64, f64, f64, f64, f64)> {
+        self.inner.lines
+            .iter()
+            .map(|l| (
+                l.transition, l.up, l.low, l.frequency, l.e_up, l.tex, l.tau, l.t_r,
+                l.pop_up, l.pop_low, l.flux_kkms, l.flux_cgs,
+            ))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Solution(lines={}, iterations={})", self.inner.lines.len(), self.inner.iterations)
+    }
+}
+
+#[pyfunction]
+fn solve(data: &PyElementData, conditions: &PyConditions) -> PyResult<PySolution> {
+    radex::solve(&data.inner, &conditions.inner)
+        .map(|inner| PySolution { inner })
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+macro_rules! unit_conversion {
+    ($fn_name:ident, $module:ident, $quantity:ident, $($abbreviation:literal => $unit:ident),+ $(,)?) => {
+        #[pyfunction]
+        fn $fn_name(value: f64, from: &str, to: &str) -> PyResult<f64> {
+            use iau::$module::*;
+
+            let quantity = match from {
+                $($abbreviation => iau::f64::$quantity::new::<$unit>(value),)+
+                _ => return Err(PyValueError::new_err(format!("unknown unit `{}`", from))),
+            };
+
+            match to {
+                $($abbreviation => Ok(quantity.get::<$unit>()),)+
+                _ => Err(PyValueError::new_err(format!("unknown unit `{}`", to))),
+            }
+        }
+    };
+}
+
+unit_conversion!(convert_length, length, Length,
+    "au" => astronomical_unit,
+    "cm" => centimeter,
+    "m" => meter,
+    "km" => kilometer,
+    "Gm" => gigameter,
+    "LD" => lunar_distance,
+    "ly" => light_year,
+    "pc" => parsec,
+    "kpc" => kiloparsec,
+    "Mpc" => megaparsec,
+);
+
+unit_conversion!(convert_mass, mass, Mass,
+    "Msun" => solar_mass,
+    "g" => gram,
+    "kg" => kilogram,
+    "Mjupiter" => jupiter_mass,
+    "Mearth" => earth_mass,
+);
+
+unit_conversion!(convert_time, time, Time,
+    "d" => day,
+    "s" => second,
+    "y" => year,
+);
+
+#[pymodule]
+#[pyo3(name = "ism")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyElementData>()?;
+    m.add_class::<PyConditions>()?;
+    m.add_class::<PySolution>()?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_length, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_mass, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_time, m)?)?;
+
+    Ok(())
+}
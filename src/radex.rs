@@ -0,0 +1,676 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use num_traits::Float;
+
+use crate::constants::{BOLTZMANN, PLANCK, SPEED_OF_LIGHT};
+use crate::lamda::{thermal_ortho_para_ratio, CollisionPartnerId, Diagnostics, EnergyLevelRef};
+use crate::molecular::MolecularData;
+use crate::progress::{CancellationToken, Progress, ProgressCallback};
+
+// 1.0645 = FWHM / (sigma * sqrt(2 pi)) turns a FWHM line width into the
+// peak of the Gaussian profile.
+const GAUSSIAN_FACTOR: f64 = 1.0645;
+
+const MIN_ITERATIONS: usize = 10;
+const MAX_ITERATIONS: usize = 10_000;
+const CONVERGENCE: f64 = 1e-6;
+// Only lines thicker than this are considered for convergence, as RADEX does.
+const TAU_THRESHOLD: f64 = 0.01;
+// Population of the highest level above which the level ladder is likely
+// too short for the conditions.
+const TRUNCATION_POPULATION: f64 = 1e-3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(try_from = "crate::interop::spectralradex::GeometryRepr", into = "u32"))]
+pub enum Geometry {
+    Sphere = 1,
+    LargeVelocityGradient,
+    Slab,
+}
+
+impl From<Geometry> for u32 {
+    fn from(item: Geometry) -> Self {
+        item as u32
+    }
+}
+
+impl Geometry {
+    // Escape probabilities of RADEX (van der Tak et al. 2007, eqs. 19-21).
+    pub fn escape_probability<F: Float>(&self, tau: F) -> F {
+        let c = constant::<F>;
+        let one = F::one();
+
+        match self {
+            Geometry::Sphere => {
+                let taur = tau / c(2.0);
+                match taur.abs() {
+                    x if x < c(0.1) => {
+                        one - c(0.75) * taur + taur.powi(2) / c(2.5) - taur.powi(3) / c(6.0) + taur.powi(4) / c(17.5)
+                    }
+                    x if x > c(50.0) => c(0.75) / taur,
+                    _ => {
+                        c(0.75) / taur * (one - one / (c(2.0) * taur.powi(2))
+                            + (one / taur + one / (c(2.0) * taur.powi(2))) * (c(-2.0) * taur).exp())
+                    }
+                }
+            }
+            Geometry::LargeVelocityGradient => match tau.abs() {
+                x if x < c(0.01) => one - tau / c(2.0),
+                _ => (one - (-tau).exp()) / tau,
+            },
+            Geometry::Slab => match tau.abs() {
+                x if x < c(0.01) => one - c(1.5) * tau,
+                _ => (one - (c(-3.0) * tau).exp()) / (c(3.0) * tau),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Geometry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Geometry::Sphere => write!(f, "uniform sphere"),
+            Geometry::LargeVelocityGradient => write!(f, "expanding sphere (LVG)"),
+            Geometry::Slab => write!(f, "plane-parallel slab"),
+        }
+    }
+}
+
+// The solver runs in the scalar type of the conditions, e.g. `f32` for
+// single precision or a dual number for derivatives of the results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conditions<F = f64> {
+    // Kinetic temperature, K
+    pub tkin: F,
+    // Background radiation temperature, K
+    pub tbg: F,
+    // Collision partner densities, cm-3
+    pub densities: Vec<(CollisionPartnerId, F)>,
+    // Column density of the species, cm-2
+    pub cdmol: F,
+    // Line width (FWHM), km s-1
+    pub linewidth: F,
+    pub geometry: Geometry,
+}
+
+impl Default for Conditions {
+    fn default() -> Self {
+        Self {
+            tkin: 30.0,
+            tbg: 2.73,
+            densities: vec!((CollisionPartnerId::H2, 1.0e5)),
+            cdmol: 1.0e13,
+            linewidth: 1.0,
+            geometry: Geometry::Sphere,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SolverError {
+    NoRadiativeTransitions,
+    NoCollisionPartners,
+    UnknownLevel {
+        transition: u32,
+        level: u32,
+    },
+    InvalidConditions {
+        note: String,
+    },
+    SingularMatrix,
+    NotConverged {
+        iterations: usize,
+    },
+    Cancelled,
+}
+
+impl std::fmt::Display for SolverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverError::NoRadiativeTransitions => write!(f, "datafile has no radiative transitions"),
+            SolverError::NoCollisionPartners => {
+                write!(f, "none of the given collision partners has rates in the datafile")
+            }
+            SolverError::UnknownLevel { transition, level } => {
+                write!(f, "transition {} refers to unknown level {}", transition, level)
+            }
+            SolverError::InvalidConditions { note } => write!(f, "invalid physical conditions: {}", note),
+            SolverError::SingularMatrix => write!(f, "statistical equilibrium matrix is singular"),
+            SolverError::NotConverged { iterations } => {
+                write!(f, "level populations did not converge in {} iterations", iterations)
+            }
+            SolverError::Cancelled => write!(f, "computation was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct LineResult<F = f64> {
+    pub transition: u32,
+    pub up: u32,
+    pub low: u32,
+    pub qnums_up: String,
+    pub qnums_low: String,
+    // Upper level energy, K
+    pub e_up: F,
+    // Rest frequency, GHz
+    pub frequency: F,
+    // Rest wavelength, um
+    pub wavelength: F,
+    pub tex: F,
+    pub tau: F,
+    // Radiation temperature above the background, K
+    pub t_r: F,
+    pub pop_up: F,
+    pub pop_low: F,
+    // Integrated intensity, K km s-1
+    pub flux_kkms: F,
+    // Line flux, erg cm-2 s-1
+    pub flux_cgs: F,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Solution<F = f64> {
+    pub populations: Vec<F>,
+    pub lines: Vec<LineResult<F>>,
+    pub iterations: usize,
+}
+
+// Transition with everything the iteration needs, levels as indices.
+struct Line<F> {
+    up: usize,
+    low: usize,
+    aeinst: F,
+    // Frequency, Hz
+    nu: F,
+    // Photon occupation number of the background
+    background: F,
+}
+
+// Constants and molecular data are f64; the solver works in `F`.
+fn constant<F: Float>(x: f64) -> F {
+    F::from(x).unwrap_or_else(F::nan)
+}
+
+fn occupation<F: Float>(nu: F, temperature: F) -> F {
+    match temperature > F::zero() {
+        true => F::one() / ((constant::<F>(PLANCK) * nu / (constant::<F>(BOLTZMANN) * temperature)).exp() - F::one()),
+        false => F::zero(),
+    }
+}
+
+// RADEX splits a total H2 density between the ortho and para species with the
+// thermal ratio when the datafile has no rates for H2 as a whole.
+pub(crate) fn partner_densities<F: Float, D: MolecularData + ?Sized>(
+    data: &D,
+    conditions: &Conditions<F>,
+) -> Vec<(CollisionPartnerId, F)> {
+    let partners = data.collision_partners();
+    let has = |id| partners.contains(&id);
+    let given = |id: CollisionPartnerId| conditions.densities.iter().any(|(p, n)| *p == id && *n > F::zero());
+
+    let mut densities = conditions.densities.clone();
+    if !has(CollisionPartnerId::H2) && !given(CollisionPartnerId::pH2) && !given(CollisionPartnerId::oH2) {
+        if let Some(&(_, n)) = conditions.densities.iter().find(|(p, _)| *p == CollisionPartnerId::H2) {
+            let opr = constant::<F>(thermal_ortho_para_ratio(conditions.tkin.to_f64().unwrap_or(f64::NAN)));
+            densities.push((CollisionPartnerId::pH2, n / (opr + F::one())));
+            densities.push((CollisionPartnerId::oH2, n / (F::one() + F::one() / opr)));
+        }
+    }
+
+    densities
+}
+
+fn collision_matrix<F: Float, D: MolecularData + ?Sized>(
+    data: &D,
+    levels: &[EnergyLevelRef<'_>],
+    conditions: &Conditions<F>,
+    index: &HashMap<u32, usize>,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<Vec<F>>, SolverError> {
+    let nlev = levels.len();
+    let partners = data.collision_partners();
+    let tkin = conditions.tkin.to_f64().unwrap_or(f64::NAN);
+    let mut matrix = vec!(vec!(F::zero(); nlev); nlev);
+    let mut used = false;
+
+    for (partner, density) in partner_densities(data, conditions) {
+        if density <= F::zero() || !partners.contains(&partner) {
+            continue;
+        }
+        used = true;
+
+        if let Some((first, last)) = data.collision_temperature_range(partner.clone()) {
+            if tkin < first || tkin > last {
+                diagnostics.warn(
+                    "rates-clamped",
+                    format!(
+                        "Kinetic temperature {} K is outside the {}-{} K tabulated for {}, rates at the nearest temperature are used",
+                        tkin, first, last, partner,
+                    ),
+                );
+            }
+        }
+
+        for cr in data.collision_rates(partner, tkin) {
+            let level = |n: u32| index.get(&n).copied().ok_or(SolverError::UnknownLevel { transition: cr.transition, level: n });
+            let (up, low) = (level(cr.up)?, level(cr.low)?);
+            let (eu, el) = (&levels[up], &levels[low]);
+
+            let down = density * constant(cr.rate);
+            let hck = constant::<F>(-(eu.energy - el.energy) * PLANCK * SPEED_OF_LIGHT / BOLTZMANN);
+            let boltzmann = (hck / conditions.tkin).exp();
+            matrix[up][low] = matrix[up][low] + down;
+            matrix[low][up] = matrix[low][up] + down * constant(eu.stat_weight / el.stat_weight) * boltzmann;
+        }
+    }
+
+    match used {
+        true => Ok(matrix),
+        false => Err(SolverError::NoCollisionPartners),
+    }
+}
+
+// Gaussian elimination with partial pivoting.
+pub(crate) fn solve_linear<F: Float>(mut a: Vec<Vec<F>>, mut b: Vec<F>) -> Result<Vec<F>, SolverError> {
+    let n = b.len();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().partial_cmp(&a[j][col].abs()).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or(col);
+        if a[pivot][col] == F::zero() || !a[pivot][col].is_finite() {
+            return Err(SolverError::SingularMatrix);
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        for row in col + 1..n {
+            let factor = a[row][col] / a[col][col];
+            if factor == F::zero() {
+                continue;
+            }
+            let (top, bottom) = a.split_at_mut(row);
+            for (x, &p) in bottom[0][col..].iter_mut().zip(top[col][col..].iter()) {
+                *x = *x - factor * p;
+            }
+            b[row] = b[row] - factor * b[col];
+        }
+    }
+
+    let mut x = vec!(F::zero(); n);
+    for row in (0..n).rev() {
+        let sum = (row + 1..n).fold(F::zero(), |sum, k| sum + a[row][k] * x[k]);
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+
+    Ok(x)
+}
+
+pub fn solve<F: Float, D: MolecularData + ?Sized>(data: &D, conditions: &Conditions<F>) -> Result<Solution<F>, SolverError> {
+    solve_with_diagnostics(data, conditions, &mut Diagnostics::new())
+}
+
+// Solves like `solve`, adding warnings about rates clamped to the tabulated
+// temperatures, masing lines and a too short level ladder to `diagnostics`.
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(
+        species = %data.species(),
+        tkin = conditions.tkin.to_f64(),
+        tbg = conditions.tbg.to_f64(),
+        cdmol = conditions.cdmol.to_f64(),
+        dv = conditions.linewidth.to_f64(),
+    )
+))]
+pub fn solve_with_diagnostics<F: Float, D: MolecularData + ?Sized>(
+    data: &D,
+    conditions: &Conditions<F>,
+    diagnostics: &mut Diagnostics,
+) -> Result<Solution<F>, SolverError> {
+    let invalid = |note: &str| Err(SolverError::InvalidConditions { note: String::from(note) });
+    if conditions.tkin.is_nan() || conditions.tkin <= F::zero() {
+        return invalid("kinetic temperature must be positive");
+    }
+    if conditions.linewidth.is_nan() || conditions.linewidth <= F::zero() {
+        return invalid("line width must be positive");
+    }
+    if conditions.cdmol.is_nan() || conditions.cdmol < F::zero() || conditions.tbg.is_nan() || conditions.tbg < F::zero() {
+        return invalid("column density and background temperature must not be negative");
+    }
+    let transitions = data.radiative_transitions();
+    if transitions.is_empty() {
+        return Err(SolverError::NoRadiativeTransitions);
+    }
+
+    let levels = &data.energy_levels();
+    let index = levels
+        .iter()
+        .enumerate()
+        .map(|(i, el)| (el.level, i))
+        .collect::<HashMap<_, _>>();
+    let nlev = levels.len();
+
+    let lines = transitions
+        .iter()
+        .map(|rt| {
+            let level = |n: u32| index.get(&n).copied().ok_or(SolverError::UnknownLevel { transition: rt.transition, level: n });
+            let (up, low) = (level(rt.up)?, level(rt.low)?);
+            let nu = constant::<F>((levels[up].energy - levels[low].energy) * SPEED_OF_LIGHT);
+            Ok(Line { up, low, aeinst: constant(rt.aeinst), nu, background: occupation(nu, conditions.tbg) })
+        })
+        .collect::<Result<Vec<_>, SolverError>>()?;
+
+    let collisions = collision_matrix(data, levels, conditions, &index, diagnostics)?;
+    let weight_ratio = |line: &Line<F>| constant::<F>(levels[line.up].stat_weight / levels[line.low].stat_weight);
+    // Column density over line width in cm s-1
+    let cddv = conditions.cdmol / (conditions.linewidth * constant(1e5));
+    let optical_depth = |line: &Line<F>, x: &[F]| {
+        let sigma = line.nu / constant(SPEED_OF_LIGHT);
+        cddv * (x[line.low] * weight_ratio(line) - x[line.up]) * line.aeinst
+            / (constant::<F>(GAUSSIAN_FACTOR * 8.0 * std::f64::consts::PI) * sigma.powi(3))
+    };
+    let excitation_temperature = |line: &Line<F>, x: &[F]| {
+        let ratio = x[line.low] * weight_ratio(line) / x[line.up];
+        match ratio.is_finite() && ratio > F::zero() {
+            true => constant::<F>(PLANCK) * line.nu / (constant::<F>(BOLTZMANN) * ratio.ln()),
+            false => F::zero(),
+        }
+    };
+    // Single precision cannot resolve changes much below its epsilon
+    let convergence = constant::<F>(CONVERGENCE).max(F::epsilon() * constant(10.0));
+
+    // The first pass is optically thin, every photon escapes.
+    let mut beta = vec!(F::one(); lines.len());
+    let mut populations = vec!(F::zero(); nlev);
+    let mut tex = vec!(F::zero(); lines.len());
+    let mut iterations = 0;
+
+    loop {
+        iterations += 1;
+
+        // rates[i][j] is the rate from level i to level j
+        let mut rates = collisions.clone();
+        for (line, &b) in lines.iter().zip(beta.iter()) {
+            rates[line.up][line.low] = rates[line.up][line.low] + line.aeinst * b * (F::one() + line.background);
+            rates[line.low][line.up] = rates[line.low][line.up] + line.aeinst * b * weight_ratio(line) * line.background;
+        }
+
+        let mut matrix = vec!(vec!(F::zero(); nlev); nlev);
+        for i in 0..nlev {
+            for j in 0..nlev {
+                if i != j {
+                    matrix[j][i] = matrix[j][i] + rates[i][j];
+                    matrix[i][i] = matrix[i][i] - rates[i][j];
+                }
+            }
+        }
+        // Populations are normalised to unity
+        matrix[0] = vec!(F::one(); nlev);
+        let mut rhs = vec!(F::zero(); nlev);
+        rhs[0] = F::one();
+
+        let new = solve_linear(matrix, rhs)?;
+        populations = match iterations {
+            1 => new,
+            _ => populations.iter().zip(new.iter()).map(|(&old, &new)| constant::<F>(0.5) * (old + new)).collect(),
+        };
+
+        let mut unconverged = 0;
+        for (i, line) in lines.iter().enumerate() {
+            let tau = optical_depth(line, &populations);
+            let t = excitation_temperature(line, &populations);
+            if tau > constant(TAU_THRESHOLD) && ((t - tex[i]) / t).abs() > convergence {
+                unconverged += 1;
+            }
+            tex[i] = t;
+            beta[i] = conditions.geometry.escape_probability(tau);
+        }
+        trace_event!(trace, iteration = iterations, unconverged, "iteration finished");
+
+        if unconverged == 0 && iterations >= MIN_ITERATIONS {
+            trace_event!(debug, iterations, "level populations converged");
+            break;
+        }
+        if iterations >= MAX_ITERATIONS {
+            trace_event!(warn, iterations, unconverged, "level populations did not converge");
+            return Err(SolverError::NotConverged { iterations });
+        }
+    }
+
+    let results = lines
+        .iter()
+        .zip(transitions.iter())
+        .zip(tex.iter())
+        .map(|((line, rt), &tex)| {
+            let tau = optical_depth(line, &populations);
+            let hnuk = constant::<F>(PLANCK / BOLTZMANN) * line.nu;
+            let t_r = hnuk * (occupation(line.nu, tex) - line.background) * (F::one() - (-tau).exp());
+            let flux_kkms = constant::<F>(GAUSSIAN_FACTOR) * t_r * conditions.linewidth;
+            let sigma = line.nu / constant(SPEED_OF_LIGHT);
+
+            LineResult {
+                transition: rt.transition,
+                up: rt.up,
+                low: rt.low,
+                qnums_up: String::from(levels[line.up].qnums),
+                qnums_low: String::from(levels[line.low].qnums),
+                e_up: constant(levels[line.up].energy * PLANCK * SPEED_OF_LIGHT / BOLTZMANN),
+                frequency: line.nu * constant(1e-9),
+                wavelength: constant::<F>(SPEED_OF_LIGHT) / line.nu * constant(1e4),
+                tex,
+                tau,
+                t_r,
+                pop_up: populations[line.up],
+                pop_low: populations[line.low],
+                flux_kkms,
+                flux_cgs: constant::<F>(8.0 * std::f64::consts::PI * BOLTZMANN * 1e5) * sigma.powi(3) * flux_kkms,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for line in results.iter().filter(|l| l.tau < F::zero()) {
+        diagnostics.warn(
+            "maser",
+            format!(
+                "Line {} ({} -> {}) is masing with optical depth {:.3e}",
+                line.transition,
+                line.up,
+                line.low,
+                line.tau.to_f64().unwrap_or(f64::NAN),
+            ),
+        );
+    }
+    if nlev > 1 && populations[nlev - 1] > constant(TRUNCATION_POPULATION) {
+        diagnostics.warn(
+            "levels-truncated",
+            format!(
+                "Highest level holds {:.2e} of the population, the datafile may have too few levels for these conditions",
+                populations[nlev - 1].to_f64().unwrap_or(f64::NAN),
+            ),
+        );
+    }
+
+    Ok(Solution { populations, lines: results, iterations })
+}
+
+// Solves every point of a parameter grid, in parallel with the `parallel` feature.
+pub fn solve_grid<F, D>(data: &D, grid: &[Conditions<F>]) -> Vec<Result<Solution<F>, SolverError>>
+where
+    F: Float + Send + Sync,
+    D: MolecularData + Sync + ?Sized,
+{
+    solve_grid_with_progress(data, grid, &|_| ())
+}
+
+pub fn solve_grid_with_progress<F, D>(
+    data: &D,
+    grid: &[Conditions<F>],
+    progress: ProgressCallback<'_, Conditions<F>>,
+) -> Vec<Result<Solution<F>, SolverError>>
+where
+    F: Float + Send + Sync,
+    D: MolecularData + Sync + ?Sized,
+{
+    solve_grid_cancellable(data, grid, progress, &CancellationToken::new())
+}
+
+// Once `cancel` is raised the remaining points are skipped with
+// `SolverError::Cancelled`; the points solved before are kept.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "info", skip_all, fields(species = %data.species(), points = grid.len())))]
+pub fn solve_grid_cancellable<F, D>(
+    data: &D,
+    grid: &[Conditions<F>],
+    progress: ProgressCallback<'_, Conditions<F>>,
+    cancel: &CancellationToken,
+) -> Vec<Result<Solution<F>, SolverError>>
+where
+    F: Float + Send + Sync,
+    D: MolecularData + Sync + ?Sized,
+{
+    let completed = AtomicUsize::new(0);
+    let solve_point = |conditions: &Conditions<F>| {
+        if cancel.is_cancelled() {
+            return Err(SolverError::Cancelled);
+        }
+        let solution = solve(data, conditions);
+        let completed = completed.fetch_add(1, Ordering::Relaxed) + 1;
+        progress(Progress { completed, total: grid.len(), current: conditions });
+        solution
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        grid.par_iter().map(solve_point).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        grid.iter().map(solve_point).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lamda::{CollisionPartnerData, CollisionalRates, ElementData, EnergyLevel, RadiativeTransition};
+
+    fn two_level() -> ElementData {
+        ElementData {
+            name: String::from("CO"),
+            weight: 28.0,
+            energy_levels: vec!(
+                EnergyLevel { level: 1, energy: 0.0, stat_weight: 1.0, qnums: String::from("0") },
+                EnergyLevel { level: 2, energy: 3.845033413, stat_weight: 3.0, qnums: String::from("1") },
+            ),
+            radiative_transitions: vec!(
+                RadiativeTransition { transition: 1, up: 2, low: 1, aeinst: 7.203e-08, ..Default::default() },
+            ),
+            collision_partners: vec!(CollisionPartnerData {
+                name: CollisionPartnerId::pH2,
+                information: String::new(),
+                temperatures: vec!(10.0, 100.0),
+                rates: vec!(CollisionalRates { transition: 1, up: 2, low: 1, rates: vec!(3.0e-11, 3.0e-11) }),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn escape_probability_limits() {
+        for geometry in [Geometry::Sphere, Geometry::LargeVelocityGradient, Geometry::Slab] {
+            assert!((geometry.escape_probability(1e-6) - 1.0).abs() < 1e-5, "Wrong thin limit for {}", geometry);
+            assert!(geometry.escape_probability(1e3) < 1e-2, "Wrong thick limit for {}", geometry);
+        }
+    }
+
+    #[test]
+    fn two_level_limits() {
+        let ed = two_level();
+        let dense = Conditions { densities: vec!((CollisionPartnerId::H2, 1e10)), ..Default::default() };
+        let sparse = Conditions { densities: vec!((CollisionPartnerId::pH2, 1e-3)), ..Default::default() };
+
+        let dense = solve(&ed, &dense).unwrap();
+        let sparse = solve(&ed, &sparse).unwrap();
+
+        assert!((dense.lines[0].tex - 30.0).abs() < 1e-2, "Wrong LTE excitation {}", dense.lines[0].tex);
+        assert!((sparse.lines[0].tex - 2.73).abs() < 1e-2, "Wrong subthermal excitation {}", sparse.lines[0].tex);
+        assert!((dense.populations.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn single_precision() {
+        let conditions = Conditions { densities: vec!((CollisionPartnerId::pH2, 1e4)), ..Default::default() };
+        let single = Conditions::<f32> {
+            tkin: 30.0,
+            tbg: 2.73,
+            densities: vec!((CollisionPartnerId::pH2, 1e4)),
+            cdmol: 1e13,
+            linewidth: 1.0,
+            geometry: Geometry::Sphere,
+        };
+
+        let tex = solve(&two_level(), &conditions).unwrap().lines[0].tex;
+        let tex_single = solve(&two_level(), &single).unwrap().lines[0].tex;
+
+        assert!(((tex_single as f64 - tex) / tex).abs() < 1e-4, "Wrong result for f32: {} and {}", tex_single, tex);
+    }
+
+    #[test]
+    fn grid_keeps_order() {
+        let grid = [10.0, 20.0, 40.0]
+            .iter()
+            .map(|&tkin| Conditions { tkin, densities: vec!((CollisionPartnerId::pH2, 1e10)), ..Default::default() })
+            .collect::<Vec<_>>();
+
+        let calls = AtomicUsize::new(0);
+        let solutions = solve_grid_with_progress(&two_level(), &grid, &|p| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            assert_eq!(p.total, 3);
+        });
+
+        assert_eq!(calls.into_inner(), 3);
+
+        for (conditions, solution) in grid.iter().zip(solutions.iter()) {
+            let tex = solution.as_ref().unwrap().lines[0].tex;
+            assert!((tex - conditions.tkin).abs() < 1e-2, "Wrong result for tkin {}: {}", conditions.tkin, tex);
+        }
+    }
+
+    #[test]
+    fn cancelled_grid() {
+        let grid = vec!(Conditions { densities: vec!((CollisionPartnerId::pH2, 1e10)), ..Default::default() }; 4);
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let solutions = solve_grid_cancellable(&two_level(), &grid, &|_| (), &cancel);
+
+        assert!(solutions.iter().all(|s| s == &Err(SolverError::Cancelled)), "Wrong result for cancelled grid");
+    }
+
+    #[test]
+    fn solver_warnings() {
+        let conditions = Conditions { tkin: 500.0, densities: vec!((CollisionPartnerId::pH2, 1e10)), ..Default::default() };
+        let mut diagnostics = Diagnostics::new();
+
+        assert!(solve_with_diagnostics(&two_level(), &conditions, &mut diagnostics).is_ok());
+
+        let codes = diagnostics.warnings().iter().map(|d| d.code.as_str()).collect::<Vec<_>>();
+        assert_eq!(codes, vec!("rates-clamped", "levels-truncated"));
+    }
+
+    #[test]
+    fn missing_collision_partner() {
+        let conditions = Conditions { densities: vec!((CollisionPartnerId::electrons, 1e3)), ..Default::default() };
+
+        assert_eq!(solve(&two_level(), &conditions), Err(SolverError::NoCollisionPartners));
+    }
+}
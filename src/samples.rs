@@ -0,0 +1,28 @@
+use crate::lamda::ElementData;
+
+// Reference datafiles from LAMDA, embedded so that examples, tests and first
+// experiments work without downloading anything.
+
+pub const OATOM: &str = include_str!("../data/oatom.dat");
+
+// Every bundled datafile, as (LAMDA name, contents)
+pub const ALL: [(&str, &str); 1] = [("oatom", OATOM)];
+
+// Fine structure levels of atomic oxygen, with rates for H, He, p-H2, o-H2, H+ and electrons
+pub fn oatom() -> ElementData {
+    OATOM.parse().expect("bundled datafile is valid")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn bundled_datafiles_parse() {
+        for (name, s) in ALL {
+            assert!(s.parse::<ElementData>().is_ok(), "Wrong result for bundled datafile `{}`", name);
+        }
+        assert_eq!(oatom().energy_levels.len(), 3);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::radex::solve_linear;
+
+use super::{Spectrum, SpectrumError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Baseline {
+    Polynomial {
+        order: usize,
+    },
+    // Cubic B-spline with `segments` equal pieces over the spectral axis
+    Spline {
+        segments: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineFit {
+    // Baseline at every channel, in the intensity unit
+    pub values: Vec<f64>,
+    // Scatter of the fitted channels around the baseline
+    pub rms: f64,
+    // Number of channels outside the line windows
+    pub channels: usize,
+}
+
+impl Baseline {
+    fn size(&self) -> usize {
+        match *self {
+            Baseline::Polynomial { order } => order + 1,
+            Baseline::Spline { segments } => segments.max(1) + 3,
+        }
+    }
+
+    // Basis functions at `t`, the position on the spectral axis scaled to
+    // [0, 1]; polynomials use Chebyshev polynomials of `2t - 1`, which keep
+    // the normal equations well conditioned.
+    fn basis(&self, t: f64) -> Vec<f64> {
+        match *self {
+            Baseline::Polynomial { order } => {
+                let x = 2.0 * t - 1.0;
+                let mut values = vec!(1.0, x);
+                for k in 2..=order {
+                    values.push(2.0 * x * values[k - 1] - values[k - 2]);
+                }
+                values.truncate(order + 1);
+                values
+            }
+            Baseline::Spline { segments } => {
+                let segments = segments.max(1);
+                let position = t.clamp(0.0, 1.0) * segments as f64;
+                let i = (position.floor() as usize).min(segments - 1);
+                let u = position - i as f64;
+
+                let mut values = vec!(0.0; segments + 3);
+                values[i] = (1.0 - u).powi(3) / 6.0;
+                values[i + 1] = (3.0 * u.powi(3) - 6.0 * u.powi(2) + 4.0) / 6.0;
+                values[i + 2] = (-3.0 * u.powi(3) + 3.0 * u.powi(2) + 3.0 * u + 1.0) / 6.0;
+                values[i + 3] = u.powi(3) / 6.0;
+                values
+            }
+        }
+    }
+}
+
+impl Spectrum {
+    // Least-squares baseline through the channels outside `windows`, ranges
+    // of the spectral axis in its own unit that hold lines. Channels with a
+    // NaN intensity are left out as well.
+    pub fn fit_baseline(&self, baseline: Baseline, windows: &[(f64, f64)]) -> Result<BaselineFit, SpectrumError> {
+        let x = &self.spectral.values;
+        let y = &self.intensity.values;
+        let (min, max) = x.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+        let scale = |v: f64| if max > min { (v - min) / (max - min) } else { 0.0 };
+
+        let masked = |v: f64| windows.iter().any(|&(a, b)| v >= a.min(b) && v <= a.max(b));
+        let used = (0..x.len()).filter(|&i| !masked(x[i]) && y[i].is_finite()).collect::<Vec<_>>();
+        let n = baseline.size();
+        if used.len() < n {
+            return Err(SpectrumError::Fit {
+                note: format!("{} free channels cannot constrain a baseline with {} parameters", used.len(), n),
+            });
+        }
+
+        let mut a = vec!(vec!(0.0; n); n);
+        let mut b = vec!(0.0; n);
+        for &i in used.iter() {
+            let basis = baseline.basis(scale(x[i]));
+            for (j, bj) in basis.iter().enumerate() {
+                b[j] += bj * y[i];
+                for (k, bk) in basis.iter().enumerate() {
+                    a[j][k] += bj * bk;
+                }
+            }
+        }
+        let coefficients = solve_linear(a, b).map_err(|_| SpectrumError::Fit {
+            note: String::from("the line windows leave part of the baseline unconstrained"),
+        })?;
+
+        let values = x
+            .iter()
+            .map(|&v| baseline.basis(scale(v)).iter().zip(coefficients.iter()).map(|(b, c)| b * c).sum::<f64>())
+            .collect::<Vec<_>>();
+        let rms = (used.iter().map(|&i| (y[i] - values[i]).powi(2)).sum::<f64>() / used.len() as f64).sqrt();
+
+        Ok(BaselineFit { values, rms, channels: used.len() })
+    }
+
+    // Fits a baseline like `fit_baseline` and subtracts it from the intensities.
+    pub fn subtract_baseline(&mut self, baseline: Baseline, windows: &[(f64, f64)]) -> Result<BaselineFit, SpectrumError> {
+        let fit = self.fit_baseline(baseline, windows)?;
+        for (y, b) in self.intensity.values.iter_mut().zip(fit.values.iter()) {
+            *y -= b;
+        }
+
+        Ok(fit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::spectrum::{Axis, Observation};
+
+    fn spectrum(f: impl Fn(f64) -> f64) -> Spectrum {
+        let velocities = (0..101).map(|i| -50.0 + i as f64).collect::<Vec<_>>();
+        Spectrum {
+            name: String::from("test"),
+            rest_frequency: 100.0,
+            intensity: Axis::new("K", velocities.iter().map(|&v| f(v)).collect()),
+            spectral: Axis::new("km s-1", velocities),
+            observation: Observation::default(),
+        }
+    }
+
+    #[test]
+    fn remove_baselines() {
+        let line = |v: f64| if v.abs() < 5.0 { 3.0 } else { 0.0 };
+        let mut s = spectrum(|v| 0.5 + 0.01 * v - 2e-4 * v * v + line(v));
+
+        let fit = s.subtract_baseline(Baseline::Polynomial { order: 2 }, &[(-6.0, 6.0)]).unwrap();
+        assert_eq!(fit.channels, 88);
+        assert!(fit.rms < 1e-12, "Wrong result for rms {}", fit.rms);
+        assert!((s.intensity.values[50] - 3.0).abs() < 1e-9, "Wrong result for line after subtraction");
+
+        let wavy = spectrum(|v| (v / 20.0).sin() + line(v));
+        let fit = wavy.fit_baseline(Baseline::Spline { segments: 6 }, &[(-6.0, 6.0)]).unwrap();
+        assert!(fit.rms < 1e-2, "Wrong result for spline rms {}", fit.rms);
+
+        let masked = wavy.fit_baseline(Baseline::Polynomial { order: 3 }, &[(-60.0, 60.0)]);
+        assert!(matches!(masked, Err(SpectrumError::Fit { .. })));
+    }
+}
@@ -0,0 +1,193 @@
+use super::{Axis, Observation, Spectrum, SpectrumError};
+
+// Spectra on a grid of `width` by `height` pixels sharing one spectral axis.
+// As in FITS, `data` holds one plane per channel, x varying fastest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cube {
+    pub name: String,
+    // Rest frequency of the velocity axis, GHz
+    pub rest_frequency: f64,
+    pub width: usize,
+    pub height: usize,
+    pub spectral: Axis,
+    pub intensity_unit: String,
+    pub data: Vec<f64>,
+}
+
+// Map of a moment with its 1-sigma uncertainties; pixels without emission
+// above the threshold are NaN.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MomentMap {
+    pub unit: String,
+    pub values: Vec<f64>,
+    pub errors: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Moments {
+    pub width: usize,
+    pub height: usize,
+    // Integrated intensity
+    pub zeroth: MomentMap,
+    // Intensity-weighted mean of the spectral axis
+    pub first: MomentMap,
+    // Intensity-weighted dispersion around the first moment
+    pub second: MomentMap,
+}
+
+impl Cube {
+    pub fn new(
+        name: &str,
+        rest_frequency: f64,
+        (width, height): (usize, usize),
+        spectral: Axis,
+        intensity_unit: &str,
+        data: Vec<f64>,
+    ) -> Result<Self, SpectrumError> {
+        if data.len() != width * height * spectral.values.len() {
+            return Err(SpectrumError::Shape {
+                note: format!(
+                    "{} values do not fill a cube of {}x{} pixels and {} channels",
+                    data.len(),
+                    width,
+                    height,
+                    spectral.values.len()
+                ),
+            });
+        }
+
+        Ok(Self {
+            name: String::from(name),
+            rest_frequency,
+            width,
+            height,
+            spectral,
+            intensity_unit: String::from(intensity_unit),
+            data,
+        })
+    }
+
+    // Cube of spectra given row by row, e.g. synthetic spectra of a grid of
+    // models. All spectra need the spectral axis of the first.
+    pub fn from_spectra(width: usize, height: usize, spectra: &[Spectrum]) -> Result<Self, SpectrumError> {
+        let first = spectra.first().ok_or_else(|| SpectrumError::Shape { note: String::from("no spectra were given") })?;
+        if spectra.len() != width * height || spectra.iter().any(|s| s.spectral != first.spectral) {
+            return Err(SpectrumError::Shape {
+                note: format!("cube of {}x{} pixels needs as many spectra on the same spectral axis", width, height),
+            });
+        }
+
+        let data = (0..first.len())
+            .flat_map(|channel| spectra.iter().map(move |s| s.intensity.values[channel]))
+            .collect();
+        Self::new(&first.name, first.rest_frequency, (width, height), first.spectral.clone(), &first.intensity.unit, data)
+    }
+
+    pub fn channels(&self) -> usize {
+        self.spectral.values.len()
+    }
+
+    fn at(&self, x: usize, y: usize, channel: usize) -> f64 {
+        self.data[(channel * self.height + y) * self.width + x]
+    }
+
+    pub fn spectrum(&self, x: usize, y: usize) -> Spectrum {
+        Spectrum {
+            name: self.name.clone(),
+            rest_frequency: self.rest_frequency,
+            spectral: self.spectral.clone(),
+            intensity: Axis::new(&self.intensity_unit, (0..self.channels()).map(|c| self.at(x, y, c)).collect()),
+            observation: Observation { object: self.name.clone(), ..Default::default() },
+        }
+    }
+
+    // Moments 0, 1 and 2 of every pixel from the channels brighter than
+    // `threshold`, with errors propagated from the channel noise `rms`. Units
+    // follow the spectral axis, e.g. K km s-1, km s-1 and km s-1.
+    pub fn moments(&self, threshold: f64, rms: f64) -> Moments {
+        let v = &self.spectral.values;
+        // Width of every channel, from the distance to its neighbours
+        let dv = (0..v.len())
+            .map(|i| {
+                let (low, high) = (i.saturating_sub(1), (i + 1).min(v.len() - 1));
+                match high > low {
+                    true => (v[high] - v[low]).abs() / (high - low) as f64,
+                    false => 0.0,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let pixels = self.width * self.height;
+        let mut maps = [(); 3].map(|_| (vec!(f64::NAN; pixels), vec!(f64::NAN; pixels)));
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let used = (0..self.channels())
+                    .map(|c| (c, self.at(x, y, c)))
+                    .filter(|&(_, t)| t.is_finite() && t > threshold)
+                    .collect::<Vec<_>>();
+                let m0 = used.iter().map(|&(c, t)| t * dv[c]).sum::<f64>();
+                if used.is_empty() || m0 <= 0.0 {
+                    continue;
+                }
+                let m1 = used.iter().map(|&(c, t)| t * v[c] * dv[c]).sum::<f64>() / m0;
+                let m2 = used.iter().map(|&(c, t)| t * (v[c] - m1).powi(2) * dv[c]).sum::<f64>() / m0;
+
+                let noise = |f: &dyn Fn(usize) -> f64| rms * used.iter().map(|&(c, _)| (f(c) * dv[c]).powi(2)).sum::<f64>().sqrt();
+                let pixel = y * self.width + x;
+                maps[0].0[pixel] = m0;
+                maps[0].1[pixel] = noise(&|_| 1.0);
+                maps[1].0[pixel] = m1;
+                maps[1].1[pixel] = noise(&|c| v[c] - m1) / m0;
+                maps[2].0[pixel] = m2.sqrt();
+                maps[2].1[pixel] = noise(&|c| (v[c] - m1).powi(2) - m2) / (m0 * 2.0 * m2.sqrt());
+            }
+        }
+
+        let [zeroth, first, second] = maps;
+        let spectral_unit = self.spectral.unit.clone();
+        Moments {
+            width: self.width,
+            height: self.height,
+            zeroth: MomentMap { unit: format!("{} {}", self.intensity_unit, spectral_unit), values: zeroth.0, errors: zeroth.1 },
+            first: MomentMap { unit: spectral_unit.clone(), values: first.0, errors: first.1 },
+            second: MomentMap { unit: spectral_unit, values: second.0, errors: second.1 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::spectrum::Gaussian;
+
+    #[test]
+    fn gaussian_moments() {
+        let velocities = (0..401).map(|i| -20.0 + 0.1 * i as f64).collect::<Vec<_>>();
+        let spectra = [0.0, 2.0].map(|centre| {
+            let line = Gaussian { amplitude: 1.0, centre, width: 2.0 };
+            Spectrum {
+                name: String::from("CO"),
+                rest_frequency: 115.271_202,
+                spectral: Axis::new("km s-1", velocities.clone()),
+                intensity: Axis::new("K", velocities.iter().map(|&v| line.at(v)).collect()),
+                observation: Observation::default(),
+            }
+        });
+        let cube = Cube::from_spectra(2, 1, &spectra).unwrap();
+        assert_eq!(cube.spectrum(1, 0).intensity, spectra[1].intensity);
+
+        let moments = cube.moments(1e-6, 0.01);
+        let sigma = 2.0 / (8.0 * core::f64::consts::LN_2).sqrt();
+
+        assert_eq!(moments.zeroth.unit, "K km s-1");
+        assert!((moments.zeroth.values[0] - Gaussian { amplitude: 1.0, centre: 0.0, width: 2.0 }.integrated()).abs() < 1e-3);
+        assert!((moments.first.values[1] - 2.0).abs() < 1e-9, "Wrong result for first moment");
+        assert!((moments.second.values[0] - sigma).abs() < 1e-3, "Wrong result for second moment");
+        assert!(moments.zeroth.errors[0] > 0.0 && moments.first.errors[0] > 0.0);
+
+        let masked = cube.moments(2.0, 0.01);
+        assert!(masked.zeroth.values.iter().all(|m| m.is_nan()), "Wrong result for masked pixels");
+    }
+}
@@ -0,0 +1,236 @@
+use crate::radex::solve_linear;
+
+use super::{Spectrum, SpectrumError};
+
+const MAX_ITERATIONS: usize = 200;
+// Relative change of chi-squared at which the fit has converged
+const TOLERANCE: f64 = 1e-10;
+const FOUR_LN_2: f64 = 4.0 * core::f64::consts::LN_2;
+// Area of a Gaussian of unit peak and unit FWHM, sqrt(pi / (4 ln 2))
+const AREA: f64 = 1.064_467_019_431_226_5;
+
+// Gaussian line; the centre and FWHM are in the unit of the spectral axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gaussian {
+    pub amplitude: f64,
+    pub centre: f64,
+    pub width: f64,
+}
+
+impl Gaussian {
+    pub fn at(&self, x: f64) -> f64 {
+        self.amplitude * (-FOUR_LN_2 * ((x - self.centre) / self.width).powi(2)).exp()
+    }
+
+    // Area under the line, intensity times spectral unit (e.g. K km s-1)
+    pub fn integrated(&self) -> f64 {
+        AREA * self.amplitude * self.width
+    }
+}
+
+// Fitted component with the 1-sigma uncertainties of its parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GaussianComponent {
+    pub line: Gaussian,
+    pub amplitude_error: f64,
+    pub centre_error: f64,
+    pub width_error: f64,
+    pub integrated: f64,
+    pub integrated_error: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianFit {
+    pub components: Vec<GaussianComponent>,
+    // Scatter of the residuals, intensity unit
+    pub rms: f64,
+    pub iterations: usize,
+}
+
+fn model(lines: &[Gaussian], x: f64) -> f64 {
+    lines.iter().map(|g| g.at(x)).sum()
+}
+
+fn lines(parameters: &[f64]) -> Vec<Gaussian> {
+    parameters.chunks_exact(3).map(|p| Gaussian { amplitude: p[0], centre: p[1], width: p[2] }).collect()
+}
+
+// Derivatives of the model at `x` with respect to every parameter
+fn gradient(parameters: &[f64], x: f64) -> Vec<f64> {
+    parameters
+        .chunks_exact(3)
+        .flat_map(|p| {
+            let (amplitude, centre, width) = (p[0], p[1], p[2]);
+            let d = x - centre;
+            let g = (-FOUR_LN_2 * (d / width).powi(2)).exp();
+            [g, amplitude * g * 2.0 * FOUR_LN_2 * d / width.powi(2), amplitude * g * 2.0 * FOUR_LN_2 * d * d / width.powi(3)]
+        })
+        .collect()
+}
+
+fn chi2(parameters: &[f64], points: &[(f64, f64)]) -> f64 {
+    let lines = lines(parameters);
+    points.iter().map(|&(x, y)| (y - model(&lines, x)).powi(2)).sum()
+}
+
+// Normal equations J^T J and J^T r at `parameters`
+fn normal_equations(parameters: &[f64], points: &[(f64, f64)]) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let n = parameters.len();
+    let lines = lines(parameters);
+    let mut a = vec!(vec!(0.0; n); n);
+    let mut b = vec!(0.0; n);
+
+    for &(x, y) in points.iter() {
+        let r = y - model(&lines, x);
+        let gradient = gradient(parameters, x);
+        for j in 0..n {
+            b[j] += gradient[j] * r;
+            for k in 0..n {
+                a[j][k] += gradient[j] * gradient[k];
+            }
+        }
+    }
+
+    (a, b)
+}
+
+impl Spectrum {
+    // Levenberg-Marquardt fit of as many Gaussians as there are initial
+    // guesses. Uncertainties are scaled by the residual scatter, since the
+    // channel noise is not known. Channels with a NaN intensity are ignored.
+    pub fn fit_gaussians(&self, guesses: &[Gaussian]) -> Result<GaussianFit, SpectrumError> {
+        let fit_error = |note: String| SpectrumError::Fit { note };
+        let points = self
+            .spectral
+            .values
+            .iter()
+            .zip(self.intensity.values.iter())
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .map(|(&x, &y)| (x, y))
+            .collect::<Vec<_>>();
+        let mut parameters = guesses.iter().flat_map(|g| [g.amplitude, g.centre, g.width]).collect::<Vec<_>>();
+        let n = parameters.len();
+        if guesses.is_empty() {
+            return Err(fit_error(String::from("no initial guesses were given")));
+        }
+        if points.len() <= n {
+            return Err(fit_error(format!("{} channels cannot constrain {} parameters", points.len(), n)));
+        }
+        if guesses.iter().any(|g| !(g.width.is_finite() && g.width != 0.0)) {
+            return Err(fit_error(String::from("initial widths must be finite and non-zero")));
+        }
+
+        let mut lambda = 1e-3;
+        let mut current = chi2(&parameters, &points);
+        let mut iterations = 0;
+        while iterations < MAX_ITERATIONS {
+            iterations += 1;
+            let (mut a, b) = normal_equations(&parameters, &points);
+            for (j, row) in a.iter_mut().enumerate() {
+                row[j] *= 1.0 + lambda;
+            }
+
+            let step = match solve_linear(a, b) {
+                Ok(step) => step,
+                Err(_) => {
+                    lambda *= 10.0;
+                    continue;
+                }
+            };
+            let trial = parameters.iter().zip(step.iter()).map(|(p, d)| p + d).collect::<Vec<_>>();
+            let next = chi2(&trial, &points);
+
+            if next.is_finite() && next <= current {
+                let change = (current - next) / current.max(f64::MIN_POSITIVE);
+                parameters = trial;
+                current = next;
+                lambda = (lambda / 10.0).max(1e-12);
+                if change < TOLERANCE {
+                    break;
+                }
+            } else {
+                lambda *= 10.0;
+                if lambda > 1e12 {
+                    break;
+                }
+            }
+        }
+
+        // Covariance (J^T J)^-1 s^2, column by column
+        let (a, _) = normal_equations(&parameters, &points);
+        let variance = current / (points.len() - n) as f64;
+        let mut covariance = vec!(vec!(0.0; n); n);
+        for k in 0..n {
+            let unit = (0..n).map(|j| if j == k { 1.0 } else { 0.0 }).collect();
+            let column = solve_linear(a.clone(), unit)
+                .map_err(|_| fit_error(String::from("the components are degenerate, e.g. two at the same centre")))?;
+            for (row, c) in covariance.iter_mut().zip(column.iter()) {
+                row[k] = c * variance;
+            }
+        }
+
+        let components = lines(&parameters)
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut line)| {
+                let (a, w) = (3 * i, 3 * i + 2);
+                line.width = line.width.abs();
+                let integrated_variance = AREA.powi(2)
+                    * (line.width.powi(2) * covariance[a][a]
+                        + line.amplitude.powi(2) * covariance[w][w]
+                        + 2.0 * line.amplitude * line.width * covariance[a][w]);
+                GaussianComponent {
+                    line,
+                    amplitude_error: covariance[a][a].sqrt(),
+                    centre_error: covariance[a + 1][a + 1].sqrt(),
+                    width_error: covariance[w][w].sqrt(),
+                    integrated: line.integrated(),
+                    integrated_error: integrated_variance.max(0.0).sqrt(),
+                }
+            })
+            .collect();
+
+        Ok(GaussianFit { components, rms: (current / points.len() as f64).sqrt(), iterations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::spectrum::{Axis, Observation};
+
+    #[test]
+    fn fit_two_components() {
+        let truth = [
+            Gaussian { amplitude: 2.0, centre: -3.0, width: 2.0 },
+            Gaussian { amplitude: 1.0, centre: 4.0, width: 3.0 },
+        ];
+        let velocities = (0..201).map(|i| -20.0 + 0.2 * i as f64).collect::<Vec<_>>();
+        // Deterministic "noise" so the uncertainties are not zero
+        let intensity = velocities.iter().enumerate().map(|(i, &v)| model(&truth, v) + 0.01 * ((i * 7919) % 13) as f64 / 13.0 - 0.005);
+        let spectrum = Spectrum {
+            name: String::from("test"),
+            rest_frequency: 100.0,
+            spectral: Axis::new("km s-1", velocities.clone()),
+            intensity: Axis::new("K", intensity.collect()),
+            observation: Observation::default(),
+        };
+
+        let guesses = [
+            Gaussian { amplitude: 1.5, centre: -2.0, width: 1.0 },
+            Gaussian { amplitude: 0.5, centre: 5.0, width: 2.0 },
+        ];
+        let fit = spectrum.fit_gaussians(&guesses).unwrap();
+
+        for (component, line) in fit.components.iter().zip(truth.iter()) {
+            assert!((component.line.centre - line.centre).abs() < 0.01, "Wrong centre {:?}", component);
+            assert!((component.line.width - line.width).abs() < 0.01, "Wrong width {:?}", component);
+            assert!((component.integrated - line.integrated()).abs() < 3.0 * component.integrated_error + 1e-3);
+            assert!(component.centre_error > 0.0 && component.centre_error < 0.01);
+        }
+        assert!(fit.rms < 0.01);
+
+        assert!(matches!(spectrum.fit_gaussians(&[]), Err(SpectrumError::Fit { .. })));
+    }
+}
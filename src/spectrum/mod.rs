@@ -0,0 +1,337 @@
+use crate::constants::SPEED_OF_LIGHT;
+use crate::iau::unit_string::{self, UnitError, UnitExpression};
+use crate::radex::Solution;
+
+mod baseline;
+mod cube;
+mod gaussian;
+mod switching;
+mod telescope;
+
+pub use baseline::{Baseline, BaselineFit};
+pub use cube::{Cube, MomentMap, Moments};
+pub use gaussian::{Gaussian, GaussianComponent, GaussianFit};
+pub use switching::Switching;
+pub use telescope::{Scale, Telescope};
+
+// Speed of light, km s-1
+const C_KMS: f64 = SPEED_OF_LIGHT * 1e-5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpectrumError {
+    Syntax {
+        line: usize,
+        note: String,
+    },
+    Fits {
+        note: String,
+    },
+    Fit {
+        note: String,
+    },
+    Shape {
+        note: String,
+    },
+    Unit(UnitError),
+}
+
+impl std::fmt::Display for SpectrumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpectrumError::Syntax { line, note } => write!(f, "line {}: {}", line, note),
+            SpectrumError::Fits { note } => write!(f, "cannot read FITS spectrum: {}", note),
+            SpectrumError::Fit { note } => write!(f, "fit failed: {}", note),
+            SpectrumError::Shape { note } => write!(f, "{}", note),
+            SpectrumError::Unit(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SpectrumError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpectrumError::Unit(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<UnitError> for SpectrumError {
+    fn from(e: UnitError) -> Self {
+        SpectrumError::Unit(e)
+    }
+}
+
+// Values along one axis of a spectrum in `unit`, a FITS unit string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    pub unit: String,
+    pub values: Vec<f64>,
+}
+
+impl Axis {
+    pub fn new(unit: &str, values: Vec<f64>) -> Self {
+        Self { unit: String::from(unit), values }
+    }
+
+    // Values converted to `unit`
+    pub fn to_unit(&self, unit: &str) -> Result<Vec<f64>, UnitError> {
+        let factor = unit_string::convert(1.0, &self.unit, unit)?;
+        Ok(self.values.iter().map(|v| v * factor).collect())
+    }
+
+    // Distance between the channels when they are evenly spaced
+    pub fn step(&self) -> Option<f64> {
+        let step = self.values.get(1)? - self.values[0];
+        let tolerance = step.abs() * 1e-6;
+        self.values.windows(2).all(|w| ((w[1] - w[0]) - step).abs() <= tolerance).then_some(step)
+    }
+}
+
+// Basic metadata of an observation, for the formats that record it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Observation {
+    pub object: String,
+    pub telescope: String,
+    // ISO 8601 date of the observation
+    pub date: String,
+    // J2000 equatorial coordinates, degrees
+    pub ra: f64,
+    pub dec: f64,
+    // Integration time, s
+    pub exposure: f64,
+    // System temperature, K
+    pub tsys: f64,
+}
+
+// Spectrum of a single position. The spectral axis holds velocities (radio
+// convention, relative to `rest_frequency`) or frequencies, depending on its
+// unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    pub name: String,
+    // Rest frequency of the velocity axis, GHz
+    pub rest_frequency: f64,
+    pub spectral: Axis,
+    pub intensity: Axis,
+    pub observation: Observation,
+}
+
+fn is_velocity(unit: &UnitExpression) -> bool {
+    unit.dimension == "km s-1".parse::<UnitExpression>().map(|u| u.dimension).unwrap_or_default()
+}
+
+impl Spectrum {
+    pub fn len(&self) -> usize {
+        self.spectral.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn has_velocity_axis(&self) -> bool {
+        self.spectral.unit.parse().is_ok_and(|u| is_velocity(&u))
+    }
+
+    // Velocities of the channels, km s-1
+    pub fn velocities(&self) -> Result<Vec<f64>, UnitError> {
+        match is_velocity(&self.spectral.unit.parse()?) {
+            true => self.spectral.to_unit("km s-1"),
+            false => Ok(self.spectral.to_unit("GHz")?.iter().map(|f| C_KMS * (1.0 - f / self.rest_frequency)).collect()),
+        }
+    }
+
+    // Frequencies of the channels, GHz
+    pub fn frequencies(&self) -> Result<Vec<f64>, UnitError> {
+        match is_velocity(&self.spectral.unit.parse()?) {
+            true => Ok(self.velocities()?.iter().map(|v| self.rest_frequency * (1.0 - v / C_KMS)).collect()),
+            false => self.spectral.to_unit("GHz"),
+        }
+    }
+}
+
+// Reads a spectrum from two columns of numbers separated by spaces, tabs or
+// commas, the spectral axis in `spectral_unit` (velocities or frequencies)
+// and the intensity in `intensity_unit`. Empty lines and lines starting with
+// `#`, `!` or `%` are skipped; further columns are ignored.
+pub fn parse_ascii(
+    s: &str,
+    name: &str,
+    rest_frequency: f64,
+    spectral_unit: &str,
+    intensity_unit: &str,
+) -> Result<Spectrum, SpectrumError> {
+    let spectral = spectral_unit.parse::<UnitExpression>()?;
+    let frequency = "Hz".parse::<UnitExpression>()?;
+    if !is_velocity(&spectral) && !spectral.is_compatible(&frequency) {
+        return Err(SpectrumError::Unit(UnitError::Incompatible {
+            from: String::from(spectral_unit),
+            to: String::from("km s-1"),
+        }));
+    }
+    intensity_unit.parse::<UnitExpression>()?;
+
+    let mut x = vec!();
+    let mut y = vec!();
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', '!', '%']) {
+            continue;
+        }
+
+        let syntax = |note: String| SpectrumError::Syntax { line: i + 1, note };
+        let mut values = line.split(|c: char| c.is_whitespace() || c == ',').filter(|v| !v.is_empty()).map(|v| {
+            v.parse::<f64>().map_err(|_| syntax(format!("`{}` is not a number", v)))
+        });
+        match (values.next(), values.next()) {
+            (Some(a), Some(b)) => {
+                x.push(a?);
+                y.push(b?);
+            }
+            _ => return Err(syntax(String::from("expected a spectral value and an intensity"))),
+        }
+    }
+
+    Ok(Spectrum {
+        name: String::from(name),
+        rest_frequency,
+        spectral: Axis::new(spectral_unit, x),
+        intensity: Axis::new(intensity_unit, y),
+        observation: Observation { object: String::from(name), ..Default::default() },
+    })
+}
+
+// (1 - exp(-tau)) / tau, which is 1 for a thin line
+fn escape_fraction(tau: f64) -> f64 {
+    match tau.abs() < 1e-10 {
+        true => 1.0,
+        false => -(-tau).exp_m1() / tau,
+    }
+}
+
+// Synthetic spectrum in radiation temperature (K) around `rest_frequency` GHz,
+// with `channels` channels between `vmin` and `vmax` km s-1. Every line of
+// the solution has a Gaussian opacity profile of width `linewidth` (FWHM,
+// km s-1), so blended lines add up in opacity, not in intensity: a channel
+// radiates with the summed opacity and the opacity-weighted mean of the
+// source functions of its lines.
+pub fn synthesize(
+    name: &str,
+    solution: &Solution,
+    linewidth: f64,
+    rest_frequency: f64,
+    (vmin, vmax): (f64, f64),
+    channels: usize,
+) -> Spectrum {
+    let step = if channels > 1 { (vmax - vmin) / (channels - 1) as f64 } else { 0.0 };
+    let velocities = (0..channels).map(|i| vmin + step * i as f64).collect::<Vec<_>>();
+
+    // Source function times opacity at the line centre, from t_r = S (1 - exp(-tau))
+    let emissivity = solution.lines.iter().map(|line| line.t_r / escape_fraction(line.tau)).collect::<Vec<_>>();
+
+    let intensity = velocities
+        .iter()
+        .map(|v| {
+            let (tau, emission) = solution.lines.iter().zip(emissivity.iter()).fold((0.0, 0.0), |(tau, emission), (line, j)| {
+                let offset = v - C_KMS * (1.0 - line.frequency / rest_frequency);
+                let phi = (-4.0 * core::f64::consts::LN_2 * (offset / linewidth).powi(2)).exp();
+                (tau + line.tau * phi, emission + j * phi)
+            });
+            emission * escape_fraction(tau)
+        })
+        .collect();
+
+    Spectrum {
+        name: String::from(name),
+        rest_frequency,
+        spectral: Axis::new("km s-1", velocities),
+        intensity: Axis::new("K", intensity),
+        observation: Observation { object: String::from(name), ..Default::default() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::radex::LineResult;
+
+    #[test]
+    fn synthesize_line() {
+        let line = LineResult {
+            transition: 1,
+            up: 2,
+            low: 1,
+            qnums_up: String::from("1"),
+            qnums_low: String::from("0"),
+            e_up: 5.53,
+            frequency: 115.271_202,
+            wavelength: 2600.757,
+            tex: 20.0,
+            tau: 2.0,
+            t_r: 10.0,
+            pop_up: 0.3,
+            pop_low: 0.2,
+            flux_kkms: 10.645,
+            flux_cgs: 1e-8,
+        };
+        let solution = Solution { populations: vec!(), lines: vec!(line), iterations: 1 };
+        let spectrum = synthesize("CO", &solution, 1.0, 115.271_202, (-5.0, 5.0), 101);
+
+        assert_eq!(spectrum.len(), 101);
+        assert!((spectrum.intensity.values[50] - 10.0).abs() < 1e-9, "Wrong result for line centre");
+        assert!(spectrum.intensity.values[0] < 1e-9, "Wrong result for line wing");
+        assert!((spectrum.spectral.step().unwrap() - 0.1).abs() < 1e-12);
+
+        let frequencies = spectrum.frequencies().unwrap();
+        assert!((frequencies[0] - 115.273_125).abs() < 1e-6, "Wrong frequency {}", frequencies[0]);
+        let back = Spectrum { spectral: Axis::new("MHz", frequencies.iter().map(|f| f * 1e3).collect()), ..spectrum };
+        assert!((back.velocities().unwrap()[0] + 5.0).abs() < 1e-9, "Wrong result for velocity from frequency");
+    }
+
+    #[test]
+    fn blended_thick_lines() {
+        let line = LineResult {
+            transition: 1,
+            up: 2,
+            low: 1,
+            qnums_up: String::from("1"),
+            qnums_low: String::from("0"),
+            e_up: 5.53,
+            frequency: 115.271_202,
+            wavelength: 2600.757,
+            tex: 20.0,
+            tau: 10.0,
+            t_r: 10.0,
+            pop_up: 0.3,
+            pop_low: 0.2,
+            flux_kkms: 10.645,
+            flux_cgs: 1e-8,
+        };
+        // Two components of the same frequency saturate at the brightness of one
+        let solution = Solution { populations: vec!(), lines: vec!(line.clone(), line.clone()), iterations: 1 };
+        let spectrum = synthesize("CO", &solution, 1.0, 115.271_202, (-5.0, 5.0), 101);
+        assert!((spectrum.intensity.values[50] - 10.0).abs() < 1e-3, "Wrong result for blend {}", spectrum.intensity.values[50]);
+
+        let thin = LineResult { tau: 1e-3, t_r: 0.01, ..line };
+        let solution = Solution { populations: vec!(), lines: vec!(thin.clone(), thin), iterations: 1 };
+        let spectrum = synthesize("CO", &solution, 1.0, 115.271_202, (-5.0, 5.0), 101);
+        assert!((spectrum.intensity.values[50] - 0.02).abs() < 1e-4, "Wrong result for thin blend {}", spectrum.intensity.values[50]);
+    }
+
+    #[test]
+    fn read_ascii_spectrum() {
+        let s = "# v [km/s]  T_mb [K]\n-1.0 0.1\n0.0, 2.5\n\n1.0\t0.2 extra\n";
+        let spectrum = parse_ascii(s, "CO", 115.271_202, "km/s", "K").unwrap();
+
+        assert_eq!(spectrum.intensity.values, vec!(0.1, 2.5, 0.2));
+        assert!(spectrum.has_velocity_axis());
+
+        assert_eq!(
+            parse_ascii("1.0 x\n", "CO", 115.271_202, "GHz", "K"),
+            Err(SpectrumError::Syntax { line: 1, note: String::from("`x` is not a number") }),
+        );
+        assert!(matches!(parse_ascii("", "CO", 115.271_202, "K", "K"), Err(SpectrumError::Unit(_))));
+    }
+}
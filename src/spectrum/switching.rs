@@ -0,0 +1,96 @@
+use super::{Axis, Spectrum, SpectrumError};
+
+// Switched single-dish observing modes. Offsets are in the unit of the
+// spectral axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Switching {
+    // The reference phase is tuned `throw` away, so the difference holds the
+    // line and a negative copy; folding shifts and averages the two halves,
+    // which leaves the line with negative ghosts of half its height at
+    // +/- `throw`.
+    Frequency {
+        throw: f64,
+        fold: bool,
+    },
+    // The reference position still sees `contamination` times the source
+    // emission, moved by `offset`, as for extended clouds, and shows up as a
+    // negative dip.
+    Position {
+        contamination: f64,
+        offset: f64,
+    },
+}
+
+// Value of the spectrum at `x`, linear between channels and zero outside
+fn sample(x: &[f64], y: &[f64], at: f64) -> f64 {
+    let descending = x[0] > x[x.len() - 1];
+    let index = |v: f64| if descending { -v } else { v };
+    let position = x.partition_point(|&v| index(v) < index(at));
+
+    match position {
+        0 if x[0] == at => y[0],
+        0 => 0.0,
+        n if n == x.len() => 0.0,
+        n => y[n - 1] + (at - x[n - 1]) / (x[n] - x[n - 1]) * (y[n] - y[n - 1]),
+    }
+}
+
+impl Spectrum {
+    // Spectrum as it would come out of a switched observation of this one,
+    // taken as the emission of the source.
+    pub fn simulate_switching(&self, switching: Switching) -> Result<Spectrum, SpectrumError> {
+        if self.len() < 2 {
+            return Err(SpectrumError::Shape { note: String::from("switching needs at least two channels") });
+        }
+
+        let x = &self.spectral.values;
+        let y = &self.intensity.values;
+        let shifted = |offset: f64| x.iter().map(|&v| sample(x, y, v - offset)).collect::<Vec<_>>();
+
+        let values = match switching {
+            Switching::Frequency { throw, fold: false } => {
+                y.iter().zip(shifted(-throw)).map(|(s, r)| s - r).collect()
+            }
+            Switching::Frequency { throw, fold: true } => {
+                let (minus, plus) = (shifted(-throw), shifted(throw));
+                y.iter().zip(minus.iter().zip(plus.iter())).map(|(s, (m, p))| s - (m + p) / 2.0).collect()
+            }
+            Switching::Position { contamination, offset } => {
+                y.iter().zip(shifted(offset)).map(|(s, r)| s - contamination * r).collect()
+            }
+        };
+
+        Ok(Spectrum { intensity: Axis::new(&self.intensity.unit, values), ..self.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::spectrum::{Gaussian, Observation};
+
+    #[test]
+    fn switching_artifacts() {
+        let line = Gaussian { amplitude: 2.0, centre: 0.0, width: 1.0 };
+        let velocities = (0..201).map(|i| -20.0 + 0.2 * i as f64).collect::<Vec<_>>();
+        let spectrum = Spectrum {
+            name: String::from("CO"),
+            rest_frequency: 115.271_202,
+            intensity: Axis::new("K", velocities.iter().map(|&v| line.at(v)).collect()),
+            spectral: Axis::new("km s-1", velocities),
+            observation: Observation::default(),
+        };
+        let at = |s: &Spectrum, v: f64| sample(&s.spectral.values, &s.intensity.values, v);
+
+        let folded = spectrum.simulate_switching(Switching::Frequency { throw: 8.0, fold: true }).unwrap();
+        assert!((at(&folded, 0.0) - 2.0).abs() < 1e-9, "Wrong result for the folded line");
+        assert!((at(&folded, 8.0) + 1.0).abs() < 1e-9 && (at(&folded, -8.0) + 1.0).abs() < 1e-9, "Missing ghosts");
+
+        let raw = spectrum.simulate_switching(Switching::Frequency { throw: 8.0, fold: false }).unwrap();
+        assert!((at(&raw, -8.0) + 2.0).abs() < 1e-9, "Wrong result for the reference phase");
+
+        let position = spectrum.simulate_switching(Switching::Position { contamination: 0.3, offset: 1.0 }).unwrap();
+        assert!((at(&position, 1.0) - (line.at(1.0) - 0.6)).abs() < 1e-9, "Wrong result for the reference dip");
+    }
+}
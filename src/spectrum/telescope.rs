@@ -0,0 +1,146 @@
+use crate::constants::SPEED_OF_LIGHT;
+
+use super::{Spectrum, SpectrumError};
+
+const ARCSEC_PER_RADIAN: f64 = 206_264.806_247;
+
+// Intensity scales of single-dish spectra (Kutner & Ulich 1981):
+// T_A* = F_eff / B_eff T_mb, and T_mb is the radiation temperature T_R of
+// the source diluted into the main beam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    Radiation,
+    AntennaTemperature,
+    MainBeam,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Telescope {
+    pub name: String,
+    // Diameter of the primary, m
+    pub diameter: f64,
+    // Half-power beam width as a multiple of lambda / D
+    pub beam_factor: f64,
+    // Frequency (GHz), forward and main beam efficiency, sorted by frequency
+    pub efficiencies: Vec<(f64, f64, f64)>,
+}
+
+impl Telescope {
+    // Telescopes with published efficiencies, by the name used in TELESCOP
+    pub fn named(name: &str) -> Option<Self> {
+        let key = name.to_uppercase().replace(['-', ' '], "");
+        let (diameter, efficiencies): (f64, &[(f64, f64, f64)]) = match key.as_str() {
+            "IRAM30M" | "PICOVELETA" => (30.0, &[
+                (86.0, 0.95, 0.81),
+                (115.0, 0.94, 0.78),
+                (145.0, 0.93, 0.73),
+                (210.0, 0.94, 0.63),
+                (230.0, 0.92, 0.59),
+                (280.0, 0.87, 0.49),
+                (340.0, 0.81, 0.35),
+            ]),
+            "APEX" | "APEX12M" => (12.0, &[
+                (230.0, 0.95, 0.75),
+                (345.0, 0.95, 0.73),
+                (460.0, 0.95, 0.60),
+                (810.0, 0.95, 0.43),
+            ]),
+            _ => return None,
+        };
+
+        Some(Self { name: String::from(name), diameter, beam_factor: 1.2, efficiencies: efficiencies.to_vec() })
+    }
+
+    // Forward and main beam efficiency at `frequency` (GHz), interpolated in
+    // the table and held constant beyond its ends; without a table the
+    // telescope is taken as ideal
+    pub fn efficiency(&self, frequency: f64) -> (f64, f64) {
+        let table = &self.efficiencies;
+        if table.is_empty() {
+            return (1.0, 1.0);
+        }
+        let upper = table.iter().position(|e| e.0 >= frequency).unwrap_or(table.len());
+        match upper {
+            0 => (table[0].1, table[0].2),
+            n if n == table.len() => (table[n - 1].1, table[n - 1].2),
+            n => {
+                let (a, b) = (table[n - 1], table[n]);
+                let t = (frequency - a.0) / (b.0 - a.0);
+                (a.1 + t * (b.1 - a.1), a.2 + t * (b.2 - a.2))
+            }
+        }
+    }
+
+    // Half-power beam width at `frequency` (GHz), arcsec
+    pub fn beam_size(&self, frequency: f64) -> f64 {
+        let wavelength = SPEED_OF_LIGHT / (frequency * 1e9) * 1e-2;
+        self.beam_factor * wavelength / self.diameter * ARCSEC_PER_RADIAN
+    }
+
+    // Fraction of the main beam filled by a Gaussian source of FWHM
+    // `source_size` (arcsec); sources without a size fill the beam.
+    pub fn dilution(&self, frequency: f64, source_size: Option<f64>) -> f64 {
+        match source_size {
+            Some(size) => size.powi(2) / (size.powi(2) + self.beam_size(frequency).powi(2)),
+            None => 1.0,
+        }
+    }
+
+    // Intensity `t` on scale `from` converted to scale `to` at `frequency` (GHz)
+    pub fn convert(&self, t: f64, from: Scale, to: Scale, frequency: f64, source_size: Option<f64>) -> f64 {
+        let (forward, beam) = self.efficiency(frequency);
+        let dilution = self.dilution(frequency, source_size);
+        let main_beam = match from {
+            Scale::Radiation => t * dilution,
+            Scale::AntennaTemperature => t * forward / beam,
+            Scale::MainBeam => t,
+        };
+
+        match to {
+            Scale::Radiation => main_beam / dilution,
+            Scale::AntennaTemperature => main_beam * beam / forward,
+            Scale::MainBeam => main_beam,
+        }
+    }
+}
+
+impl Spectrum {
+    // Intensities moved from scale `from` to `to`, channel by channel, e.g.
+    // a synthetic T_R spectrum to the T_A* a telescope reports.
+    pub fn convert_scale(
+        &mut self,
+        telescope: &Telescope,
+        from: Scale,
+        to: Scale,
+        source_size: Option<f64>,
+    ) -> Result<(), SpectrumError> {
+        let frequencies = self.frequencies()?;
+        for (t, f) in self.intensity.values.iter_mut().zip(frequencies.iter()) {
+            *t = telescope.convert(*t, from, to, *f, source_size);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn antenna_temperature_scales() {
+        let iram = Telescope::named("IRAM-30m").unwrap();
+        assert!((iram.beam_size(230.0) - 10.75).abs() < 0.1, "Wrong result for beam size {}", iram.beam_size(230.0));
+        let (forward, beam) = iram.efficiency(220.0);
+        assert!((forward - 0.93).abs() < 1e-12 && (beam - 0.61).abs() < 1e-12, "Wrong result for interpolated efficiency");
+        assert_eq!(iram.efficiency(50.0), (0.95, 0.81), "Wrong result below the table");
+
+        let ta = iram.convert(1.0, Scale::MainBeam, Scale::AntennaTemperature, 230.0, None);
+        assert!((ta - 0.59 / 0.92).abs() < 1e-12);
+        let tr = iram.convert(ta, Scale::AntennaTemperature, Scale::Radiation, 230.0, Some(iram.beam_size(230.0)));
+        assert!((tr - 2.0).abs() < 1e-12, "Wrong result for a source as large as the beam");
+
+        assert!(Telescope::named("Arecibo").is_none());
+    }
+}
@@ -0,0 +1,101 @@
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::subsequence;
+
+use crate::lamda::generate::{self, level_pairs, PARTNERS};
+use crate::lamda::{CollisionPartnerData, CollisionPartnerId, ElementData, EnergyLevel, RadiativeTransition};
+
+// Proptest strategies for structurally valid molecular data, with the
+// invariants of `lamda::generate`. Generated data parses back from the
+// datafile it writes.
+
+pub(crate) fn energy_levels(nlev: u32) -> impl Strategy<Value = Vec<EnergyLevel>> {
+    vec((0.1f64..500.0, 1u32..20), nlev as usize).prop_map(|levels| {
+        generate::energy_levels(
+            levels.into_iter().enumerate().map(|(i, (step, stat_weight))| (step, stat_weight as f64, i.to_string())),
+        )
+    })
+}
+
+pub(crate) fn radiative_transitions(nlev: u32) -> impl Strategy<Value = Vec<RadiativeTransition>> {
+    let pairs = level_pairs(nlev);
+    let n = pairs.len();
+
+    (subsequence(pairs, 1..=n), vec(-10.0f64..-2.0, n)).prop_map(|(pairs, exponents)| {
+        generate::radiative_transitions(pairs.into_iter().zip(exponents).map(|(pair, e)| (pair, 10f64.powf(e), None)))
+    })
+}
+
+// Rate table of `name` for every pair of `nlev` levels.
+pub fn collision_partner(name: CollisionPartnerId, nlev: u32) -> impl Strategy<Value = CollisionPartnerData> {
+    vec(1.0f64..100.0, 1..8).prop_flat_map(move |steps| {
+        let temperatures = generate::increasing(steps);
+        let pairs = level_pairs(nlev);
+        let name = name.clone();
+
+        vec(vec(-14.0f64..-9.0, temperatures.len()), pairs.len()).prop_map(move |exponents| {
+            let rates = exponents.into_iter().map(|e| e.into_iter().map(|e| 10f64.powf(e)).collect());
+            generate::collision_partner(name.clone(), temperatures.clone(), pairs.iter().copied().zip(rates))
+        })
+    })
+}
+
+pub fn element_data() -> impl Strategy<Value = ElementData> {
+    (2u32..=12)
+        .prop_flat_map(|nlev| {
+            let partners = subsequence(PARTNERS.to_vec(), 1..=3).prop_flat_map(move |names| {
+                names.into_iter().map(|name| collision_partner(name, nlev)).collect::<Vec<_>>()
+            });
+            (
+                "[A-Z][a-z]?[0-9A-Z+]{0,4}",
+                1.0f64..200.0,
+                energy_levels(nlev),
+                radiative_transitions(nlev),
+                partners,
+            )
+        })
+        .prop_map(|(name, weight, energy_levels, radiative_transitions, collision_partners)| {
+            generate::element_data(name, "", weight, energy_levels, radiative_transitions, collision_partners)
+        })
+}
+
+impl Arbitrary for ElementData {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        element_data().boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn generated_data_is_consistent(data in any::<ElementData>()) {
+            let energy = |level: u32| data.energy_levels[level as usize - 1].energy;
+
+            for (i, level) in data.energy_levels.iter().enumerate() {
+                prop_assert_eq!(level.level as usize, i + 1);
+            }
+            for line in data.radiative_transitions.iter() {
+                prop_assert!(energy(line.up) > energy(line.low));
+            }
+            for partner in data.collision_partners.iter() {
+                prop_assert!(partner.temperatures.windows(2).all(|t| t[0] < t[1]));
+                for rate in partner.rates.iter() {
+                    prop_assert_eq!(rate.rates.len(), partner.temperatures.len());
+                    prop_assert!(rate.up as usize <= data.energy_levels.len() && rate.low < rate.up);
+                }
+            }
+        }
+
+        #[test]
+        fn written_data_parses_back(data in any::<ElementData>()) {
+            prop_assert_eq!(data.to_lamda_string().parse::<ElementData>(), Ok(data));
+        }
+    }
+}
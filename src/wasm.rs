@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::interop::{ecsv, spectralradex, table, votable};
+use crate::lamda::{CollisionPartnerId, ElementData};
+use crate::radex::{self, Conditions, Geometry};
+
+fn parse(s: &str) -> Result<ElementData, JsError> {
+    s.parse::<ElementData>().map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = parseLamda)]
+pub fn parse_lamda(s: &str) -> Result<String, JsError> {
+    spectralradex::element_data_to_json(&parse(s)?).map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = validateLamda)]
+pub fn validate_lamda(s: &str) -> Option<String> {
+    s.parse::<ElementData>().err().map(|e| e.to_string())
+}
+
+// JSON array of diagnostics, empty when the datafile is valid.
+#[wasm_bindgen(js_name = lamdaDiagnostics)]
+pub fn lamda_diagnostics(s: &str) -> Result<String, JsError> {
+    let diagnostics = s.parse::<ElementData>().err().map(|e| e.diagnostic()).into_iter().collect::<Vec<_>>();
+    serde_json::to_string(&diagnostics).map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = lineListEcsv)]
+pub fn line_list_ecsv(s: &str) -> Result<String, JsError> {
+    Ok(ecsv::to_string(&table::line_list(&parse(s)?)))
+}
+
+#[wasm_bindgen(js_name = lineListVotable)]
+pub fn line_list_votable(s: &str) -> Result<String, JsError> {
+    Ok(votable::to_string(&table::line_list(&parse(s)?)))
+}
+
+// Conditions as given from JavaScript, e.g. `{ tkin: 20, densities: { "p-H2": 1e4 },
+// geometry: "lvg" }`; missing fields take the defaults of `Conditions`.
+#[derive(serde::Deserialize)]
+#[serde(default)]
+struct ConditionsObject {
+    tkin: f64,
+    tbg: f64,
+    densities: BTreeMap<String, f64>,
+    cdmol: f64,
+    linewidth: f64,
+    geometry: Geometry,
+}
+
+impl Default for ConditionsObject {
+    fn default() -> Self {
+        let c = Conditions::default();
+        Self {
+            tkin: c.tkin,
+            tbg: c.tbg,
+            densities: c.densities.iter().map(|(partner, n)| (partner.to_string(), *n)).collect(),
+            cdmol: c.cdmol,
+            linewidth: c.linewidth,
+            geometry: c.geometry,
+        }
+    }
+}
+
+impl TryFrom<ConditionsObject> for Conditions {
+    type Error = JsError;
+
+    fn try_from(item: ConditionsObject) -> Result<Self, Self::Error> {
+        let densities = item.densities
+            .into_iter()
+            .map(|(name, n)| match CollisionPartnerId::from_name(&name) {
+                Some(partner) => Ok((partner, n)),
+                None => Err(JsError::new(&format!("unknown collision partner `{}`", name))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Conditions {
+            tkin: item.tkin,
+            tbg: item.tbg,
+            densities,
+            cdmol: item.cdmol,
+            linewidth: item.linewidth,
+            geometry: item.geometry,
+        })
+    }
+}
+
+// Level populations and line results of a datafile under the conditions of
+// a JavaScript object.
+#[wasm_bindgen]
+pub fn solve(s: &str, conditions: JsValue) -> Result<JsValue, JsError> {
+    let conditions: ConditionsObject = serde_wasm_bindgen::from_value(conditions)?;
+    let solution = radex::solve(&parse(s)?, &Conditions::try_from(conditions)?).map_err(|e| JsError::new(&e.to_string()))?;
+
+    Ok(serde_wasm_bindgen::to_value(&solution)?)
+}